@@ -1,5 +1,6 @@
 mod config;
 pub mod pwd;
+pub mod sign;
 pub mod token;
 
 use config::auth_config;
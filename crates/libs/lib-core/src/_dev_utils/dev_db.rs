@@ -82,11 +82,10 @@ async fn pexec(db: &Db, file: &Path) -> Result<(), sqlx::Error> {
 	// -- Read the file.
 	let content = fs::read_to_string(file)?;
 
-	// FIXME: Make the split more sql proof.
-	let sqls: Vec<&str> = content.split(';').collect();
+	let sqls = split_sql(&content);
 
 	for sql in sqls {
-		sqlx::query(sql).execute(db).await.map_err(|e| {
+		sqlx::query(&sql).execute(db).await.map_err(|e| {
 			println!("pexec error while running:\n{sql}");
 			println!("cause:\n{e}");
 			e
@@ -96,6 +95,30 @@ async fn pexec(db: &Db, file: &Path) -> Result<(), sqlx::Error> {
 	Ok(())
 }
 
+/// Split the sql content on `;`, except within the `$$` quoted bodies
+/// (e.g., plpgsql functions).
+fn split_sql(content: &str) -> Vec<String> {
+	let mut sqls = vec![String::new()];
+
+	for (i, part) in content.split("$$").enumerate() {
+		let is_body = i % 2 == 1;
+		let mut stmts = part.split(';');
+		if let Some(sql) = sqls.last_mut() {
+			if i > 0 {
+				sql.push_str("$$");
+			}
+			if is_body {
+				sql.push_str(part);
+				continue;
+			}
+			sql.push_str(stmts.next().unwrap_or_default());
+		}
+		sqls.extend(stmts.map(String::from));
+	}
+
+	sqls
+}
+
 async fn new_db_pool(db_con_url: &str) -> Result<Db, sqlx::Error> {
 	PgPoolOptions::new()
 		.max_connections(1)
@@ -103,3 +126,79 @@ async fn new_db_pool(db_con_url: &str) -> Result<Db, sqlx::Error> {
 		.connect(db_con_url)
 		.await
 }
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+
+	fn trimmed(sqls: Vec<String>) -> Vec<String> {
+		sqls.iter()
+			.map(|sql| sql.trim().to_string())
+			.filter(|sql| !sql.is_empty())
+			.collect()
+	}
+
+	#[test]
+	fn test_split_sql_simple_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_content = "CREATE TABLE a (id int);\nINSERT INTO a VALUES (1);\n";
+
+		// -- Exec
+		let sqls = split_sql(fx_content);
+
+		// -- Check
+		assert_eq!(
+			trimmed(sqls),
+			&["CREATE TABLE a (id int)", "INSERT INTO a VALUES (1)"]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_split_sql_dollar_quoted_body_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_content = r#"CREATE TABLE a (id int);
+CREATE FUNCTION f() RETURNS trigger AS $$
+BEGIN
+  PERFORM 1;
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+CREATE TRIGGER t AFTER INSERT ON a FOR EACH ROW EXECUTE FUNCTION f();"#;
+
+		// -- Exec
+		let sqls = trimmed(split_sql(fx_content));
+
+		// -- Check
+		assert_eq!(sqls.len(), 3);
+		assert_eq!(sqls[0], "CREATE TABLE a (id int)");
+		assert_eq!(
+			sqls[1],
+			"CREATE FUNCTION f() RETURNS trigger AS $$\nBEGIN\n  PERFORM 1;\n  RETURN NEW;\nEND;\n$$ LANGUAGE plpgsql"
+		);
+		assert_eq!(
+			sqls[2],
+			"CREATE TRIGGER t AFTER INSERT ON a FOR EACH ROW EXECUTE FUNCTION f()"
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_split_sql_no_statement_ok() -> Result<()> {
+		// -- Exec & Check
+		assert!(trimmed(split_sql("")).is_empty());
+		assert!(trimmed(split_sql(" ;\n; ")).is_empty());
+		assert_eq!(trimmed(split_sql("SELECT 1")), &["SELECT 1"]);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -195,6 +195,9 @@ pub async fn seed_agent(
 		mm,
 		AgentForCreate {
 			name: name.to_string(),
+			tools: None,
+			auto_title: None,
+			summary_token_budget: None,
 		},
 	)
 	.await
@@ -16,6 +16,9 @@ pub struct CoreConfig {
 	// -- Db
 	pub DB_URL: String,
 
+	// -- Blob Store
+	pub BLOB_DIR: String,
+
 	// -- Web
 	pub WEB_FOLDER: String,
 }
@@ -26,6 +29,9 @@ impl CoreConfig {
 			// -- Db
 			DB_URL: get_env("SERVICE_DB_URL")?,
 
+			// -- Blob Store
+			BLOB_DIR: get_env("SERVICE_BLOB_DIR")?,
+
 			// -- Web
 			WEB_FOLDER: get_env("SERVICE_WEB_FOLDER")?,
 		})
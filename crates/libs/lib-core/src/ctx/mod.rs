@@ -2,7 +2,11 @@
 
 mod error;
 
+use lib_utils::time::{now_utc, Rfc3339};
 use serde::{Serialize, Deserialize};
+use serde_with::serde_as;
+use std::time::Duration;
+use time::OffsetDateTime;
 use uuid;
 use uuid::Uuid;
 
@@ -19,6 +23,7 @@ pub struct ReqChainLink {
 	pub uuid : Uuid,
 }
 
+#[serde_as]
 #[cfg_attr(feature = "with-rpc", derive(rpc_router::RpcResource))]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Ctx {
@@ -30,6 +35,12 @@ pub struct Ctx {
 	/// Call chain for when this Ctx is sent from gateway down 
 	/// to a chain of workers
 	req_chain: Option<Vec<ReqChainLink>>,
+
+	/// Deadline of the request, set by the route timeout of the gateway, and
+	/// shrunk on each hop down the chain of workers (see `shrink_deadline`).
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[serde(default)]
+	deadline: Option<OffsetDateTime>,
 }
 
 // Constructors.
@@ -39,6 +50,7 @@ impl Ctx {
 			user_id: 0,
 			conv_id: None,
 			req_chain : None,
+			deadline: None,
 		}
 	}
 
@@ -50,6 +62,7 @@ impl Ctx {
 				user_id,
 				conv_id: None,
 				req_chain: None,
+				deadline: None,
 			})
 		}
 	}
@@ -70,6 +83,26 @@ impl Ctx {
 
 		ctx			
 	}
+
+	/// Set the deadline, unless the ctx already has an earlier one.
+	pub fn with_deadline(&self, deadline: OffsetDateTime) -> Ctx {
+		let mut ctx = self.clone();
+		ctx.deadline = match ctx.deadline {
+			Some(current) if current <= deadline => Some(current),
+			_ => Some(deadline),
+		};
+
+		ctx
+	}
+
+	/// Shrink the deadline by `margin` (e.g., before calling a worker), so that the
+	/// callee times out first, and the caller still gets its timeout error response.
+	pub fn shrink_deadline(&self, margin: Duration) -> Ctx {
+		let mut ctx = self.clone();
+		ctx.deadline = ctx.deadline.map(|deadline| deadline - margin);
+
+		ctx
+	}
 }
 
 // Property Accessors.
@@ -86,4 +119,58 @@ impl Ctx {
 	pub fn req_chain(&self) ->Option<&Vec<ReqChainLink>> {
 		self.req_chain.as_ref()
 	}
+
+	pub fn deadline(&self) -> Option<OffsetDateTime> {
+		self.deadline
+	}
+
+	/// The time left before the deadline (zero when passed),
+	/// None when the ctx has no deadline.
+	pub fn remaining(&self) -> Option<Duration> {
+		self.deadline.map(|deadline| {
+			let remaining = deadline - now_utc();
+			remaining.try_into().unwrap_or(Duration::ZERO)
+		})
+	}
+
+	pub fn is_deadline_exceeded(&self) -> bool {
+		self.remaining().is_some_and(|remaining| remaining.is_zero())
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_ctx_deadline_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_deadline = now_utc() + Duration::from_secs(60);
+		let ctx = Ctx::new(1000)?.with_deadline(fx_deadline);
+
+		// -- Exec
+		let later_ctx = ctx.with_deadline(fx_deadline + Duration::from_secs(60));
+		let hop_ctx = ctx.shrink_deadline(Duration::from_secs(1));
+		let expired_ctx = ctx.with_deadline(now_utc() - Duration::from_secs(1));
+
+		// -- Check
+		assert_eq!(later_ctx.deadline(), Some(fx_deadline));
+		assert_eq!(hop_ctx.deadline(), Some(fx_deadline - Duration::from_secs(1)));
+		assert!(hop_ctx.remaining().ok_or("should have remaining")? < Duration::from_secs(60));
+		assert!(!ctx.is_deadline_exceeded());
+		assert!(expired_ctx.is_deadline_exceeded());
+
+		// -- Check - serde round trip (ctx header)
+		let ctx_de: Ctx = serde_json::from_str(&serde_json::to_string(&hop_ctx)?)?;
+		assert_eq!(ctx_de.deadline(), hop_ctx.deadline());
+
+		Ok(())
+	}
 }
+
+// endregion: --- Tests
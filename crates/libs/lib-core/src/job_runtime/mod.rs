@@ -0,0 +1,301 @@
+//! Job Runtime
+//!
+//! Runs the `job` queue (see `JobBmc`) jobs of a service, for example:
+//!
+//! ```ignore
+//! JobRuntime::new(mm, "llm-worker-01")
+//!     .with_queue("llm", 4)
+//!     .with_handler("auto_respond", AutoRespondHandler)
+//!     .start();
+//! ```
+//!
+//! - Each queue is polled, and its due jobs claimed up to its concurrency.
+//! - Each job is run with the ctx of its creator (`job.cid`), by the handler of its `kind`.
+//!   A handler acting as another user must verify it from the db, not from the payload
+//!   (e.g., the llm-worker `auto_respond` checks the responder is a conv participant).
+//! - Each job has a deadline (`with_job_timeout`), set on its ctx, and is cancelled
+//!   (failed) when it is reached.
+//! - A handler error fails the job (retried with backoff, then dead-lettered).
+//! - The jobs locked for too long (e.g., crashed worker) are requeued.
+
+use crate::ctx::Ctx;
+use crate::model::job::{Job, JobBmc};
+use crate::model::ModelManager;
+use async_trait::async_trait;
+use lib_utils::time::now_utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::{debug, error, info};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+const DEFAULT_JOB_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+pub type JobError = Box<dyn std::error::Error + Send + Sync>;
+pub type JobResult = core::result::Result<(), JobError>;
+
+/// The handler of the jobs of a given `kind`.
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+	async fn run(&self, ctx: &Ctx, mm: &ModelManager, job: &Job) -> JobResult;
+}
+
+pub struct JobRuntime {
+	mm: ModelManager,
+	worker_id: String,
+	/// (queue, concurrency)
+	queues: Vec<(String, usize)>,
+	handlers: HashMap<String, Arc<dyn JobHandler>>,
+	poll_interval: Duration,
+	lock_timeout: Duration,
+	job_timeout: Duration,
+}
+
+// region:    --- Builder
+
+impl JobRuntime {
+	/// `worker_id` identifies the claims of this runtime (`job.locked_by`).
+	pub fn new(mm: ModelManager, worker_id: impl Into<String>) -> Self {
+		JobRuntime {
+			mm,
+			worker_id: worker_id.into(),
+			queues: Vec::new(),
+			handlers: HashMap::new(),
+			poll_interval: DEFAULT_POLL_INTERVAL,
+			lock_timeout: DEFAULT_LOCK_TIMEOUT,
+			job_timeout: DEFAULT_JOB_TIMEOUT,
+		}
+	}
+
+	/// Run the jobs of `queue`, at most `concurrency` at a time.
+	pub fn with_queue(mut self, queue: impl Into<String>, concurrency: usize) -> Self {
+		self.queues.push((queue.into(), concurrency.max(1)));
+		self
+	}
+
+	pub fn with_handler(
+		mut self,
+		kind: impl Into<String>,
+		handler: impl JobHandler + 'static,
+	) -> Self {
+		self.handlers.insert(kind.into(), Arc::new(handler));
+		self
+	}
+
+	pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+		self.poll_interval = poll_interval;
+		self
+	}
+
+	/// `Running` jobs locked for longer are requeued.
+	///
+	/// Note: Must be longer than the job timeout (otherwise a running job can be requeued).
+	pub fn with_lock_timeout(mut self, lock_timeout: Duration) -> Self {
+		self.lock_timeout = lock_timeout;
+		self
+	}
+
+	/// The max duration of a job run (its ctx deadline).
+	pub fn with_job_timeout(mut self, job_timeout: Duration) -> Self {
+		self.job_timeout = job_timeout;
+		self
+	}
+}
+
+// endregion: --- Builder
+
+// region:    --- Run
+
+impl JobRuntime {
+	/// Spawn the queue loops (and the stale job reaper) on the tokio runtime.
+	pub fn start(self) {
+		let queues = self.queues.clone();
+		let runtime = Arc::new(self);
+
+		for (queue, concurrency) in queues {
+			info!("{:<12} - queue {queue:?} (concurrency {concurrency})", "JOB RUNTIME");
+			tokio::spawn(runtime.clone().run_queue(queue, concurrency));
+		}
+		tokio::spawn(runtime.run_reaper());
+	}
+
+	async fn run_queue(self: Arc<Self>, queue: String, concurrency: usize) {
+		let ctx = Ctx::root_ctx();
+		let permits = Arc::new(Semaphore::new(concurrency));
+
+		loop {
+			let available = permits.available_permits();
+			if available == 0 {
+				tokio::time::sleep(self.poll_interval).await;
+				continue;
+			}
+
+			let jobs = match JobBmc::claim(&ctx, &self.mm, &queue, &self.worker_id, available as i64)
+				.await
+			{
+				Ok(jobs) => jobs,
+				Err(ex) => {
+					error!("{:<12} - claim on queue {queue:?} failed: {ex:?}", "JOB RUNTIME");
+					Vec::new()
+				}
+			};
+
+			// Note: When jobs were claimed, poll again right away (the queue might have more).
+			if jobs.is_empty() {
+				tokio::time::sleep(self.poll_interval).await;
+				continue;
+			}
+
+			for job in jobs {
+				let Ok(permit) = permits.clone().acquire_owned().await else {
+					return;
+				};
+				let runtime = self.clone();
+				tokio::spawn(async move {
+					runtime.run_job(job).await;
+					drop(permit);
+				});
+			}
+		}
+	}
+
+	async fn run_job(&self, job: Job) {
+		debug!("{:<12} - run job {} ({}) attempt {}", "JOB RUNTIME", job.id, job.kind, job.attempts);
+
+		let ctx = Ctx::new(job.cid)
+			.unwrap_or_else(|_| Ctx::root_ctx())
+			.with_deadline(now_utc() + self.job_timeout);
+		let result = match self.handlers.get(&job.kind) {
+			Some(handler) => {
+				tokio::time::timeout(self.job_timeout, handler.run(&ctx, &self.mm, &job))
+					.await
+					.unwrap_or_else(|_| {
+						Err(format!("job timeout after {}ms", self.job_timeout.as_millis()).into())
+					})
+			}
+			None => Err(format!("no handler for job kind '{}'", job.kind).into()),
+		};
+
+		let root_ctx = Ctx::root_ctx();
+		let worker_id = &self.worker_id;
+		let res = match result {
+			Ok(()) => JobBmc::succeed(&root_ctx, &self.mm, worker_id, &job).await,
+			Err(ex) => {
+				error!("{:<12} - job {} ({}) failed: {ex}", "JOB RUNTIME", job.id, job.kind);
+				JobBmc::fail(&root_ctx, &self.mm, worker_id, &job, &ex.to_string()).await
+			}
+		};
+		if let Err(ex) = res {
+			error!("{:<12} - job {} state update failed: {ex:?}", "JOB RUNTIME", job.id);
+		}
+	}
+
+	async fn run_reaper(self: Arc<Self>) {
+		let ctx = Ctx::root_ctx();
+		let lock_timeout = time::Duration::seconds(self.lock_timeout.as_secs() as i64);
+
+		loop {
+			tokio::time::sleep(self.lock_timeout / 2).await;
+
+			match JobBmc::requeue_stale(&ctx, &self.mm, lock_timeout).await {
+				Ok(0) => (),
+				Ok(count) => info!("{:<12} - requeued {count} stale jobs", "JOB RUNTIME"),
+				Err(ex) => error!("{:<12} - requeue stale failed: {ex:?}", "JOB RUNTIME"),
+			}
+		}
+	}
+}
+
+// endregion: --- Run
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::job::{JobForCreate, JobState};
+	use serial_test::serial;
+	use std::sync::Mutex;
+
+	/// Records the ctx deadline of the run, then sleeps.
+	struct FxSlowHandler {
+		sleep: Duration,
+		deadlines: Arc<Mutex<Vec<Option<time::OffsetDateTime>>>>,
+	}
+
+	#[async_trait]
+	impl JobHandler for FxSlowHandler {
+		async fn run(&self, ctx: &Ctx, _mm: &ModelManager, _job: &Job) -> JobResult {
+			self.deadlines
+				.lock()
+				.unwrap_or_else(|poisoned| poisoned.into_inner())
+				.push(ctx.deadline());
+			tokio::time::sleep(self.sleep).await;
+			Ok(())
+		}
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_run_job_timeout_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_queue = "test_run_job_timeout_err queue";
+		let fx_job_timeout = Duration::from_millis(100);
+		let deadlines = Arc::new(Mutex::new(Vec::new()));
+		let runtime = JobRuntime::new(mm.clone(), "worker-01")
+			.with_handler(
+				"slow",
+				FxSlowHandler {
+					sleep: Duration::from_secs(5),
+					deadlines: deadlines.clone(),
+				},
+			)
+			.with_job_timeout(fx_job_timeout);
+		let job_id = JobBmc::enqueue(
+			&ctx,
+			&mm,
+			JobForCreate {
+				queue: Some(fx_queue.to_string()),
+				kind: "slow".to_string(),
+				..Default::default()
+			},
+		)
+		.await?;
+		let mut jobs = JobBmc::claim(&ctx, &mm, fx_queue, "worker-01", 1).await?;
+		let job = jobs.pop().ok_or("Should have claimed the job")?;
+
+		// -- Exec
+		let start = now_utc();
+		runtime.run_job(job).await;
+
+		// -- Check - The job failed at its deadline
+		let job = JobBmc::get(&ctx, &mm, job_id).await?;
+		assert_eq!(job.state, JobState::Pending, "Should be retried");
+		assert_eq!(job.last_error.as_deref(), Some("job timeout after 100ms"));
+		assert!(now_utc() - start < time::Duration::seconds(2));
+
+		// -- Check - The handler ctx had the deadline
+		let deadlines = deadlines.lock().map_err(|ex| ex.to_string())?.clone();
+		let deadline = deadlines
+			.first()
+			.copied()
+			.flatten()
+			.ok_or("Should have a ctx deadline")?;
+		assert!(deadline <= start + fx_job_timeout + time::Duration::milliseconds(50));
+
+		// -- Clean
+		JobBmc::delete(&ctx, &mm, job_id).await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
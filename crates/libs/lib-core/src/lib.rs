@@ -1,6 +1,8 @@
 pub mod config;
 pub mod ctx;
+pub mod job_runtime;
 pub mod model;
+pub mod outbox_relay;
 
 // #[cfg(test)] // Commented during early development.
 pub mod _dev_utils;
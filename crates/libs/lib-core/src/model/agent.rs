@@ -3,20 +3,133 @@ use crate::generate_common_bmc_fns;
 use crate::model::base::{self, DbBmc};
 use crate::model::modql_utils::time_to_sea_value;
 use crate::model::ModelManager;
-use crate::model::Result;
+use crate::model::{Error, Result};
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
 use lib_utils::time::Rfc3339;
 use modql::field::Fields;
 use modql::filter::{FilterNodes, OpValsString, OpValsValue};
 use modql::filter::{ListOptions, OpValsInt64};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sea_query::Nullable;
 use serde_with::serde_as;
+use sqlx::postgres::{PgTypeInfo, PgValueRef};
 use sqlx::types::time::OffsetDateTime;
-use sqlx::FromRow;
+use sqlx::types::Json;
+use sqlx::{FromRow, Postgres};
+use std::collections::HashSet;
+
+// region:    --- Agent Tool
+
+/// A tool the agent model can call, stored as json in `agent.tools`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct AgentTool {
+	pub name: String,
+	pub description: Option<String>,
+	/// JSON schema of the tool arguments.
+	pub parameters: Value,
+	pub target: AgentToolTarget,
+}
+
+/// What executes the tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentToolTarget {
+	/// An internal rpc method (e.g., `list_convs`), with the tool arguments as params.
+	Rpc { method: String },
+	/// A Rust handler registered in the worker.
+	Handler { name: String },
+}
+
+/// The `agent.tools` column, a json array of `AgentTool`.
+///
+/// Note: Validated on write (see `AgentTools::validate`), so the worker
+///       only reads well formed tools.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct AgentTools(pub Vec<AgentTool>);
+
+impl AgentTools {
+	/// Each tool must have a unique non empty name, object `parameters`,
+	/// and a non empty target method/handler name.
+	pub fn validate(&self) -> Result<()> {
+		let mut names = HashSet::new();
+		for tool in self.0.iter() {
+			let invalid = |cause: &str| Error::AgentToolInvalid {
+				name: tool.name.clone(),
+				cause: cause.to_string(),
+			};
+
+			if tool.name.trim().is_empty() {
+				return Err(invalid("name empty"));
+			}
+			if !names.insert(tool.name.as_str()) {
+				return Err(invalid("name duplicate"));
+			}
+			if !tool.parameters.is_object() {
+				return Err(invalid("parameters must be a json schema object"));
+			}
+			let target_name = match &tool.target {
+				AgentToolTarget::Rpc { method } => method,
+				AgentToolTarget::Handler { name } => name,
+			};
+			if target_name.trim().is_empty() {
+				return Err(invalid("target name empty"));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Note: Manual implementation.
+///       Stored (and decoded) as the jsonb of the `Vec<AgentTool>`.
+impl sqlx::Type<Postgres> for AgentTools {
+	fn type_info() -> PgTypeInfo {
+		<Json<Vec<AgentTool>> as sqlx::Type<Postgres>>::type_info()
+	}
+
+	fn compatible(ty: &PgTypeInfo) -> bool {
+		<Json<Vec<AgentTool>> as sqlx::Type<Postgres>>::compatible(ty)
+	}
+}
+
+impl<'r> sqlx::Decode<'r, Postgres> for AgentTools {
+	fn decode(value: PgValueRef<'r>) -> core::result::Result<Self, sqlx::error::BoxDynError> {
+		let Json(tools) = <Json<Vec<AgentTool>> as sqlx::Decode<Postgres>>::decode(value)?;
+		Ok(Self(tools))
+	}
+}
+
+/// Note: Manual implementation.
+///       Required for a modql::field::Fields
+impl From<AgentTools> for sea_query::Value {
+	fn from(val: AgentTools) -> Self {
+		// Note: A `Vec<AgentTool>` always serializes (string keys only).
+		let json = serde_json::to_value(val.0).unwrap_or_default();
+		sea_query::Value::Json(Some(Box::new(json)))
+	}
+}
+
+/// Note: Manual implementation.
+///       This is required for sea::query in case of None.
+impl Nullable for AgentTools {
+	fn null() -> sea_query::Value {
+		sea_query::Value::Json(None)
+	}
+}
+
+// endregion: --- Agent Tool
 
 // region:    --- Agent Types
 
 #[serde_as]
-#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct Agent {
 	pub id: i64,
 
@@ -27,37 +140,69 @@ pub struct Agent {
 	pub name: String,
 	pub ai_provider: String,
 	pub ai_model: String,
+	pub tools: Option<AgentTools>,
+
+	// -- Conv Settings
+	/// Generate the conv title after the first exchange.
+	pub auto_title: bool,
+	/// When set, the older messages of the conv history are replaced by
+	/// a rolling summary once the history exceeds this (estimated) token budget.
+	pub summary_token_budget: Option<i32>,
 
 	// -- Timestamps
 	//    (creator and last modified user_id/time)
 	pub cid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
 	pub ctime: OffsetDateTime,
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
 	pub mtime: OffsetDateTime,
 }
 
-#[derive(Fields, Deserialize)]
+impl Agent {
+	/// The agent tools (empty if the agent has no tools).
+	pub fn tools(&self) -> &[AgentTool] {
+		self.tools.as_ref().map(|tools| tools.0.as_slice()).unwrap_or_default()
+	}
+}
+
+#[derive(Fields, Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct AgentForCreate {
 	pub name: String,
+	pub tools: Option<AgentTools>,
+	pub auto_title: Option<bool>,
+	pub summary_token_budget: Option<i32>,
 }
 
-#[derive(Fields, Deserialize)]
+#[derive(Fields, Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct AgentForUpdate {
 	pub name: Option<String>,
+	pub tools: Option<AgentTools>,
+	pub auto_title: Option<bool>,
+	pub summary_token_budget: Option<i32>,
 }
 
 #[derive(FilterNodes, Default, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct AgentFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
 	pub name: Option<OpValsString>,
 
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub cid: Option<OpValsInt64>,
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
 	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub mid: Option<OpValsInt64>,
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
 	pub mtime: Option<OpValsValue>,
 }
 
@@ -76,14 +221,50 @@ impl DbBmc for AgentBmc {
 }
 
 // This will generate the `impl AgentBmc {...}` with the default CRUD functions.
+// Note: The create/update are below, to validate the `tools` first.
 generate_common_bmc_fns!(
 	Bmc: AgentBmc,
 	Entity: Agent,
-	ForCreate: AgentForCreate,
-	ForUpdate: AgentForUpdate,
 	Filter: AgentFilter,
 );
 
+impl AgentBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		agent_c: AgentForCreate,
+	) -> Result<i64> {
+		validate_tools(agent_c.tools.as_ref())?;
+		base::create::<Self, _>(ctx, mm, agent_c).await
+	}
+
+	pub async fn create_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		agent_cs: Vec<AgentForCreate>,
+	) -> Result<Vec<i64>> {
+		for agent_c in agent_cs.iter() {
+			validate_tools(agent_c.tools.as_ref())?;
+		}
+		base::create_many::<Self, _>(ctx, mm, agent_cs).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		agent_u: AgentForUpdate,
+	) -> Result<()> {
+		validate_tools(agent_u.tools.as_ref())?;
+		base::update::<Self, _>(ctx, mm, id, agent_u).await
+	}
+}
+
+fn validate_tools(tools: Option<&AgentTools>) -> Result<()> {
+	tools.map(AgentTools::validate).transpose()?;
+	Ok(())
+}
+
 // endregion: --- AgentBmc
 
 // region:    --- Tests
@@ -110,6 +291,9 @@ mod tests {
 		// -- Exec
 		let fx_agent_c = AgentForCreate {
 			name: fx_name.to_string(),
+			tools: None,
+			auto_title: None,
+			summary_token_budget: None,
 		};
 		let agent_id = AgentBmc::create(&ctx, &mm, fx_agent_c).await?;
 
@@ -135,9 +319,15 @@ mod tests {
 		// -- Exec
 		let fx_agent_c = AgentForCreate {
 			name: fx_name.to_string(),
+			tools: None,
+			auto_title: None,
+			summary_token_budget: None,
 		};
 		let fx_agent_c2 = AgentForCreate {
 			name: fx_name.to_string(),
+			tools: None,
+			auto_title: None,
+			summary_token_budget: None,
 		};
 
 		let agent_ids =
@@ -171,6 +361,9 @@ mod tests {
 		// -- Exec
 		let fx_agent_u = AgentForUpdate {
 			name: Some(fx_name_updated.to_string()),
+			tools: None,
+			auto_title: None,
+			summary_token_budget: None,
 		};
 		AgentBmc::update(&ctx, &mm, fx_agent_id, fx_agent_u).await?;
 
@@ -221,9 +414,15 @@ mod tests {
 		// -- Exec
 		let fx_agent_c = AgentForCreate {
 			name: fx_name.to_string(),
+			tools: None,
+			auto_title: None,
+			summary_token_budget: None,
 		};
 		let fx_agent_c2 = AgentForCreate {
 			name: fx_name.to_string(),
+			tools: None,
+			auto_title: None,
+			summary_token_budget: None,
 		};
 
 		let agent_ids =
@@ -342,6 +541,166 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_with_tools_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_name = "test_create_with_tools_ok agent 01";
+		let fx_tools: AgentTools = serde_json::from_value(json!([
+			{
+				"name": "list_convs",
+				"description": "List the user convs",
+				"parameters": {"type": "object"},
+				"target": {"type": "rpc", "method": "list_convs"}
+			},
+			{
+				"name": "now",
+				"parameters": {},
+				"target": {"type": "handler", "name": "now"}
+			}
+		]))?;
+
+		// -- Exec
+		let agent_id = AgentBmc::create(
+			&ctx,
+			&mm,
+			AgentForCreate {
+				name: fx_name.to_string(),
+				tools: Some(fx_tools),
+				auto_title: None,
+				summary_token_budget: None,
+			},
+		)
+		.await?;
+
+		// -- Check
+		let agent = AgentBmc::get(&ctx, &mm, agent_id).await?;
+		let names: Vec<&str> = agent.tools().iter().map(|t| t.name.as_str()).collect();
+		assert_eq!(names, &["list_convs", "now"]);
+		assert!(matches!(
+			&agent.tools()[1].target,
+			AgentToolTarget::Handler { name } if name == "now"
+		));
+
+		// -- Clean
+		clean_agents(&ctx, &mm, "test_create_with_tools_ok").await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_update_tools_invalid_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_name = "test_create_update_tools_invalid_err agent 01";
+		let fx_agent_id = seed_agent(&ctx, &mm, fx_name).await?;
+		let fx_tools: AgentTools = serde_json::from_value(json!([
+			{
+				"name": "now",
+				"parameters": "not a schema",
+				"target": {"type": "handler", "name": "now"}
+			}
+		]))?;
+
+		// -- Exec
+		let create_res = AgentBmc::create(
+			&ctx,
+			&mm,
+			AgentForCreate {
+				name: fx_name.to_string(),
+				tools: Some(fx_tools.clone()),
+				auto_title: None,
+				summary_token_budget: None,
+			},
+		)
+		.await;
+		let update_res = AgentBmc::update(
+			&ctx,
+			&mm,
+			fx_agent_id,
+			AgentForUpdate {
+				name: None,
+				tools: Some(fx_tools),
+				auto_title: None,
+				summary_token_budget: None,
+			},
+		)
+		.await;
+
+		// -- Check
+		for res in [create_res.map(|_| ()), update_res] {
+			assert!(
+				matches!(&res, Err(model::Error::AgentToolInvalid { name, .. }) if name == "now"),
+				"should be AgentToolInvalid, but was {res:?}"
+			);
+		}
+		let count = clean_agents(&ctx, &mm, "test_create_update_tools_invalid_err").await?;
+		assert_eq!(count, 1, "Should have created no agent");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_agent_tools_validate() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_tool = |name: &str, parameters: Value, target: Value| -> Result<AgentTool> {
+			Ok(serde_json::from_value(json!({
+				"name": name,
+				"parameters": parameters,
+				"target": target,
+			}))?)
+		};
+		let fx_rpc = json!({"type": "rpc", "method": "list_convs"});
+		let fx_cases = [
+			(vec![fx_tool("a", json!({}), fx_rpc.clone())?], None),
+			(
+				vec![
+					fx_tool("a", json!({}), fx_rpc.clone())?,
+					fx_tool("b", json!({}), fx_rpc.clone())?,
+				],
+				None,
+			),
+			(vec![fx_tool(" ", json!({}), fx_rpc.clone())?], Some("name empty")),
+			(
+				vec![
+					fx_tool("a", json!({}), fx_rpc.clone())?,
+					fx_tool("a", json!({}), fx_rpc.clone())?,
+				],
+				Some("name duplicate"),
+			),
+			(
+				vec![fx_tool("a", json!([]), fx_rpc.clone())?],
+				Some("parameters must be a json schema object"),
+			),
+			(
+				vec![fx_tool("a", json!({}), json!({"type": "handler", "name": ""}))?],
+				Some("target name empty"),
+			),
+		];
+
+		for (fx_tools, fx_cause) in fx_cases {
+			// -- Exec
+			let res = AgentTools(fx_tools).validate();
+
+			// -- Check
+			match (res, fx_cause) {
+				(Ok(()), None) => (),
+				(Err(model::Error::AgentToolInvalid { cause, .. }), Some(fx_cause)) => {
+					assert_eq!(cause, fx_cause)
+				}
+				(res, fx_cause) => {
+					return Err(format!("expected {fx_cause:?}, but was {res:?}").into())
+				}
+			}
+		}
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
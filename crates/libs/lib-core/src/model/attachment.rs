@@ -0,0 +1,262 @@
+use crate::ctx::Ctx;
+use crate::model::base::{self, DbBmc};
+use crate::model::conv::{ConvBmc, ConvScoped};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::ModelManager;
+use crate::model::Result;
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+// region:    --- Attachment Types
+
+/// Attachment metadata. The content itself is in the `ModelManager` blob store,
+/// at `blob_key`.
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct Attachment {
+	pub id: i64,
+
+	// -- FK
+	pub conv_id: i64,
+	pub conv_msg_id: i64,
+
+	// -- Properties
+	pub file_name: String,
+	pub mime_type: String,
+	pub size: i64,
+	#[serde(skip)]
+	pub blob_key: String,
+
+	// -- Timestamps
+	// creator user_id and time
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+	// last modifier user_id and time
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub mtime: OffsetDateTime,
+}
+
+impl ConvScoped for Attachment {
+	fn conv_id(&self) -> i64 {
+		self.conv_id
+	}
+}
+
+/// Note: The `mime_type` is expected to be validated (sniffed) by the caller,
+///       the model layer stores it as is.
+#[derive(Deserialize)]
+pub struct AttachmentForCreate {
+	pub conv_msg_id: i64,
+	pub file_name: String,
+	pub mime_type: String,
+}
+
+#[derive(Fields)]
+struct AttachmentForInsert {
+	conv_id: i64,
+	conv_msg_id: i64,
+	file_name: String,
+	mime_type: String,
+	size: i64,
+	blob_key: String,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct AttachmentFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub conv_id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub conv_msg_id: Option<OpValsInt64>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub file_name: Option<OpValsString>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub mime_type: Option<OpValsString>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub mtime: Option<OpValsValue>,
+}
+
+// endregion: --- Attachment Types
+
+// region:    --- AttachmentBmc
+
+pub struct AttachmentBmc;
+
+impl DbBmc for AttachmentBmc {
+	const TABLE: &'static str = "attachment";
+}
+
+// Note: Not using the `generate_common_bmc_fns!` since create and delete
+//       must also manage the blob in the `ModelManager` blob store.
+impl AttachmentBmc {
+	/// Store the content in the blob store and create the attachment for the conv msg.
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		att_c: AttachmentForCreate,
+		content: Vec<u8>,
+	) -> Result<i64> {
+		let msg = ConvBmc::get_msg(ctx, mm, att_c.conv_msg_id).await?;
+
+		let blob_key = format!("conv/{}/{}", msg.conv_id, Uuid::new_v4());
+		let att_i = AttachmentForInsert {
+			conv_id: msg.conv_id,
+			conv_msg_id: msg.id,
+			file_name: att_c.file_name,
+			mime_type: att_c.mime_type,
+			size: content.len() as i64,
+			blob_key: blob_key.clone(),
+		};
+
+		// -- Blob first, so that an attachment never points to a missing blob.
+		mm.blob_store().put(&blob_key, content).await?;
+
+		match base::create::<Self, _>(ctx, mm, att_i).await {
+			Ok(id) => Ok(id),
+			Err(ex) => {
+				// Best effort, the insert error is the one to report.
+				let _ = mm.blob_store().delete(&blob_key).await;
+				Err(ex)
+			}
+		}
+	}
+
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Attachment> {
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	/// Returns the attachment with its content.
+	pub async fn get_with_content(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<(Attachment, Vec<u8>)> {
+		let attachment = Self::get(ctx, mm, id).await?;
+		let content = mm.blob_store().get(&attachment.blob_key).await?;
+
+		Ok((attachment, content))
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<AttachmentFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Attachment>> {
+		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// Delete the attachment and its blob.
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		let attachment = Self::get(ctx, mm, id).await?;
+		base::delete::<Self>(ctx, mm, id).await?;
+		mm.blob_store().delete(&attachment.blob_key).await?;
+
+		Ok(())
+	}
+}
+
+// Note: When a conv or conv msg is deleted, the attachment rows are deleted by cascade,
+//       but not their blobs. A blob purge job will be needed eventually.
+
+// endregion: --- AttachmentBmc
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils::{self, seed_agent, seed_conv};
+	use crate::model::agent::AgentBmc;
+	use crate::model::blob::MemBlobStore;
+	use crate::model::conv_msg::ConvMsgForCreate;
+	use crate::model;
+	use serial_test::serial;
+	use std::sync::Arc;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_get_delete_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test()
+			.await
+			.with_blob_store(Arc::new(MemBlobStore::default()));
+		let ctx = Ctx::root_ctx();
+		let fx_content = b"attachment content 01".to_vec();
+		let agent_id =
+			seed_agent(&ctx, &mm, "test_create_get_delete_ok agent 01").await?;
+		let conv_id =
+			seed_conv(&ctx, &mm, agent_id, "test_create_get_delete_ok conv 01")
+				.await?;
+		let msg_id = ConvBmc::add_msg(
+			&ctx,
+			&mm,
+			ConvMsgForCreate {
+				conv_id,
+				content: "msg with attachment".to_string(),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let att_id = AttachmentBmc::create(
+			&ctx,
+			&mm,
+			AttachmentForCreate {
+				conv_msg_id: msg_id,
+				file_name: "notes.txt".to_string(),
+				mime_type: "text/plain".to_string(),
+			},
+			fx_content.clone(),
+		)
+		.await?;
+
+		// -- Check
+		let (attachment, content) =
+			AttachmentBmc::get_with_content(&ctx, &mm, att_id).await?;
+		assert_eq!(attachment.conv_id, conv_id);
+		assert_eq!(attachment.size, fx_content.len() as i64);
+		assert_eq!(content, fx_content);
+
+		AttachmentBmc::delete(&ctx, &mm, att_id).await?;
+		let res = mm.blob_store().get(&attachment.blob_key).await;
+		assert!(
+			matches!(res, Err(model::blob::Error::BlobNotFound { .. })),
+			"blob should have been deleted"
+		);
+
+		// -- Clean
+		AgentBmc::delete(&ctx, &mm, agent_id).await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
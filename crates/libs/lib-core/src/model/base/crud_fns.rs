@@ -5,7 +5,7 @@ use crate::model::base::{
 };
 use crate::model::ModelManager;
 use crate::model::{Error, Result};
-use modql::field::HasSeaFields;
+use modql::field::{HasSeaFields, SeaFields};
 use modql::filter::{FilterGroups, ListOptions};
 use sea_query::{Condition, Expr, PostgresQueryBuilder, Query};
 use sea_query_binder::SqlxBinder;
@@ -230,9 +230,34 @@ pub async fn update<MC, E>(
 where
 	MC: DbBmc,
 	E: HasSeaFields,
+{
+	update_fields::<MC>(ctx, mm, id, data.not_none_sea_fields()).await
+}
+
+/// Same as `update`, but the `None` fields are set to NULL.
+pub async fn update_all_fields<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+	data: E,
+) -> Result<()>
+where
+	MC: DbBmc,
+	E: HasSeaFields,
+{
+	update_fields::<MC>(ctx, mm, id, data.all_sea_fields()).await
+}
+
+async fn update_fields<MC>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+	mut fields: SeaFields,
+) -> Result<()>
+where
+	MC: DbBmc,
 {
 	// -- Prep Fields
-	let mut fields = data.not_none_sea_fields();
 	prep_fields_for_update::<MC>(&mut fields, ctx.user_id());
 
 	// -- Build query
@@ -0,0 +1,387 @@
+use crate::ctx::Ctx;
+use crate::model::base::DbBmc;
+use crate::model::document::DocumentBmc;
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::ModelManager;
+use crate::model::Result;
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{FilterNodes, OpValsInt64, OpValsString, OpValsValue};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+
+/// The candidate embeddings scored per query page (see `ChunkBmc::search`).
+const SEARCH_PAGE_SIZE: i64 = 1_000;
+
+// region:    --- Chunk Types
+
+/// A piece of a `Document` content, with its embedding.
+///
+/// Note: The embedding is stored as little endian f32 bytes in a `bytea` column,
+///       and searched by brute force (see `ChunkBmc::search`), so no pgvector needed.
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct Chunk {
+	pub id: i64,
+
+	// -- FK
+	pub document_id: i64,
+
+	// -- Properties
+	/// Position of the chunk in the document.
+	pub idx: i32,
+	pub content: String,
+	/// The embedder model (only chunks of the same model are comparable).
+	pub embed_model: String,
+	#[serde(skip)]
+	pub embedding: Vec<u8>,
+
+	// -- Timestamps
+	// creator user_id and time
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+	// last modifier user_id and time
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub mtime: OffsetDateTime,
+}
+
+impl Chunk {
+	pub fn embedding(&self) -> Vec<f32> {
+		embedding_from_bytes(&self.embedding)
+	}
+}
+
+pub struct ChunkForCreate {
+	pub content: String,
+	pub embed_model: String,
+	pub embedding: Vec<f32>,
+}
+
+#[derive(Fields)]
+pub(in crate::model) struct ChunkForInsert {
+	document_id: i64,
+	idx: i32,
+	content: String,
+	embed_model: String,
+	embedding: Vec<u8>,
+}
+
+impl ChunkForInsert {
+	pub(in crate::model) fn new(
+		document_id: i64,
+		idx: i32,
+		chunk_c: ChunkForCreate,
+	) -> Self {
+		Self {
+			document_id,
+			idx,
+			content: chunk_c.content,
+			embed_model: chunk_c.embed_model,
+			embedding: embedding_to_bytes(&chunk_c.embedding),
+		}
+	}
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct ChunkFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub document_id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub embed_model: Option<OpValsString>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub mtime: Option<OpValsValue>,
+}
+
+/// A `ChunkBmc::search` result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct ChunkMatch {
+	pub chunk_id: i64,
+	pub document_id: i64,
+	pub document_title: String,
+	pub idx: i32,
+	pub content: String,
+	/// Cosine similarity with the query embedding.
+	pub score: f32,
+}
+
+// endregion: --- Chunk Types
+
+// region:    --- ChunkBmc
+
+pub struct ChunkBmc;
+
+impl DbBmc for ChunkBmc {
+	const TABLE: &'static str = "chunk";
+}
+
+impl ChunkBmc {
+	/// Returns the `top_k` chunks most similar (cosine) to the `query` embedding,
+	/// among the chunks of `embed_model` of the ctx user documents,
+	/// optionally restricted to `document_ids`.
+	///
+	/// Note: Brute force for now, but only the embeddings are scored (by pages of
+	///       `SEARCH_PAGE_SIZE`), keeping the top k, and the content is loaded for them only.
+	///       An index (e.g., HNSW) can come later behind the same function.
+	pub async fn search(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		embed_model: &str,
+		query: &[f32],
+		top_k: usize,
+		document_ids: Option<Vec<i64>>,
+	) -> Result<Vec<ChunkMatch>> {
+		if top_k == 0 {
+			return Ok(Vec::new());
+		}
+
+		// -- Score the candidates, by pages (keyset on the chunk id)
+		let sql = format!(
+			r#"
+			SELECT c.id, c.embedding
+			FROM {chunk} c JOIN {document} d ON d.id = c.document_id
+			WHERE c.embed_model = $1
+				AND d.owner_id = $2
+				AND ($3::BIGINT[] IS NULL OR c.document_id = ANY($3))
+				AND c.id > $4
+			ORDER BY c.id
+			LIMIT $5
+			"#,
+			chunk = Self::TABLE,
+			document = DocumentBmc::TABLE,
+		);
+
+		// Min-heap of the best `top_k` so far.
+		// Note: Not pre-sized, as `top_k` can come from the client.
+		let mut top: BinaryHeap<Reverse<Scored>> = BinaryHeap::new();
+		let mut last_id = 0;
+		loop {
+			let sqlx_query = sqlx::query_as::<_, (i64, Vec<u8>)>(&sql)
+				.bind(embed_model)
+				.bind(ctx.user_id())
+				.bind(&document_ids)
+				.bind(last_id)
+				.bind(SEARCH_PAGE_SIZE);
+			let rows = mm.dbx().fetch_all(sqlx_query).await?;
+			let page_len = rows.len();
+
+			for (chunk_id, embedding) in rows {
+				last_id = chunk_id;
+				let score = cosine_similarity(query, &embedding_from_bytes(&embedding));
+				top.push(Reverse(Scored { score, chunk_id }));
+				if top.len() > top_k {
+					top.pop();
+				}
+			}
+
+			if (page_len as i64) < SEARCH_PAGE_SIZE {
+				break;
+			}
+		}
+
+		// -- Load the top chunks
+		let scores: HashMap<i64, f32> = top
+			.into_iter()
+			.map(|Reverse(scored)| (scored.chunk_id, scored.score))
+			.collect();
+		let chunk_ids: Vec<i64> = scores.keys().copied().collect();
+
+		let sql = format!(
+			r#"
+			SELECT c.id, c.document_id, d.title, c.idx, c.content
+			FROM {chunk} c JOIN {document} d ON d.id = c.document_id
+			WHERE c.id = ANY($1)
+			"#,
+			chunk = Self::TABLE,
+			document = DocumentBmc::TABLE,
+		);
+		let sqlx_query = sqlx::query_as::<_, (i64, i64, String, i32, String)>(&sql)
+			.bind(chunk_ids);
+		let rows = mm.dbx().fetch_all(sqlx_query).await?;
+
+		let mut matches: Vec<ChunkMatch> = rows
+			.into_iter()
+			.map(|(chunk_id, document_id, document_title, idx, content)| ChunkMatch {
+				chunk_id,
+				document_id,
+				document_title,
+				idx,
+				content,
+				score: scores.get(&chunk_id).copied().unwrap_or_default(),
+			})
+			.collect();
+
+		matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+		Ok(matches)
+	}
+}
+
+/// A search candidate, ordered by score (then chunk id, for a total order).
+struct Scored {
+	score: f32,
+	chunk_id: i64,
+}
+
+impl PartialEq for Scored {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Scored {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.score
+			.total_cmp(&other.score)
+			.then_with(|| other.chunk_id.cmp(&self.chunk_id))
+	}
+}
+
+// endregion: --- ChunkBmc
+
+// region:    --- Embedding Support
+
+fn embedding_to_bytes(embedding: &[f32]) -> Vec<u8> {
+	embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn embedding_from_bytes(bytes: &[u8]) -> Vec<f32> {
+	bytes
+		.chunks_exact(4)
+		.map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+		.collect()
+}
+
+/// Returns 0 for vectors of different dimensions or with a zero norm.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+	if a.len() != b.len() {
+		return 0.;
+	}
+	let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+	let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+	let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+	if norm_a == 0. || norm_b == 0. {
+		0.
+	} else {
+		dot / (norm_a * norm_b)
+	}
+}
+
+// endregion: --- Embedding Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::document::DocumentForCreate;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_search_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_model = "test_search_ok";
+		let fx_chunks = [
+			("chunk about cats", vec![1., 0., 0.]),
+			("chunk about dogs", vec![0., 1., 0.]),
+			("chunk about cats and dogs", vec![0.7, 0.7, 0.]),
+		];
+		let chunks_c = fx_chunks
+			.iter()
+			.map(|(content, embedding)| ChunkForCreate {
+				content: content.to_string(),
+				embed_model: fx_model.to_string(),
+				embedding: embedding.clone(),
+			})
+			.collect();
+		let doc_id = DocumentBmc::create_with_chunks(
+			&ctx,
+			&mm,
+			DocumentForCreate {
+				title: "test_search_ok doc 01".to_string(),
+				source: None,
+			},
+			chunks_c,
+		)
+		.await?;
+
+		// Note: The best match, but of another user.
+		let fx_user_id = _dev_utils::seed_user(&ctx, &mm, "test_search_ok user 01").await?;
+		let fx_user_ctx = Ctx::new(fx_user_id)?;
+		let user_doc_id = DocumentBmc::create_with_chunks(
+			&fx_user_ctx,
+			&mm,
+			DocumentForCreate {
+				title: "test_search_ok doc 02".to_string(),
+				source: None,
+			},
+			vec![ChunkForCreate {
+				content: "chunk of another user".to_string(),
+				embed_model: fx_model.to_string(),
+				embedding: vec![1., 0.1, 0.],
+			}],
+		)
+		.await?;
+
+		// -- Exec
+		let matches =
+			ChunkBmc::search(&ctx, &mm, fx_model, &[1., 0.1, 0.], 2, None).await?;
+		let user_matches =
+			ChunkBmc::search(&fx_user_ctx, &mm, fx_model, &[1., 0.1, 0.], 2, None).await?;
+
+		// -- Check
+		let contents: Vec<&str> = matches.iter().map(|m| m.content.as_str()).collect();
+		assert_eq!(contents, &["chunk about cats", "chunk about cats and dogs"]);
+		assert!(matches[0].score > matches[1].score);
+		let contents: Vec<&str> = user_matches.iter().map(|m| m.content.as_str()).collect();
+		assert_eq!(contents, &["chunk of another user"]);
+		let chunks = DocumentBmc::list_chunks(&ctx, &mm, doc_id).await?;
+		assert_eq!(chunks.len(), 3);
+		assert_eq!(chunks[1].embedding(), vec![0., 1., 0.]);
+
+		// -- Clean
+		DocumentBmc::delete(&ctx, &mm, doc_id).await?;
+		DocumentBmc::delete(&ctx, &mm, user_doc_id).await?;
+		_dev_utils::clean_users(&ctx, &mm, "test_search_ok").await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,16 +1,31 @@
 use crate::ctx::Ctx;
 use crate::generate_common_bmc_fns;
 use crate::model::base::{self, DbBmc};
+use crate::model::conv_event::{
+	add_conv_event, CONV_ARCHIVED, CONV_CREATED, CONV_MSG_CREATED, CONV_MSG_UPDATED,
+	CONV_UPDATED,
+};
 use crate::model::conv_msg::{
-	ConvMsg, ConvMsgBmc, ConvMsgForCreate, ConvMsgForInsert,
+	ConvMsg, ConvMsgBmc, ConvMsgFilter, ConvMsgForCreate, ConvMsgForEdit,
+	ConvMsgForInsert, ConvMsgForReply, ConvMsgForUpdate,
+};
+use crate::model::conv_summary::{
+	ConvSummary, ConvSummaryBmc, ConvSummaryFilter, ConvSummaryForCreate,
+	ConvSummaryForUpdate,
+};
+use crate::model::conv_user::{
+	ConvUser, ConvUserBmc, ConvUserFilter, ConvUserForCreate,
 };
 use crate::model::modql_utils::time_to_sea_value;
+use crate::model::user::UserBmc;
 use crate::model::ModelManager;
-use crate::model::Result;
+use crate::model::{Error, Result};
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
 use lib_utils::time::Rfc3339;
 use modql::field::{Fields, SeaFieldValue};
 use modql::filter::{
-	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+	FilterNodes, ListOptions, OpValInt64, OpValsInt64, OpValsString, OpValsValue,
 };
 use sea_query::Nullable;
 use serde::{Deserialize, Serialize};
@@ -28,6 +43,7 @@ pub trait ConvScoped {
 }
 
 #[derive(Debug, Clone, sqlx::Type, derive_more::Display, Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 #[sqlx(type_name = "conv_kind")]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum ConvKind {
@@ -68,6 +84,7 @@ impl Nullable for ConvKind {
 	Deserialize,
 	Serialize,
 )]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 #[sqlx(type_name = "conv_state")]
 pub enum ConvState {
 	Active,
@@ -75,7 +92,8 @@ pub enum ConvState {
 }
 
 #[serde_as]
-#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct Conv {
 	pub id: i64,
 
@@ -92,14 +110,17 @@ pub struct Conv {
 	// creator user_id and time
 	pub cid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
 	pub ctime: OffsetDateTime,
 	// last modifier user_id and time
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
 	pub mtime: OffsetDateTime,
 }
 
-#[derive(Fields, Deserialize, Default)]
+#[derive(Fields, Deserialize, Default, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct ConvForCreate {
 	pub agent_id: i64,
 
@@ -109,7 +130,8 @@ pub struct ConvForCreate {
 	pub kind: Option<ConvKind>,
 }
 
-#[derive(Fields, Deserialize, Default)]
+#[derive(Fields, Deserialize, Default, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct ConvForUpdate {
 	pub owner_id: Option<i64>,
 	pub title: Option<String>,
@@ -119,22 +141,32 @@ pub struct ConvForUpdate {
 }
 
 #[derive(FilterNodes, Deserialize, Default, Debug)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct ConvFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub id: Option<OpValsInt64>,
 
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub owner_id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub agent_id: Option<OpValsInt64>,
 
 	#[modql(cast_as = "conv_kind")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
 	pub kind: Option<OpValsString>,
 
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
 	pub title: Option<OpValsString>,
 
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub cid: Option<OpValsInt64>,
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
 	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub mid: Option<OpValsInt64>,
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
 	pub mtime: Option<OpValsValue>,
 }
 
@@ -153,29 +185,234 @@ impl DbBmc for ConvBmc {
 }
 
 // This will generate the `impl ConvBmc {...}` with the default CRUD functions.
+// Note: `create` and `update` are custom (they add their events to the outbox).
 generate_common_bmc_fns!(
 	Bmc: ConvBmc,
 	Entity: Conv,
-	ForCreate: ConvForCreate,
-	ForUpdate: ConvForUpdate,
 	Filter: ConvFilter,
 );
 
+// The writes with their domain events (`conv_event`), added to the outbox in the write transaction.
+impl ConvBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_c: ConvForCreate,
+	) -> Result<i64> {
+		// Start the transaction
+		let mm = mm.new_with_txn()?;
+
+		mm.dbx().begin_txn().await?;
+
+		let conv_id = base::create::<Self, _>(ctx, &mm, conv_c).await?;
+		let conv = Self::get(ctx, &mm, conv_id).await?;
+		add_conv_event(ctx, &mm, CONV_CREATED, conv_id, &conv).await?;
+
+		// Commit the transaction
+		mm.dbx().commit_txn().await?;
+
+		Ok(conv_id)
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		conv_u: ConvForUpdate,
+	) -> Result<()> {
+		// Start the transaction
+		let mm = mm.new_with_txn()?;
+
+		mm.dbx().begin_txn().await?;
+
+		let archived = matches!(conv_u.state, Some(ConvState::Archived));
+		base::update::<Self, _>(ctx, &mm, id, conv_u).await?;
+		let conv = Self::get(ctx, &mm, id).await?;
+		add_conv_event(ctx, &mm, CONV_UPDATED, id, &conv).await?;
+		if archived {
+			add_conv_event(ctx, &mm, CONV_ARCHIVED, id, &conv).await?;
+		}
+
+		// Commit the transaction
+		mm.dbx().commit_txn().await?;
+
+		Ok(())
+	}
+}
+
 // Additional ConvBmc methods to manage the `ConvMsg` constructs.
 impl ConvBmc {
-	/// Add a `ConvMsg` to a `Conv`
+	/// Returns true if `user_id` is the conv owner or a conv participant (`conv_user`).
+	pub async fn is_member(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_id: i64,
+		user_id: i64,
+	) -> Result<bool> {
+		let conv = Self::get(ctx, mm, conv_id).await?;
+		if conv.owner_id == user_id {
+			return Ok(true);
+		}
+
+		let filter = ConvUserFilter {
+			conv_id: Some(conv_id.into()),
+			user_id: Some(user_id.into()),
+			..Default::default()
+		};
+		let count = base::count::<ConvUserBmc, _>(ctx, mm, Some(vec![filter])).await?;
+
+		Ok(count > 0)
+	}
+
+	/// Ensure the ctx user is a member of the conv `conv_id` (see `is_member`),
+	/// or is a `Sys` user.
+	pub async fn ensure_member(ctx: &Ctx, mm: &ModelManager, conv_id: i64) -> Result<()> {
+		if Self::is_member(ctx, mm, conv_id, ctx.user_id()).await?
+			|| UserBmc::is_sys_user(ctx, mm).await?
+		{
+			Ok(())
+		} else {
+			Err(Error::ConvMemberRequired {
+				conv_id,
+				user_id: ctx.user_id(),
+			})
+		}
+	}
+
+	/// Ensure the ctx user owns the conv `conv_id` (or is a `Sys` user).
+	///
+	/// Note: The participants (`conv_user`) have no role yet, so only the owner manages them.
+	pub async fn ensure_owner(ctx: &Ctx, mm: &ModelManager, conv_id: i64) -> Result<()> {
+		let conv = Self::get(ctx, mm, conv_id).await?;
+		if conv.owner_id == ctx.user_id() || UserBmc::is_sys_user(ctx, mm).await? {
+			Ok(())
+		} else {
+			Err(Error::ConvOwnerRequired {
+				conv_id,
+				user_id: ctx.user_id(),
+			})
+		}
+	}
+
+	/// Add a participant (`ConvUser`) to a `Conv` (the ctx user must own the conv).
+	pub async fn add_user(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_user_c: ConvUserForCreate,
+	) -> Result<i64> {
+		Self::ensure_owner(ctx, mm, conv_user_c.conv_id).await?;
+
+		let conv_user_id = base::create::<ConvUserBmc, _>(ctx, mm, conv_user_c).await?;
+
+		Ok(conv_user_id)
+	}
+
+	pub async fn get_user(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_user_id: i64,
+	) -> Result<ConvUser> {
+		base::get::<ConvUserBmc, _>(ctx, mm, conv_user_id).await
+	}
+
+	/// Returns the participants of the conv `conv_id`.
+	pub async fn list_users(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_id: i64,
+	) -> Result<Vec<ConvUser>> {
+		let filter = ConvUserFilter {
+			conv_id: Some(conv_id.into()),
+			..Default::default()
+		};
+
+		base::list::<ConvUserBmc, _, _>(ctx, mm, Some(vec![filter]), None).await
+	}
+
+	/// Returns the auto-respond participants (machine users) of the conv `conv_id`.
+	pub async fn list_auto_responders(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_id: i64,
+	) -> Result<Vec<ConvUser>> {
+		let filter = ConvUserFilter {
+			conv_id: Some(conv_id.into()),
+			auto_respond: Some(true.into()),
+			..Default::default()
+		};
+
+		base::list::<ConvUserBmc, _, _>(ctx, mm, Some(vec![filter]), None).await
+	}
+
+	/// Add a `ConvMsg` to a `Conv`, with its `conv_msg.created` event (in the outbox).
 	///
 	// For access constrol, we will add:
 	// #[ctx_add(conv, space)]
 	// #[requires_privilege_any_of("og:FullAccess", "sp:FullAccess", "conv@owner_id" "conv:AddMsg")]
+	///
+	/// Note: The message is always a `User` message, without metadata
+	///       (see `add_reply_msg` for the llm-worker replies).
 	pub async fn add_msg(
 		ctx: &Ctx,
 		mm: &ModelManager,
 		msg_c: ConvMsgForCreate,
 	) -> Result<i64> {
-		let msg_i = ConvMsgForInsert::from_msg_for_create(ctx.user_id(), msg_c);
+		let msg_i = ConvMsgForInsert::from_msg_for_create(ctx.user_id(), msg_c)?;
+		Self::insert_msg(ctx, mm, msg_i).await
+	}
+
+	/// Add the reply of the llm-worker (e.g., `Assistant` or `Tool` message), with its
+	/// `conv_msg.created` event (in the outbox).
+	///
+	/// Note: Not exposed to the RPC clients (see `ConvMsgForReply`).
+	pub async fn add_reply_msg(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		msg_r: ConvMsgForReply,
+	) -> Result<i64> {
+		let msg_i = ConvMsgForInsert::from_msg_for_reply(ctx.user_id(), msg_r)?;
+		Self::insert_msg(ctx, mm, msg_i).await
+	}
+
+	async fn insert_msg(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		mut msg_i: ConvMsgForInsert,
+	) -> Result<i64> {
+		let conv_id = msg_i.conv_id;
+
+		// Start the transaction
+		let mm = &mm.new_with_txn()?;
+
+		mm.dbx().begin_txn().await?;
+
+		// -- Resolve the parent
+		//    (explicit parent must be in the same conv, otherwise append to the latest msg)
+		match msg_i.parent_msg_id {
+			Some(parent_msg_id) => {
+				let parent = Self::get_msg(ctx, mm, parent_msg_id).await?;
+				if parent.conv_id != conv_id {
+					return Err(Error::ConvMsgNotInConv {
+						conv_id,
+						msg_id: parent_msg_id,
+					});
+				}
+			}
+			None => {
+				msg_i.parent_msg_id =
+					Self::last_msg(ctx, mm, conv_id).await?.map(|msg| msg.id);
+			}
+		}
+
 		let conv_msg_id = base::create::<ConvMsgBmc, _>(ctx, mm, msg_i).await?;
 
+		// -- Add the domain event
+		let msg = Self::get_msg(ctx, mm, conv_msg_id).await?;
+		add_conv_event(ctx, mm, CONV_MSG_CREATED, conv_id, &msg).await?;
+
+		// Commit the transaction
+		mm.dbx().commit_txn().await?;
+
 		Ok(conv_msg_id)
 	}
 
@@ -194,6 +431,304 @@ impl ConvBmc {
 
 		Ok(conv_msg)
 	}
+
+	/// List `ConvMsg`s of convs (e.g., filtered by `conv_id` and `role`).
+	///
+	/// Note: Each filter must have a `conv_id` (single `$eq`), and the ctx user
+	///       must be a member of these convs (see `ensure_member`).
+	pub async fn list_msgs(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<ConvMsgFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<ConvMsg>> {
+		let filter = filter.unwrap_or_default();
+		if filter.is_empty() {
+			return Err(Error::ConvMsgFilterConvRequired);
+		}
+		for node in filter.iter() {
+			let conv_id = match node.conv_id.as_ref().map(|ovs| ovs.0.as_slice()) {
+				Some([OpValInt64::Eq(conv_id)]) => *conv_id,
+				_ => return Err(Error::ConvMsgFilterConvRequired),
+			};
+			Self::ensure_member(ctx, mm, conv_id).await?;
+		}
+
+		base::list::<ConvMsgBmc, _, _>(ctx, mm, Some(filter), list_options).await
+	}
+
+	/// Update a `ConvMsg` in place (no new branch), with its `conv_msg.updated` event.
+	///
+	/// Note: `msg_u.conv_id` must match the conv of the message,
+	///       a message cannot be moved to another conv.
+	pub async fn update_msg(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		msg_id: i64,
+		msg_u: ConvMsgForUpdate,
+	) -> Result<()> {
+		let conv_id = msg_u.conv_id;
+
+		// Start the transaction
+		let mm = &mm.new_with_txn()?;
+
+		mm.dbx().begin_txn().await?;
+
+		let msg = Self::get_msg(ctx, mm, msg_id).await?;
+		if msg.conv_id != conv_id {
+			return Err(Error::ConvMsgNotInConv { conv_id, msg_id });
+		}
+
+		base::update::<ConvMsgBmc, _>(ctx, mm, msg_id, msg_u).await?;
+
+		// -- Add the domain event
+		let msg = Self::get_msg(ctx, mm, msg_id).await?;
+		add_conv_event(ctx, mm, CONV_MSG_UPDATED, conv_id, &msg).await?;
+
+		// Commit the transaction
+		mm.dbx().commit_txn().await?;
+
+		Ok(())
+	}
+
+	/// Edit a `ConvMsg` by adding a sibling message with the new content.
+	/// The original message (and its replies) is kept, so this creates a new branch.
+	///
+	/// Returns the id of the new message (the leaf of the new branch),
+	/// which has its `conv_msg.created` event (as `add_msg`).
+	pub async fn edit_msg(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		msg_id: i64,
+		msg_e: ConvMsgForEdit,
+	) -> Result<i64> {
+		let msg = Self::get_msg(ctx, mm, msg_id).await?;
+		let conv_id = msg.conv_id;
+
+		let msg_i = ConvMsgForInsert {
+			conv_id,
+			user_id: ctx.user_id(),
+			parent_msg_id: msg.parent_msg_id,
+			role: Some(msg.role),
+			content: msg_e.content,
+			content_parts: None,
+			metadata: None,
+		};
+
+		// Start the transaction
+		let mm = &mm.new_with_txn()?;
+
+		mm.dbx().begin_txn().await?;
+
+		let conv_msg_id = base::create::<ConvMsgBmc, _>(ctx, mm, msg_i).await?;
+
+		// -- Add the domain event
+		let msg = Self::get_msg(ctx, mm, conv_msg_id).await?;
+		add_conv_event(ctx, mm, CONV_MSG_CREATED, conv_id, &msg).await?;
+
+		// Commit the transaction
+		mm.dbx().commit_txn().await?;
+
+		Ok(conv_msg_id)
+	}
+
+	/// Returns all of the messages of a conv, across all branches, in creation order.
+	///
+	/// Note: Since a parent is always created before its replies, parents always come
+	///       before their children, and the tree can be rebuilt from `parent_msg_id`.
+	pub async fn list_msg_tree(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_id: i64,
+	) -> Result<Vec<ConvMsg>> {
+		let filter = ConvMsgFilter {
+			conv_id: Some(conv_id.into()),
+			..Default::default()
+		};
+		let list_options = ListOptions {
+			order_bys: Some("id".into()),
+			..Default::default()
+		};
+
+		base::list::<ConvMsgBmc, _, _>(ctx, mm, Some(vec![filter]), Some(list_options))
+			.await
+	}
+
+	/// Returns the linear history of a branch, from the conv root message down to `leaf_msg_id`.
+	pub async fn get_conv_thread(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+		leaf_msg_id: i64,
+	) -> Result<Vec<ConvMsg>> {
+		// Note: Recursive CTE is not worth the sea-query ceremony here.
+		//       `depth` is ignored by the `ConvMsg` FromRow.
+		let sql = format!(
+			r#"
+			WITH RECURSIVE thread AS (
+				SELECT m.*, 0 AS depth FROM {table} m WHERE m.id = $1
+				UNION ALL
+				SELECT m.*, t.depth + 1 FROM {table} m
+					JOIN thread t ON m.id = t.parent_msg_id
+			)
+			SELECT * FROM thread ORDER BY depth DESC
+			"#,
+			table = ConvMsgBmc::TABLE
+		);
+
+		let sqlx_query = sqlx::query_as::<_, ConvMsg>(&sql).bind(leaf_msg_id);
+		let msgs = mm.dbx().fetch_all(sqlx_query).await?;
+
+		if msgs.is_empty() {
+			return Err(Error::EntityNotFound {
+				entity: ConvMsgBmc::TABLE,
+				id: leaf_msg_id,
+			});
+		}
+
+		Ok(msgs)
+	}
+
+	/// Fork a conv at `msg_id` into a new conv, which receives a copy of the thread
+	/// from the conv root message down to `msg_id`.
+	///
+	/// Returns the new conv id.
+	///
+	/// Note: The copied messages have their `conv_msg.created` events (in the fork conv).
+	pub async fn fork_conv(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_id: i64,
+		msg_id: i64,
+	) -> Result<i64> {
+		let conv = Self::get(ctx, mm, conv_id).await?;
+		let thread = Self::get_conv_thread(ctx, mm, msg_id).await?;
+		if thread.iter().any(|msg| msg.conv_id != conv_id) {
+			return Err(Error::ConvMsgNotInConv { conv_id, msg_id });
+		}
+
+		// Start the transaction
+		let mm = mm.new_with_txn()?;
+
+		mm.dbx().begin_txn().await?;
+
+		let fork_conv_id = Self::create(
+			ctx,
+			&mm,
+			ConvForCreate {
+				agent_id: conv.agent_id,
+				title: conv.title,
+				kind: Some(conv.kind),
+			},
+		)
+		.await?;
+
+		// Copy the thread, re-chaining the parents to the copied messages.
+		let mut parent_msg_id = None;
+		for msg in thread {
+			let msg_i = ConvMsgForInsert {
+				conv_id: fork_conv_id,
+				user_id: msg.user_id,
+				parent_msg_id,
+				role: Some(msg.role),
+				content: msg.content,
+				content_parts: msg.content_parts,
+				metadata: msg.metadata,
+			};
+			let id = base::create::<ConvMsgBmc, _>(ctx, &mm, msg_i).await?;
+			let msg = Self::get_msg(ctx, &mm, id).await?;
+			add_conv_event(ctx, &mm, CONV_MSG_CREATED, fork_conv_id, &msg).await?;
+			parent_msg_id = Some(id);
+		}
+
+		// Commit the transaction
+		mm.dbx().commit_txn().await?;
+
+		Ok(fork_conv_id)
+	}
+
+	/// Set the rolling summary of the branch going through `msg_id`, which covers
+	/// the messages of the branch up to `msg_id` (included).
+	///
+	/// Note: Set by the workers only (see `Agent::summary_token_budget`).
+	pub async fn update_summary(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_id: i64,
+		summary: String,
+		msg_id: i64,
+	) -> Result<()> {
+		let msg = Self::get_msg(ctx, mm, msg_id).await?;
+		if msg.conv_id != conv_id {
+			return Err(Error::ConvMsgNotInConv { conv_id, msg_id });
+		}
+
+		let filter = ConvSummaryFilter {
+			msg_id: Some(msg_id.into()),
+			..Default::default()
+		};
+		let conv_summary: Option<ConvSummary> =
+			base::first::<ConvSummaryBmc, _, _>(ctx, mm, Some(vec![filter]), None).await?;
+		match conv_summary {
+			Some(conv_summary) => {
+				let conv_summary_u = ConvSummaryForUpdate { summary };
+				base::update::<ConvSummaryBmc, _>(ctx, mm, conv_summary.id, conv_summary_u)
+					.await?;
+			}
+			None => {
+				let conv_summary_c = ConvSummaryForCreate {
+					conv_id,
+					msg_id,
+					summary,
+				};
+				base::create::<ConvSummaryBmc, _>(ctx, mm, conv_summary_c).await?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Returns the rolling summary of the `thread` branch (see `get_conv_thread`),
+	/// which is the summary of its latest summarized message, if any.
+	pub async fn get_thread_summary(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_id: i64,
+		thread: &[ConvMsg],
+	) -> Result<Option<ConvSummary>> {
+		let msg_ids: Vec<i64> = thread.iter().map(|msg| msg.id).collect();
+		let filter = ConvSummaryFilter {
+			conv_id: Some(conv_id.into()),
+			msg_id: Some(OpValInt64::In(msg_ids).into()),
+			..Default::default()
+		};
+		let conv_summaries: Vec<ConvSummary> =
+			base::list::<ConvSummaryBmc, _, _>(ctx, mm, Some(vec![filter]), None).await?;
+
+		let conv_summary = conv_summaries.into_iter().max_by_key(|conv_summary| {
+			thread.iter().position(|msg| msg.id == conv_summary.msg_id)
+		});
+
+		Ok(conv_summary)
+	}
+
+	/// Returns the latest message of the conv (across all branches), if any.
+	pub async fn last_msg(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		conv_id: i64,
+	) -> Result<Option<ConvMsg>> {
+		let filter = ConvMsgFilter {
+			conv_id: Some(conv_id.into()),
+			..Default::default()
+		};
+		let list_options = ListOptions {
+			order_bys: Some("!id".into()),
+			..Default::default()
+		};
+
+		base::first::<ConvMsgBmc, _, _>(ctx, mm, Some(vec![filter]), Some(list_options))
+			.await
+	}
 }
 
 // endregion: --- ConvBmc
@@ -206,9 +741,10 @@ mod tests {
 	type Result<T> = core::result::Result<T, Error>; // For tests.
 
 	use super::*;
-	use crate::_dev_utils::{self, seed_agent};
+	use crate::_dev_utils::{self, clean_users, seed_agent, seed_conv, seed_users};
 	use crate::ctx::Ctx;
 	use crate::model::agent::AgentBmc;
+	use crate::model::conv_msg::{ContentPart, ConvMsgMeta, MsgRole};
 	use modql::filter::OpValString;
 	use serial_test::serial;
 
@@ -304,6 +840,391 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_msg_branch_and_fork_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let agent_id =
+			seed_agent(&ctx, &mm, "test_msg_branch_and_fork_ok agent 01").await?;
+		let conv_id = seed_conv(
+			&ctx,
+			&mm,
+			agent_id,
+			"test_msg_branch_and_fork_ok conv 01",
+		)
+		.await?;
+		let mut msg_ids = Vec::new();
+		for content in ["msg 01", "msg 02", "msg 03"] {
+			let msg_c = ConvMsgForCreate {
+				conv_id,
+				content: content.to_string(),
+				..Default::default()
+			};
+			msg_ids.push(ConvBmc::add_msg(&ctx, &mm, msg_c).await?);
+		}
+
+		// -- Exec
+		let edit_msg_id = ConvBmc::edit_msg(
+			&ctx,
+			&mm,
+			msg_ids[1],
+			ConvMsgForEdit {
+				content: "msg 02 - edited".to_string(),
+			},
+		)
+		.await?;
+		let fork_conv_id = ConvBmc::fork_conv(&ctx, &mm, conv_id, msg_ids[1]).await?;
+
+		// -- Check
+		let thread = ConvBmc::get_conv_thread(&ctx, &mm, edit_msg_id).await?;
+		let contents = thread.iter().map(|m| m.content.as_str()).collect::<Vec<_>>();
+		assert_eq!(contents, &["msg 01", "msg 02 - edited"]);
+
+		let tree = ConvBmc::list_msg_tree(&ctx, &mm, conv_id).await?;
+		assert_eq!(tree.len(), 4, "should have the 3 msgs and the edit");
+
+		let fork_tree = ConvBmc::list_msg_tree(&ctx, &mm, fork_conv_id).await?;
+		let contents = fork_tree.iter().map(|m| m.content.as_str()).collect::<Vec<_>>();
+		assert_eq!(contents, &["msg 01", "msg 02"]);
+		assert_eq!(fork_tree[1].parent_msg_id, Some(fork_tree[0].id));
+
+		// -- Clean
+		// This should delete cascade
+		AgentBmc::delete(&ctx, &mm, agent_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_summary_by_branch_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let agent_id =
+			seed_agent(&ctx, &mm, "test_update_summary_by_branch_ok agent 01").await?;
+		let conv_id = seed_conv(
+			&ctx,
+			&mm,
+			agent_id,
+			"test_update_summary_by_branch_ok conv 01",
+		)
+		.await?;
+		let mut msg_ids = Vec::new();
+		for content in ["msg 01", "msg 02", "msg 03"] {
+			let msg_c = ConvMsgForCreate {
+				conv_id,
+				content: content.to_string(),
+				..Default::default()
+			};
+			msg_ids.push(ConvBmc::add_msg(&ctx, &mm, msg_c).await?);
+		}
+		// branch: msg 01 > msg 02 - edited
+		let edit_msg_id = ConvBmc::edit_msg(
+			&ctx,
+			&mm,
+			msg_ids[1],
+			ConvMsgForEdit {
+				content: "msg 02 - edited".to_string(),
+			},
+		)
+		.await?;
+		let fx_main_thread = ConvBmc::get_conv_thread(&ctx, &mm, msg_ids[2]).await?;
+		let fx_edit_thread = ConvBmc::get_conv_thread(&ctx, &mm, edit_msg_id).await?;
+
+		// -- Exec
+		ConvBmc::update_summary(&ctx, &mm, conv_id, "summary 01".to_string(), msg_ids[0])
+			.await?;
+		ConvBmc::update_summary(&ctx, &mm, conv_id, "summary 02".to_string(), msg_ids[1])
+			.await?;
+		ConvBmc::update_summary(&ctx, &mm, conv_id, "summary edit".to_string(), edit_msg_id)
+			.await?;
+		// re-summarize the same message (updates the existing summary)
+		ConvBmc::update_summary(&ctx, &mm, conv_id, "summary 02 - v2".to_string(), msg_ids[1])
+			.await?;
+		let res = ConvBmc::update_summary(&ctx, &mm, conv_id + 1, "bad".to_string(), msg_ids[0])
+			.await;
+
+		// -- Check
+		let main_summary = ConvBmc::get_thread_summary(&ctx, &mm, conv_id, &fx_main_thread)
+			.await?
+			.ok_or("main thread should have a summary")?;
+		assert_eq!(main_summary.msg_id, msg_ids[1]);
+		assert_eq!(main_summary.summary, "summary 02 - v2");
+
+		let edit_summary = ConvBmc::get_thread_summary(&ctx, &mm, conv_id, &fx_edit_thread)
+			.await?
+			.ok_or("edit thread should have a summary")?;
+		assert_eq!(edit_summary.msg_id, edit_msg_id);
+		assert_eq!(edit_summary.summary, "summary edit");
+
+		let root_summary =
+			ConvBmc::get_thread_summary(&ctx, &mm, conv_id, &fx_main_thread[..1]).await?;
+		assert_eq!(root_summary.map(|s| s.summary).as_deref(), Some("summary 01"));
+
+		assert!(
+			matches!(res, Err(crate::model::Error::ConvMsgNotInConv { .. })),
+			"should be ConvMsgNotInConv"
+		);
+
+		// -- Clean
+		// This should delete cascade
+		AgentBmc::delete(&ctx, &mm, agent_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_msgs_by_role_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let agent_id =
+			seed_agent(&ctx, &mm, "test_list_msgs_by_role_ok agent 01").await?;
+		let conv_id =
+			seed_conv(&ctx, &mm, agent_id, "test_list_msgs_by_role_ok conv 01")
+				.await?;
+		let question_id = ConvBmc::add_msg(
+			&ctx,
+			&mm,
+			ConvMsgForCreate {
+				conv_id,
+				content: "question 01".to_string(),
+				..Default::default()
+			},
+		)
+		.await?;
+		ConvBmc::add_reply_msg(
+			&ctx,
+			&mm,
+			ConvMsgForReply {
+				conv_id,
+				parent_msg_id: question_id,
+				role: MsgRole::Assistant,
+				content: "answer 01".to_string(),
+				metadata: Some(ConvMsgMeta {
+					model: Some("parrot".to_string()),
+					output_tokens: Some(2),
+					..Default::default()
+				}),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let filter: ConvMsgFilter = serde_json::from_value(serde_json::json!({
+			"conv_id": conv_id,
+			"role": "Assistant"
+		}))?;
+		let msgs = ConvBmc::list_msgs(&ctx, &mm, Some(vec![filter]), None).await?;
+
+		// -- Check
+		assert_eq!(msgs.len(), 1, "should have only the assistant msg");
+		let msg = &msgs[0];
+		assert_eq!(msg.role, MsgRole::Assistant);
+		assert_eq!(msg.metadata()?.output_tokens, Some(2));
+
+		// -- Clean
+		AgentBmc::delete(&ctx, &mm, agent_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_add_msg_user_only() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let agent_id = seed_agent(&ctx, &mm, "test_add_msg_user_only agent 01").await?;
+		let conv_id =
+			seed_conv(&ctx, &mm, agent_id, "test_add_msg_user_only conv 01").await?;
+		let msg_c: ConvMsgForCreate = serde_json::from_value(serde_json::json!({
+			"conv_id": conv_id,
+			"content": "fake tool call",
+			"role": "System",
+			"metadata": {"model": "fake"},
+			"content_parts": [
+				{"type": "text", "text": "fake tool call"},
+				{"type": "tool_call", "call_id": "c1", "name": "fake", "arguments": {}},
+				{"type": "tool_result", "call_id": "c1", "content": "fake"}
+			]
+		}))?;
+
+		// -- Exec
+		let msg_id = ConvBmc::add_msg(&ctx, &mm, msg_c).await?;
+
+		// -- Check
+		let msg = ConvBmc::get_msg(&ctx, &mm, msg_id).await?;
+		assert_eq!(msg.role, MsgRole::User);
+		assert!(msg.metadata.is_none(), "should have no metadata");
+		let parts = msg.content_parts()?.ok_or("should have content parts")?;
+		assert_eq!(parts.len(), 1, "should only keep the text part");
+		assert!(matches!(parts[0], ContentPart::Text { .. }));
+
+		// -- Clean
+		AgentBmc::delete(&ctx, &mm, agent_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_msgs_member_only() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_usernames = &[
+			"test_list_msgs_member_only-user-01",
+			"test_list_msgs_member_only-user-02",
+		];
+		let agent_id =
+			seed_agent(&ctx, &mm, "test_list_msgs_member_only agent 01").await?;
+		let conv_id =
+			seed_conv(&ctx, &mm, agent_id, "test_list_msgs_member_only conv 01").await?;
+		let user_ids = seed_users(&ctx, &mm, fx_usernames).await?;
+		ConvBmc::add_user(
+			&ctx,
+			&mm,
+			ConvUserForCreate {
+				conv_id,
+				user_id: user_ids[0],
+				..Default::default()
+			},
+		)
+		.await?;
+		let msg_c = ConvMsgForCreate {
+			conv_id,
+			content: "msg 01".to_string(),
+			..Default::default()
+		};
+		ConvBmc::add_msg(&ctx, &mm, msg_c).await?;
+		let fx_conv_filter: ConvMsgFilter =
+			serde_json::from_value(serde_json::json!({"conv_id": conv_id}))?;
+		let fx_conv_filter_02: ConvMsgFilter =
+			serde_json::from_value(serde_json::json!({"conv_id": conv_id}))?;
+		let fx_in_filter: ConvMsgFilter =
+			serde_json::from_value(serde_json::json!({"conv_id": {"$in": [conv_id]}}))?;
+		let fx_member_ctx = Ctx::new(user_ids[0])?;
+		let fx_other_ctx = Ctx::new(user_ids[1])?;
+
+		// -- Exec
+		let member_msgs = ConvBmc::list_msgs(
+			&fx_member_ctx,
+			&mm,
+			Some(vec![fx_conv_filter]),
+			None,
+		)
+		.await?;
+		let other_res = ConvBmc::list_msgs(
+			&fx_other_ctx,
+			&mm,
+			Some(vec![fx_conv_filter_02]),
+			None,
+		)
+		.await;
+		let no_filter_res = ConvBmc::list_msgs(&fx_other_ctx, &mm, None, None).await;
+		let in_filter_res = ConvBmc::list_msgs(
+			&fx_other_ctx,
+			&mm,
+			Some(vec![fx_in_filter]),
+			None,
+		)
+		.await;
+
+		// -- Check
+		assert_eq!(member_msgs.len(), 1);
+		assert!(
+			matches!(other_res, Err(crate::model::Error::ConvMemberRequired { .. })),
+			"should return a ConvMemberRequired"
+		);
+		for res in [no_filter_res, in_filter_res] {
+			assert!(
+				matches!(res, Err(crate::model::Error::ConvMsgFilterConvRequired)),
+				"should return a ConvMsgFilterConvRequired"
+			);
+		}
+
+		// -- Clean
+		AgentBmc::delete(&ctx, &mm, agent_id).await?;
+		clean_users(&ctx, &mm, "test_list_msgs_member_only").await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_auto_responders_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_usernames = &[
+			"test_list_auto_responders_ok-user-01",
+			"test_list_auto_responders_ok-bot-01",
+		];
+		let agent_id =
+			seed_agent(&ctx, &mm, "test_list_auto_responders_ok agent 01").await?;
+		let conv_id =
+			seed_conv(&ctx, &mm, agent_id, "test_list_auto_responders_ok conv 01")
+				.await?;
+		let user_ids = seed_users(&ctx, &mm, fx_usernames).await?;
+		ConvBmc::add_user(
+			&ctx,
+			&mm,
+			ConvUserForCreate {
+				conv_id,
+				user_id: user_ids[0],
+				..Default::default()
+			},
+		)
+		.await?;
+		ConvBmc::add_user(
+			&ctx,
+			&mm,
+			ConvUserForCreate {
+				conv_id,
+				user_id: user_ids[1],
+				auto_respond: Some(true),
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let responders = ConvBmc::list_auto_responders(&ctx, &mm, conv_id).await?;
+
+		// -- Check
+		assert_eq!(ConvBmc::list_users(&ctx, &mm, conv_id).await?.len(), 2);
+		assert_eq!(responders.len(), 1, "should have only the bot");
+		assert_eq!(responders[0].user_id, user_ids[1]);
+		assert!(responders[0].auto_respond);
+
+		// -- Check - Only the conv owner adds participants
+		let res = ConvBmc::add_user(
+			&Ctx::new(user_ids[0])?,
+			&mm,
+			ConvUserForCreate {
+				conv_id,
+				user_id: user_ids[0],
+				auto_respond: Some(true),
+			},
+		)
+		.await;
+		assert!(
+			matches!(res, Err(crate::model::Error::ConvOwnerRequired { .. })),
+			"should return a ConvOwnerRequired"
+		);
+
+		// -- Clean
+		AgentBmc::delete(&ctx, &mm, agent_id).await?;
+		clean_users(&ctx, &mm, "test_list_auto_responders_ok").await?;
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
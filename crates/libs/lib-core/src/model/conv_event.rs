@@ -0,0 +1,150 @@
+//! Conv events
+//!
+//! - Notified by the db triggers (see `notify_conv_event` in the schema),
+//!   so that all the writers (gateway, workers, jobs) are covered, and all the
+//!   listeners (e.g., gateway instances) receive them.
+//! - Added to the `outbox` by the model writes (as `CloudEvent`s, in the write transaction),
+//!   and relayed to the `EventBus` for the event driven workers (e.g., `conv_msg.created`).
+
+use crate::ctx::Ctx;
+use crate::model::conv::{Conv, ConvBmc};
+use crate::model::conv_msg::ConvMsg;
+use crate::model::outbox::OutboxBmc;
+use crate::model::store::dbx;
+use crate::model::{ModelManager, Result};
+use lib_events::CloudEvent;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{debug, error};
+
+/// The postgres LISTEN/NOTIFY channel of the conv events.
+pub const CONV_EVENT_CHANNEL: &str = "conv_event";
+
+// -- Event types
+pub const CONV_MSG_CREATED: &str = "conv_msg.created";
+pub const CONV_MSG_UPDATED: &str = "conv_msg.updated";
+pub const CONV_CREATED: &str = "conv.created";
+pub const CONV_UPDATED: &str = "conv.updated";
+pub const CONV_ARCHIVED: &str = "conv.archived";
+
+/// The `CloudEvent` source of the model events.
+const EVENT_SOURCE: &str = "/lib-core/model";
+
+const LISTEN_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum ConvEvent {
+	#[serde(rename = "conv_msg.created")]
+	ConvMsgCreated(ConvMsg),
+	#[serde(rename = "conv.updated")]
+	ConvUpdated(Conv),
+}
+
+impl ConvEvent {
+	pub fn conv_id(&self) -> i64 {
+		match self {
+			ConvEvent::ConvMsgCreated(msg) => msg.conv_id,
+			ConvEvent::ConvUpdated(conv) => conv.id,
+		}
+	}
+}
+
+/// The notification payload (the entity is fetched by the listener,
+/// to stay under the NOTIFY payload size limit).
+#[derive(Deserialize)]
+struct ConvNotification {
+	#[serde(rename = "type")]
+	typ: String,
+	id: i64,
+}
+
+// endregion: --- Types
+
+// region:    --- Listener
+
+/// Listen to the conv event notifications, and broadcast the conv events to `tx`.
+///
+/// Note: Runs until the listener can't be created. On connection loss, the listener
+///       reconnects (the notifications in between are lost).
+pub async fn listen_conv_events(
+	mm: ModelManager,
+	tx: broadcast::Sender<ConvEvent>,
+) -> Result<()> {
+	let mut listener = PgListener::connect_with(mm.dbx().db())
+		.await
+		.map_err(dbx::Error::from)?;
+	listener
+		.listen(CONV_EVENT_CHANNEL)
+		.await
+		.map_err(dbx::Error::from)?;
+
+	let ctx = Ctx::root_ctx();
+
+	loop {
+		let notification = match listener.recv().await {
+			Ok(notification) => notification,
+			Err(ex) => {
+				error!("{:<12} - conv event listener: {ex}", "CONV EVENT");
+				tokio::time::sleep(LISTEN_RETRY_DELAY).await;
+				continue;
+			}
+		};
+
+		match to_conv_event(&ctx, &mm, notification.payload()).await {
+			// Note: No receivers (e.g., no websocket) is not an error.
+			Ok(Some(event)) => {
+				let _ = tx.send(event);
+			}
+			Ok(None) => (),
+			Err(ex) => error!("{:<12} - conv event {ex:?}", "CONV EVENT"),
+		}
+	}
+}
+
+async fn to_conv_event(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	payload: &str,
+) -> Result<Option<ConvEvent>> {
+	let ConvNotification { typ, id } = serde_json::from_str(payload)?;
+	debug!("{:<12} - {typ} {id}", "CONV EVENT");
+
+	let event = match typ.as_str() {
+		CONV_MSG_CREATED => Some(ConvEvent::ConvMsgCreated(
+			ConvBmc::get_msg(ctx, mm, id).await?,
+		)),
+		CONV_UPDATED => Some(ConvEvent::ConvUpdated(ConvBmc::get(ctx, mm, id).await?)),
+		_ => None,
+	};
+
+	Ok(event)
+}
+
+// endregion: --- Listener
+
+// region:    --- Outbox
+
+/// Add the conv event `typ` (with the ctx, and the conv id as subject) to the outbox.
+///
+/// Note: Must be called with the `mm` of the write transaction.
+pub(in crate::model) async fn add_conv_event(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	typ: &str,
+	conv_id: i64,
+	data: &impl Serialize,
+) -> Result<()> {
+	let event = CloudEvent::new(EVENT_SOURCE, typ, data)?
+		.with_subject(conv_id.to_string())
+		.with_ctx(ctx)?;
+	OutboxBmc::add(ctx, mm, event).await?;
+
+	Ok(())
+}
+
+// endregion: --- Outbox
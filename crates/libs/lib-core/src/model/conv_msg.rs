@@ -1,49 +1,180 @@
 use crate::model::base::DbBmc;
 use crate::model::conv::ConvScoped;
 use crate::model::modql_utils::time_to_sea_value;
+use crate::model::Result;
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
 use lib_utils::time::Rfc3339;
-use modql::field::Fields;
+use modql::field::{Fields, SeaFieldValue};
 use modql::filter::{FilterNodes, OpValsInt64, OpValsString, OpValsValue};
 use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
+use serde_json::Value;
+use serde_with::{serde_as, skip_serializing_none};
 use sqlx::FromRow;
 use time::OffsetDateTime;
 
+// region:    --- Msg Role & Content
+
+/// Who authored the message, from the LLM chat point of view.
+#[derive(
+	Debug,
+	Clone,
+	Default,
+	PartialEq,
+	sqlx::Type,
+	SeaFieldValue,
+	derive_more::Display,
+	Deserialize,
+	Serialize,
+)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+#[sqlx(type_name = "msg_role")]
+pub enum MsgRole {
+	#[default]
+	User,
+	Assistant,
+	System,
+	Tool,
+}
+
+/// Structured content of a message, stored as json in `conv_msg.content_parts`.
+///
+/// Note: `conv_msg.content` always holds the plain text version of the message,
+///       so that simple clients do not have to understand the parts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+	Text {
+		text: String,
+	},
+	Image {
+		url: String,
+		mime_type: Option<String>,
+	},
+	ToolCall {
+		call_id: String,
+		name: String,
+		arguments: Value,
+	},
+	ToolResult {
+		call_id: String,
+		content: Value,
+	},
+}
+
+impl ContentPart {
+	/// True for the parts a user can author (not the tool call protocol parts,
+	/// which are only added by the llm-worker).
+	pub fn is_user_part(&self) -> bool {
+		matches!(self, ContentPart::Text { .. } | ContentPart::Image { .. })
+	}
+}
+
+/// Metadata of a message, stored as json in `conv_msg.metadata`.
+/// (mostly set on `Assistant` messages generated by an LLM)
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct ConvMsgMeta {
+	pub provider: Option<String>,
+	pub model: Option<String>,
+	pub finish_reason: Option<String>,
+	pub input_tokens: Option<i32>,
+	pub output_tokens: Option<i32>,
+	pub latency_ms: Option<f64>,
+	/// The knowledge base chunks given to the model (see `ChunkBmc::search`).
+	pub citations: Option<Vec<MsgCitation>>,
+}
+
+/// A knowledge base chunk cited by a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct MsgCitation {
+	pub document_id: i64,
+	pub document_title: String,
+	pub chunk_id: i64,
+	pub score: f32,
+}
+
+// endregion: --- Msg Role & Content
+
 // region:    --- Types
 
 #[serde_as]
-#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct ConvMsg {
 	pub id: i64,
 
 	// -- FK
 	pub conv_id: i64,
 	pub user_id: i64,
+	/// The message this one replies to (None for the conv root message).
+	/// Sibling messages sharing the same parent are branches.
+	pub parent_msg_id: Option<i64>,
 
 	// -- Properties
+	pub role: MsgRole,
 	pub content: String,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<Vec<ContentPart>>"))]
+	pub content_parts: Option<Value>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<ConvMsgMeta>"))]
+	pub metadata: Option<Value>,
 
 	// -- Timestamps
 	// creator user_id and time
 	pub cid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
 	pub ctime: OffsetDateTime,
 	// last modifier user_id and time
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
 	pub mtime: OffsetDateTime,
 }
 
+impl ConvMsg {
+	/// Typed `content_parts` (None if the message only has a plain `content`).
+	pub fn content_parts(&self) -> Result<Option<Vec<ContentPart>>> {
+		let parts = self
+			.content_parts
+			.clone()
+			.map(serde_json::from_value)
+			.transpose()?;
+		Ok(parts)
+	}
+
+	/// Typed `metadata` (default if the message has no metadata).
+	pub fn metadata(&self) -> Result<ConvMsgMeta> {
+		let meta = self
+			.metadata
+			.clone()
+			.map(serde_json::from_value)
+			.transpose()?
+			.unwrap_or_default();
+		Ok(meta)
+	}
+}
+
 impl ConvScoped for ConvMsg {
 	fn conv_id(&self) -> i64 {
 		self.conv_id
 	}
 }
 
-#[derive(Fields, Deserialize)]
+#[derive(Deserialize, Default, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct ConvMsgForCreate {
 	pub conv_id: i64,
 	pub content: String,
+
+	/// When None, the message is appended after the latest message of the conv.
+	pub parent_msg_id: Option<i64>,
+
+	/// Only the `Text` and `Image` parts are kept (see `ContentPart::is_user_part`).
+	pub content_parts: Option<Vec<ContentPart>>,
 }
 
 impl ConvScoped for ConvMsgForCreate {
@@ -52,6 +183,20 @@ impl ConvScoped for ConvMsgForCreate {
 	}
 }
 
+/// Reply of the llm-worker (e.g., `Assistant` or `Tool` message) to a conv branch.
+///
+/// Note: Unlike the public `ConvMsgForCreate` (always a `User` message), this sets the
+///       role, the tool call parts, and the metadata, so it is not exposed to the RPC clients.
+#[derive(Debug, Default)]
+pub struct ConvMsgForReply {
+	pub conv_id: i64,
+	pub parent_msg_id: i64,
+	pub role: MsgRole,
+	pub content: String,
+	pub content_parts: Option<Vec<ContentPart>>,
+	pub metadata: Option<ConvMsgMeta>,
+}
+
 /// ConvMsg for Insert, which is derived from the public `ConvMsgForCreate`.
 ///
 /// Notes:
@@ -67,20 +212,63 @@ impl ConvScoped for ConvMsgForCreate {
 pub(in crate::model) struct ConvMsgForInsert {
 	pub conv_id: i64,
 	pub user_id: i64,
+	pub parent_msg_id: Option<i64>,
+	#[field(cast_as = "msg_role")]
+	pub role: Option<MsgRole>,
 	pub content: String,
+	pub content_parts: Option<Value>,
+	pub metadata: Option<Value>,
 }
 
 impl ConvMsgForInsert {
-	pub fn from_msg_for_create(user_id: i64, msg_c: ConvMsgForCreate) -> Self {
-		Self {
+	pub fn from_msg_for_create(
+		user_id: i64,
+		msg_c: ConvMsgForCreate,
+	) -> Result<Self> {
+		let content_parts = msg_c
+			.content_parts
+			.map(|parts| {
+				parts
+					.into_iter()
+					.filter(ContentPart::is_user_part)
+					.collect::<Vec<_>>()
+			})
+			.filter(|parts| !parts.is_empty())
+			.map(serde_json::to_value)
+			.transpose()?;
+
+		Ok(Self {
 			conv_id: msg_c.conv_id,
 			user_id,
+			parent_msg_id: msg_c.parent_msg_id,
+			role: Some(MsgRole::User),
 			content: msg_c.content,
-		}
+			content_parts,
+			metadata: None,
+		})
+	}
+
+	pub fn from_msg_for_reply(user_id: i64, msg_r: ConvMsgForReply) -> Result<Self> {
+		let content_parts = msg_r
+			.content_parts
+			.map(serde_json::to_value)
+			.transpose()?;
+		let metadata = msg_r.metadata.map(serde_json::to_value).transpose()?;
+
+		Ok(Self {
+			conv_id: msg_r.conv_id,
+			user_id,
+			parent_msg_id: Some(msg_r.parent_msg_id),
+			role: Some(msg_r.role),
+			content: msg_r.content,
+			content_parts,
+			metadata,
+		})
 	}
 }
 
-#[derive(Fields, Deserialize, Default)]
+#[derive(Fields, Deserialize, Default, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct ConvMsgForUpdate {
 	pub conv_id: i64,
 	pub content: Option<String>,
@@ -92,19 +280,42 @@ impl ConvScoped for ConvMsgForUpdate {
 	}
 }
 
+/// Edit of an existing message.
+///
+/// Note: Unlike `ConvMsgForUpdate`, an edit does not modify the original message,
+///       but adds a sibling message (same parent), which starts a new branch of the conv.
+#[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct ConvMsgForEdit {
+	pub content: String,
+}
+
 #[derive(FilterNodes, Deserialize, Default, Debug)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct ConvMsgFilter {
-	id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
 
-	conv_id: Option<OpValsInt64>,
-	content: Option<OpValsString>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub conv_id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub parent_msg_id: Option<OpValsInt64>,
+	#[modql(cast_as = "msg_role")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub role: Option<OpValsString>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub content: Option<OpValsString>,
 
-	cid: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub cid: Option<OpValsInt64>,
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	ctime: Option<OpValsValue>,
-	mid: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub mid: Option<OpValsInt64>,
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	mtime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub mtime: Option<OpValsValue>,
 }
 
 // endregion: --- Types
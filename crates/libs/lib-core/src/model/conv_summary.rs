@@ -0,0 +1,73 @@
+use crate::model::base::DbBmc;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{FilterNodes, OpValsInt64};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+// region:    --- Types
+
+/// The rolling summary of a conv branch, which covers the messages of the
+/// branch from the conv root message down to `msg_id` (included).
+///
+/// Note: Keyed by `msg_id`, so each branch of the message tree keeps its own summary
+///       (a summary applies to all the threads going through its `msg_id`).
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+pub struct ConvSummary {
+	pub id: i64,
+
+	// -- FK
+	pub conv_id: i64,
+	pub msg_id: i64,
+
+	// -- Properties
+	pub summary: String,
+
+	// -- Timestamps
+	// creator user_id and time
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+	// last modifier user_id and time
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields)]
+pub(in crate::model) struct ConvSummaryForCreate {
+	pub conv_id: i64,
+	pub msg_id: i64,
+	pub summary: String,
+}
+
+#[derive(Fields)]
+pub(in crate::model) struct ConvSummaryForUpdate {
+	pub summary: String,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+pub struct ConvSummaryFilter {
+	pub id: Option<OpValsInt64>,
+
+	pub conv_id: Option<OpValsInt64>,
+	pub msg_id: Option<OpValsInt64>,
+}
+
+// endregion: --- Types
+
+// region:    --- ConvSummary
+
+pub struct ConvSummaryBmc;
+
+impl DbBmc for ConvSummaryBmc {
+	const TABLE: &'static str = "conv_summary";
+}
+
+// Note: Like `ConvMsg`, `ConvSummary` is managed by the `ConvBmc` container entity
+//       (e.g., `ConvBmc::update_summary`).
+
+// endregion: --- ConvSummary
@@ -1,6 +1,9 @@
 use crate::model::base::DbBmc;
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsBoolSchema, OpValsInt64Schema};
 use lib_utils::time::Rfc3339;
 use modql::field::Fields;
+use modql::filter::{FilterNodes, OpValsBool, OpValsInt64};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sqlx::FromRow;
@@ -9,7 +12,8 @@ use time::OffsetDateTime;
 // region:    --- Types
 
 #[serde_as]
-#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct ConvUser {
 	pub id: i64,
 
@@ -17,21 +21,46 @@ pub struct ConvUser {
 	pub conv_id: i64,
 	pub user_id: i64,
 
+	// -- Machine User Properties
+	/// When true, this (machine) user replies to the messages
+	/// posted by the other participants (see `ConvBmc::list_auto_responders`).
+	pub auto_respond: bool,
+
 	// -- Timestamps
 	// creator user_id and time
 	pub cid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
 	pub ctime: OffsetDateTime,
 	// last modifier user_id and time
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
 	pub mtime: OffsetDateTime,
 }
 
-#[derive(Fields, Deserialize)]
+#[derive(Fields, Deserialize, Default, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct ConvUserForCreate {
 	pub conv_id: i64,
 	pub user_id: i64,
+
+	/// Default to false.
+	pub auto_respond: Option<bool>,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct ConvUserFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub conv_id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub user_id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsBoolSchema>"))]
+	pub auto_respond: Option<OpValsBool>,
 }
 
 // endregion: --- Types
@@ -44,7 +73,7 @@ impl DbBmc for ConvUserBmc {
 	const TABLE: &'static str = "conv_user";
 }
 
-// Note: This is not implemented yet. It will likely be similar to `ConvMsg`, meaning it will be
-//       managed by the `ConvBmc` container entity.
+// Note: Like `ConvMsg`, `ConvUser` is managed by the `ConvBmc` container entity
+//       (e.g., `ConvBmc::add_user`).
 
 // endregion: --- ConvUser
@@ -0,0 +1,161 @@
+use crate::ctx::Ctx;
+use crate::generate_common_bmc_fns;
+use crate::model::base::{self, DbBmc};
+use crate::model::chunk::{Chunk, ChunkBmc, ChunkFilter, ChunkForCreate, ChunkForInsert};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::ModelManager;
+use crate::model::Result;
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- Document Types
+
+/// A knowledge base document. The content is in its `Chunk`s.
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct Document {
+	pub id: i64,
+
+	// -- Relations
+	pub owner_id: i64,
+
+	// -- Properties
+	pub title: String,
+	/// Where the document comes from (e.g., url, file path).
+	pub source: Option<String>,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct DocumentForCreate {
+	pub title: String,
+	pub source: Option<String>,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct DocumentForUpdate {
+	pub title: Option<String>,
+	pub source: Option<String>,
+}
+
+#[derive(FilterNodes, Default, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct DocumentFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub owner_id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub title: Option<OpValsString>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub source: Option<OpValsString>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub mtime: Option<OpValsValue>,
+}
+
+// endregion: --- Document Types
+
+// region:    --- DocumentBmc
+
+pub struct DocumentBmc;
+
+impl DbBmc for DocumentBmc {
+	const TABLE: &'static str = "document";
+
+	fn has_owner_id() -> bool {
+		true
+	}
+}
+
+// This will generate the `impl DocumentBmc {...}` with the default CRUD functions.
+generate_common_bmc_fns!(
+	Bmc: DocumentBmc,
+	Entity: Document,
+	ForCreate: DocumentForCreate,
+	ForUpdate: DocumentForUpdate,
+	Filter: DocumentFilter,
+);
+
+// Additional DocumentBmc methods to manage the `Chunk` constructs.
+impl DocumentBmc {
+	/// Create the document with its (already embedded) chunks, in one transaction.
+	///
+	/// Returns the document id.
+	pub async fn create_with_chunks(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		doc_c: DocumentForCreate,
+		chunks_c: Vec<ChunkForCreate>,
+	) -> Result<i64> {
+		// Start the transaction
+		let mm = mm.new_with_txn()?;
+
+		mm.dbx().begin_txn().await?;
+
+		let doc_id = Self::create(ctx, &mm, doc_c).await?;
+
+		let chunks_i: Vec<ChunkForInsert> = chunks_c
+			.into_iter()
+			.enumerate()
+			.map(|(idx, chunk_c)| ChunkForInsert::new(doc_id, idx as i32, chunk_c))
+			.collect();
+		if !chunks_i.is_empty() {
+			base::create_many::<ChunkBmc, _>(ctx, &mm, chunks_i).await?;
+		}
+
+		// Commit the transaction
+		mm.dbx().commit_txn().await?;
+
+		Ok(doc_id)
+	}
+
+	/// Returns the document chunks, in document order.
+	pub async fn list_chunks(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		doc_id: i64,
+	) -> Result<Vec<Chunk>> {
+		let filter = ChunkFilter {
+			document_id: Some(doc_id.into()),
+			..Default::default()
+		};
+		let list_options = ListOptions {
+			order_bys: Some("idx".into()),
+			..Default::default()
+		};
+
+		base::list::<ChunkBmc, _, _>(ctx, mm, Some(vec![filter]), Some(list_options))
+			.await
+	}
+}
+
+// endregion: --- DocumentBmc
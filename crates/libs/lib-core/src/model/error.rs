@@ -1,10 +1,15 @@
-use crate::model::store::dbx;
+use crate::model::job::JobState;
+use crate::model::quota::{QuotaPeriod, QuotaScope};
+use crate::model::webhook::WebhookScope;
+use crate::model::store::{blob, dbx};
 use derive_more::From;
 use lib_auth::pwd;
+use lib_utils::time::Rfc3339;
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::error::DatabaseError;
 use std::borrow::Cow;
+use time::OffsetDateTime;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -22,6 +27,74 @@ pub enum Error {
 
 	CountFail,
 
+	// -- Agent
+	AgentToolInvalid {
+		name: String,
+		cause: String,
+	},
+
+	// -- Conv
+	ConvMsgNotInConv {
+		conv_id: i64,
+		msg_id: i64,
+	},
+	ConvOwnerRequired {
+		conv_id: i64,
+		user_id: i64,
+	},
+	ConvMemberRequired {
+		conv_id: i64,
+		user_id: i64,
+	},
+	/// The conv msgs are listed per conv (each filter with a single `conv_id` `$eq`).
+	ConvMsgFilterConvRequired,
+
+	// -- Quota
+	QuotaExceeded {
+		scope: QuotaScope,
+		scope_id: i64,
+		period: QuotaPeriod,
+		#[serde_as(as = "Rfc3339")]
+		reset_time: OffsetDateTime,
+	},
+	QuotaScopeDenied {
+		scope: QuotaScope,
+		scope_id: i64,
+	},
+
+	// -- Job
+	JobCronInvalid {
+		cron: String,
+		cause: String,
+	},
+	JobNotCancellable {
+		id: i64,
+		state: JobState,
+	},
+	JobNotRetryable {
+		id: i64,
+		state: JobState,
+	},
+
+	// -- Webhook
+	WebhookUrlInvalid {
+		url: String,
+	},
+	/// The url host resolves to a non public address (e.g., loopback, private).
+	WebhookUrlNotPublic {
+		url: String,
+		addr: String,
+	},
+	WebhookScopeDenied {
+		scope: WebhookScope,
+		scope_id: Option<i64>,
+	},
+
+	// -- Access
+	SysUserRequired {
+		user_id: i64,
+	},
+
 	// -- DB
 	UserAlreadyExists {
 		username: String,
@@ -39,8 +112,15 @@ pub enum Error {
 	Pwd(pwd::Error),
 	#[from]
 	Dbx(dbx::Error),
+	#[from]
+	Blob(blob::Error),
+	#[from]
+	Events(lib_events::Error),
 
 	// -- Externals
+	#[from]
+	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
+
 	#[from]
 	SeaQuery(#[serde_as(as = "DisplayFromStr")] sea_query::error::Error),
 
@@ -0,0 +1,700 @@
+use crate::ctx::Ctx;
+use crate::model::base::{self, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::user::UserBmc;
+use crate::model::ModelManager;
+// Note: `model::Result` not imported (shadows the `Result` of the schemars derive code).
+use crate::model::{self, Error};
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
+use lib_utils::cron::CronSchedule;
+use lib_utils::time::{now_utc, Rfc3339};
+use modql::field::{Fields, SeaFieldValue};
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use time::Duration;
+
+/// Queue of the jobs enqueued without queue.
+pub const JOB_DEFAULT_QUEUE: &str = "default";
+
+/// Retry backoff: `base * 2^(attempts - 1)`, capped at max.
+const JOB_BACKOFF_BASE_SEC: i64 = 10;
+const JOB_BACKOFF_MAX_SEC: i64 = 3600;
+
+// region:    --- Job Types
+
+/// Job lifecycle:
+///   `Pending` -> (claimed) `Running` -> `Succeeded`
+///                                    -> `Pending` (retry with backoff, or next cron time)
+///                                    -> `Dead` (max attempts reached, i.e., dead-letter)
+///   `Pending` | `Running` -> `Cancelled`
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	PartialEq,
+	sqlx::Type,
+	SeaFieldValue,
+	derive_more::Display,
+	Deserialize,
+	Serialize,
+)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+#[sqlx(type_name = "job_state")]
+pub enum JobState {
+	Pending,
+	Running,
+	Succeeded,
+	Dead,
+	Cancelled,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct Job {
+	pub id: i64,
+
+	// -- Properties
+	pub queue: String,
+	/// The handler of the job (see `job_runtime::JobHandler`).
+	pub kind: String,
+	pub payload: Option<Value>,
+
+	// -- Scheduling
+	pub state: JobState,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub run_at: OffsetDateTime,
+	pub attempts: i32,
+	pub max_attempts: i32,
+	/// When set, the job is rescheduled at the next cron time after each run.
+	pub cron: Option<String>,
+	pub last_error: Option<String>,
+
+	// -- Lock (while `Running`)
+	pub locked_by: Option<String>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<String>"))]
+	pub locked_at: Option<OffsetDateTime>,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub mtime: OffsetDateTime,
+}
+
+impl Job {
+	/// Typed `payload` (deserialized from null if the job has no payload).
+	pub fn payload_as<T: DeserializeOwned>(&self) -> model::Result<T> {
+		let payload = self.payload.clone().unwrap_or_default();
+		Ok(serde_json::from_value(payload)?)
+	}
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Default, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct JobForCreate {
+	/// Default to `JOB_DEFAULT_QUEUE`.
+	pub queue: Option<String>,
+	pub kind: String,
+	pub payload: Option<Value>,
+
+	/// Default to now (or the next cron time for a cron job).
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<String>"))]
+	#[serde(default)]
+	pub run_at: Option<OffsetDateTime>,
+	/// Default to 5 (db default).
+	pub max_attempts: Option<i32>,
+	/// 5 fields cron expression in UTC (e.g., `0 3 * * *`), see `lib_utils::cron`.
+	pub cron: Option<String>,
+}
+
+#[derive(Fields)]
+struct JobForInsert {
+	queue: String,
+	kind: String,
+	payload: Option<Value>,
+	run_at: OffsetDateTime,
+	max_attempts: Option<i32>,
+	cron: Option<String>,
+}
+
+#[derive(FilterNodes, Default, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct JobFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub queue: Option<OpValsString>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub kind: Option<OpValsString>,
+	#[modql(cast_as = "job_state")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub state: Option<OpValsString>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub mtime: Option<OpValsValue>,
+}
+
+// endregion: --- Job Types
+
+// region:    --- JobBmc
+
+pub struct JobBmc;
+
+impl DbBmc for JobBmc {
+	const TABLE: &'static str = "job";
+}
+
+impl JobBmc {
+	/// Enqueue a job, which will be run as the ctx user.
+	pub async fn enqueue(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		job_c: JobForCreate,
+	) -> model::Result<i64> {
+		let now = now_utc();
+		let run_at = match (&job_c.cron, job_c.run_at) {
+			(_, Some(run_at)) => run_at,
+			(Some(cron), None) => next_cron_time(cron, now)?,
+			(None, None) => now,
+		};
+
+		let job_i = JobForInsert {
+			queue: job_c.queue.unwrap_or_else(|| JOB_DEFAULT_QUEUE.to_string()),
+			kind: job_c.kind,
+			payload: job_c.payload,
+			run_at,
+			max_attempts: job_c.max_attempts,
+			cron: job_c.cron,
+		};
+
+		base::create::<Self, _>(ctx, mm, job_i).await
+	}
+
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> model::Result<Job> {
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<JobFilter>>,
+		list_options: Option<ListOptions>,
+	) -> model::Result<Vec<Job>> {
+		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> model::Result<()> {
+		base::delete::<Self>(ctx, mm, id).await
+	}
+
+	/// Returns the job if created by the ctx user (any job for a `Sys` user).
+	///
+	/// Note: The job of another user is `EntityNotFound` (i.e., its existence is not leaked).
+	pub async fn get_own(ctx: &Ctx, mm: &ModelManager, id: i64) -> model::Result<Job> {
+		let job = Self::get(ctx, mm, id).await?;
+		if job.cid != ctx.user_id() && !UserBmc::is_sys_user(ctx, mm).await? {
+			return Err(Error::EntityNotFound {
+				entity: Self::TABLE,
+				id,
+			});
+		}
+
+		Ok(job)
+	}
+
+	/// Returns the jobs created by the ctx user (all jobs for a `Sys` user).
+	pub async fn list_own(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filters: Option<Vec<JobFilter>>,
+		list_options: Option<ListOptions>,
+	) -> model::Result<Vec<Job>> {
+		let filters = if UserBmc::is_sys_user(ctx, mm).await? {
+			filters
+		} else {
+			// Note: The `cid` of each filter (OR-ed) is the ctx user.
+			let filters = filters.unwrap_or_else(|| vec![JobFilter::default()]);
+			let filters = filters
+				.into_iter()
+				.map(|filter| JobFilter {
+					cid: Some(ctx.user_id().into()),
+					..filter
+				})
+				.collect();
+			Some(filters)
+		};
+
+		Self::list(ctx, mm, filters, list_options).await
+	}
+
+	/// Cancel a `Pending` or `Running` job (of the ctx user, see `get_own`).
+	///
+	/// Note: A `Running` job is not interrupted, but its result will be ignored.
+	pub async fn cancel(ctx: &Ctx, mm: &ModelManager, id: i64) -> model::Result<()> {
+		let job = Self::get_own(ctx, mm, id).await?;
+		if !matches!(job.state, JobState::Pending | JobState::Running) {
+			return Err(Error::JobNotCancellable {
+				id,
+				state: job.state,
+			});
+		}
+
+		let sql = format!(
+			"UPDATE {} SET state = $2, locked_by = NULL, locked_at = NULL, mid = $3, mtime = $4
+			 WHERE id = $1 AND state IN ('Pending', 'Running')",
+			Self::TABLE
+		);
+		let sqlx_query = sqlx::query(&sql)
+			.bind(id)
+			.bind(JobState::Cancelled)
+			.bind(ctx.user_id())
+			.bind(now_utc());
+		mm.dbx().execute(sqlx_query).await?;
+
+		Ok(())
+	}
+
+	/// Re-enqueue a `Dead` or `Cancelled` job (of the ctx user, see `get_own`)
+	/// with its attempts reset, to run now.
+	pub async fn retry(ctx: &Ctx, mm: &ModelManager, id: i64) -> model::Result<()> {
+		let job = Self::get_own(ctx, mm, id).await?;
+		if !matches!(job.state, JobState::Dead | JobState::Cancelled) {
+			return Err(Error::JobNotRetryable {
+				id,
+				state: job.state,
+			});
+		}
+
+		let sql = format!(
+			"UPDATE {} SET state = $2, run_at = $3, attempts = 0, mid = $4, mtime = $3
+			 WHERE id = $1",
+			Self::TABLE
+		);
+		let sqlx_query = sqlx::query(&sql)
+			.bind(id)
+			.bind(JobState::Pending)
+			.bind(now_utc())
+			.bind(ctx.user_id());
+		mm.dbx().execute(sqlx_query).await?;
+
+		Ok(())
+	}
+}
+
+/// Job runtime functions (see `job_runtime::JobRuntime`).
+impl JobBmc {
+	/// Claim up to `limit` due `Pending` jobs of `queue` for `worker_id`.
+	/// The claimed jobs are `Running`, with their attempts incremented.
+	///
+	/// Note: `FOR UPDATE SKIP LOCKED` so that concurrent workers never claim the same job.
+	pub async fn claim(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		queue: &str,
+		worker_id: &str,
+		limit: i64,
+	) -> model::Result<Vec<Job>> {
+		let sql = format!(
+			"UPDATE {table} SET state = $3, attempts = attempts + 1,
+			   locked_by = $4, locked_at = $5, mid = $6, mtime = $5
+			 WHERE id IN (
+			   SELECT id FROM {table}
+			   WHERE queue = $1 AND state = $7 AND run_at <= $5
+			   ORDER BY run_at, id
+			   LIMIT $2
+			   FOR UPDATE SKIP LOCKED
+			 )
+			 RETURNING *",
+			table = Self::TABLE
+		);
+		let sqlx_query = sqlx::query_as::<_, Job>(&sql)
+			.bind(queue)
+			.bind(limit)
+			.bind(JobState::Running)
+			.bind(worker_id)
+			.bind(now_utc())
+			.bind(ctx.user_id())
+			.bind(JobState::Pending);
+
+		Ok(mm.dbx().fetch_all(sqlx_query).await?)
+	}
+
+	/// Mark a job claimed by `worker_id` as `Succeeded` (or reschedule it at its next cron time).
+	pub async fn succeed(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		worker_id: &str,
+		job: &Job,
+	) -> model::Result<()> {
+		let (id, attempts) = (job.id, job.attempts);
+		match &job.cron {
+			Some(cron) => {
+				let run_at = next_cron_time(cron, now_utc())?;
+				Self::release(ctx, mm, id, worker_id, JobState::Pending, run_at, 0, None).await
+			}
+			None => {
+				let (state, run_at) = (JobState::Succeeded, job.run_at);
+				Self::release(ctx, mm, id, worker_id, state, run_at, attempts, None).await
+			}
+		}
+	}
+
+	/// Record the failure of a job claimed by `worker_id`, which is retried with backoff,
+	/// until `max_attempts` where it is `Dead`.
+	///
+	/// Note: A cron job is not dead-lettered, but rescheduled at its next cron time.
+	pub async fn fail(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		worker_id: &str,
+		job: &Job,
+		error: &str,
+	) -> model::Result<()> {
+		let now = now_utc();
+		let (id, attempts) = (job.id, job.attempts);
+		let error = Some(error);
+
+		if attempts < job.max_attempts {
+			let run_at = now + backoff(attempts);
+			let state = JobState::Pending;
+			return Self::release(ctx, mm, id, worker_id, state, run_at, attempts, error).await;
+		}
+
+		match &job.cron {
+			Some(cron) => {
+				let run_at = next_cron_time(cron, now)?;
+				Self::release(ctx, mm, id, worker_id, JobState::Pending, run_at, 0, error).await
+			}
+			None => {
+				let (state, run_at) = (JobState::Dead, job.run_at);
+				Self::release(ctx, mm, id, worker_id, state, run_at, attempts, error).await
+			}
+		}
+	}
+
+	/// Put back to `Pending` the `Running` jobs locked for more than `lock_timeout`
+	/// (e.g., their worker died). Returns the number of jobs requeued.
+	pub async fn requeue_stale(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		lock_timeout: Duration,
+	) -> model::Result<u64> {
+		let now = now_utc();
+		let sql = format!(
+			"UPDATE {} SET state = $1, locked_by = NULL, locked_at = NULL, mid = $3, mtime = $4
+			 WHERE state = $2 AND locked_at < $5",
+			Self::TABLE
+		);
+		let sqlx_query = sqlx::query(&sql)
+			.bind(JobState::Pending)
+			.bind(JobState::Running)
+			.bind(ctx.user_id())
+			.bind(now)
+			.bind(now - lock_timeout);
+
+		Ok(mm.dbx().execute(sqlx_query).await?)
+	}
+}
+
+/// Private helpers
+impl JobBmc {
+	/// Release the lock of a `Running` job claimed by `worker_id` with its new state.
+	/// (no-op if the job is not `Running` anymore, e.g., cancelled, or if it was requeued
+	///  as stale and claimed by another worker)
+	#[allow(clippy::too_many_arguments)]
+	async fn release(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		worker_id: &str,
+		state: JobState,
+		run_at: OffsetDateTime,
+		attempts: i32,
+		last_error: Option<&str>,
+	) -> model::Result<()> {
+		let sql = format!(
+			"UPDATE {} SET state = $2, run_at = $3, attempts = $4, last_error = $5,
+			   locked_by = NULL, locked_at = NULL, mid = $6, mtime = $7
+			 WHERE id = $1 AND state = $8 AND locked_by = $9",
+			Self::TABLE
+		);
+		let sqlx_query = sqlx::query(&sql)
+			.bind(id)
+			.bind(state)
+			.bind(run_at)
+			.bind(attempts)
+			.bind(last_error)
+			.bind(ctx.user_id())
+			.bind(now_utc())
+			.bind(JobState::Running)
+			.bind(worker_id);
+		mm.dbx().execute(sqlx_query).await?;
+
+		Ok(())
+	}
+}
+
+fn backoff(attempts: i32) -> Duration {
+	let exp = attempts.saturating_sub(1).clamp(0, 20) as u32;
+	let sec = JOB_BACKOFF_BASE_SEC.saturating_mul(2_i64.pow(exp));
+	Duration::seconds(sec.min(JOB_BACKOFF_MAX_SEC))
+}
+
+fn next_cron_time(cron: &str, after: OffsetDateTime) -> model::Result<OffsetDateTime> {
+	let invalid = |cause: String| Error::JobCronInvalid {
+		cron: cron.to_string(),
+		cause,
+	};
+
+	CronSchedule::parse(cron)
+		.map_err(|ex| invalid(ex.to_string()))?
+		.next_after(after)
+		.ok_or_else(|| invalid("no next time".to_string()))
+}
+
+// endregion: --- JobBmc
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_claim_retry_dead_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_queue = "test_claim_retry_dead_ok queue";
+		let job_id = JobBmc::enqueue(
+			&ctx,
+			&mm,
+			JobForCreate {
+				queue: Some(fx_queue.to_string()),
+				kind: "noop".to_string(),
+				max_attempts: Some(2),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Exec & Check - first attempt fails, retried later
+		let jobs = JobBmc::claim(&ctx, &mm, fx_queue, "worker-01", 10).await?;
+		assert_eq!(jobs.len(), 1);
+		assert_eq!(jobs[0].state, JobState::Running);
+		assert_eq!(jobs[0].attempts, 1);
+		assert!(
+			JobBmc::claim(&ctx, &mm, fx_queue, "worker-02", 10).await?.is_empty(),
+			"running job should not be claimed again"
+		);
+		JobBmc::fail(&ctx, &mm, "worker-01", &jobs[0], "boom 01").await?;
+		let job = JobBmc::get(&ctx, &mm, job_id).await?;
+		assert_eq!(job.state, JobState::Pending);
+		assert!(job.run_at > now_utc(), "retry should be backed off");
+
+		// -- Exec & Check - last attempt fails, dead-lettered
+		let sql = "UPDATE job SET run_at = $2 WHERE id = $1";
+		let sqlx_query = sqlx::query(sql).bind(job_id).bind(now_utc());
+		mm.dbx().execute(sqlx_query).await?;
+		let jobs = JobBmc::claim(&ctx, &mm, fx_queue, "worker-01", 10).await?;
+		assert_eq!(jobs[0].attempts, 2);
+		JobBmc::fail(&ctx, &mm, "worker-01", &jobs[0], "boom 02").await?;
+		let job = JobBmc::get(&ctx, &mm, job_id).await?;
+		assert_eq!(job.state, JobState::Dead);
+		assert_eq!(job.last_error.as_deref(), Some("boom 02"));
+
+		// -- Exec & Check - retry (from the dead-letter)
+		JobBmc::retry(&ctx, &mm, job_id).await?;
+		let jobs = JobBmc::claim(&ctx, &mm, fx_queue, "worker-01", 10).await?;
+		assert_eq!(jobs[0].attempts, 1);
+		JobBmc::succeed(&ctx, &mm, "worker-01", &jobs[0]).await?;
+		let job = JobBmc::get(&ctx, &mm, job_id).await?;
+		assert_eq!(job.state, JobState::Succeeded);
+
+		// -- Clean
+		JobBmc::delete(&ctx, &mm, job_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_release_stale_lock_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_queue = "test_release_stale_lock_ok queue";
+		let job_id = JobBmc::enqueue(
+			&ctx,
+			&mm,
+			JobForCreate {
+				queue: Some(fx_queue.to_string()),
+				kind: "noop".to_string(),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Exec - worker-01 lock is stale, and the job is claimed by worker-02
+		let stale_jobs = JobBmc::claim(&ctx, &mm, fx_queue, "worker-01", 10).await?;
+		let requeued = JobBmc::requeue_stale(&ctx, &mm, Duration::seconds(-1)).await?;
+		let jobs = JobBmc::claim(&ctx, &mm, fx_queue, "worker-02", 10).await?;
+		JobBmc::succeed(&ctx, &mm, "worker-01", &stale_jobs[0]).await?;
+
+		// -- Check - the stale worker does not release the job
+		assert!(requeued >= 1, "should requeue the stale job");
+		assert_eq!(jobs.len(), 1);
+		let job = JobBmc::get(&ctx, &mm, job_id).await?;
+		assert_eq!(job.state, JobState::Running);
+		assert_eq!(job.locked_by.as_deref(), Some("worker-02"));
+
+		// -- Exec & Check - the lock owner releases the job
+		JobBmc::succeed(&ctx, &mm, "worker-02", &jobs[0]).await?;
+		let job = JobBmc::get(&ctx, &mm, job_id).await?;
+		assert_eq!(job.state, JobState::Succeeded);
+
+		// -- Clean
+		JobBmc::delete(&ctx, &mm, job_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_own_list_own_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let fx_queue = "test_get_own_list_own_ok queue";
+		let fx_user_ids = _dev_utils::seed_users(
+			&root_ctx,
+			&mm,
+			&["test_get_own_list_own_ok user 01", "test_get_own_list_own_ok user 02"],
+		)
+		.await?;
+		let fx_ctx_01 = Ctx::new(fx_user_ids[0])?;
+		let fx_ctx_02 = Ctx::new(fx_user_ids[1])?;
+		let fx_job_c = || JobForCreate {
+			queue: Some(fx_queue.to_string()),
+			kind: "noop".to_string(),
+			..Default::default()
+		};
+		let job_id_01 = JobBmc::enqueue(&fx_ctx_01, &mm, fx_job_c()).await?;
+		let job_id_02 = JobBmc::enqueue(&fx_ctx_02, &mm, fx_job_c()).await?;
+		let fx_filters = || {
+			Some(vec![JobFilter {
+				queue: Some(fx_queue.into()),
+				..Default::default()
+			}])
+		};
+
+		// -- Exec & Check - user, own jobs only
+		assert_eq!(JobBmc::get_own(&fx_ctx_01, &mm, job_id_01).await?.id, job_id_01);
+		let res = JobBmc::get_own(&fx_ctx_01, &mm, job_id_02).await;
+		assert!(
+			matches!(res, Err(model::Error::EntityNotFound { .. })),
+			"should be EntityNotFound, but was {res:?}"
+		);
+		let res = JobBmc::cancel(&fx_ctx_01, &mm, job_id_02).await;
+		assert!(
+			matches!(res, Err(model::Error::EntityNotFound { .. })),
+			"cancel should be EntityNotFound, but was {res:?}"
+		);
+		let jobs = JobBmc::list_own(&fx_ctx_01, &mm, fx_filters(), None).await?;
+		let job_ids: Vec<i64> = jobs.iter().map(|job| job.id).collect();
+		assert_eq!(job_ids, [job_id_01]);
+
+		// -- Exec & Check - sys user, all jobs
+		assert_eq!(JobBmc::get_own(&root_ctx, &mm, job_id_02).await?.id, job_id_02);
+		let jobs = JobBmc::list_own(&root_ctx, &mm, fx_filters(), None).await?;
+		assert_eq!(jobs.len(), 2);
+
+		// -- Clean
+		JobBmc::delete(&root_ctx, &mm, job_id_01).await?;
+		JobBmc::delete(&root_ctx, &mm, job_id_02).await?;
+		_dev_utils::clean_users(&root_ctx, &mm, "test_get_own_list_own_ok").await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_enqueue_cron_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_queue = "test_enqueue_cron_ok queue";
+
+		// -- Exec
+		let job_id = JobBmc::enqueue(
+			&ctx,
+			&mm,
+			JobForCreate {
+				queue: Some(fx_queue.to_string()),
+				kind: "noop".to_string(),
+				cron: Some("*/15 * * * *".to_string()),
+				..Default::default()
+			},
+		)
+		.await?;
+		let res = JobBmc::enqueue(
+			&ctx,
+			&mm,
+			JobForCreate {
+				kind: "noop".to_string(),
+				cron: Some("*/15 * *".to_string()),
+				..Default::default()
+			},
+		)
+		.await;
+
+		// -- Check
+		let job = JobBmc::get(&ctx, &mm, job_id).await?;
+		assert_eq!(job.run_at.minute() % 15, 0);
+		assert!(job.run_at > now_utc());
+		assert!(
+			matches!(res, Err(model::Error::JobCronInvalid { .. })),
+			"should be JobCronInvalid, but was {res:?}"
+		);
+
+		// -- Clean
+		JobBmc::delete(&ctx, &mm, job_id).await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,225 @@
+use crate::ctx::Ctx;
+use crate::model::base::{self, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::user::UserBmc;
+use crate::model::ModelManager;
+use crate::model::Result;
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- LlmPrice Types
+
+/// Price of a provider/model, in USD per 1M tokens.
+/// Used to compute the `UsageEvent` cost.
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct LlmPrice {
+	pub id: i64,
+
+	// -- Properties
+	pub provider: String,
+	pub model: String,
+	pub input_price: f64,
+	pub output_price: f64,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub mtime: OffsetDateTime,
+}
+
+impl LlmPrice {
+	/// Returns the cost (in USD) of the given token counts.
+	pub fn cost(&self, input_tokens: i32, output_tokens: i32) -> f64 {
+		(input_tokens as f64 * self.input_price
+			+ output_tokens as f64 * self.output_price)
+			/ 1_000_000.
+	}
+}
+
+#[derive(Fields, Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct LlmPriceForCreate {
+	pub provider: String,
+	pub model: String,
+	pub input_price: f64,
+	pub output_price: f64,
+}
+
+#[derive(Fields, Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct LlmPriceForUpdate {
+	pub input_price: Option<f64>,
+	pub output_price: Option<f64>,
+}
+
+#[derive(FilterNodes, Default, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct LlmPriceFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub provider: Option<OpValsString>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub model: Option<OpValsString>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub mtime: Option<OpValsValue>,
+}
+
+// endregion: --- LlmPrice Types
+
+// region:    --- LlmPriceBmc
+
+pub struct LlmPriceBmc;
+
+impl DbBmc for LlmPriceBmc {
+	const TABLE: &'static str = "llm_price";
+}
+
+// Note: Not using the `generate_common_bmc_fns!` since the write functions
+//       are restricted to `Sys` users (the prices are the cost of all the users).
+impl LlmPriceBmc {
+	/// (`Sys` user only)
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		price_c: LlmPriceForCreate,
+	) -> Result<i64> {
+		UserBmc::ensure_sys_user(ctx, mm).await?;
+
+		base::create::<Self, _>(ctx, mm, price_c).await
+	}
+
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<LlmPrice> {
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<LlmPriceFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<LlmPrice>> {
+		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// (`Sys` user only)
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		price_u: LlmPriceForUpdate,
+	) -> Result<()> {
+		UserBmc::ensure_sys_user(ctx, mm).await?;
+
+		base::update::<Self, _>(ctx, mm, id, price_u).await
+	}
+
+	/// (`Sys` user only)
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		UserBmc::ensure_sys_user(ctx, mm).await?;
+
+		base::delete::<Self>(ctx, mm, id).await
+	}
+
+	/// Returns the price of the provider/model, if any.
+	pub async fn first_by_model(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		provider: &str,
+		model: &str,
+	) -> Result<Option<LlmPrice>> {
+		let filter = LlmPriceFilter {
+			provider: Some(provider.into()),
+			model: Some(model.into()),
+			..Default::default()
+		};
+
+		base::first::<Self, _, _>(ctx, mm, Some(vec![filter]), None).await
+	}
+}
+
+// endregion: --- LlmPriceBmc
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::Error as ModelError;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_write_sys_user_only() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let fx_user_id =
+			_dev_utils::seed_user(&root_ctx, &mm, "test_write_sys_user_only user 01").await?;
+		let fx_ctx = Ctx::new(fx_user_id)?;
+		let fx_price_c = || LlmPriceForCreate {
+			provider: "test_write_sys_user_only".to_string(),
+			model: "model-01".to_string(),
+			input_price: 1.,
+			output_price: 2.,
+		};
+		let fx_price_u = || LlmPriceForUpdate {
+			input_price: Some(0.),
+			output_price: None,
+		};
+		let price_id = LlmPriceBmc::create(&root_ctx, &mm, fx_price_c()).await?;
+
+		// -- Exec
+		let create_res = LlmPriceBmc::create(&fx_ctx, &mm, fx_price_c()).await;
+		let update_res = LlmPriceBmc::update(&fx_ctx, &mm, price_id, fx_price_u()).await;
+		let delete_res = LlmPriceBmc::delete(&fx_ctx, &mm, price_id).await;
+
+		// -- Check
+		for res in [create_res.map(|_| ()), update_res, delete_res] {
+			assert!(
+				matches!(res, Err(ModelError::SysUserRequired { .. })),
+				"should be SysUserRequired, but was {res:?}"
+			);
+		}
+		let price = LlmPriceBmc::get(&fx_ctx, &mm, price_id).await?;
+		assert_eq!(price.input_price, 1.);
+
+		// -- Clean
+		LlmPriceBmc::delete(&root_ctx, &mm, price_id).await?;
+		_dev_utils::clean_users(&root_ctx, &mm, "test_write_sys_user_only").await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -25,16 +25,32 @@ mod error;
 mod store;
 
 pub mod agent;
+pub mod attachment;
+pub mod chunk;
 pub mod conv;
+pub mod conv_event;
 pub mod conv_msg;
+pub mod conv_summary;
 pub mod conv_user;
+pub mod document;
+pub mod job;
+pub mod llm_price;
 pub mod modql_utils;
+pub mod outbox;
+pub mod quota;
+pub mod usage_event;
 pub mod user;
+pub mod webhook;
 
 pub use self::error::{Error, Result};
+pub use self::store::blob;
 
+use crate::core_config;
+use crate::model::store::blob::{BlobStore, FsBlobStore};
 use crate::model::store::dbx::Dbx;
 use crate::model::store::new_db_pool;
+use lib_events::{EventBus, PgEventBus};
+use std::sync::Arc;
 
 // endregion: --- Modules
 
@@ -44,6 +60,8 @@ use crate::model::store::new_db_pool;
 #[derive(Clone)]
 pub struct ModelManager {
 	dbx: Dbx,
+	blob_store: Arc<dyn BlobStore>,
+	event_bus: Arc<dyn EventBus>,
 }
 
 impl ModelManager {
@@ -52,18 +70,185 @@ impl ModelManager {
 		let db_pool = new_db_pool()
 			.await
 			.map_err(|ex| Error::CantCreateModelManagerProvider(ex.to_string()))?;
+		let event_bus = Arc::new(PgEventBus::new(db_pool.clone()));
 		let dbx = Dbx::new(db_pool, false)?;
-		Ok(ModelManager { dbx })
+		let blob_store = Arc::new(FsBlobStore::new(&core_config().BLOB_DIR));
+		Ok(ModelManager {
+			dbx,
+			blob_store,
+			event_bus,
+		})
 	}
 
+	/// Returns a ModelManager sharing the same db pool, but with another blob store
+	/// (e.g., `MemBlobStore` for tests).
+	pub fn with_blob_store(&self, blob_store: Arc<dyn BlobStore>) -> ModelManager {
+		ModelManager {
+			dbx: self.dbx.clone(),
+			blob_store,
+			event_bus: self.event_bus.clone(),
+		}
+	}
+
+	/// Returns a ModelManager sharing the same db pool, but with another event bus
+	/// (e.g., `MemEventBus` for tests).
+	pub fn with_event_bus(&self, event_bus: Arc<dyn EventBus>) -> ModelManager {
+		ModelManager {
+			dbx: self.dbx.clone(),
+			blob_store: self.blob_store.clone(),
+			event_bus,
+		}
+	}
+
+	/// Returns a ModelManager with its own transaction (see `Dbx::begin_txn`).
+	///
+	/// Note: When already with a transaction, returns a clone sharing it, so that the nested
+	///       writes (e.g., `ConvBmc::create` in `ConvBmc::fork_conv`) join the outer transaction.
 	pub fn new_with_txn(&self) -> Result<ModelManager> {
+		if self.dbx.with_txn() {
+			return Ok(self.clone());
+		}
+
 		let dbx = Dbx::new(self.dbx.db().clone(), true)?;
-		Ok(ModelManager { dbx })
+		Ok(ModelManager {
+			dbx,
+			blob_store: self.blob_store.clone(),
+			event_bus: self.event_bus.clone(),
+		})
 	}
 
 	pub fn dbx(&self) -> &Dbx {
 		&self.dbx
 	}
+
+	/// Check that the db answers (e.g., for the readiness health check).
+	pub async fn ping(&self) -> Result<()> {
+		self.dbx.execute(sqlx::query("SELECT 1")).await?;
+		Ok(())
+	}
+
+	pub fn blob_store(&self) -> &dyn BlobStore {
+		self.blob_store.as_ref()
+	}
+
+	/// Note: Returns the `Arc`, so that consumers can hold it (e.g., `EventConsumer`).
+	pub fn event_bus(&self) -> &Arc<dyn EventBus> {
+		&self.event_bus
+	}
 }
 
 // endregion: --- ModelManager
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils;
+	use lib_events::{CloudEvent, Subscription};
+	use serde_json::json;
+	use serial_test::serial;
+	use std::time::Duration;
+
+	/// The pg event bus of `mm`, with a zero ack timeout (i.e., not acked is redelivered).
+	fn fx_pg_bus(mm: &ModelManager) -> PgEventBus {
+		PgEventBus::new(mm.dbx().db().clone()).with_ack_timeout(time::Duration::ZERO)
+	}
+
+	async fn fx_event_exists(mm: &ModelManager, event: &CloudEvent) -> Result<bool> {
+		let (count,): (i64,) = sqlx::query_as("SELECT count(*) FROM event WHERE ce_id = $1")
+			.bind(&event.id)
+			.fetch_one(mm.dbx().db())
+			.await?;
+		Ok(count > 0)
+	}
+
+	async fn fx_clean_subscription(mm: &ModelManager, name: &str) -> Result<()> {
+		sqlx::query("DELETE FROM event_subscription WHERE name = $1")
+			.bind(name)
+			.execute(mm.dbx().db())
+			.await?;
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_pg_event_bus_publish_receive_ack_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let bus = fx_pg_bus(&mm);
+		let fx_sub = "test_pg_event_bus_publish_receive_ack_ok";
+		bus.subscribe(Subscription::new(fx_sub).with_type("test.pg.msg."))
+			.await?;
+		let fx_data = json!({"id": 1000});
+		let fx_event = CloudEvent::new("/test", "test.pg.msg.created", &fx_data)?;
+
+		// -- Exec
+		bus.publish(fx_event.clone()).await?;
+		// republished (e.g., by the outbox relay), should not be delivered twice
+		bus.publish(fx_event.clone()).await?;
+		bus.publish(CloudEvent::new("/test", "test.pg.conv.updated", &fx_data)?)
+			.await?;
+		let deliveries = bus.receive(fx_sub, 10).await?;
+
+		// -- Check
+		assert_eq!(deliveries.len(), 1, "should only have the msg event, once");
+		assert_eq!(deliveries[0].event.id, fx_event.id);
+		assert_eq!(deliveries[0].event.data_as::<serde_json::Value>()?, fx_data);
+		// not acked (and zero ack timeout), so redelivered
+		let deliveries = bus.receive(fx_sub, 10).await?;
+		assert_eq!(deliveries[0].attempts, 2);
+		bus.ack(&deliveries[0]).await?;
+		assert!(bus.receive(fx_sub, 10).await?.is_empty());
+
+		// -- Clean
+		fx_clean_subscription(&mm, fx_sub).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_pg_event_bus_dead_letter_purge_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let bus = fx_pg_bus(&mm);
+		let fx_sub = "test_pg_event_bus_dead_letter_purge_ok";
+		bus.subscribe(Subscription::new(fx_sub).with_type("test.pg.dead."))
+			.await?;
+		let fx_data = json!({"id": 1000});
+		let fx_dead_event = CloudEvent::new("/test", "test.pg.dead.01", &fx_data)?;
+		let fx_pending_event = CloudEvent::new("/test", "test.pg.dead.02", &fx_data)?;
+		bus.publish(fx_dead_event.clone()).await?;
+		bus.publish(fx_pending_event.clone()).await?;
+
+		// -- Exec
+		let deliveries = bus.receive(fx_sub, 10).await?;
+		bus.dead_letter(&deliveries[0], "handler failed").await?;
+		let deliveries = bus.receive(fx_sub, 10).await?;
+		bus.purge(Duration::from_secs(60 * 60)).await?;
+		let within_retention = fx_event_exists(&mm, &fx_dead_event).await?;
+		bus.purge(Duration::ZERO).await?;
+
+		// -- Check
+		assert_eq!(deliveries.len(), 1, "dead-lettered should not be delivered");
+		assert_eq!(deliveries[0].event.id, fx_pending_event.id);
+		assert!(within_retention, "should be kept within the retention");
+		assert!(!fx_event_exists(&mm, &fx_dead_event).await?, "should be purged");
+		assert!(
+			fx_event_exists(&mm, &fx_pending_event).await?,
+			"pending delivery should not be purged"
+		);
+
+		// -- Clean
+		fx_clean_subscription(&mm, fx_sub).await?;
+		bus.purge(Duration::ZERO).await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
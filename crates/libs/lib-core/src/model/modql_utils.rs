@@ -5,3 +5,151 @@ pub fn time_to_sea_value(
 ) -> modql::filter::SeaResult<sea_query::Value> {
 	Ok(rfc3339::deserialize(json_value)?.into())
 }
+
+// region:    --- Filter Schemas
+
+// The JSON Schemas of the modql `OpVals...` filter properties (as deserialized by modql),
+// for the `JsonSchema` of the entity filters, e.g.:
+//
+// #[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+// pub title: Option<OpValsString>,
+//
+// Note: The time properties (`OpValsValue` with `time_to_sea_value`) are rfc3339 strings,
+//       with the `OpValsStringSchema`.
+
+#[cfg(feature = "with-rpc")]
+pub use self::filter_schemas::*;
+
+#[cfg(feature = "with-rpc")]
+mod filter_schemas {
+	use schemars::JsonSchema;
+
+	/// A string filter, the value (i.e., `$eq`) or the operators
+	/// (e.g., `{"$startsWith": "Hello", "$notContains": "World"}`).
+	#[derive(JsonSchema)]
+	#[serde(untagged)]
+	#[schemars(rename = "OpValsString")]
+	pub enum OpValsStringSchema {
+		Value(String),
+		Ops(Box<OpValStringSchema>),
+	}
+
+	#[derive(JsonSchema)]
+	#[schemars(rename = "OpValString")]
+	pub struct OpValStringSchema {
+		#[serde(rename = "$eq")]
+		pub eq: Option<String>,
+		#[serde(rename = "$not")]
+		pub not: Option<String>,
+		#[serde(rename = "$in")]
+		pub in_: Option<Vec<String>>,
+		#[serde(rename = "$notIn")]
+		pub not_in: Option<Vec<String>>,
+		#[serde(rename = "$lt")]
+		pub lt: Option<String>,
+		#[serde(rename = "$lte")]
+		pub lte: Option<String>,
+		#[serde(rename = "$gt")]
+		pub gt: Option<String>,
+		#[serde(rename = "$gte")]
+		pub gte: Option<String>,
+		#[serde(rename = "$contains")]
+		pub contains: Option<String>,
+		#[serde(rename = "$notContains")]
+		pub not_contains: Option<String>,
+		#[serde(rename = "$containsAny")]
+		pub contains_any: Option<Vec<String>>,
+		#[serde(rename = "$notContainsAny")]
+		pub not_contains_any: Option<Vec<String>>,
+		#[serde(rename = "$containsAll")]
+		pub contains_all: Option<Vec<String>>,
+		#[serde(rename = "$startsWith")]
+		pub starts_with: Option<String>,
+		#[serde(rename = "$notStartsWith")]
+		pub not_starts_with: Option<String>,
+		#[serde(rename = "$startsWithAny")]
+		pub starts_with_any: Option<Vec<String>>,
+		#[serde(rename = "$notStartsWithAny")]
+		pub not_starts_with_any: Option<Vec<String>>,
+		#[serde(rename = "$endsWith")]
+		pub ends_with: Option<String>,
+		#[serde(rename = "$notEndsWith")]
+		pub not_ends_with: Option<String>,
+		#[serde(rename = "$endsWithAny")]
+		pub ends_with_any: Option<Vec<String>>,
+		#[serde(rename = "$notEndsWithAny")]
+		pub not_ends_with_any: Option<Vec<String>>,
+		#[serde(rename = "$containsCi")]
+		pub contains_ci: Option<String>,
+		#[serde(rename = "$notContainsCi")]
+		pub not_contains_ci: Option<String>,
+		#[serde(rename = "$startsWithCi")]
+		pub starts_with_ci: Option<String>,
+		#[serde(rename = "$notStartsWithCi")]
+		pub not_starts_with_ci: Option<String>,
+		#[serde(rename = "$endsWithCi")]
+		pub ends_with_ci: Option<String>,
+		#[serde(rename = "$notEndsWithCi")]
+		pub not_ends_with_ci: Option<String>,
+		#[serde(rename = "$ilike")]
+		pub ilike: Option<String>,
+		#[serde(rename = "$empty")]
+		pub empty: Option<bool>,
+		#[serde(rename = "$null")]
+		pub null: Option<bool>,
+	}
+
+	/// An integer filter, the value (i.e., `$eq`) or the operators (e.g., `{"$gt": 100}`).
+	#[derive(JsonSchema)]
+	#[serde(untagged)]
+	#[schemars(rename = "OpValsInt64")]
+	pub enum OpValsInt64Schema {
+		Value(i64),
+		Ops(OpValInt64Schema),
+	}
+
+	#[derive(JsonSchema)]
+	#[schemars(rename = "OpValInt64")]
+	pub struct OpValInt64Schema {
+		#[serde(rename = "$eq")]
+		pub eq: Option<i64>,
+		#[serde(rename = "$not")]
+		pub not: Option<i64>,
+		#[serde(rename = "$in")]
+		pub in_: Option<Vec<i64>>,
+		#[serde(rename = "$notIn")]
+		pub not_in: Option<Vec<i64>>,
+		#[serde(rename = "$lt")]
+		pub lt: Option<i64>,
+		#[serde(rename = "$lte")]
+		pub lte: Option<i64>,
+		#[serde(rename = "$gt")]
+		pub gt: Option<i64>,
+		#[serde(rename = "$gte")]
+		pub gte: Option<i64>,
+		#[serde(rename = "$null")]
+		pub null: Option<bool>,
+	}
+
+	/// A boolean filter, the value (i.e., `$eq`) or the operators (e.g., `{"$not": true}`).
+	#[derive(JsonSchema)]
+	#[serde(untagged)]
+	#[schemars(rename = "OpValsBool")]
+	pub enum OpValsBoolSchema {
+		Value(bool),
+		Ops(OpValBoolSchema),
+	}
+
+	#[derive(JsonSchema)]
+	#[schemars(rename = "OpValBool")]
+	pub struct OpValBoolSchema {
+		#[serde(rename = "$eq")]
+		pub eq: Option<bool>,
+		#[serde(rename = "$not")]
+		pub not: Option<bool>,
+		#[serde(rename = "$null")]
+		pub null: Option<bool>,
+	}
+}
+
+// endregion: --- Filter Schemas
@@ -0,0 +1,199 @@
+//! Transactional outbox of the model domain events.
+//!
+//! - The model writes add their events to the `outbox` table with the same `Dbx`
+//!   (i.e., in the same transaction), so that a rolled back write has no event,
+//!   and a committed write has its event.
+//! - The `OutboxRelay` (see `crate::outbox_relay`) publishes the pending entries,
+//!   in id order, to an `OutboxSink`, and marks them sent (i.e., at-least-once delivery).
+//! - An entry failing `max_attempts` times is dead-lettered (`dead_at`), so that it does not
+//!   block the entries after it. It stays in the table for inspection (and manual replay,
+//!   by resetting its `dead_at`).
+
+use crate::ctx::Ctx;
+use crate::model::base::DbBmc;
+use crate::model::ModelManager;
+use crate::model::Result;
+use lib_events::CloudEvent;
+use lib_utils::time::{now_utc, Rfc3339};
+use serde::Serialize;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::types::Json;
+use sqlx::FromRow;
+
+// region:    --- Outbox Types
+
+#[serde_as]
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct OutboxEntry {
+	pub id: i64,
+
+	// -- Properties
+	pub event_type: String,
+	pub event: Json<CloudEvent>,
+
+	// -- Delivery
+	pub attempts: i32,
+	pub last_error: Option<String>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	pub sent_at: Option<OffsetDateTime>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	pub dead_at: Option<OffsetDateTime>,
+
+	// -- Timestamps
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+}
+
+// endregion: --- Outbox Types
+
+// region:    --- OutboxBmc
+
+pub struct OutboxBmc;
+
+impl DbBmc for OutboxBmc {
+	const TABLE: &'static str = "outbox";
+}
+
+impl OutboxBmc {
+	/// Add the event to the outbox.
+	///
+	/// Note: Must be called with the `mm` of the write transaction (see `ModelManager::new_with_txn`).
+	pub async fn add(ctx: &Ctx, mm: &ModelManager, event: CloudEvent) -> Result<i64> {
+		let sql = format!(
+			"INSERT INTO {table} (event_type, event, cid, ctime)
+			 VALUES ($1, $2, $3, $4)
+			 RETURNING id",
+			table = Self::TABLE
+		);
+		let sqlx_query = sqlx::query_as::<_, (i64,)>(&sql)
+			.bind(event.typ.clone())
+			.bind(Json(event))
+			.bind(ctx.user_id())
+			.bind(now_utc());
+		let (id,) = mm.dbx().fetch_one(sqlx_query).await?;
+
+		Ok(id)
+	}
+
+	pub async fn get(_ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Option<OutboxEntry>> {
+		let sql = format!("SELECT * FROM {table} WHERE id = $1", table = Self::TABLE);
+		let sqlx_query = sqlx::query_as::<_, OutboxEntry>(&sql).bind(id);
+
+		Ok(mm.dbx().fetch_optional(sqlx_query).await?)
+	}
+
+	/// Returns the first `limit` pending (not sent, nor dead) entries, in id order,
+	/// locked until the end of the `mm` transaction.
+	///
+	/// Note: Without `SKIP LOCKED`, so that concurrent relays wait on each other
+	///       rather than publishing out of order.
+	pub async fn lock_pending(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+		limit: i64,
+	) -> Result<Vec<OutboxEntry>> {
+		let sql = format!(
+			"SELECT * FROM {table}
+			 WHERE sent_at IS NULL AND dead_at IS NULL
+			 ORDER BY id
+			 LIMIT $1
+			 FOR UPDATE",
+			table = Self::TABLE
+		);
+		let sqlx_query = sqlx::query_as::<_, OutboxEntry>(&sql).bind(limit);
+
+		Ok(mm.dbx().fetch_all(sqlx_query).await?)
+	}
+
+	pub async fn mark_sent(_ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		let sql = format!(
+			"UPDATE {table} SET sent_at = $2, attempts = attempts + 1, last_error = NULL
+			 WHERE id = $1",
+			table = Self::TABLE
+		);
+		let sqlx_query = sqlx::query(&sql).bind(id).bind(now_utc());
+		mm.dbx().execute(sqlx_query).await?;
+
+		Ok(())
+	}
+
+	/// Record a failed publish. The entry stays pending (retried by the relay),
+	/// or is dead-lettered once it reached `max_attempts`.
+	///
+	/// Returns true if the entry was dead-lettered.
+	pub async fn mark_failed(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		error: &str,
+		max_attempts: i32,
+	) -> Result<bool> {
+		let sql = format!(
+			"UPDATE {table} SET attempts = attempts + 1, last_error = $2,
+			   dead_at = CASE WHEN attempts + 1 >= $3 THEN $4 END
+			 WHERE id = $1
+			 RETURNING dead_at IS NOT NULL",
+			table = Self::TABLE
+		);
+		let sqlx_query = sqlx::query_as::<_, (bool,)>(&sql)
+			.bind(id)
+			.bind(error)
+			.bind(max_attempts)
+			.bind(now_utc());
+		let (dead,) = mm.dbx().fetch_one(sqlx_query).await?;
+
+		Ok(dead)
+	}
+}
+
+// endregion: --- OutboxBmc
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils;
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_outbox_rollback_no_entry_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_event = CloudEvent::new("/test", "test.outbox", &json!({"value": 1}))?;
+
+		// -- Exec
+		let mm_txn = mm.new_with_txn()?;
+		mm_txn.dbx().begin_txn().await?;
+		let rolled_back_id = OutboxBmc::add(&ctx, &mm_txn, fx_event.clone()).await?;
+		mm_txn.dbx().rollback_txn().await?;
+
+		let mm_txn = mm.new_with_txn()?;
+		mm_txn.dbx().begin_txn().await?;
+		let committed_id = OutboxBmc::add(&ctx, &mm_txn, fx_event).await?;
+		mm_txn.dbx().commit_txn().await?;
+
+		// -- Check
+		assert!(OutboxBmc::get(&ctx, &mm, rolled_back_id).await?.is_none());
+		let entry = OutboxBmc::get(&ctx, &mm, committed_id)
+			.await?
+			.ok_or("should have committed entry")?;
+		assert_eq!(entry.event_type, "test.outbox");
+		assert!(entry.sent_at.is_none());
+
+		// -- Clean
+		OutboxBmc::mark_sent(&ctx, &mm, committed_id).await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,542 @@
+use crate::ctx::Ctx;
+use crate::model::base::{self, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::usage_event::UsageEventBmc;
+use crate::model::user::{User, UserBmc, UserTyp};
+use crate::model::ModelManager;
+// Note: `model::Result` not imported (shadows the `Result` of the schemars derive code).
+use crate::model::{self, Error};
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
+use lib_utils::time::{now_utc, Rfc3339};
+use modql::field::{Fields, SeaFieldValue};
+use modql::filter::{FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use time::Duration;
+
+// region:    --- Quota Types
+
+/// Who the quota applies to.
+/// For `Org`, the usage of all the users of the org (`user.org_id`) is counted.
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	PartialEq,
+	sqlx::Type,
+	SeaFieldValue,
+	derive_more::Display,
+	Deserialize,
+	Serialize,
+)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+#[sqlx(type_name = "quota_scope")]
+pub enum QuotaScope {
+	User,
+	Org,
+}
+
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	PartialEq,
+	sqlx::Type,
+	SeaFieldValue,
+	derive_more::Display,
+	Deserialize,
+	Serialize,
+)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+#[sqlx(type_name = "quota_period")]
+pub enum QuotaPeriod {
+	/// UTC day
+	Daily,
+	/// UTC calendar month
+	Monthly,
+}
+
+impl QuotaPeriod {
+	/// Returns the (start, reset) times of the period containing `now`.
+	pub fn bounds(&self, now: OffsetDateTime) -> (OffsetDateTime, OffsetDateTime) {
+		let today = now.date();
+		match self {
+			QuotaPeriod::Daily => {
+				let start = today.midnight().assume_utc();
+				(start, start + Duration::days(1))
+			}
+			QuotaPeriod::Monthly => {
+				let first = today - Duration::days(today.day() as i64 - 1);
+				let next = first + Duration::days(32);
+				let next_first = next - Duration::days(next.day() as i64 - 1);
+				(
+					first.midnight().assume_utc(),
+					next_first.midnight().assume_utc(),
+				)
+			}
+		}
+	}
+}
+
+/// Token and/or cost (USD) caps of a scope for a period.
+/// A `None` cap is not enforced.
+///
+/// The `override_...` caps replace the base caps until `override_until`.
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct Quota {
+	pub id: i64,
+
+	// -- Properties
+	pub scope: QuotaScope,
+	pub scope_id: i64,
+	pub period: QuotaPeriod,
+	pub max_tokens: Option<i64>,
+	pub max_cost: Option<f64>,
+
+	// -- Temporary override
+	pub override_max_tokens: Option<i64>,
+	pub override_max_cost: Option<f64>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<String>"))]
+	pub override_until: Option<OffsetDateTime>,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub mtime: OffsetDateTime,
+}
+
+impl Quota {
+	/// Returns the (max_tokens, max_cost) in effect at `now`.
+	pub fn caps_at(&self, now: OffsetDateTime) -> (Option<i64>, Option<f64>) {
+		match self.override_until {
+			Some(until) if until > now => (
+				self.override_max_tokens.or(self.max_tokens),
+				self.override_max_cost.or(self.max_cost),
+			),
+			_ => (self.max_tokens, self.max_cost),
+		}
+	}
+}
+
+/// Set (create or replace) the caps of a scope/period.
+#[derive(Fields, Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct QuotaForSet {
+	#[field(cast_as = "quota_scope")]
+	pub scope: QuotaScope,
+	pub scope_id: i64,
+	#[field(cast_as = "quota_period")]
+	pub period: QuotaPeriod,
+	pub max_tokens: Option<i64>,
+	pub max_cost: Option<f64>,
+}
+
+/// Temporary caps, in effect until `until`.
+#[serde_as]
+#[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct QuotaOverride {
+	pub max_tokens: Option<i64>,
+	pub max_cost: Option<f64>,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub until: OffsetDateTime,
+}
+
+#[derive(Fields)]
+struct QuotaForUpdate {
+	max_tokens: Option<i64>,
+	max_cost: Option<f64>,
+}
+
+/// Note: All fields are set (even if None), so that an override clears the previous one.
+#[derive(Fields)]
+struct QuotaOverrideForUpdate {
+	override_max_tokens: Option<i64>,
+	override_max_cost: Option<f64>,
+	override_until: Option<OffsetDateTime>,
+}
+
+#[derive(FilterNodes, Clone, Default, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct QuotaFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub scope_id: Option<OpValsInt64>,
+
+	/// Note: Set by `QuotaBmc::list` to the ctx user scopes (but for `Sys` users).
+	#[serde(skip)]
+	#[modql(cast_as = "quota_scope")]
+	scope: Option<OpValsString>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub mtime: Option<OpValsValue>,
+}
+
+// endregion: --- Quota Types
+
+// region:    --- QuotaBmc
+
+pub struct QuotaBmc;
+
+impl DbBmc for QuotaBmc {
+	const TABLE: &'static str = "quota";
+}
+
+// Note: Not using the `generate_common_bmc_fns!` since the write functions
+//       are restricted to `Sys` users.
+impl QuotaBmc {
+	/// Create or replace the caps of `quota_s.scope/scope_id/period`.
+	/// (`Sys` user only)
+	pub async fn set(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		quota_s: QuotaForSet,
+	) -> model::Result<i64> {
+		UserBmc::ensure_sys_user(ctx, mm).await?;
+
+		let existing = Self::first_by_scope(
+			ctx,
+			mm,
+			quota_s.scope,
+			quota_s.scope_id,
+			quota_s.period,
+		)
+		.await?;
+
+		match existing {
+			Some(quota) => {
+				let quota_u = QuotaForUpdate {
+					max_tokens: quota_s.max_tokens,
+					max_cost: quota_s.max_cost,
+				};
+				base::update_all_fields::<Self, _>(ctx, mm, quota.id, quota_u)
+					.await?;
+				Ok(quota.id)
+			}
+			None => base::create::<Self, _>(ctx, mm, quota_s).await,
+		}
+	}
+
+	/// Grant temporary caps on the quota `id` (replacing any previous override).
+	/// (`Sys` user only)
+	pub async fn grant_override(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		quota_o: QuotaOverride,
+	) -> model::Result<()> {
+		UserBmc::ensure_sys_user(ctx, mm).await?;
+
+		let quota_u = QuotaOverrideForUpdate {
+			override_max_tokens: quota_o.max_tokens,
+			override_max_cost: quota_o.max_cost,
+			override_until: Some(quota_o.until),
+		};
+		base::update_all_fields::<Self, _>(ctx, mm, id, quota_u).await
+	}
+
+	/// The quota must be of the ctx user, or of its org (unless a `Sys` user).
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> model::Result<Quota> {
+		let quota: Quota = base::get::<Self, _>(ctx, mm, id).await?;
+
+		let user: User = UserBmc::get(ctx, mm, ctx.user_id()).await?;
+		if !matches!(user.typ, UserTyp::Sys)
+			&& !user_scopes(&user).contains(&(quota.scope, quota.scope_id))
+		{
+			return Err(Error::QuotaScopeDenied {
+				scope: quota.scope,
+				scope_id: quota.scope_id,
+			});
+		}
+
+		Ok(quota)
+	}
+
+	/// Returns the quotas of the ctx user and of its org (all for `Sys` users)
+	/// matching the filters.
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filters: Option<Vec<QuotaFilter>>,
+		list_options: Option<ListOptions>,
+	) -> model::Result<Vec<Quota>> {
+		let user: User = UserBmc::get(ctx, mm, ctx.user_id()).await?;
+		let filters = match user.typ {
+			UserTyp::Sys => filters,
+			UserTyp::User => {
+				let scopes = user_scopes(&user);
+				let filters = filters.unwrap_or_else(|| vec![QuotaFilter::default()]);
+				// Each filter node, once per scope (the nodes are OR'ed).
+				let scoped_filters = filters
+					.into_iter()
+					.flat_map(|filter| {
+						scopes.iter().map(move |(scope, scope_id)| QuotaFilter {
+							scope: Some(scope.to_string().into()),
+							scope_id: Some((*scope_id).into()),
+							..filter.clone()
+						})
+					})
+					.collect();
+				Some(scoped_filters)
+			}
+		};
+
+		base::list::<Self, _, _>(ctx, mm, filters, list_options).await
+	}
+
+	/// (`Sys` user only)
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> model::Result<()> {
+		UserBmc::ensure_sys_user(ctx, mm).await?;
+
+		base::delete::<Self>(ctx, mm, id).await
+	}
+
+	/// Check the quotas of the ctx user (and of its org) against the usage
+	/// of the current periods.
+	///
+	/// Returns `Error::QuotaExceeded` on the first exceeded cap.
+	pub async fn check(ctx: &Ctx, mm: &ModelManager) -> model::Result<()> {
+		let user: User = UserBmc::get(ctx, mm, ctx.user_id()).await?;
+
+		let now = now_utc();
+		for (scope, scope_id) in user_scopes(&user) {
+			let quotas = Self::list_by_scope(ctx, mm, scope, scope_id).await?;
+			for quota in quotas {
+				let (max_tokens, max_cost) = quota.caps_at(now);
+				if max_tokens.is_none() && max_cost.is_none() {
+					continue;
+				}
+
+				let (start, reset_time) = quota.period.bounds(now);
+				let (tokens, cost) =
+					UsageEventBmc::usage_since(ctx, mm, scope, scope_id, start)
+						.await?;
+
+				let exceeded = max_tokens.is_some_and(|max| tokens >= max)
+					|| max_cost.is_some_and(|max| cost >= max);
+				if exceeded {
+					return Err(Error::QuotaExceeded {
+						scope,
+						scope_id,
+						period: quota.period,
+						reset_time,
+					});
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Private helpers
+impl QuotaBmc {
+	async fn first_by_scope(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		scope: QuotaScope,
+		scope_id: i64,
+		period: QuotaPeriod,
+	) -> model::Result<Option<Quota>> {
+		let quotas = Self::list_by_scope(ctx, mm, scope, scope_id).await?;
+		Ok(quotas.into_iter().find(|q| q.period == period))
+	}
+
+	async fn list_by_scope(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+		scope: QuotaScope,
+		scope_id: i64,
+	) -> model::Result<Vec<Quota>> {
+		// Note: Raw sql for the enum bind (the FilterNodes does not cast enums).
+		let sql = format!(
+			"SELECT * FROM {} WHERE scope = $1 AND scope_id = $2 ORDER BY id",
+			Self::TABLE
+		);
+		let sqlx_query = sqlx::query_as::<_, Quota>(&sql).bind(scope).bind(scope_id);
+
+		Ok(mm.dbx().fetch_all(sqlx_query).await?)
+	}
+}
+
+/// The quota scopes of the user (the user, and its org if any).
+fn user_scopes(user: &User) -> Vec<(QuotaScope, i64)> {
+	let mut scopes = vec![(QuotaScope::User, user.id)];
+	if let Some(org_id) = user.org_id {
+		scopes.push((QuotaScope::Org, org_id));
+	}
+	scopes
+}
+
+// endregion: --- QuotaBmc
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils::{self, clean_users, seed_user};
+	use crate::model;
+	use crate::model::usage_event::UsageEventForCreate;
+	use serial_test::serial;
+	use time::Month;
+
+	#[test]
+	fn test_period_bounds_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_now = time::Date::from_calendar_date(2024, Month::December, 15)?
+			.with_hms(10, 30, 0)?
+			.assume_utc();
+
+		// -- Exec
+		let daily = QuotaPeriod::Daily.bounds(fx_now);
+		let monthly = QuotaPeriod::Monthly.bounds(fx_now);
+
+		// -- Check
+		assert_eq!(daily.0.to_string(), "2024-12-15 0:00:00.0 +00:00:00");
+		assert_eq!(daily.1.to_string(), "2024-12-16 0:00:00.0 +00:00:00");
+		assert_eq!(monthly.0.to_string(), "2024-12-01 0:00:00.0 +00:00:00");
+		assert_eq!(monthly.1.to_string(), "2025-01-01 0:00:00.0 +00:00:00");
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_check_quota_exceeded_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let fx_user_id =
+			seed_user(&root_ctx, &mm, "test_check_quota_exceeded_ok user 01").await?;
+		let fx_ctx = Ctx::new(fx_user_id)?;
+		let quota_id = QuotaBmc::set(
+			&root_ctx,
+			&mm,
+			QuotaForSet {
+				scope: QuotaScope::User,
+				scope_id: fx_user_id,
+				period: QuotaPeriod::Daily,
+				max_tokens: Some(100),
+				max_cost: None,
+			},
+		)
+		.await?;
+
+		// -- Exec
+		QuotaBmc::check(&fx_ctx, &mm).await?;
+		UsageEventBmc::create(
+			&fx_ctx,
+			&mm,
+			UsageEventForCreate {
+				agent_id: None,
+				conv_id: None,
+				provider: "test_check_quota_exceeded_ok".to_string(),
+				model: "model-01".to_string(),
+				input_tokens: 80,
+				output_tokens: 20,
+			},
+		)
+		.await?;
+		let res = QuotaBmc::check(&fx_ctx, &mm).await;
+
+		// -- Check
+		assert!(
+			matches!(&res, Err(model::Error::QuotaExceeded { .. })),
+			"should return a QuotaExceeded"
+		);
+		let res = QuotaBmc::delete(&fx_ctx, &mm, quota_id).await;
+		assert!(
+			matches!(&res, Err(model::Error::SysUserRequired { .. })),
+			"should return a SysUserRequired"
+		);
+
+		// -- Clean
+		QuotaBmc::delete(&root_ctx, &mm, quota_id).await?;
+		let sql = format!("DELETE FROM {} WHERE user_id = $1", UsageEventBmc::TABLE);
+		mm.dbx().execute(sqlx::query(&sql).bind(fx_user_id)).await?;
+		clean_users(&root_ctx, &mm, "test_check_quota_exceeded_ok").await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_get_scoped_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let fx_user_01 =
+			seed_user(&root_ctx, &mm, "test_list_get_scoped_ok user 01").await?;
+		let fx_user_02 =
+			seed_user(&root_ctx, &mm, "test_list_get_scoped_ok user 02").await?;
+		let mut quota_ids = Vec::new();
+		for scope_id in [fx_user_01, fx_user_02] {
+			let quota_s = QuotaForSet {
+				scope: QuotaScope::User,
+				scope_id,
+				period: QuotaPeriod::Daily,
+				max_tokens: Some(100),
+				max_cost: None,
+			};
+			quota_ids.push(QuotaBmc::set(&root_ctx, &mm, quota_s).await?);
+		}
+		let fx_ctx = Ctx::new(fx_user_01)?;
+
+		// -- Exec
+		let quotas = QuotaBmc::list(&fx_ctx, &mm, None, None).await?;
+		let own_res = QuotaBmc::get(&fx_ctx, &mm, quota_ids[0]).await;
+		let other_res = QuotaBmc::get(&fx_ctx, &mm, quota_ids[1]).await;
+		let sys_quotas = QuotaBmc::list(&root_ctx, &mm, None, None).await?;
+
+		// -- Check
+		assert_eq!(quotas.len(), 1, "should only list the user quota");
+		assert_eq!(quotas[0].scope_id, fx_user_01);
+		assert!(own_res.is_ok(), "should get the user quota");
+		assert!(
+			matches!(&other_res, Err(model::Error::QuotaScopeDenied { .. })),
+			"should return a QuotaScopeDenied"
+		);
+		assert!(
+			quota_ids
+				.iter()
+				.all(|id| sys_quotas.iter().any(|quota| quota.id == *id)),
+			"sys should list all the quotas"
+		);
+
+		// -- Clean
+		for quota_id in quota_ids {
+			QuotaBmc::delete(&root_ctx, &mm, quota_id).await?;
+		}
+		clean_users(&root_ctx, &mm, "test_list_get_scoped_ok").await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
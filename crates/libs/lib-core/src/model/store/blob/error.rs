@@ -0,0 +1,31 @@
+use derive_more::From;
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[serde_as]
+#[derive(Debug, Serialize, From)]
+pub enum Error {
+	BlobNotFound { key: String },
+	BlobKeyInvalid { key: String },
+
+	// -- Externals
+	#[from]
+	Io(#[serde_as(as = "DisplayFromStr")] std::io::Error),
+}
+
+// region:    --- Error Boilerplate
+
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+
+// endregion: --- Error Boilerplate
@@ -0,0 +1,53 @@
+use super::{validate_key, BlobStore, Error, Result};
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Local filesystem blob store. Each blob is a file at `root_dir/key`.
+pub struct FsBlobStore {
+	root_dir: PathBuf,
+}
+
+impl FsBlobStore {
+	pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+		FsBlobStore {
+			root_dir: root_dir.into(),
+		}
+	}
+
+	fn path_for(&self, key: &str) -> Result<PathBuf> {
+		validate_key(key)?;
+		Ok(self.root_dir.join(key))
+	}
+}
+
+#[async_trait]
+impl BlobStore for FsBlobStore {
+	async fn put(&self, key: &str, content: Vec<u8>) -> Result<()> {
+		let path = self.path_for(key)?;
+		if let Some(dir) = path.parent() {
+			tokio::fs::create_dir_all(dir).await?;
+		}
+		tokio::fs::write(path, content).await?;
+
+		Ok(())
+	}
+
+	async fn get(&self, key: &str) -> Result<Vec<u8>> {
+		let path = self.path_for(key)?;
+		tokio::fs::read(path).await.map_err(|ex| match ex.kind() {
+			ErrorKind::NotFound => Error::BlobNotFound {
+				key: key.to_string(),
+			},
+			_ => Error::Io(ex),
+		})
+	}
+
+	async fn delete(&self, key: &str) -> Result<()> {
+		let path = self.path_for(key)?;
+		match tokio::fs::remove_file(path).await {
+			Err(ex) if ex.kind() != ErrorKind::NotFound => Err(Error::Io(ex)),
+			_ => Ok(()),
+		}
+	}
+}
@@ -0,0 +1,90 @@
+use super::{validate_key, BlobStore, Error, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// In-memory blob store (for tests).
+#[derive(Default)]
+pub struct MemBlobStore {
+	blobs: RwLock<HashMap<String, Vec<u8>>>,
+}
+
+#[async_trait]
+impl BlobStore for MemBlobStore {
+	async fn put(&self, key: &str, content: Vec<u8>) -> Result<()> {
+		validate_key(key)?;
+		self.blobs.write().await.insert(key.to_string(), content);
+
+		Ok(())
+	}
+
+	async fn get(&self, key: &str) -> Result<Vec<u8>> {
+		validate_key(key)?;
+		self.blobs
+			.read()
+			.await
+			.get(key)
+			.cloned()
+			.ok_or_else(|| Error::BlobNotFound {
+				key: key.to_string(),
+			})
+	}
+
+	async fn delete(&self, key: &str) -> Result<()> {
+		validate_key(key)?;
+		self.blobs.write().await.remove(key);
+
+		Ok(())
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+
+	#[tokio::test]
+	async fn test_mem_put_get_delete_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let store = MemBlobStore::default();
+		let fx_key = "conv/1000/blob-01";
+		let fx_content = b"hello blob".to_vec();
+
+		// -- Exec
+		store.put(fx_key, fx_content.clone()).await?;
+		let content = store.get(fx_key).await?;
+		store.delete(fx_key).await?;
+
+		// -- Check
+		assert_eq!(content, fx_content);
+		assert!(
+			matches!(store.get(fx_key).await, Err(super::Error::BlobNotFound { .. })),
+			"should return a BlobNotFound"
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_key_invalid_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let store = MemBlobStore::default();
+
+		// -- Exec & Check
+		for fx_key in ["", "/abs/path", "conv/../../etc/passwd", "conv//x"] {
+			let res = store.put(fx_key, vec![]).await;
+			assert!(
+				matches!(res, Err(super::Error::BlobKeyInvalid { .. })),
+				"key {fx_key:?} should be invalid"
+			);
+		}
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
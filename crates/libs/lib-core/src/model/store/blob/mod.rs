@@ -0,0 +1,52 @@
+//! Blob store for binary content (e.g., conv msg attachments) that does not belong in the db.
+//!
+//! Design:
+//!
+//! - `BlobStore` is the trait held by the `ModelManager` (as `Arc<dyn BlobStore>`),
+//!   so that other stores (e.g., S3) can be added without changing the model layer.
+//! - Keys are relative, `/` separated paths (e.g., `conv/1000/<uuid>`),
+//!   and are validated by each store to prevent any escape from the store root.
+//! - `FsBlobStore` is the local filesystem implementation (default),
+//!   `MemBlobStore` is the in-memory one for tests.
+//!
+
+// region:    --- Modules
+
+mod error;
+mod fs;
+mod mem;
+
+pub use self::error::{Error, Result};
+pub use fs::FsBlobStore;
+pub use mem::MemBlobStore;
+
+use async_trait::async_trait;
+
+// endregion: --- Modules
+
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+	async fn put(&self, key: &str, content: Vec<u8>) -> Result<()>;
+
+	async fn get(&self, key: &str) -> Result<Vec<u8>>;
+
+	/// Note: Deleting a missing blob is not an error.
+	async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Validate that the key is a relative path without `.` or `..` components.
+fn validate_key(key: &str) -> Result<()> {
+	let valid = !key.is_empty()
+		&& !key.starts_with('/')
+		&& key
+			.split('/')
+			.all(|part| !part.is_empty() && part != "." && part != "..");
+
+	if valid {
+		Ok(())
+	} else {
+		Err(Error::BlobKeyInvalid {
+			key: key.to_string(),
+		})
+	}
+}
@@ -6,10 +6,11 @@ pub use error::{Error, Result};
 
 use crate::model::store::Db;
 use sqlx::query::{Query, QueryAs};
-use sqlx::{FromRow, IntoArguments, Pool, Postgres, Transaction};
+use sqlx::{Execute, FromRow, IntoArguments, Pool, Postgres, Transaction};
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tracing::{info_span, Instrument, Span};
 
 // endregion: --- Modules
 
@@ -128,6 +129,10 @@ impl Dbx {
 		}
 	}
 
+	pub fn with_txn(&self) -> bool {
+		self.with_txn
+	}
+
 	pub fn db(&self) -> &Pool<Postgres> {
 		&self.db_pool
 	}
@@ -140,16 +145,21 @@ impl Dbx {
 		O: for<'r> FromRow<'r, <Postgres as sqlx::Database>::Row> + Send + Unpin,
 		A: IntoArguments<'q, Postgres> + 'q,
 	{
-		let data = if self.with_txn {
-			let mut txh_g = self.txn_holder.lock().await;
-			if let Some(txn) = txh_g.as_deref_mut() {
-				query.fetch_one(txn.as_mut()).await?
+		let span = query_span(query.sql());
+		let data = async {
+			if self.with_txn {
+				let mut txh_g = self.txn_holder.lock().await;
+				if let Some(txn) = txh_g.as_deref_mut() {
+					query.fetch_one(txn.as_mut()).await
+				} else {
+					query.fetch_one(self.db()).await
+				}
 			} else {
-				query.fetch_one(self.db()).await?
+				query.fetch_one(self.db()).await
 			}
-		} else {
-			query.fetch_one(self.db()).await?
-		};
+		}
+		.instrument(span)
+		.await?;
 
 		Ok(data)
 	}
@@ -162,16 +172,21 @@ impl Dbx {
 		O: for<'r> FromRow<'r, <Postgres as sqlx::Database>::Row> + Send + Unpin,
 		A: IntoArguments<'q, Postgres> + 'q,
 	{
-		let data = if self.with_txn {
-			let mut txh_g = self.txn_holder.lock().await;
-			if let Some(txn) = txh_g.as_deref_mut() {
-				query.fetch_optional(txn.as_mut()).await?
+		let span = query_span(query.sql());
+		let data = async {
+			if self.with_txn {
+				let mut txh_g = self.txn_holder.lock().await;
+				if let Some(txn) = txh_g.as_deref_mut() {
+					query.fetch_optional(txn.as_mut()).await
+				} else {
+					query.fetch_optional(self.db()).await
+				}
 			} else {
-				query.fetch_optional(self.db()).await?
+				query.fetch_optional(self.db()).await
 			}
-		} else {
-			query.fetch_optional(self.db()).await?
-		};
+		}
+		.instrument(span)
+		.await?;
 
 		Ok(data)
 	}
@@ -184,16 +199,21 @@ impl Dbx {
 		O: for<'r> FromRow<'r, <Postgres as sqlx::Database>::Row> + Send + Unpin,
 		A: IntoArguments<'q, Postgres> + 'q,
 	{
-		let data = if self.with_txn {
-			let mut txh_g = self.txn_holder.lock().await;
-			if let Some(txn) = txh_g.as_deref_mut() {
-				query.fetch_all(txn.as_mut()).await?
+		let span = query_span(query.sql());
+		let data = async {
+			if self.with_txn {
+				let mut txh_g = self.txn_holder.lock().await;
+				if let Some(txn) = txh_g.as_deref_mut() {
+					query.fetch_all(txn.as_mut()).await
+				} else {
+					query.fetch_all(self.db()).await
+				}
 			} else {
-				query.fetch_all(self.db()).await?
+				query.fetch_all(self.db()).await
 			}
-		} else {
-			query.fetch_all(self.db()).await?
-		};
+		}
+		.instrument(span)
+		.await?;
 
 		Ok(data)
 	}
@@ -202,17 +222,33 @@ impl Dbx {
 	where
 		A: IntoArguments<'q, Postgres> + 'q,
 	{
-		let row_affected = if self.with_txn {
-			let mut txh_g = self.txn_holder.lock().await;
-			if let Some(txn) = txh_g.as_deref_mut() {
-				query.execute(txn.as_mut()).await?.rows_affected()
+		let span = query_span(query.sql());
+		let row_affected = async {
+			if self.with_txn {
+				let mut txh_g = self.txn_holder.lock().await;
+				if let Some(txn) = txh_g.as_deref_mut() {
+					query.execute(txn.as_mut()).await
+				} else {
+					query.execute(self.db()).await
+				}
 			} else {
-				query.execute(self.db()).await?.rows_affected()
+				query.execute(self.db()).await
 			}
-		} else {
-			query.execute(self.db()).await?.rows_affected()
-		};
+		}
+		.instrument(span)
+		.await?
+		.rows_affected();
 
 		Ok(row_affected)
 	}
 }
+
+/// The span of a query (child of the current one, e.g., the rpc span).
+fn query_span(sql: &str) -> Span {
+	info_span!(
+		"db.query",
+		otel.kind = "client",
+		db.system = "postgresql",
+		db.statement = sql,
+	)
+}
@@ -0,0 +1,460 @@
+use crate::ctx::Ctx;
+use crate::model::base::{self, DbBmc};
+use crate::model::llm_price::LlmPriceBmc;
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::quota::QuotaScope;
+use crate::model::user::UserBmc;
+use crate::model::ModelManager;
+// Note: `model::Result` not imported (shadows the `Result` of the schemars derive code).
+use crate::model;
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- UsageEvent Types
+
+/// One LLM call token usage, with its cost (when the provider/model has a `LlmPrice`).
+///
+/// Note: The `cid` is the user of the call (i.e., `ctx.user_id()`).
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct UsageEvent {
+	pub id: i64,
+
+	// -- Relations
+	pub user_id: i64,
+	pub agent_id: Option<i64>,
+	pub conv_id: Option<i64>,
+
+	// -- Properties
+	pub provider: String,
+	pub model: String,
+	pub input_tokens: i32,
+	pub output_tokens: i32,
+	/// In USD. `None` when no price for the provider/model.
+	pub cost: Option<f64>,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsageEventForCreate {
+	pub agent_id: Option<i64>,
+	pub conv_id: Option<i64>,
+
+	pub provider: String,
+	pub model: String,
+	pub input_tokens: i32,
+	pub output_tokens: i32,
+}
+
+#[derive(Fields)]
+struct UsageEventForInsert {
+	user_id: i64,
+	agent_id: Option<i64>,
+	conv_id: Option<i64>,
+
+	provider: String,
+	model: String,
+	input_tokens: i32,
+	output_tokens: i32,
+	cost: Option<f64>,
+}
+
+#[derive(FilterNodes, Default, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct UsageEventFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub user_id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub agent_id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub conv_id: Option<OpValsInt64>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub provider: Option<OpValsString>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub model: Option<OpValsString>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub mtime: Option<OpValsValue>,
+}
+
+/// The `UsageEventBmc::summary` grouping.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub enum UsageGroupBy {
+	/// UTC day, as `YYYY-MM-DD`
+	Day,
+	User,
+	Agent,
+	Conv,
+	/// As `provider/model`
+	Model,
+}
+
+impl UsageGroupBy {
+	/// The sql key expression.
+	/// Note: `&'static str` only, since it is formatted in the sql.
+	fn key_sql(&self) -> &'static str {
+		match self {
+			UsageGroupBy::Day => {
+				"to_char(ctime AT TIME ZONE 'UTC', 'YYYY-MM-DD')"
+			}
+			UsageGroupBy::User => "user_id::text",
+			UsageGroupBy::Agent => "agent_id::text",
+			UsageGroupBy::Conv => "conv_id::text",
+			UsageGroupBy::Model => "provider || '/' || model",
+		}
+	}
+}
+
+/// The `UsageEventBmc::summary` restrictions. All optional.
+/// `from` is inclusive, `to` exclusive.
+#[serde_as]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct UsageSummaryFilter {
+	#[serde(default)]
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<String>"))]
+	pub from: Option<OffsetDateTime>,
+	#[serde(default)]
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<String>"))]
+	pub to: Option<OffsetDateTime>,
+	pub user_id: Option<i64>,
+	pub agent_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct UsageSummary {
+	/// The group key (see `UsageGroupBy`).
+	/// `None` for the events without agent/conv.
+	pub key: Option<String>,
+	pub event_count: i64,
+	pub input_tokens: i64,
+	pub output_tokens: i64,
+	/// In USD, of the priced events.
+	pub cost: f64,
+}
+
+// endregion: --- UsageEvent Types
+
+// region:    --- UsageEventBmc
+
+pub struct UsageEventBmc;
+
+impl DbBmc for UsageEventBmc {
+	const TABLE: &'static str = "usage_event";
+}
+
+// Note: Not using the `generate_common_bmc_fns!` since usage events are
+//       append only, and the cost is computed on create.
+impl UsageEventBmc {
+	/// Record the usage of an LLM call made by `ctx.user_id()`.
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		ue_c: UsageEventForCreate,
+	) -> model::Result<i64> {
+		let price =
+			LlmPriceBmc::first_by_model(ctx, mm, &ue_c.provider, &ue_c.model)
+				.await?;
+		let cost = price.map(|p| p.cost(ue_c.input_tokens, ue_c.output_tokens));
+
+		let ue_i = UsageEventForInsert {
+			user_id: ctx.user_id(),
+			agent_id: ue_c.agent_id,
+			conv_id: ue_c.conv_id,
+			provider: ue_c.provider,
+			model: ue_c.model,
+			input_tokens: ue_c.input_tokens,
+			output_tokens: ue_c.output_tokens,
+			cost,
+		};
+
+		base::create::<Self, _>(ctx, mm, ue_i).await
+	}
+
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> model::Result<UsageEvent> {
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	/// Returns the usage events of the ctx user (of all the users for a `Sys` user).
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filters: Option<Vec<UsageEventFilter>>,
+		list_options: Option<ListOptions>,
+	) -> model::Result<Vec<UsageEvent>> {
+		let filters = if UserBmc::is_sys_user(ctx, mm).await? {
+			filters
+		} else {
+			// Note: The `user_id` of each filter (OR-ed) is the ctx user.
+			let filters = filters.unwrap_or_else(|| vec![UsageEventFilter::default()]);
+			let filters = filters
+				.into_iter()
+				.map(|filter| UsageEventFilter {
+					user_id: Some(ctx.user_id().into()),
+					..filter
+				})
+				.collect();
+			Some(filters)
+		};
+
+		base::list::<Self, _, _>(ctx, mm, filters, list_options).await
+	}
+
+	/// Aggregate the usage events by `group_by`, ordered by key.
+	///
+	/// Note: Of the ctx user only, unless a `Sys` user (i.e., `filter.user_id` is then ignored).
+	pub async fn summary(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		group_by: UsageGroupBy,
+		mut filter: UsageSummaryFilter,
+	) -> model::Result<Vec<UsageSummary>> {
+		if !UserBmc::is_sys_user(ctx, mm).await? {
+			filter.user_id = Some(ctx.user_id());
+		}
+
+		// Note: Group by on an expression is not worth the sea-query ceremony here.
+		let sql = format!(
+			r#"
+			SELECT {key} AS key,
+				count(*) AS event_count,
+				COALESCE(sum(input_tokens), 0)::BIGINT AS input_tokens,
+				COALESCE(sum(output_tokens), 0)::BIGINT AS output_tokens,
+				COALESCE(sum(cost), 0)::DOUBLE PRECISION AS cost
+			FROM {table}
+			WHERE ($1::timestamptz IS NULL OR ctime >= $1)
+				AND ($2::timestamptz IS NULL OR ctime < $2)
+				AND ($3::BIGINT IS NULL OR user_id = $3)
+				AND ($4::BIGINT IS NULL OR agent_id = $4)
+			GROUP BY 1
+			ORDER BY 1
+			"#,
+			key = group_by.key_sql(),
+			table = Self::TABLE
+		);
+
+		let sqlx_query = sqlx::query_as::<_, UsageSummary>(&sql)
+			.bind(filter.from)
+			.bind(filter.to)
+			.bind(filter.user_id)
+			.bind(filter.agent_id);
+		let summaries = mm.dbx().fetch_all(sqlx_query).await?;
+
+		Ok(summaries)
+	}
+
+	/// Returns the (tokens, cost) used by the scope since `start`.
+	/// (tokens are input + output tokens)
+	pub async fn usage_since(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+		scope: QuotaScope,
+		scope_id: i64,
+		start: OffsetDateTime,
+	) -> model::Result<(i64, f64)> {
+		let scope_cond = match scope {
+			QuotaScope::User => "user_id = $2",
+			QuotaScope::Org => r#"user_id IN (SELECT id FROM "user" WHERE org_id = $2)"#,
+		};
+		let sql = format!(
+			r#"
+			SELECT COALESCE(sum(input_tokens + output_tokens), 0)::BIGINT,
+				COALESCE(sum(cost), 0)::DOUBLE PRECISION
+			FROM {table}
+			WHERE ctime >= $1 AND {scope_cond}
+			"#,
+			table = Self::TABLE
+		);
+
+		let sqlx_query = sqlx::query_as::<_, (i64, f64)>(&sql)
+			.bind(start)
+			.bind(scope_id);
+		let usage = mm.dbx().fetch_one(sqlx_query).await?;
+
+		Ok(usage)
+	}
+}
+
+// endregion: --- UsageEventBmc
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::llm_price::LlmPriceForCreate;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_and_summary_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_provider = "test_create_and_summary_ok";
+		let fx_price_id = LlmPriceBmc::create(
+			&ctx,
+			&mm,
+			LlmPriceForCreate {
+				provider: fx_provider.to_string(),
+				model: "model-01".to_string(),
+				input_price: 1.,
+				output_price: 2.,
+			},
+		)
+		.await?;
+		let fx_events = [("model-01", 1_000_000, 500_000), ("model-02", 10, 20)];
+
+		// -- Exec
+		let mut ids = Vec::new();
+		for (model, input_tokens, output_tokens) in fx_events {
+			let ue_c = UsageEventForCreate {
+				agent_id: None,
+				conv_id: None,
+				provider: fx_provider.to_string(),
+				model: model.to_string(),
+				input_tokens,
+				output_tokens,
+			};
+			ids.push(UsageEventBmc::create(&ctx, &mm, ue_c).await?);
+		}
+
+		// -- Check
+		let event = UsageEventBmc::get(&ctx, &mm, ids[0]).await?;
+		assert_eq!(event.cost, Some(2.));
+		let event = UsageEventBmc::get(&ctx, &mm, ids[1]).await?;
+		assert_eq!(event.cost, None, "model-02 has no price");
+
+		let summaries = UsageEventBmc::summary(
+			&ctx,
+			&mm,
+			UsageGroupBy::Model,
+			UsageSummaryFilter::default(),
+		)
+		.await?;
+		let summary = summaries
+			.iter()
+			.find(|s| s.key.as_deref() == Some("test_create_and_summary_ok/model-01"))
+			.ok_or("Should have a model-01 summary")?;
+		assert_eq!(summary.event_count, 1);
+		assert_eq!(summary.input_tokens, 1_000_000);
+		assert_eq!(summary.cost, 2.);
+
+		// -- Clean
+		let sql = format!("DELETE FROM {} WHERE provider = $1", UsageEventBmc::TABLE);
+		mm.dbx()
+			.execute(sqlx::query(&sql).bind(fx_provider))
+			.await?;
+		LlmPriceBmc::delete(&ctx, &mm, fx_price_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_and_summary_own_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let fx_provider = "test_list_and_summary_own_ok";
+		let fx_user_ids = _dev_utils::seed_users(
+			&root_ctx,
+			&mm,
+			&["test_list_and_summary_own_ok user 01", "test_list_and_summary_own_ok user 02"],
+		)
+		.await?;
+		for (user_id, input_tokens) in [(fx_user_ids[0], 10), (fx_user_ids[1], 20)] {
+			let ue_c = UsageEventForCreate {
+				agent_id: None,
+				conv_id: None,
+				provider: fx_provider.to_string(),
+				model: "model-01".to_string(),
+				input_tokens,
+				output_tokens: 0,
+			};
+			UsageEventBmc::create(&Ctx::new(user_id)?, &mm, ue_c).await?;
+		}
+		let fx_ctx = Ctx::new(fx_user_ids[0])?;
+		let fx_filters = || {
+			Some(vec![UsageEventFilter {
+				provider: Some(fx_provider.into()),
+				..Default::default()
+			}])
+		};
+		// Note: Asking for the other user usage.
+		let fx_summary_filter = || UsageSummaryFilter {
+			user_id: Some(fx_user_ids[1]),
+			..Default::default()
+		};
+
+		// -- Exec
+		let user_events = UsageEventBmc::list(&fx_ctx, &mm, fx_filters(), None).await?;
+		let root_events = UsageEventBmc::list(&root_ctx, &mm, fx_filters(), None).await?;
+		let user_summaries =
+			UsageEventBmc::summary(&fx_ctx, &mm, UsageGroupBy::User, fx_summary_filter())
+				.await?;
+		let root_summaries =
+			UsageEventBmc::summary(&root_ctx, &mm, UsageGroupBy::User, fx_summary_filter())
+				.await?;
+
+		// -- Check
+		assert_eq!(user_events.len(), 1);
+		assert_eq!(user_events[0].user_id, fx_user_ids[0]);
+		assert_eq!(root_events.len(), 2);
+		assert_eq!(user_summaries.len(), 1);
+		assert_eq!(user_summaries[0].key, Some(fx_user_ids[0].to_string()));
+		assert_eq!(root_summaries.len(), 1);
+		assert_eq!(root_summaries[0].key, Some(fx_user_ids[1].to_string()));
+
+		// -- Clean
+		let sql = format!("DELETE FROM {} WHERE provider = $1", UsageEventBmc::TABLE);
+		mm.dbx()
+			.execute(sqlx::query(&sql).bind(fx_provider))
+			.await?;
+		_dev_utils::clean_users(&root_ctx, &mm, "test_list_and_summary_own_ok").await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -3,6 +3,8 @@ use crate::model::base::{self, prep_fields_for_update, DbBmc};
 use crate::model::modql_utils::time_to_sea_value;
 use crate::model::ModelManager;
 use crate::model::{Error, Result};
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsInt64Schema, OpValsStringSchema};
 use lib_auth::pwd::{self, ContentToHash};
 use modql::field::{Fields, HasSeaFields, SeaField, SeaFields};
 use modql::filter::{
@@ -33,6 +35,9 @@ pub struct User {
 	pub id: i64,
 	pub username: String,
 	pub typ: UserTyp,
+	pub org_id: Option<i64>,
+	/// Manages the org (e.g., its webhooks).
+	pub org_admin: bool,
 }
 
 #[derive(Deserialize)]
@@ -84,16 +89,23 @@ enum UserIden {
 }
 
 #[derive(FilterNodes, Deserialize, Default, Debug)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
 pub struct UserFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub id: Option<OpValsInt64>,
 
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
 	pub username: Option<OpValsString>,
 
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub cid: Option<OpValsInt64>,
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
 	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
 	pub mid: Option<OpValsInt64>,
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
 	pub mtime: Option<OpValsValue>,
 }
 
@@ -183,6 +195,25 @@ impl UserBmc {
 		Ok(entity)
 	}
 
+	/// Returns true if the ctx user is a `Sys` user (e.g., root).
+	pub async fn is_sys_user(ctx: &Ctx, mm: &ModelManager) -> Result<bool> {
+		let user: User = Self::get(ctx, mm, ctx.user_id()).await?;
+
+		Ok(matches!(user.typ, UserTyp::Sys))
+	}
+
+	/// Ensure the ctx user is a `Sys` user (e.g., root), for the admin operations
+	/// (e.g., quota writes).
+	pub async fn ensure_sys_user(ctx: &Ctx, mm: &ModelManager) -> Result<()> {
+		if Self::is_sys_user(ctx, mm).await? {
+			Ok(())
+		} else {
+			Err(Error::SysUserRequired {
+				user_id: ctx.user_id(),
+			})
+		}
+	}
+
 	pub async fn list(
 		ctx: &Ctx,
 		mm: &ModelManager,
@@ -0,0 +1,920 @@
+//! Outbound webhooks
+//!
+//! - A `Webhook` subscribes a target url to event types (type prefixes, empty for all),
+//!   for a user or an org (`WebhookScope`).
+//! - Each matching event is logged as a `WebhookDelivery`, sent (signed with the webhook secret)
+//!   and retried by the webhook jobs (see `lib_web::webhooks`).
+//! - After `WEBHOOK_MAX_FAILED_DELIVERIES` consecutive failed deliveries, the webhook is disabled
+//!   (re-enabled by an update with `enabled: true`).
+//! - The url host must resolve to public addresses only, checked on write and on each delivery
+//!   (see `resolve_public_url`), so a webhook cannot target the internal services.
+//! - The `Org` webhooks are managed by the org admins (`user.org_admin`) only.
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::user::{User, UserBmc, UserTyp};
+use crate::model::ModelManager;
+// Note: `model::Result` not imported (shadows the `Result` of the schemars derive code).
+use crate::model::{self, Error};
+#[cfg(feature = "with-rpc")]
+use crate::model::modql_utils::{OpValsBoolSchema, OpValsInt64Schema, OpValsStringSchema};
+use lib_events::CloudEvent;
+use lib_utils::time::{now_utc, Rfc3339};
+use modql::field::{Fields, SeaFieldValue};
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsBool, OpValsInt64, OpValsString, OpValsValue,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use url::Url;
+
+/// Consecutive failed deliveries (i.e., after their retries) disabling the webhook.
+pub const WEBHOOK_MAX_FAILED_DELIVERIES: i32 = 5;
+
+// region:    --- Webhook Types
+
+/// Who the webhook belongs to.
+/// For `Org`, the events of all the users of the org (`user.org_id`) are sent.
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	PartialEq,
+	sqlx::Type,
+	SeaFieldValue,
+	derive_more::Display,
+	Deserialize,
+	Serialize,
+)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+#[sqlx(type_name = "webhook_scope")]
+pub enum WebhookScope {
+	User,
+	Org,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct Webhook {
+	pub id: i64,
+
+	// -- Properties
+	pub scope: WebhookScope,
+	pub scope_id: i64,
+	pub url: String,
+	/// Json array of the event type prefixes (empty for all).
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Vec<String>"))]
+	pub event_types: Value,
+	/// Note: Never sent back (set by the client on create).
+	#[serde(skip_serializing, default)]
+	pub secret: String,
+
+	// -- Health
+	pub enabled: bool,
+	pub failed_deliveries: i32,
+	pub disabled_reason: Option<String>,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub mtime: OffsetDateTime,
+}
+
+impl Webhook {
+	/// Returns true if the webhook subscribes to the event type `typ`.
+	pub fn matches(&self, typ: &str) -> bool {
+		let prefixes: Vec<String> =
+			serde_json::from_value(self.event_types.clone()).unwrap_or_default();
+		prefixes.is_empty() || prefixes.iter().any(|prefix| typ.starts_with(prefix))
+	}
+}
+
+#[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct WebhookForCreate {
+	pub scope: WebhookScope,
+	/// Default to the ctx user (for `User`) or its org (for `Org`).
+	pub scope_id: Option<i64>,
+	pub url: String,
+	#[serde(default)]
+	pub event_types: Vec<String>,
+	/// The HMAC secret of the payload signatures (see `lib_auth::sign`).
+	pub secret: String,
+}
+
+#[derive(Fields)]
+struct WebhookForInsert {
+	#[field(cast_as = "webhook_scope")]
+	scope: WebhookScope,
+	scope_id: i64,
+	url: String,
+	event_types: Value,
+	secret: String,
+}
+
+#[derive(Deserialize, Default, Serialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct WebhookForUpdate {
+	pub url: Option<String>,
+	pub event_types: Option<Vec<String>>,
+	pub secret: Option<String>,
+	/// `true` re-enables a disabled webhook (and resets its failed deliveries).
+	pub enabled: Option<bool>,
+}
+
+#[derive(Fields, Default)]
+struct WebhookForDbUpdate {
+	url: Option<String>,
+	event_types: Option<Value>,
+	secret: Option<String>,
+	enabled: Option<bool>,
+}
+
+#[derive(FilterNodes, Default, Deserialize, Clone)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct WebhookFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub url: Option<OpValsString>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsBoolSchema>"))]
+	pub enabled: Option<OpValsBool>,
+
+	/// Note: Set by `WebhookBmc::list` to the ctx user scopes.
+	#[serde(skip)]
+	#[modql(cast_as = "webhook_scope")]
+	scope: Option<OpValsString>,
+	#[serde(skip)]
+	scope_id: Option<OpValsInt64>,
+
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub ctime: Option<OpValsValue>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub mtime: Option<OpValsValue>,
+}
+
+// endregion: --- Webhook Types
+
+// region:    --- WebhookDelivery Types
+
+/// Delivery lifecycle:
+///   `Pending` (sent, and retried with backoff) -> `Succeeded`
+///                                              -> `Failed` (all attempts failed)
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	PartialEq,
+	sqlx::Type,
+	SeaFieldValue,
+	derive_more::Display,
+	Deserialize,
+	Serialize,
+)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+#[sqlx(type_name = "webhook_delivery_state")]
+pub enum WebhookDeliveryState {
+	Pending,
+	Succeeded,
+	Failed,
+}
+
+/// The delivery log entry of an event to a webhook.
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct WebhookDelivery {
+	pub id: i64,
+
+	// -- FK
+	pub webhook_id: i64,
+
+	// -- Properties
+	/// The CloudEvent id (sent as the `webhook-id` header, for the receiver dedup).
+	pub event_id: String,
+	pub event_type: String,
+	/// The CloudEvent json (the request body).
+	pub payload: Value,
+
+	// -- Delivery
+	pub state: WebhookDeliveryState,
+	pub attempts: i32,
+	/// The http status of the last attempt (None when no response).
+	pub last_status: Option<i32>,
+	pub last_error: Option<String>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<String>"))]
+	pub delivered_at: Option<OffsetDateTime>,
+
+	// -- Timestamps
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "String"))]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields)]
+struct WebhookDeliveryForInsert {
+	webhook_id: i64,
+	event_id: String,
+	event_type: String,
+	payload: Value,
+}
+
+#[derive(FilterNodes, Default, Deserialize)]
+#[cfg_attr(feature = "with-rpc", derive(schemars::JsonSchema))]
+pub struct WebhookDeliveryFilter {
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsInt64Schema>"))]
+	pub webhook_id: Option<OpValsInt64>,
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub event_type: Option<OpValsString>,
+	#[modql(cast_as = "webhook_delivery_state")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub state: Option<OpValsString>,
+
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	#[cfg_attr(feature = "with-rpc", schemars(with = "Option<OpValsStringSchema>"))]
+	pub ctime: Option<OpValsValue>,
+}
+
+// endregion: --- WebhookDelivery Types
+
+// region:    --- WebhookBmc
+
+pub struct WebhookBmc;
+
+impl DbBmc for WebhookBmc {
+	const TABLE: &'static str = "webhook";
+}
+
+pub struct WebhookDeliveryBmc;
+
+impl DbBmc for WebhookDeliveryBmc {
+	const TABLE: &'static str = "webhook_delivery";
+}
+
+// Note: Not using the `generate_common_bmc_fns!` since all the functions
+//       are restricted to the webhook scope users (or `Sys` users).
+impl WebhookBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		webhook_c: WebhookForCreate,
+	) -> model::Result<i64> {
+		let WebhookForCreate {
+			scope,
+			scope_id,
+			url,
+			event_types,
+			secret,
+		} = webhook_c;
+
+		let scope_id = match scope_id {
+			Some(scope_id) => scope_id,
+			None => default_scope_id(ctx, mm, scope).await?,
+		};
+		ensure_scope_access(ctx, mm, scope, scope_id).await?;
+		resolve_public_url(&url).await?;
+
+		let webhook_i = WebhookForInsert {
+			scope,
+			scope_id,
+			url,
+			event_types: serde_json::to_value(event_types)?,
+			secret,
+		};
+		base::create::<Self, _>(ctx, mm, webhook_i).await
+	}
+
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> model::Result<Webhook> {
+		let webhook: Webhook = base::get::<Self, _>(ctx, mm, id).await?;
+		ensure_scope_access(ctx, mm, webhook.scope, webhook.scope_id).await?;
+
+		Ok(webhook)
+	}
+
+	/// Returns the webhooks of the ctx user scopes (all for `Sys` users) matching the filters.
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filters: Option<Vec<WebhookFilter>>,
+		list_options: Option<ListOptions>,
+	) -> model::Result<Vec<Webhook>> {
+		let user: User = UserBmc::get(ctx, mm, ctx.user_id()).await?;
+		let filters = match user.typ {
+			UserTyp::Sys => filters,
+			UserTyp::User => {
+				let scopes = user_scopes(&user);
+				let filters = filters.unwrap_or_else(|| vec![WebhookFilter::default()]);
+				// Each filter node, once per scope (the nodes are OR'ed).
+				let scoped_filters = filters
+					.into_iter()
+					.flat_map(|filter| {
+						scopes.iter().map(move |(scope, scope_id)| WebhookFilter {
+							scope: Some(scope.to_string().into()),
+							scope_id: Some((*scope_id).into()),
+							..filter.clone()
+						})
+					})
+					.collect();
+				Some(scoped_filters)
+			}
+		};
+
+		base::list::<Self, _, _>(ctx, mm, filters, list_options).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		webhook_u: WebhookForUpdate,
+	) -> model::Result<()> {
+		Self::get(ctx, mm, id).await?;
+
+		let WebhookForUpdate {
+			url,
+			event_types,
+			secret,
+			enabled,
+		} = webhook_u;
+		if let Some(url) = &url {
+			resolve_public_url(url).await?;
+		}
+
+		let webhook_u = WebhookForDbUpdate {
+			url,
+			event_types: event_types.map(serde_json::to_value).transpose()?,
+			secret,
+			enabled,
+		};
+		base::update::<Self, _>(ctx, mm, id, webhook_u).await?;
+
+		// -- Re-enable (i.e., reset the health)
+		if enabled == Some(true) {
+			let sql = format!(
+				"UPDATE {table} SET failed_deliveries = 0, disabled_reason = NULL
+				 WHERE id = $1",
+				table = Self::TABLE
+			);
+			mm.dbx().execute(sqlx::query(&sql).bind(id)).await?;
+		}
+
+		Ok(())
+	}
+
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> model::Result<()> {
+		Self::get(ctx, mm, id).await?;
+
+		base::delete::<Self>(ctx, mm, id).await
+	}
+
+	/// Returns the enabled webhooks of the `scopes` subscribing to the event type `typ`.
+	///
+	/// Note: For the webhook dispatch (no access check).
+	pub async fn list_for_event(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+		scopes: &[(WebhookScope, i64)],
+		typ: &str,
+	) -> model::Result<Vec<Webhook>> {
+		let mut webhooks = Vec::new();
+		for (scope, scope_id) in scopes {
+			let sql = format!(
+				"SELECT * FROM {table}
+				 WHERE enabled AND scope = $1 AND scope_id = $2
+				 ORDER BY id",
+				table = Self::TABLE
+			);
+			let sqlx_query = sqlx::query_as::<_, Webhook>(&sql).bind(scope).bind(scope_id);
+			webhooks.extend(mm.dbx().fetch_all(sqlx_query).await?);
+		}
+		webhooks.retain(|webhook| webhook.matches(typ));
+
+		Ok(webhooks)
+	}
+}
+
+// Additional WebhookBmc methods to manage the `WebhookDelivery` log.
+impl WebhookBmc {
+	/// Log a `Pending` delivery of `event` to the webhook `webhook_id`.
+	pub async fn add_delivery(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		webhook_id: i64,
+		event: &CloudEvent,
+	) -> model::Result<i64> {
+		// Note: The `ctx` extension is internal (the ctx of the event user), never sent.
+		let mut payload = serde_json::to_value(event)?;
+		if let Some(payload) = payload.as_object_mut() {
+			payload.remove("ctx");
+		}
+
+		let delivery_i = WebhookDeliveryForInsert {
+			webhook_id,
+			event_id: event.id.clone(),
+			event_type: event.typ.clone(),
+			payload,
+		};
+		base::create::<WebhookDeliveryBmc, _>(ctx, mm, delivery_i).await
+	}
+
+	/// Note: For the webhook dispatch (no access check).
+	pub async fn get_delivery(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		delivery_id: i64,
+	) -> model::Result<WebhookDelivery> {
+		base::get::<WebhookDeliveryBmc, _>(ctx, mm, delivery_id).await
+	}
+
+	/// Returns the delivery log of the webhook `webhook_id`.
+	pub async fn list_deliveries(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		webhook_id: i64,
+		filters: Option<Vec<WebhookDeliveryFilter>>,
+		list_options: Option<ListOptions>,
+	) -> model::Result<Vec<WebhookDelivery>> {
+		Self::get(ctx, mm, webhook_id).await?;
+
+		let filters: Vec<WebhookDeliveryFilter> = filters
+			.unwrap_or_else(|| vec![WebhookDeliveryFilter::default()])
+			.into_iter()
+			.map(|filter| WebhookDeliveryFilter {
+				webhook_id: Some(webhook_id.into()),
+				..filter
+			})
+			.collect();
+
+		base::list::<WebhookDeliveryBmc, _, _>(ctx, mm, Some(filters), list_options)
+			.await
+	}
+
+	/// Record a successful delivery attempt (and reset the webhook failed deliveries).
+	pub async fn delivery_succeeded(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		delivery: &WebhookDelivery,
+		status: i32,
+	) -> model::Result<()> {
+		let sql = format!(
+			"UPDATE {table} SET state = $2, attempts = attempts + 1, last_status = $3,
+			   last_error = NULL, delivered_at = $4, mid = $5, mtime = $4
+			 WHERE id = $1",
+			table = WebhookDeliveryBmc::TABLE
+		);
+		let sqlx_query = sqlx::query(&sql)
+			.bind(delivery.id)
+			.bind(WebhookDeliveryState::Succeeded)
+			.bind(status)
+			.bind(now_utc())
+			.bind(ctx.user_id());
+		mm.dbx().execute(sqlx_query).await?;
+
+		let sql = format!(
+			"UPDATE {table} SET failed_deliveries = 0 WHERE id = $1 AND failed_deliveries > 0",
+			table = Self::TABLE
+		);
+		mm.dbx()
+			.execute(sqlx::query(&sql).bind(delivery.webhook_id))
+			.await?;
+
+		Ok(())
+	}
+
+	/// Record a failed delivery attempt.
+	///
+	/// When `last_attempt`, the delivery is `Failed`, and the webhook is disabled after
+	/// `WEBHOOK_MAX_FAILED_DELIVERIES` consecutive failed deliveries.
+	pub async fn delivery_failed(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		delivery: &WebhookDelivery,
+		status: Option<i32>,
+		error: &str,
+		last_attempt: bool,
+	) -> model::Result<()> {
+		let state = if last_attempt {
+			WebhookDeliveryState::Failed
+		} else {
+			WebhookDeliveryState::Pending
+		};
+		let sql = format!(
+			"UPDATE {table} SET state = $2, attempts = attempts + 1, last_status = $3,
+			   last_error = $4, mid = $5, mtime = $6
+			 WHERE id = $1",
+			table = WebhookDeliveryBmc::TABLE
+		);
+		let sqlx_query = sqlx::query(&sql)
+			.bind(delivery.id)
+			.bind(state)
+			.bind(status)
+			.bind(error)
+			.bind(ctx.user_id())
+			.bind(now_utc());
+		mm.dbx().execute(sqlx_query).await?;
+
+		if last_attempt {
+			let sql = format!(
+				"UPDATE {table} SET failed_deliveries = failed_deliveries + 1,
+				   enabled = enabled AND (failed_deliveries + 1 < $2),
+				   disabled_reason = CASE WHEN failed_deliveries + 1 < $2 THEN disabled_reason
+				     ELSE $3 END
+				 WHERE id = $1",
+				table = Self::TABLE
+			);
+			let reason = format!(
+				"{WEBHOOK_MAX_FAILED_DELIVERIES} consecutive failed deliveries (last: {error})"
+			);
+			let sqlx_query = sqlx::query(&sql)
+				.bind(delivery.webhook_id)
+				.bind(WEBHOOK_MAX_FAILED_DELIVERIES)
+				.bind(reason);
+			mm.dbx().execute(sqlx_query).await?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Parse the webhook url (`http` or `https`), and resolve its host to public addresses only
+/// (e.g., not loopback, private, or link-local), returning the (host, addresses).
+///
+/// Note: Checked on write, and on each delivery, which must connect to the returned addresses
+///       (i.e., no re-resolve, so a dns change cannot point the webhook to an internal address).
+pub async fn resolve_public_url(url: &str) -> model::Result<(String, Vec<SocketAddr>)> {
+	let invalid = || Error::WebhookUrlInvalid {
+		url: url.to_string(),
+	};
+
+	let parsed = Url::parse(url).map_err(|_| invalid())?;
+	if !matches!(parsed.scheme(), "http" | "https") {
+		return Err(invalid());
+	}
+	let host = parsed.host_str().ok_or_else(invalid)?;
+	let port = parsed.port_or_known_default().ok_or_else(invalid)?;
+
+	// Note: The ipv6 host is bracketed in the url.
+	let lookup_host = host.trim_start_matches('[').trim_end_matches(']');
+	let addrs: Vec<SocketAddr> = tokio::net::lookup_host((lookup_host, port))
+		.await
+		.map_err(|_| invalid())?
+		.collect();
+	if addrs.is_empty() {
+		return Err(invalid());
+	}
+	if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+		return Err(Error::WebhookUrlNotPublic {
+			url: url.to_string(),
+			addr: addr.ip().to_string(),
+		});
+	}
+
+	Ok((lookup_host.to_string(), addrs))
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+	match ip {
+		IpAddr::V4(ip) => is_public_ipv4(ip),
+		IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+			Some(ip) => is_public_ipv4(ip),
+			None => is_public_ipv6(ip),
+		},
+	}
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+	let [a, b, ..] = ip.octets();
+	!(ip.is_unspecified()
+		|| ip.is_loopback()
+		|| ip.is_private()
+		|| ip.is_link_local()
+		|| ip.is_broadcast()
+		|| ip.is_documentation()
+		|| ip.is_multicast()
+		// 0.0.0.0/8 (this network)
+		|| a == 0
+		// 100.64.0.0/10 (carrier-grade nat)
+		|| (a == 100 && (b & 0b1100_0000) == 64)
+		// 198.18.0.0/15 (benchmarking)
+		|| (a == 198 && (b & 0b1111_1110) == 18)
+		// 240.0.0.0/4 (reserved)
+		|| a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+	let [s0, s1, ..] = ip.segments();
+	!(ip.is_unspecified()
+		|| ip.is_loopback()
+		|| ip.is_multicast()
+		// fc00::/7 (unique local)
+		|| (s0 & 0xfe00) == 0xfc00
+		// fe80::/10 (link-local)
+		|| (s0 & 0xffc0) == 0xfe80
+		// 2001:db8::/32 (documentation)
+		|| (s0 == 0x2001 && s1 == 0x0db8))
+}
+
+/// The webhook scopes the user manages (its org for an org admin only).
+fn user_scopes(user: &User) -> Vec<(WebhookScope, i64)> {
+	let mut scopes = vec![(WebhookScope::User, user.id)];
+	if let (Some(org_id), true) = (user.org_id, user.org_admin) {
+		scopes.push((WebhookScope::Org, org_id));
+	}
+	scopes
+}
+
+async fn default_scope_id(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	scope: WebhookScope,
+) -> model::Result<i64> {
+	let user: User = UserBmc::get(ctx, mm, ctx.user_id()).await?;
+	user_scopes(&user)
+		.into_iter()
+		.find(|(user_scope, _)| *user_scope == scope)
+		.map(|(_, scope_id)| scope_id)
+		.ok_or(Error::WebhookScopeDenied {
+			scope,
+			scope_id: None,
+		})
+}
+
+/// The ctx user must be the scope user, or an admin of the scope org (or a `Sys` user).
+async fn ensure_scope_access(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	scope: WebhookScope,
+	scope_id: i64,
+) -> model::Result<()> {
+	let user: User = UserBmc::get(ctx, mm, ctx.user_id()).await?;
+	if matches!(user.typ, UserTyp::Sys) || user_scopes(&user).contains(&(scope, scope_id)) {
+		Ok(())
+	} else {
+		Err(Error::WebhookScopeDenied {
+			scope,
+			scope_id: Some(scope_id),
+		})
+	}
+}
+
+// endregion: --- WebhookBmc
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils::{self, clean_users, seed_users};
+	use crate::model;
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_delivery_failed_disable_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let user_ids = seed_users(
+			&root_ctx,
+			&mm,
+			&[
+				"test_delivery_failed_disable_ok-user-01",
+				"test_delivery_failed_disable_ok-user-02",
+			],
+		)
+		.await?;
+		let ctx = Ctx::new(user_ids[0])?;
+		let webhook_id = WebhookBmc::create(
+			&ctx,
+			&mm,
+			WebhookForCreate {
+				scope: WebhookScope::User,
+				scope_id: None,
+				url: "https://93.184.215.14/hook".to_string(),
+				event_types: vec!["conv_msg.".to_string()],
+				secret: "test-secret".to_string(),
+			},
+		)
+		.await?;
+		let fx_event = CloudEvent::new("/test", "conv_msg.created", &json!({"id": 1}))?
+			.with_ctx(&ctx)?;
+
+		// -- Exec
+		for _ in 0..WEBHOOK_MAX_FAILED_DELIVERIES {
+			let delivery_id =
+				WebhookBmc::add_delivery(&ctx, &mm, webhook_id, &fx_event).await?;
+			let delivery = WebhookBmc::get_delivery(&ctx, &mm, delivery_id).await?;
+			WebhookBmc::delivery_failed(&ctx, &mm, &delivery, Some(500), "status 500", true)
+				.await?;
+		}
+
+		// -- Check
+		let webhook = WebhookBmc::get(&ctx, &mm, webhook_id).await?;
+		assert!(webhook.matches("conv_msg.created"));
+		assert!(!webhook.matches("conv.updated"));
+		assert!(!webhook.enabled, "should be disabled");
+		assert_eq!(webhook.failed_deliveries, WEBHOOK_MAX_FAILED_DELIVERIES);
+		let deliveries =
+			WebhookBmc::list_deliveries(&ctx, &mm, webhook_id, None, None).await?;
+		assert_eq!(deliveries.len() as i32, WEBHOOK_MAX_FAILED_DELIVERIES);
+		assert!(deliveries
+			.iter()
+			.all(|delivery| delivery.state == WebhookDeliveryState::Failed));
+		assert!(
+			deliveries
+				.iter()
+				.all(|delivery| delivery.payload.get("ctx").is_none()),
+			"ctx should not be delivered"
+		);
+
+		// re-enable
+		WebhookBmc::update(
+			&ctx,
+			&mm,
+			webhook_id,
+			WebhookForUpdate {
+				enabled: Some(true),
+				..Default::default()
+			},
+		)
+		.await?;
+		let webhook = WebhookBmc::get(&ctx, &mm, webhook_id).await?;
+		assert!(webhook.enabled);
+		assert_eq!(webhook.failed_deliveries, 0);
+
+		// other user has no access
+		let res = WebhookBmc::get(&Ctx::new(user_ids[1])?, &mm, webhook_id).await;
+		assert!(
+			matches!(res, Err(model::Error::WebhookScopeDenied { .. })),
+			"should be denied"
+		);
+
+		// -- Clean
+		WebhookBmc::delete(&ctx, &mm, webhook_id).await?;
+		clean_users(&root_ctx, &mm, "test_delivery_failed_disable_ok").await?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_is_public_ip() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_cases = [
+			("93.184.215.14", true),
+			("2606:2800:21f:cb07:6820:80da:af6b:8b2c", true),
+			("127.0.0.1", false),
+			("0.0.0.0", false),
+			("10.0.0.1", false),
+			("172.16.0.1", false),
+			("192.168.1.1", false),
+			("169.254.169.254", false),
+			("100.64.0.1", false),
+			("255.255.255.255", false),
+			("::1", false),
+			("::", false),
+			("fd00::1", false),
+			("fe80::1", false),
+			("::ffff:127.0.0.1", false),
+			("::ffff:93.184.215.14", true),
+		];
+
+		// -- Exec & Check
+		for (ip, expected) in fx_cases {
+			assert_eq!(is_public_ip(ip.parse()?), expected, "ip: {ip}");
+		}
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_resolve_public_url() -> Result<()> {
+		// -- Exec & Check
+		let (host, addrs) = resolve_public_url("https://93.184.215.14/hook").await?;
+		assert_eq!(host, "93.184.215.14");
+		assert_eq!(addrs, vec!["93.184.215.14:443".parse::<SocketAddr>()?]);
+
+		for url in [
+			"http://127.0.0.1/hook",
+			"http://localhost:8080/hook",
+			"http://10.0.0.1/hook",
+			"http://169.254.169.254/latest/meta-data",
+			"http://[::1]/hook",
+		] {
+			let res = resolve_public_url(url).await;
+			assert!(
+				matches!(res, Err(model::Error::WebhookUrlNotPublic { .. })),
+				"url '{url}' should not be public"
+			);
+		}
+
+		for url in ["ftp://93.184.215.14/hook", "not a url"] {
+			let res = resolve_public_url(url).await;
+			assert!(
+				matches!(res, Err(model::Error::WebhookUrlInvalid { .. })),
+				"url '{url}' should be invalid"
+			);
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_org_scope_admin_only() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let user_ids = seed_users(
+			&root_ctx,
+			&mm,
+			&[
+				"test_org_scope_admin_only-user-01",
+				"test_org_scope_admin_only-user-02",
+			],
+		)
+		.await?;
+		let (org_id,): (i64,) = mm
+			.dbx()
+			.fetch_one(sqlx::query_as(
+				"INSERT INTO org (name, cid, ctime, mid, mtime)
+				 VALUES ('test_org_scope_admin_only-org', 0, now(), 0, now()) RETURNING id",
+			))
+			.await?;
+		// user-01 is the org admin, user-02 a member.
+		for (user_id, org_admin) in [(user_ids[0], true), (user_ids[1], false)] {
+			let sql = r#"UPDATE "user" SET org_id = $1, org_admin = $2 WHERE id = $3"#;
+			mm.dbx()
+				.execute(sqlx::query(sql).bind(org_id).bind(org_admin).bind(user_id))
+				.await?;
+		}
+		let fx_webhook_c = || WebhookForCreate {
+			scope: WebhookScope::Org,
+			scope_id: None,
+			url: "https://93.184.215.14/hook".to_string(),
+			event_types: vec!["conv.".to_string()],
+			secret: "test-secret".to_string(),
+		};
+		let admin_ctx = Ctx::new(user_ids[0])?;
+		let member_ctx = Ctx::new(user_ids[1])?;
+
+		// -- Exec
+		let webhook_id = WebhookBmc::create(&admin_ctx, &mm, fx_webhook_c()).await?;
+		let member_create_res = WebhookBmc::create(&member_ctx, &mm, fx_webhook_c()).await;
+		let member_get_res = WebhookBmc::get(&member_ctx, &mm, webhook_id).await;
+		let member_webhooks = WebhookBmc::list(&member_ctx, &mm, None, None).await?;
+
+		// -- Check
+		let webhook = WebhookBmc::get(&admin_ctx, &mm, webhook_id).await?;
+		assert_eq!(webhook.scope_id, org_id);
+		assert!(
+			matches!(member_create_res, Err(model::Error::WebhookScopeDenied { .. })),
+			"member create should be denied"
+		);
+		assert!(
+			matches!(member_get_res, Err(model::Error::WebhookScopeDenied { .. })),
+			"member get should be denied"
+		);
+		assert!(member_webhooks.is_empty(), "member should list no org webhook");
+
+		// -- Clean
+		WebhookBmc::delete(&admin_ctx, &mm, webhook_id).await?;
+		clean_users(&root_ctx, &mm, "test_org_scope_admin_only").await?;
+		mm.dbx()
+			.execute(sqlx::query("DELETE FROM org WHERE id = $1").bind(org_id))
+			.await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
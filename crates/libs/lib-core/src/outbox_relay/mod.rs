@@ -0,0 +1,410 @@
+//! Outbox Relay
+//!
+//! Publishes the pending `outbox` entries (see `OutboxBmc`) to an `OutboxSink`, for example:
+//!
+//! ```ignore
+//! OutboxRelay::new(mm.clone(), EventBusSink::new(mm.event_bus().clone())).start();
+//! ```
+//!
+//! - The entries are published in id order, and marked sent once the sink accepted them
+//!   (i.e., at-least-once, the consumers must be idempotent on the CloudEvent id).
+//! - A sink error stops the batch (to preserve the order), and the entry is retried
+//!   at the next poll, up to `max_attempts`. Then, it is dead-lettered
+//!   (see `OutboxBmc::mark_failed`), and the batch goes on (i.e., a poison entry
+//!   does not block the outbox).
+//! - Each batch runs in a transaction, locking its entries, so that multiple relays
+//!   (e.g., one per service instance) do not publish the same entries concurrently.
+
+use crate::ctx::Ctx;
+use crate::model::job::{JobBmc, JobForCreate};
+use crate::model::outbox::{OutboxBmc, OutboxEntry};
+use crate::model::{ModelManager, Result};
+use async_trait::async_trait;
+use lib_events::{CloudEvent, EventBus};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_BATCH_SIZE: i64 = 100;
+/// Note: With the poll interval, about a minute of sink errors before dead-lettering.
+const DEFAULT_MAX_ATTEMPTS: i32 = 120;
+
+pub type SinkError = Box<dyn std::error::Error + Send + Sync>;
+pub type SinkResult = core::result::Result<(), SinkError>;
+
+/// The destination of the outbox events.
+///
+/// Note: `mm` is the relay batch transaction, so that a db sink (e.g., `JobSink`)
+///       writes atomically with the entry being marked sent.
+#[async_trait]
+pub trait OutboxSink: Send + Sync {
+	async fn send(&self, ctx: &Ctx, mm: &ModelManager, event: &CloudEvent) -> SinkResult;
+}
+
+// region:    --- Sinks
+
+/// Publish the events to an `EventBus` (e.g., `mm.event_bus()`, or a `MemEventBus`
+/// for in-process consumers).
+pub struct EventBusSink {
+	bus: Arc<dyn EventBus>,
+}
+
+impl EventBusSink {
+	pub fn new(bus: Arc<dyn EventBus>) -> Self {
+		EventBusSink { bus }
+	}
+}
+
+#[async_trait]
+impl OutboxSink for EventBusSink {
+	async fn send(&self, _ctx: &Ctx, _mm: &ModelManager, event: &CloudEvent) -> SinkResult {
+		self.bus.publish(event.clone()).await?;
+		Ok(())
+	}
+}
+
+/// Enqueue a job per event (payload is the CloudEvent), on `queue` with the given `kind`.
+pub struct JobSink {
+	queue: String,
+	kind: String,
+}
+
+impl JobSink {
+	pub fn new(queue: impl Into<String>, kind: impl Into<String>) -> Self {
+		JobSink {
+			queue: queue.into(),
+			kind: kind.into(),
+		}
+	}
+}
+
+#[async_trait]
+impl OutboxSink for JobSink {
+	async fn send(&self, ctx: &Ctx, mm: &ModelManager, event: &CloudEvent) -> SinkResult {
+		let job_c = JobForCreate {
+			queue: Some(self.queue.clone()),
+			kind: self.kind.clone(),
+			payload: Some(serde_json::to_value(event)?),
+			..Default::default()
+		};
+		JobBmc::enqueue(ctx, mm, job_c).await?;
+		Ok(())
+	}
+}
+
+// endregion: --- Sinks
+
+pub struct OutboxRelay {
+	mm: ModelManager,
+	sink: Arc<dyn OutboxSink>,
+	poll_interval: Duration,
+	batch_size: i64,
+	max_attempts: i32,
+}
+
+// region:    --- Builder
+
+impl OutboxRelay {
+	pub fn new(mm: ModelManager, sink: impl OutboxSink + 'static) -> Self {
+		OutboxRelay {
+			mm,
+			sink: Arc::new(sink),
+			poll_interval: DEFAULT_POLL_INTERVAL,
+			batch_size: DEFAULT_BATCH_SIZE,
+			max_attempts: DEFAULT_MAX_ATTEMPTS,
+		}
+	}
+
+	pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+		self.poll_interval = poll_interval;
+		self
+	}
+
+	pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+		self.batch_size = batch_size.max(1);
+		self
+	}
+
+	pub fn with_max_attempts(mut self, max_attempts: i32) -> Self {
+		self.max_attempts = max_attempts.max(1);
+		self
+	}
+}
+
+// endregion: --- Builder
+
+// region:    --- Run
+
+impl OutboxRelay {
+	/// Spawn the relay loop on the tokio runtime.
+	pub fn start(self) {
+		info!("{:<12} - started (batch size {})", "OUTBOX RELAY", self.batch_size);
+		tokio::spawn(self.run());
+	}
+
+	async fn run(self) {
+		loop {
+			match self.relay_batch().await {
+				// Note: When a full batch was sent, relay again right away (the outbox might have more).
+				Ok(sent) if sent == self.batch_size as usize => continue,
+				Ok(_) => (),
+				Err(ex) => error!("{:<12} - relay batch failed: {ex:?}", "OUTBOX RELAY"),
+			}
+			tokio::time::sleep(self.poll_interval).await;
+		}
+	}
+
+	/// Send the first pending entries, and returns the number of entries sent (or dead-lettered).
+	pub async fn relay_batch(&self) -> Result<usize> {
+		let ctx = Ctx::root_ctx();
+
+		// Start the transaction
+		let mm = self.mm.new_with_txn()?;
+
+		mm.dbx().begin_txn().await?;
+
+		let entries = OutboxBmc::lock_pending(&ctx, &mm, self.batch_size).await?;
+
+		let mut sent = 0;
+		for OutboxEntry { id, event, .. } in entries {
+			match self.sink.send(&ctx, &mm, &event).await {
+				Ok(()) => {
+					debug!("{:<12} - sent {id} ({})", "OUTBOX RELAY", event.typ);
+					OutboxBmc::mark_sent(&ctx, &mm, id).await?;
+					sent += 1;
+				}
+				Err(ex) => {
+					warn!("{:<12} - send {id} ({}) failed: {ex}", "OUTBOX RELAY", event.typ);
+					let dead =
+						OutboxBmc::mark_failed(&ctx, &mm, id, &ex.to_string(), self.max_attempts)
+							.await?;
+					if !dead {
+						break;
+					}
+					error!("{:<12} - dead-lettered {id} ({})", "OUTBOX RELAY", event.typ);
+					sent += 1;
+				}
+			}
+		}
+
+		// Commit the transaction
+		mm.dbx().commit_txn().await?;
+
+		Ok(sent)
+	}
+}
+
+// endregion: --- Run
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::_dev_utils::{self, seed_agent, seed_conv};
+	use crate::model::agent::AgentBmc;
+	use crate::model::conv::{ConvBmc, ConvForUpdate};
+	use crate::model::conv_event::{CONV_MSG_CREATED, CONV_MSG_UPDATED, CONV_UPDATED};
+	use crate::model::conv_msg::{ConvMsgForCreate, ConvMsgForEdit, ConvMsgForUpdate};
+	use lib_events::{MemEventBus, Subscription};
+	use serde_json::json;
+	use serial_test::serial;
+
+	/// Fails the events of type `FxFailSink::FAIL_TYPE`.
+	struct FxFailSink;
+
+	impl FxFailSink {
+		const FAIL_TYPE: &'static str = "test.outbox.fail";
+	}
+
+	#[async_trait]
+	impl OutboxSink for FxFailSink {
+		async fn send(&self, _ctx: &Ctx, _mm: &ModelManager, event: &CloudEvent) -> SinkResult {
+			if event.typ == Self::FAIL_TYPE {
+				Err("fx sink failed".into())
+			} else {
+				Ok(())
+			}
+		}
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_relay_conv_msg_created_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_content = "test_relay_conv_msg_created_ok msg 01";
+		let agent_id =
+			seed_agent(&ctx, &mm, "test_relay_conv_msg_created_ok agent 01").await?;
+		let conv_id =
+			seed_conv(&ctx, &mm, agent_id, "test_relay_conv_msg_created_ok conv 01").await?;
+		let bus = Arc::new(MemEventBus::new());
+		bus.subscribe(Subscription::new("test").with_type(CONV_MSG_CREATED))
+			.await?;
+		let relay = OutboxRelay::new(mm.clone(), EventBusSink::new(bus.clone()));
+
+		// -- Exec
+		let msg_id = ConvBmc::add_msg(
+			&ctx,
+			&mm,
+			ConvMsgForCreate {
+				conv_id,
+				content: fx_content.to_string(),
+				..Default::default()
+			},
+		)
+		.await?;
+		while relay.relay_batch().await? > 0 {}
+
+		// -- Check
+		let deliveries = bus.receive("test", 100).await?;
+		let event = deliveries
+			.iter()
+			.map(|delivery| &delivery.event)
+			.find(|event| event.subject == Some(conv_id.to_string()))
+			.ok_or("should have the conv_msg.created event")?;
+		let data: serde_json::Value = event.data_as()?;
+		assert_eq!(data["id"], msg_id);
+		assert_eq!(data["content"], fx_content);
+
+		// -- Clean
+		AgentBmc::delete(&ctx, &mm, agent_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_relay_conv_msg_writes_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let agent_id =
+			seed_agent(&ctx, &mm, "test_relay_conv_msg_writes_ok agent 01").await?;
+		let conv_id =
+			seed_conv(&ctx, &mm, agent_id, "test_relay_conv_msg_writes_ok conv 01").await?;
+		let msg_id = ConvBmc::add_msg(
+			&ctx,
+			&mm,
+			ConvMsgForCreate {
+				conv_id,
+				content: "msg 01".to_string(),
+				..Default::default()
+			},
+		)
+		.await?;
+		let relay = OutboxRelay::new(mm.clone(), EventBusSink::new(Arc::new(MemEventBus::new())));
+		while relay.relay_batch().await? > 0 {}
+		let bus = Arc::new(MemEventBus::new());
+		bus.subscribe(Subscription::new("test").with_type("conv")).await?;
+		let relay = OutboxRelay::new(mm.clone(), EventBusSink::new(bus.clone()));
+
+		// -- Exec
+		let edit_msg_id = ConvBmc::edit_msg(
+			&ctx,
+			&mm,
+			msg_id,
+			ConvMsgForEdit {
+				content: "msg 01 edited".to_string(),
+			},
+		)
+		.await?;
+		ConvBmc::update_msg(
+			&ctx,
+			&mm,
+			edit_msg_id,
+			ConvMsgForUpdate {
+				conv_id,
+				content: Some("msg 01 updated".to_string()),
+			},
+		)
+		.await?;
+		ConvBmc::update(
+			&ctx,
+			&mm,
+			conv_id,
+			ConvForUpdate {
+				title: Some("title 01".to_string()),
+				..Default::default()
+			},
+		)
+		.await?;
+		let fork_conv_id = ConvBmc::fork_conv(&ctx, &mm, conv_id, edit_msg_id).await?;
+		while relay.relay_batch().await? > 0 {}
+
+		// -- Check
+		let events: Vec<(String, String, serde_json::Value)> = bus
+			.receive("test", 100)
+			.await?
+			.into_iter()
+			.map(|delivery| delivery.event)
+			.filter_map(|event| {
+				let subject = event.subject.clone()?;
+				let data = event.data_as().ok()?;
+				Some((event.typ, subject, data))
+			})
+			.collect();
+		let conv_subject = conv_id.to_string();
+		let fork_subject = fork_conv_id.to_string();
+		let has_event = |typ: &str, subject: &str, id: i64| {
+			events
+				.iter()
+				.any(|(t, s, data)| t == typ && s == subject && data["id"] == id)
+		};
+		assert!(has_event(CONV_MSG_CREATED, &conv_subject, edit_msg_id), "edit");
+		assert!(has_event(CONV_MSG_UPDATED, &conv_subject, edit_msg_id), "update");
+		assert!(has_event(CONV_UPDATED, &conv_subject, conv_id), "conv update");
+		let fork_msgs = ConvBmc::list_msg_tree(&ctx, &mm, fork_conv_id).await?;
+		assert_eq!(fork_msgs.len(), 1);
+		assert!(has_event(CONV_MSG_CREATED, &fork_subject, fork_msgs[0].id), "fork");
+
+		// -- Clean
+		AgentBmc::delete(&ctx, &mm, agent_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_relay_dead_letter_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let relay = OutboxRelay::new(mm.clone(), FxFailSink)
+			.with_batch_size(1_000)
+			.with_max_attempts(2);
+		while relay.relay_batch().await? > 0 {}
+		let fx_fail_event = CloudEvent::new("/test", FxFailSink::FAIL_TYPE, &json!({}))?;
+		let fx_ok_event = CloudEvent::new("/test", "test.outbox.ok", &json!({}))?;
+		let fail_id = OutboxBmc::add(&ctx, &mm, fx_fail_event).await?;
+		let ok_id = OutboxBmc::add(&ctx, &mm, fx_ok_event).await?;
+
+		// -- Exec & Check
+		// first attempt, blocks the batch
+		assert_eq!(relay.relay_batch().await?, 0);
+		let ok_entry = OutboxBmc::get(&ctx, &mm, ok_id).await?.ok_or("should have ok")?;
+		assert!(ok_entry.sent_at.is_none(), "should be blocked");
+
+		// max attempts, dead-lettered, and the batch goes on
+		assert_eq!(relay.relay_batch().await?, 2);
+		let fail_entry = OutboxBmc::get(&ctx, &mm, fail_id).await?.ok_or("should have fail")?;
+		assert_eq!(fail_entry.attempts, 2);
+		assert!(fail_entry.dead_at.is_some(), "should be dead");
+		assert!(fail_entry.sent_at.is_none());
+		assert_eq!(fail_entry.last_error.as_deref(), Some("fx sink failed"));
+		let ok_entry = OutboxBmc::get(&ctx, &mm, ok_id).await?.ok_or("should have ok")?;
+		assert!(ok_entry.sent_at.is_some(), "should be sent");
+
+		// dead entry not relayed anymore
+		assert_eq!(relay.relay_batch().await?, 0);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,190 @@
+use crate::{CloudEvent, EventBus, Result, Subscription};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const DEFAULT_BATCH_SIZE: usize = 16;
+/// Note: With the default ack timeout (60s), about 10 minutes of handler errors
+///       before dead-lettering.
+const DEFAULT_MAX_ATTEMPTS: i32 = 10;
+
+pub type HandlerError = Box<dyn std::error::Error + Send + Sync>;
+pub type HandlerResult = core::result::Result<(), HandlerError>;
+
+#[async_trait]
+pub trait EventHandler: Send + Sync {
+	async fn handle(&self, event: &CloudEvent) -> HandlerResult;
+}
+
+/// Runs a subscription: the deliveries are handled in order, and acked on success.
+/// On handler error, the delivery is not acked, so it is delivered again after the ack timeout,
+/// up to `max_attempts`. Then, it is dead-lettered (see `EventBus::dead_letter`).
+pub struct EventConsumer {
+	bus: Arc<dyn EventBus>,
+	subscription: Subscription,
+	handler: Arc<dyn EventHandler>,
+	poll_interval: Duration,
+	batch_size: usize,
+	max_attempts: i32,
+}
+
+impl EventConsumer {
+	pub fn new(
+		bus: Arc<dyn EventBus>,
+		subscription: Subscription,
+		handler: impl EventHandler + 'static,
+	) -> Self {
+		EventConsumer {
+			bus,
+			subscription,
+			handler: Arc::new(handler),
+			poll_interval: DEFAULT_POLL_INTERVAL,
+			batch_size: DEFAULT_BATCH_SIZE,
+			max_attempts: DEFAULT_MAX_ATTEMPTS,
+		}
+	}
+
+	pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+		self.poll_interval = poll_interval;
+		self
+	}
+
+	pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+		self.batch_size = batch_size.max(1);
+		self
+	}
+
+	pub fn with_max_attempts(mut self, max_attempts: i32) -> Self {
+		self.max_attempts = max_attempts.max(1);
+		self
+	}
+
+	/// Create/update the subscription, and spawn the consumer loop on the tokio runtime.
+	pub async fn start(self) -> Result<()> {
+		self.bus.subscribe(self.subscription.clone()).await?;
+		info!("{:<12} - subscription {:?} {:?}", "EVENT CONSUMER", self.subscription.name, self.subscription.types);
+
+		tokio::spawn(self.run());
+
+		Ok(())
+	}
+
+	async fn run(self) {
+		let name = &self.subscription.name;
+
+		loop {
+			match self.consume_batch().await {
+				Ok(0) => tokio::time::sleep(self.poll_interval).await,
+				Ok(_) => (),
+				Err(ex) => {
+					error!("{:<12} - receive {name:?} failed: {ex:?}", "EVENT CONSUMER");
+					tokio::time::sleep(self.poll_interval).await;
+				}
+			}
+		}
+	}
+
+	/// Handle the next deliveries, and returns the number of deliveries handled
+	/// (acked, failed or dead-lettered).
+	pub async fn consume_batch(&self) -> Result<usize> {
+		let name = &self.subscription.name;
+		let deliveries = self.bus.receive(name, self.batch_size).await?;
+		let count = deliveries.len();
+
+		for delivery in deliveries {
+			let event = &delivery.event;
+			debug!("{:<12} - {name:?} {} {} (attempt {})", "EVENT CONSUMER", event.typ, event.id, delivery.attempts);
+
+			match self.handler.handle(event).await {
+				Ok(()) => {
+					if let Err(ex) = self.bus.ack(&delivery).await {
+						error!("{:<12} - ack {} failed: {ex:?}", "EVENT CONSUMER", delivery.id);
+					}
+				}
+				Err(ex) if delivery.attempts >= self.max_attempts => {
+					error!(
+						"{:<12} - {name:?} event {} dead-lettered after {} attempts: {ex}",
+						"EVENT CONSUMER", event.id, delivery.attempts
+					);
+					if let Err(ex) = self.bus.dead_letter(&delivery, &ex.to_string()).await {
+						error!("{:<12} - dead-letter {} failed: {ex:?}", "EVENT CONSUMER", delivery.id);
+					}
+				}
+				Err(ex) => {
+					error!("{:<12} - {name:?} event {} failed: {ex}", "EVENT CONSUMER", event.id)
+				}
+			}
+		}
+
+		Ok(count)
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::MemEventBus;
+	use serde_json::json;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	/// Fails the events of type `FxHandler::FAIL_TYPE`, and counts the calls.
+	#[derive(Default)]
+	struct FxHandler {
+		calls: Arc<AtomicUsize>,
+	}
+
+	impl FxHandler {
+		const FAIL_TYPE: &'static str = "test.consumer.fail";
+	}
+
+	#[async_trait]
+	impl EventHandler for FxHandler {
+		async fn handle(&self, event: &CloudEvent) -> HandlerResult {
+			self.calls.fetch_add(1, Ordering::Relaxed);
+			if event.typ == Self::FAIL_TYPE {
+				return Err("handler failed".into());
+			}
+			Ok(())
+		}
+	}
+
+	#[tokio::test]
+	async fn test_consumer_dead_letter_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let bus = Arc::new(MemEventBus::new().with_ack_timeout(Duration::ZERO));
+		let fx_handler = FxHandler::default();
+		let fx_calls = fx_handler.calls.clone();
+		let consumer = EventConsumer::new(bus.clone(), Subscription::new("sub-01"), fx_handler)
+			.with_max_attempts(3);
+		bus.subscribe(Subscription::new("sub-01")).await?;
+		let fx_data = json!({"id": 1000});
+		bus.publish(CloudEvent::new("/test", FxHandler::FAIL_TYPE, &fx_data)?)
+			.await?;
+		bus.publish(CloudEvent::new("/test", "test.consumer.ok", &fx_data)?)
+			.await?;
+
+		// -- Exec
+		let mut handled = Vec::new();
+		for _ in 0..5 {
+			handled.push(consumer.consume_batch().await?);
+		}
+
+		// -- Check
+		// 1st: both (ok acked), 2nd & 3rd: the failing one, then dead-lettered.
+		assert_eq!(handled, &[2, 1, 1, 0, 0]);
+		assert_eq!(fx_calls.load(Ordering::Relaxed), 4);
+		assert!(bus.receive("sub-01", 10).await?.is_empty());
+		assert_eq!(bus.purge(Duration::ZERO).await?, 1, "should purge the dead-lettered");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,88 @@
+use crate::{Error, Result};
+use lib_utils::time::{now_utc, Rfc3339};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{serde_as, skip_serializing_none};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+const SPEC_VERSION: &str = "1.0";
+const DATA_CONTENT_TYPE: &str = "application/json";
+
+/// CloudEvents 1.0 JSON envelope.
+///
+/// Note: The `ctx` extension is the serialized `Ctx` (json string, as in the `Auth1` header),
+///       so that the consumers act with the emitter ctx (and extend its `req_chain`).
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudEvent {
+	// -- Required attributes
+	pub specversion: String,
+	pub id: String,
+	/// e.g., `/lib-core/model`
+	pub source: String,
+	/// e.g., `conv_msg.created`
+	#[serde(rename = "type")]
+	pub typ: String,
+
+	// -- Optional attributes
+	/// e.g., the conv id
+	pub subject: Option<String>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[serde(default)]
+	pub time: Option<OffsetDateTime>,
+	pub datacontenttype: Option<String>,
+	pub data: Option<Value>,
+
+	// -- Extensions
+	pub ctx: Option<String>,
+}
+
+impl CloudEvent {
+	pub fn new(
+		source: impl Into<String>,
+		typ: impl Into<String>,
+		data: &impl Serialize,
+	) -> Result<Self> {
+		Ok(CloudEvent {
+			specversion: SPEC_VERSION.to_string(),
+			id: Uuid::new_v4().to_string(),
+			source: source.into(),
+			typ: typ.into(),
+			subject: None,
+			time: Some(now_utc()),
+			datacontenttype: Some(DATA_CONTENT_TYPE.to_string()),
+			data: Some(serde_json::to_value(data)?),
+			ctx: None,
+		})
+	}
+
+	pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+		self.subject = Some(subject.into());
+		self
+	}
+
+	/// Set the `ctx` extension (e.g., `lib_core::ctx::Ctx`).
+	pub fn with_ctx(mut self, ctx: &impl Serialize) -> Result<Self> {
+		self.ctx = Some(serde_json::to_string(ctx)?);
+		Ok(self)
+	}
+}
+
+impl CloudEvent {
+	/// Typed `data`
+	pub fn data_as<T: DeserializeOwned>(&self) -> Result<T> {
+		let data = self.data.clone().ok_or_else(|| Error::EventDataMissing {
+			id: self.id.clone(),
+		})?;
+		Ok(serde_json::from_value(data)?)
+	}
+
+	/// Typed `ctx` extension (None if the event has no ctx)
+	pub fn ctx_as<C: DeserializeOwned>(&self) -> Result<Option<C>> {
+		let ctx = self.ctx.as_deref().map(serde_json::from_str).transpose()?;
+		Ok(ctx)
+	}
+}
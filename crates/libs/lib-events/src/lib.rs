@@ -0,0 +1,106 @@
+//! Event bus for the event driven workers (see `docs/03_event_driven_architecture`).
+//!
+//! Design:
+//!
+//! - `CloudEvent` is the CloudEvents 1.0 JSON envelope (`ce-*` attributes), with
+//!   the `ctx` extension carrying the emitter `Ctx` (and its `req_chain`).
+//! - `EventBus` is the trait held by the `ModelManager` (as `Arc<dyn EventBus>`),
+//!   so that other brokers (e.g., Redis/Valkey, Kafka) can be added later.
+//! - Subscriptions are durable and named. Instances using the same subscription name
+//!   share its deliveries (consumer group), which are delivered at least once
+//!   (redelivered when not acked within the ack timeout).
+//! - A delivery failing `max_attempts` times is dead-lettered (see `EventConsumer`),
+//!   and the old events are purged (see `EventPurger`).
+//! - `PgEventBus` is the postgres implementation (default),
+//!   `MemEventBus` is the in-memory one (e.g., tests, single process).
+//! - `EventConsumer` runs a subscription with an `EventHandler`.
+//! - `EventPurger` purges the events older than the retention.
+//!
+
+// region:    --- Modules
+
+mod consumer;
+mod envelope;
+mod error;
+mod mem;
+mod pg;
+mod purger;
+
+pub use self::error::{Error, Result};
+pub use consumer::{EventConsumer, EventHandler, HandlerError, HandlerResult};
+pub use envelope::CloudEvent;
+pub use mem::MemEventBus;
+pub use pg::PgEventBus;
+pub use purger::EventPurger;
+
+use async_trait::async_trait;
+use std::time::Duration;
+
+// endregion: --- Modules
+
+// region:    --- EventBus
+
+#[async_trait]
+pub trait EventBus: Send + Sync {
+	/// Publish the event to the subscriptions matching its type.
+	async fn publish(&self, event: CloudEvent) -> Result<()>;
+
+	/// Create or update the (durable) subscription.
+	/// Only the events published after the subscription are delivered to it.
+	async fn subscribe(&self, subscription: Subscription) -> Result<()>;
+
+	/// Returns up to `max` deliveries of the subscription (empty if unknown).
+	/// A delivery not acked within the ack timeout is delivered again.
+	async fn receive(&self, subscription: &str, max: usize) -> Result<Vec<Delivery>>;
+
+	/// Note: Acking an already acked delivery is not an error.
+	async fn ack(&self, delivery: &Delivery) -> Result<()>;
+
+	/// Dead-letter the delivery (e.g., max attempts reached), with its last `error`.
+	/// A dead-lettered delivery is not delivered anymore (and kept until purged).
+	async fn dead_letter(&self, delivery: &Delivery, error: &str) -> Result<()>;
+
+	/// Delete the events published more than `retention` ago, which have no pending
+	/// deliveries (their dead-lettered deliveries are deleted with them).
+	/// Returns the number of events deleted.
+	async fn purge(&self, retention: Duration) -> Result<u64>;
+}
+
+/// A named subscription to the events whose type starts with one of `types`
+/// (all the events when `types` is empty).
+#[derive(Debug, Clone)]
+pub struct Subscription {
+	pub name: String,
+	pub types: Vec<String>,
+}
+
+impl Subscription {
+	pub fn new(name: impl Into<String>) -> Self {
+		Subscription {
+			name: name.into(),
+			types: Vec::new(),
+		}
+	}
+
+	/// Add an event type (prefix), e.g., `conv_msg.created` or `conv_msg.`
+	pub fn with_type(mut self, typ: impl Into<String>) -> Self {
+		self.types.push(typ.into());
+		self
+	}
+
+	pub fn matches(&self, typ: &str) -> bool {
+		self.types.is_empty() || self.types.iter().any(|prefix| typ.starts_with(prefix.as_str()))
+	}
+}
+
+/// An event delivered to a subscription, to be acked once handled.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+	pub id: i64,
+	pub subscription: String,
+	/// Number of deliveries so far (including this one).
+	pub attempts: i32,
+	pub event: CloudEvent,
+}
+
+// endregion: --- EventBus
@@ -0,0 +1,226 @@
+use crate::{CloudEvent, Delivery, EventBus, Result, Subscription};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_ACK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// In-memory event bus (e.g., tests, single process).
+/// Note: The subscriptions and deliveries are lost on restart.
+pub struct MemEventBus {
+	subscriptions: Mutex<HashMap<String, MemSubscription>>,
+	next_id: AtomicI64,
+	ack_timeout: Duration,
+}
+
+struct MemSubscription {
+	subscription: Subscription,
+	deliveries: Vec<MemDelivery>,
+}
+
+struct MemDelivery {
+	id: i64,
+	attempts: i32,
+	visible_at: Instant,
+	/// When the event was published (for the purge).
+	ctime: Instant,
+	/// Dead-letter (not delivered anymore), with the last error.
+	dead: Option<String>,
+	event: CloudEvent,
+}
+
+impl Default for MemEventBus {
+	fn default() -> Self {
+		MemEventBus {
+			subscriptions: Mutex::default(),
+			next_id: AtomicI64::new(1),
+			ack_timeout: DEFAULT_ACK_TIMEOUT,
+		}
+	}
+}
+
+impl MemEventBus {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn with_ack_timeout(mut self, ack_timeout: Duration) -> Self {
+		self.ack_timeout = ack_timeout;
+		self
+	}
+}
+
+#[async_trait]
+impl EventBus for MemEventBus {
+	async fn publish(&self, event: CloudEvent) -> Result<()> {
+		let mut subscriptions = self.subscriptions.lock().unwrap_or_else(|ex| ex.into_inner());
+		let now = Instant::now();
+
+		for sub in subscriptions.values_mut() {
+			if sub.subscription.matches(&event.typ) {
+				sub.deliveries.push(MemDelivery {
+					id: self.next_id.fetch_add(1, Ordering::Relaxed),
+					attempts: 0,
+					visible_at: now,
+					ctime: now,
+					dead: None,
+					event: event.clone(),
+				});
+			}
+		}
+
+		Ok(())
+	}
+
+	async fn subscribe(&self, subscription: Subscription) -> Result<()> {
+		let mut subscriptions = self.subscriptions.lock().unwrap_or_else(|ex| ex.into_inner());
+
+		match subscriptions.get_mut(&subscription.name) {
+			Some(sub) => sub.subscription = subscription,
+			None => {
+				let name = subscription.name.clone();
+				let sub = MemSubscription {
+					subscription,
+					deliveries: Vec::new(),
+				};
+				subscriptions.insert(name, sub);
+			}
+		}
+
+		Ok(())
+	}
+
+	async fn receive(&self, subscription: &str, max: usize) -> Result<Vec<Delivery>> {
+		let mut subscriptions = self.subscriptions.lock().unwrap_or_else(|ex| ex.into_inner());
+		let Some(sub) = subscriptions.get_mut(subscription) else {
+			return Ok(Vec::new());
+		};
+
+		let now = Instant::now();
+		let deliveries = sub
+			.deliveries
+			.iter_mut()
+			.filter(|delivery| delivery.dead.is_none() && delivery.visible_at <= now)
+			.take(max)
+			.map(|delivery| {
+				delivery.attempts += 1;
+				delivery.visible_at = now + self.ack_timeout;
+				Delivery {
+					id: delivery.id,
+					subscription: subscription.to_string(),
+					attempts: delivery.attempts,
+					event: delivery.event.clone(),
+				}
+			})
+			.collect();
+
+		Ok(deliveries)
+	}
+
+	async fn ack(&self, delivery: &Delivery) -> Result<()> {
+		let mut subscriptions = self.subscriptions.lock().unwrap_or_else(|ex| ex.into_inner());
+		if let Some(sub) = subscriptions.get_mut(&delivery.subscription) {
+			sub.deliveries.retain(|d| d.id != delivery.id);
+		}
+
+		Ok(())
+	}
+
+	async fn dead_letter(&self, delivery: &Delivery, error: &str) -> Result<()> {
+		let mut subscriptions = self.subscriptions.lock().unwrap_or_else(|ex| ex.into_inner());
+		let mem_delivery = subscriptions
+			.get_mut(&delivery.subscription)
+			.and_then(|sub| sub.deliveries.iter_mut().find(|d| d.id == delivery.id));
+		if let Some(mem_delivery) = mem_delivery {
+			mem_delivery.dead = Some(error.to_string());
+		}
+
+		Ok(())
+	}
+
+	/// Note: The events are held by the deliveries, so only the dead-lettered
+	///       ones can be purged (and each counts as an event).
+	async fn purge(&self, retention: Duration) -> Result<u64> {
+		let mut subscriptions = self.subscriptions.lock().unwrap_or_else(|ex| ex.into_inner());
+		let now = Instant::now();
+
+		let mut count = 0;
+		for sub in subscriptions.values_mut() {
+			let before = sub.deliveries.len();
+			sub.deliveries.retain(|d| d.dead.is_none() || now.duration_since(d.ctime) < retention);
+			count += (before - sub.deliveries.len()) as u64;
+		}
+
+		Ok(count)
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_mem_publish_receive_ack_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let bus = MemEventBus::new().with_ack_timeout(Duration::ZERO);
+		bus.subscribe(Subscription::new("sub-01").with_type("conv_msg."))
+			.await?;
+		let fx_data = json!({"id": 1000});
+
+		// -- Exec
+		bus.publish(CloudEvent::new("/test", "conv_msg.created", &fx_data)?)
+			.await?;
+		bus.publish(CloudEvent::new("/test", "conv.updated", &fx_data)?)
+			.await?;
+		let deliveries = bus.receive("sub-01", 10).await?;
+
+		// -- Check
+		assert_eq!(deliveries.len(), 1, "should only have the conv_msg event");
+		let delivery = &deliveries[0];
+		assert_eq!(delivery.event.typ, "conv_msg.created");
+		assert_eq!(delivery.event.data_as::<serde_json::Value>()?, fx_data);
+		// not acked (and zero ack timeout), so redelivered
+		let deliveries = bus.receive("sub-01", 10).await?;
+		assert_eq!(deliveries[0].attempts, 2);
+		bus.ack(&deliveries[0]).await?;
+		assert!(bus.receive("sub-01", 10).await?.is_empty());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_mem_dead_letter_purge_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let bus = MemEventBus::new().with_ack_timeout(Duration::ZERO);
+		bus.subscribe(Subscription::new("sub-01")).await?;
+		let fx_data = json!({"id": 1000});
+		bus.publish(CloudEvent::new("/test", "conv_msg.created", &fx_data)?)
+			.await?;
+		bus.publish(CloudEvent::new("/test", "conv.updated", &fx_data)?)
+			.await?;
+
+		// -- Exec
+		let deliveries = bus.receive("sub-01", 10).await?;
+		bus.dead_letter(&deliveries[0], "handler failed").await?;
+
+		// -- Check
+		let deliveries = bus.receive("sub-01", 10).await?;
+		assert_eq!(deliveries.len(), 1, "dead-lettered should not be delivered");
+		assert_eq!(deliveries[0].event.typ, "conv.updated");
+		assert_eq!(bus.purge(Duration::from_secs(60)).await?, 0, "within retention");
+		assert_eq!(bus.purge(Duration::ZERO).await?, 1, "only the dead-lettered");
+		assert_eq!(bus.receive("sub-01", 10).await?.len(), 1);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,148 @@
+use crate::{CloudEvent, Delivery, EventBus, Result, Subscription};
+use async_trait::async_trait;
+use lib_utils::time::now_utc;
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use std::time::Duration as StdDuration;
+use time::Duration;
+
+const DEFAULT_ACK_TIMEOUT_SEC: i64 = 60;
+
+/// Postgres event bus, on the `event`, `event_subscription` and `event_delivery` tables.
+///
+/// Note: The deliveries of the matching subscriptions are created on publish,
+///       and claimed on receive with `FOR UPDATE SKIP LOCKED` (as the `job` queue).
+pub struct PgEventBus {
+	db: Pool<Postgres>,
+	ack_timeout: Duration,
+}
+
+impl PgEventBus {
+	pub fn new(db: Pool<Postgres>) -> Self {
+		PgEventBus {
+			db,
+			ack_timeout: Duration::seconds(DEFAULT_ACK_TIMEOUT_SEC),
+		}
+	}
+
+	pub fn with_ack_timeout(mut self, ack_timeout: Duration) -> Self {
+		self.ack_timeout = ack_timeout;
+		self
+	}
+}
+
+#[async_trait]
+impl EventBus for PgEventBus {
+	/// Note: Idempotent on the CloudEvent id (e.g., republished by the outbox relay).
+	async fn publish(&self, event: CloudEvent) -> Result<()> {
+		let envelope = serde_json::to_value(&event)?;
+
+		let sql = "
+			WITH ev AS (
+			  INSERT INTO event (ce_id, typ, source, envelope, ctime)
+			  VALUES ($1, $2, $3, $4, $5)
+			  ON CONFLICT (ce_id) DO NOTHING
+			  RETURNING id
+			)
+			INSERT INTO event_delivery (subscription, event_id, attempts, visible_at)
+			SELECT s.name, ev.id, 0, $5
+			FROM event_subscription s, ev
+			WHERE cardinality(s.types) = 0
+			   OR EXISTS (SELECT 1 FROM unnest(s.types) AS t(prefix) WHERE starts_with($2, t.prefix))";
+		sqlx::query(sql)
+			.bind(&event.id)
+			.bind(&event.typ)
+			.bind(&event.source)
+			.bind(envelope)
+			.bind(now_utc())
+			.execute(&self.db)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn subscribe(&self, subscription: Subscription) -> Result<()> {
+		let sql = "
+			INSERT INTO event_subscription (name, types, ctime)
+			VALUES ($1, $2, $3)
+			ON CONFLICT (name) DO UPDATE SET types = EXCLUDED.types";
+		sqlx::query(sql)
+			.bind(&subscription.name)
+			.bind(&subscription.types)
+			.bind(now_utc())
+			.execute(&self.db)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn receive(&self, subscription: &str, max: usize) -> Result<Vec<Delivery>> {
+		let now = now_utc();
+		let sql = "
+			UPDATE event_delivery d SET attempts = d.attempts + 1, visible_at = $3
+			FROM event e
+			WHERE e.id = d.event_id AND d.id IN (
+			  SELECT id FROM event_delivery
+			  WHERE subscription = $1 AND visible_at <= $4 AND dead_at IS NULL
+			  ORDER BY id
+			  LIMIT $2
+			  FOR UPDATE SKIP LOCKED
+			)
+			RETURNING d.id, d.attempts, e.envelope";
+		let rows = sqlx::query_as::<_, (i64, i32, Value)>(sql)
+			.bind(subscription)
+			.bind(max as i64)
+			.bind(now + self.ack_timeout)
+			.bind(now)
+			.fetch_all(&self.db)
+			.await?;
+
+		let mut deliveries = Vec::with_capacity(rows.len());
+		for (id, attempts, envelope) in rows {
+			deliveries.push(Delivery {
+				id,
+				subscription: subscription.to_string(),
+				attempts,
+				event: serde_json::from_value(envelope)?,
+			});
+		}
+		deliveries.sort_by_key(|delivery| delivery.id);
+
+		Ok(deliveries)
+	}
+
+	async fn ack(&self, delivery: &Delivery) -> Result<()> {
+		sqlx::query("DELETE FROM event_delivery WHERE id = $1")
+			.bind(delivery.id)
+			.execute(&self.db)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn dead_letter(&self, delivery: &Delivery, error: &str) -> Result<()> {
+		sqlx::query("UPDATE event_delivery SET dead_at = $2, last_error = $3 WHERE id = $1")
+			.bind(delivery.id)
+			.bind(now_utc())
+			.bind(error)
+			.execute(&self.db)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn purge(&self, retention: StdDuration) -> Result<u64> {
+		let sql = "
+			DELETE FROM event e
+			WHERE e.ctime < $1
+			  AND NOT EXISTS (
+			    SELECT 1 FROM event_delivery d WHERE d.event_id = e.id AND d.dead_at IS NULL
+			  )";
+		let res = sqlx::query(sql)
+			.bind(now_utc() - retention)
+			.execute(&self.db)
+			.await?;
+
+		Ok(res.rows_affected())
+	}
+}
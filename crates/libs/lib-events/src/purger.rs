@@ -0,0 +1,53 @@
+use crate::EventBus;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+const DEFAULT_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const DEFAULT_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Purges the events older than the retention (see `EventBus::purge`), at each interval.
+///
+/// Note: Several purgers (e.g., one per service instance) can run on the same bus.
+pub struct EventPurger {
+	bus: Arc<dyn EventBus>,
+	retention: Duration,
+	interval: Duration,
+}
+
+impl EventPurger {
+	pub fn new(bus: Arc<dyn EventBus>) -> Self {
+		EventPurger {
+			bus,
+			retention: DEFAULT_RETENTION,
+			interval: DEFAULT_PURGE_INTERVAL,
+		}
+	}
+
+	pub fn with_retention(mut self, retention: Duration) -> Self {
+		self.retention = retention;
+		self
+	}
+
+	pub fn with_interval(mut self, interval: Duration) -> Self {
+		self.interval = interval;
+		self
+	}
+
+	/// Spawn the purge loop on the tokio runtime.
+	pub fn start(self) {
+		info!("{:<12} - started (retention {:?})", "EVENT PURGER", self.retention);
+		tokio::spawn(self.run());
+	}
+
+	async fn run(self) {
+		loop {
+			match self.bus.purge(self.retention).await {
+				Ok(0) => (),
+				Ok(count) => info!("{:<12} - purged {count} events", "EVENT PURGER"),
+				Err(ex) => error!("{:<12} - purge failed: {ex:?}", "EVENT PURGER"),
+			}
+			tokio::time::sleep(self.interval).await;
+		}
+	}
+}
@@ -0,0 +1,183 @@
+//! JSON-RPC batch, the calls (and notifications) sent in one request.
+//!
+//! ```ignore
+//! let mut batch = client.batch();
+//! let agent = batch.call_data::<Agent>("get_agent", json!({"id": agent_id}))?;
+//! let convs = batch.call_data::<Vec<Conv>>("list_convs", json!({}))?;
+//! let mut res = batch.send().await?;
+//!
+//! let agent = res.take(agent)?;
+//! let convs = res.take(convs)?;
+//! ```
+
+use crate::client::{into_data, into_result, RpcClient, PATH_RPC};
+use crate::{Error, Result};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+// region:    --- RpcBatch
+
+pub struct RpcBatch<'a> {
+	client: &'a RpcClient,
+	entries: Vec<Value>,
+}
+
+/// The typed handle of a batch call, to `take` its result from the `BatchResponse`.
+pub struct BatchCall<R> {
+	id: u64,
+	method: String,
+	data: bool,
+	_r: PhantomData<R>,
+}
+
+impl<'a> RpcBatch<'a> {
+	pub(crate) fn new(client: &'a RpcClient) -> Self {
+		Self {
+			client,
+			entries: Vec::new(),
+		}
+	}
+
+	/// Add the call, its handle gives the `result`.
+	pub fn call<R>(&mut self, method: &str, params: impl Serialize) -> Result<BatchCall<R>>
+	where
+		R: DeserializeOwned,
+	{
+		self.add_call(method, params, false)
+	}
+
+	/// Add the call, its handle gives the `data` of its `DataRpcResult`.
+	pub fn call_data<R>(
+		&mut self,
+		method: &str,
+		params: impl Serialize,
+	) -> Result<BatchCall<R>>
+	where
+		R: DeserializeOwned,
+	{
+		self.add_call(method, params, true)
+	}
+
+	/// Add the notification (no response).
+	pub fn notify(&mut self, method: &str, params: impl Serialize) -> Result<()> {
+		self.entries.push(json!({
+			"jsonrpc": "2.0",
+			"method": method,
+			"params": serde_json::to_value(params)?,
+		}));
+		Ok(())
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	pub async fn send(self) -> Result<BatchResponse> {
+		let mut response = BatchResponse {
+			status: StatusCode::OK,
+			entries: HashMap::new(),
+		};
+		if self.entries.is_empty() {
+			return Ok(response);
+		}
+
+		let res = self
+			.client
+			.post(PATH_RPC)
+			.json(&Value::Array(self.entries))
+			.send()
+			.await?;
+		response.status = res.status();
+
+		// Note: Only notifications, no content.
+		if response.status == StatusCode::NO_CONTENT {
+			return Ok(response);
+		}
+
+		let res_body: Value = res.json().await?;
+		match res_body {
+			Value::Array(res_entries) => {
+				for res_entry in res_entries {
+					// Note: The errors without id (e.g., invalid entry) cannot be matched.
+					if let Some(id) = res_entry.get("id").and_then(Value::as_u64) {
+						response.entries.insert(id, res_entry);
+					}
+				}
+			}
+			// Note: The whole batch failed (e.g., `NO_AUTH`).
+			res_body => {
+				into_result("batch", response.status, res_body)?;
+				return Err(Error::RpcResponseInvalid {
+					method: "batch".to_string(),
+					status: response.status.as_u16(),
+				});
+			}
+		}
+
+		Ok(response)
+	}
+
+	fn add_call<R>(
+		&mut self,
+		method: &str,
+		params: impl Serialize,
+		data: bool,
+	) -> Result<BatchCall<R>> {
+		let id = self.client.next_id();
+		self.entries.push(json!({
+			"jsonrpc": "2.0",
+			"id": id,
+			"method": method,
+			"params": serde_json::to_value(params)?,
+		}));
+
+		Ok(BatchCall {
+			id,
+			method: method.to_string(),
+			data,
+			_r: PhantomData,
+		})
+	}
+}
+
+// endregion: --- RpcBatch
+
+// region:    --- BatchResponse
+
+pub struct BatchResponse {
+	status: StatusCode,
+	entries: HashMap<u64, Value>,
+}
+
+impl BatchResponse {
+	/// Take the result of the call (or its `Error::Rpc`).
+	pub fn take<R>(&mut self, call: BatchCall<R>) -> Result<R>
+	where
+		R: DeserializeOwned,
+	{
+		let BatchCall {
+			id, method, data, ..
+		} = call;
+
+		let res_entry = self
+			.entries
+			.remove(&id)
+			.ok_or_else(|| Error::BatchResponseMissing {
+				method: method.clone(),
+				id,
+			})?;
+		let result = into_result(&method, self.status, res_entry)?;
+
+		if data {
+			into_data(result)
+		} else {
+			Ok(serde_json::from_value(result)?)
+		}
+	}
+}
+
+// endregion: --- BatchResponse
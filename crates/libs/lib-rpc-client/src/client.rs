@@ -0,0 +1,187 @@
+use crate::batch::RpcBatch;
+use crate::error::RpcErrorObject;
+use crate::llm::LlmClient;
+use crate::{Error, Result};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+pub(crate) const PATH_RPC: &str = "/api/rpc";
+const PATH_LOGIN: &str = "/api/login";
+const PATH_LOGOFF: &str = "/api/logoff";
+
+// region:    --- RpcClient
+
+/// The client of the gateway rpc api (`/api/rpc`).
+///
+/// Cheap to clone, the clones share the http connections and the auth cookie.
+#[derive(Clone)]
+pub struct RpcClient {
+	base_url: String,
+	http_client: reqwest::Client,
+	next_id: Arc<AtomicU64>,
+}
+
+impl RpcClient {
+	/// e.g., `RpcClient::new("http://localhost:8080")?`
+	pub fn new(base_url: impl Into<String>) -> Result<Self> {
+		let http_client = reqwest::Client::builder().cookie_store(true).build()?;
+
+		Ok(Self {
+			base_url: base_url.into().trim_end_matches('/').to_string(),
+			http_client,
+			next_id: Arc::new(AtomicU64::new(1)),
+		})
+	}
+}
+
+/// Auth
+impl RpcClient {
+	/// Login with the user credentials.
+	/// The `auth-token` cookie is then sent with the next requests.
+	pub async fn login(&self, username: &str, pwd: &str) -> Result<()> {
+		let body = json!({
+			"username": username,
+			"pwd": pwd,
+		});
+		self.post_json(PATH_LOGIN, "login", &body).await?;
+
+		Ok(())
+	}
+
+	pub async fn logoff(&self) -> Result<()> {
+		self.post_json(PATH_LOGOFF, "logoff", &json!({"logoff": true}))
+			.await?;
+
+		Ok(())
+	}
+}
+
+/// Rpc calls
+impl RpcClient {
+	/// Call the rpc `method` and return its `result`.
+	///
+	/// Note: The worker methods are called through the gateway as `service/method`
+	///       (e.g., `llm-worker/one_shot_msg`, see `RpcClient::llm`).
+	pub async fn call<R>(&self, method: &str, params: impl Serialize) -> Result<R>
+	where
+		R: DeserializeOwned,
+	{
+		let body = json!({
+			"jsonrpc": "2.0",
+			"id": self.next_id(),
+			"method": method,
+			"params": serde_json::to_value(params)?,
+		});
+		let result = self.post_json(PATH_RPC, method, &body).await?;
+
+		Ok(serde_json::from_value(result)?)
+	}
+
+	/// Call the rpc `method` and return the `data` of its `DataRpcResult`
+	/// (i.e., all the entity methods).
+	pub async fn call_data<R>(&self, method: &str, params: impl Serialize) -> Result<R>
+	where
+		R: DeserializeOwned,
+	{
+		let result: Value = self.call(method, params).await?;
+		into_data(result)
+	}
+
+	/// Send the rpc `method` as a notification (no `id`, no response).
+	pub async fn notify(&self, method: &str, params: impl Serialize) -> Result<()> {
+		let body = json!({
+			"jsonrpc": "2.0",
+			"method": method,
+			"params": serde_json::to_value(params)?,
+		});
+		self.post(PATH_RPC).json(&body).send().await?;
+
+		Ok(())
+	}
+
+	/// Start a batch of rpc calls, sent in one request (see `RpcBatch`).
+	pub fn batch(&self) -> RpcBatch<'_> {
+		RpcBatch::new(self)
+	}
+
+	/// The `llm-worker` methods (through the gateway).
+	pub fn llm(&self) -> LlmClient<'_> {
+		LlmClient::new(self)
+	}
+
+	/// The OpenRPC document of the gateway (with the worker methods).
+	pub async fn rpc_discover(&self) -> Result<Value> {
+		self.call("rpc.discover", Value::Null).await
+	}
+}
+
+/// Request support
+impl RpcClient {
+	pub(crate) fn next_id(&self) -> u64 {
+		self.next_id.fetch_add(1, Ordering::Relaxed)
+	}
+
+	/// The POST request to the gateway `path`.
+	pub(crate) fn post(&self, path: &str) -> RequestBuilder {
+		self.http_client.post(format!("{}{path}", self.base_url))
+	}
+
+	/// POST the json body and return the `result` of the response.
+	///
+	/// Note: The gateway error responses (with a 4xx or 5xx status)
+	///       have a JSON-RPC `error` body, decoded as `Error::Rpc`.
+	async fn post_json(&self, path: &str, method: &str, body: &Value) -> Result<Value> {
+		let res = self.post(path).json(body).send().await?;
+		let status = res.status();
+		let res_body: Value = res.json().await.map_err(|_| Error::RpcResponseInvalid {
+			method: method.to_string(),
+			status: status.as_u16(),
+		})?;
+
+		into_result(method, status, res_body)
+	}
+}
+
+// endregion: --- RpcClient
+
+// region:    --- Response Support
+
+#[derive(Deserialize)]
+struct DataResult<T> {
+	data: T,
+}
+
+/// Return the `result` of the JSON-RPC response, or its `error` as `Error::Rpc`.
+pub(crate) fn into_result(
+	method: &str,
+	status: StatusCode,
+	mut res_body: Value,
+) -> Result<Value> {
+	if let Some(error) = res_body.get_mut("error").map(Value::take) {
+		let error: RpcErrorObject = serde_json::from_value(error)?;
+		return Err(error.into_error(method));
+	}
+
+	match res_body.get_mut("result").map(Value::take) {
+		Some(result) => Ok(result),
+		None => Err(Error::RpcResponseInvalid {
+			method: method.to_string(),
+			status: status.as_u16(),
+		}),
+	}
+}
+
+/// Return the `data` of a `DataRpcResult` value.
+pub(crate) fn into_data<R>(result: Value) -> Result<R>
+where
+	R: DeserializeOwned,
+{
+	let DataResult { data } = serde_json::from_value(result)?;
+	Ok(data)
+}
+
+// endregion: --- Response Support
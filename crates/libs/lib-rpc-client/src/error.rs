@@ -0,0 +1,153 @@
+use derive_more::From;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{serde_as, DisplayFromStr};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[serde_as]
+#[derive(Debug, Serialize, From)]
+pub enum Error {
+	/// The JSON-RPC error response of the `method` call.
+	Rpc {
+		method: String,
+		client_error: ClientError,
+		req_uuid: Option<String>,
+	},
+	RpcResponseInvalid {
+		method: String,
+		status: u16,
+	},
+	BatchResponseMissing {
+		method: String,
+		id: u64,
+	},
+
+	// -- Externals
+	#[from]
+	Reqwest(#[serde_as(as = "DisplayFromStr")] reqwest::Error),
+	#[from]
+	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
+}
+
+impl Error {
+	/// The client error of the rpc error response (None for the other errors).
+	pub fn client_error(&self) -> Option<&ClientError> {
+		match self {
+			Error::Rpc { client_error, .. } => Some(client_error),
+			_ => None,
+		}
+	}
+}
+
+// region:    --- Error Boilerplate
+
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+
+// endregion: --- Error Boilerplate
+
+// region:    --- Client Error
+
+/// The client errors of the gateway, decoded from the JSON-RPC error `message`
+/// (variant name) and `data.detail`.
+///
+/// Note: An error unknown by this client version (e.g., newer gateway)
+///       is decoded as `UNKNOWN`, with its raw message and detail.
+pub use lib_rpc_core::ClientError;
+
+/// The JSON-RPC `error` object of the gateway responses.
+#[derive(Deserialize)]
+pub(crate) struct RpcErrorObject {
+	message: String,
+	data: Option<RpcErrorData>,
+}
+
+#[derive(Deserialize)]
+struct RpcErrorData {
+	req_uuid: Option<String>,
+	detail: Option<Value>,
+}
+
+impl RpcErrorObject {
+	pub(crate) fn into_error(self, method: &str) -> Error {
+		let (req_uuid, detail) = match self.data {
+			Some(RpcErrorData { req_uuid, detail }) => (req_uuid, detail),
+			None => (None, None),
+		};
+
+		let client_error = serde_json::from_value(serde_json::json!({
+			"message": self.message,
+			"detail": detail,
+		}))
+		.unwrap_or(ClientError::UNKNOWN {
+			message: self.message,
+			detail,
+		});
+
+		Error::Rpc {
+			method: method.to_string(),
+			client_error,
+			req_uuid,
+		}
+	}
+}
+
+// endregion: --- Client Error
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_rpc_error_into_error_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_errors = [
+			(
+				json!({"message": "ENTITY_NOT_FOUND", "data": {
+					"req_uuid": "fx-uuid", "detail": {"entity": "agent", "id": 123}
+				}}),
+				ClientError::ENTITY_NOT_FOUND {
+					entity: "agent".to_string(),
+					id: 123,
+				},
+			),
+			(
+				json!({"message": "NO_AUTH", "data": {"req_uuid": "fx-uuid", "detail": null}}),
+				ClientError::NO_AUTH,
+			),
+			(
+				json!({"message": "NEW_ERROR", "data": {"detail": "some detail"}}),
+				ClientError::UNKNOWN {
+					message: "NEW_ERROR".to_string(),
+					detail: Some(json!("some detail")),
+				},
+			),
+		];
+
+		// -- Exec & Check
+		for (fx_error, expected) in fx_errors {
+			let error = serde_json::from_value::<RpcErrorObject>(fx_error)?
+				.into_error("get_agent");
+			assert_eq!(error.client_error(), Some(&expected));
+		}
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
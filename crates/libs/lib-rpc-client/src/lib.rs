@@ -0,0 +1,41 @@
+//! Typed client of the gateway rpc api (`/api/rpc`), e.g., for the services,
+//! the integration tests, and the `quick_dev` examples.
+//!
+//! Design:
+//!
+//! - `RpcClient` has one typed async method per gateway rpc method
+//!   (e.g., `client.create_agent(agent_c)`, `client.list_convs(filters, list_options)`),
+//!   with the `lib-core` model types as params and results.
+//! - `client.llm()` has the `llm-worker` methods (dispatched by the gateway).
+//! - Auth is the gateway `auth-token` cookie (`client.login(...)`).
+//! - The JSON-RPC errors are decoded as `Error::Rpc` with the gateway `ClientError`
+//!   (shared with the gateway, see `lib_rpc_core::ClientError`).
+//! - `client.batch()` sends several calls in one JSON-RPC batch request.
+//!
+//! ```ignore
+//! let client = RpcClient::new("http://localhost:8080")?;
+//! client.login("demo1", "welcome").await?;
+//!
+//! let agent = client.create_agent(agent_c).await?;
+//! let convs = client.list_convs(Some(json!({"agent_id": agent.id})), None).await?;
+//! ```
+
+// region:    --- Modules
+
+mod batch;
+mod client;
+mod error;
+mod llm;
+mod params;
+mod rpcs;
+
+pub use self::error::{ClientError, Error, Result};
+pub use batch::{BatchCall, BatchResponse, RpcBatch};
+pub use client::RpcClient;
+pub use llm::{
+	ConvChat, DocumentIngest, GenAIProviderType, GetModelListResponse, KbSearch,
+	LlmClient, OneShotMsg, OneShotMsgResponse,
+};
+pub use params::ListOptions;
+
+// endregion: --- Modules
@@ -0,0 +1,125 @@
+//! The `llm-worker` rpc methods, called through the gateway as `llm-worker/method`.
+//!
+//! Note: The params and results mirror the llm-worker rpc types
+//!       (e.g., `OneShotMsg`, `ConvChat`), the entities are the `lib-core` ones.
+
+use crate::params::ParamsForCreate;
+use crate::{Result, RpcClient};
+use lib_core::model::chunk::ChunkMatch;
+use lib_core::model::conv_msg::{ConvMsg, MsgRole};
+use lib_core::model::document::Document;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const SERVICE_NAME: &str = "llm-worker";
+
+// region:    --- LlmClient
+
+pub struct LlmClient<'a> {
+	client: &'a RpcClient,
+}
+
+impl<'a> LlmClient<'a> {
+	pub(crate) fn new(client: &'a RpcClient) -> Self {
+		Self { client }
+	}
+
+	pub async fn one_shot_msg(&self, data: OneShotMsg) -> Result<OneShotMsgResponse> {
+		self.call_data("one_shot_msg", data).await
+	}
+
+	pub async fn get_model_list(
+		&self,
+		provider: GenAIProviderType,
+	) -> Result<GetModelListResponse> {
+		self.call_data("get_model_list", GetModelListRequest { provider })
+			.await
+	}
+
+	/// Returns the reply (`Assistant` conv_msg) to the conv branch.
+	pub async fn conv_chat(&self, data: ConvChat) -> Result<ConvMsg> {
+		self.call_data("conv_chat", data).await
+	}
+
+	pub async fn ingest_document(&self, data: DocumentIngest) -> Result<Document> {
+		self.call_data("ingest_document", data).await
+	}
+
+	pub async fn search_kb(&self, data: KbSearch) -> Result<Vec<ChunkMatch>> {
+		self.call_data("search_kb", data).await
+	}
+
+	/// Note: The llm-worker params are all `{"data": ...}` (i.e., `ParamsW`).
+	async fn call_data<D, R>(&self, method: &str, data: D) -> Result<R>
+	where
+		D: Serialize,
+		R: serde::de::DeserializeOwned,
+	{
+		let method = format!("{SERVICE_NAME}/{method}");
+		self.client
+			.call_data(&method, ParamsForCreate { data })
+			.await
+	}
+}
+
+// endregion: --- LlmClient
+
+// region:    --- Llm Types
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OneShotMsg {
+	pub mode: MsgRole,
+	pub prompt: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OneShotMsgResponse {
+	pub response: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum GenAIProviderType {
+	OpenAI,
+	Gemini,
+	Anthropic,
+	Groq,
+	Cohere,
+	All,
+}
+
+#[derive(Serialize)]
+struct GetModelListRequest {
+	provider: GenAIProviderType,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetModelListResponse {
+	/// Model names by provider name.
+	pub models: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConvChat {
+	pub conv_id: i64,
+	/// Leaf message of the branch to reply to (default to the latest).
+	pub msg_id: Option<i64>,
+	pub kb_top_k: Option<usize>,
+	pub kb_document_ids: Option<Vec<i64>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DocumentIngest {
+	pub title: String,
+	pub source: Option<String>,
+	pub text: String,
+	pub chunk_max_chars: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KbSearch {
+	pub query: String,
+	pub top_k: Option<usize>,
+	pub document_ids: Option<Vec<i64>>,
+}
+
+// endregion: --- Llm Types
@@ -0,0 +1,42 @@
+//! The params of the gateway rpc methods (serialize side of the `lib-rpc-core` params).
+
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Serialize)]
+pub(crate) struct ParamsForCreate<D> {
+	pub data: D,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ParamsForUpdate<D> {
+	pub id: i64,
+	pub data: D,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ParamsIded {
+	pub id: i64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ParamsList {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub filters: Option<Value>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub list_options: Option<ListOptions>,
+}
+
+/// The list options of the `list_...` methods (see modql `ListOptions`).
+///
+/// e.g., `ListOptions { limit: Some(10), order_bys: Some(vec!["!ctime".into()]), ..Default::default() }`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListOptions {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub limit: Option<i64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub offset: Option<i64>,
+	/// Property names, `!` prefixed for descending order.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub order_bys: Option<Vec<String>>,
+}
@@ -0,0 +1,9 @@
+use crate::rpcs::impl_common_rpc_fns;
+use lib_core::model::agent::{Agent, AgentForCreate, AgentForUpdate};
+
+impl_common_rpc_fns!(
+	Entity: Agent,
+	ForCreate: AgentForCreate,
+	ForUpdate: AgentForUpdate,
+	Suffix: agent
+);
@@ -0,0 +1,84 @@
+use crate::params::{ParamsForCreate, ParamsForUpdate, ParamsIded, ParamsList};
+use crate::rpcs::impl_common_rpc_fns;
+use crate::{ListOptions, Result, RpcClient};
+use lib_core::model::conv::{Conv, ConvForCreate, ConvForUpdate};
+use lib_core::model::conv_msg::{
+	ConvMsg, ConvMsgForCreate, ConvMsgForEdit, ConvMsgForUpdate,
+};
+use lib_core::model::conv_user::{ConvUser, ConvUserForCreate};
+use serde_json::{json, Value};
+
+impl_common_rpc_fns!(
+	Entity: Conv,
+	ForCreate: ConvForCreate,
+	ForUpdate: ConvForUpdate,
+	Suffix: conv
+);
+
+impl RpcClient {
+	/// Returns the new (forked) conv, forked at `msg_id`.
+	pub async fn fork_conv(&self, conv_id: i64, msg_id: i64) -> Result<Conv> {
+		let params = json!({
+			"conv_id": conv_id,
+			"msg_id": msg_id,
+		});
+		self.call_data("fork_conv", params).await
+	}
+
+	pub async fn add_conv_user(&self, data: ConvUserForCreate) -> Result<ConvUser> {
+		self.call_data("add_conv_user", ParamsForCreate { data }).await
+	}
+
+	pub async fn list_conv_users(&self, conv_id: i64) -> Result<Vec<ConvUser>> {
+		self.call_data("list_conv_users", ParamsIded { id: conv_id })
+			.await
+	}
+
+	pub async fn add_conv_msg(&self, data: ConvMsgForCreate) -> Result<ConvMsg> {
+		self.call_data("add_conv_msg", ParamsForCreate { data }).await
+	}
+
+	pub async fn get_conv_msg(&self, id: i64) -> Result<ConvMsg> {
+		self.call_data("get_conv_msg", ParamsIded { id }).await
+	}
+
+	/// e.g., filters `json!({"conv_id": conv_id})`
+	pub async fn list_conv_msgs(
+		&self,
+		filters: Option<Value>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<ConvMsg>> {
+		let params = ParamsList {
+			filters,
+			list_options,
+		};
+		self.call_data("list_conv_msgs", params).await
+	}
+
+	pub async fn update_conv_msg(
+		&self,
+		id: i64,
+		data: ConvMsgForUpdate,
+	) -> Result<ConvMsg> {
+		self.call_data("update_conv_msg", ParamsForUpdate { id, data })
+			.await
+	}
+
+	/// Returns the new conv_msg (sibling of the edited one, i.e., a new branch)
+	pub async fn edit_conv_msg(&self, id: i64, data: ConvMsgForEdit) -> Result<ConvMsg> {
+		self.call_data("edit_conv_msg", ParamsForUpdate { id, data })
+			.await
+	}
+
+	/// Returns all conv_msgs of the conv (all branches)
+	pub async fn list_conv_msg_tree(&self, conv_id: i64) -> Result<Vec<ConvMsg>> {
+		self.call_data("list_conv_msg_tree", ParamsIded { id: conv_id })
+			.await
+	}
+
+	/// Returns the conv_msgs from the conv root down to the leaf conv_msg
+	pub async fn get_conv_thread(&self, leaf_msg_id: i64) -> Result<Vec<ConvMsg>> {
+		self.call_data("get_conv_thread", ParamsIded { id: leaf_msg_id })
+			.await
+	}
+}
@@ -0,0 +1,34 @@
+use crate::params::{ParamsForCreate, ParamsIded, ParamsList};
+use crate::{ListOptions, Result, RpcClient};
+use lib_core::model::job::{Job, JobForCreate};
+use serde_json::Value;
+
+impl RpcClient {
+	pub async fn enqueue_job(&self, data: JobForCreate) -> Result<Job> {
+		self.call_data("enqueue_job", ParamsForCreate { data }).await
+	}
+
+	pub async fn get_job(&self, id: i64) -> Result<Job> {
+		self.call_data("get_job", ParamsIded { id }).await
+	}
+
+	pub async fn list_jobs(
+		&self,
+		filters: Option<Value>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Job>> {
+		let params = ParamsList {
+			filters,
+			list_options,
+		};
+		self.call_data("list_jobs", params).await
+	}
+
+	pub async fn cancel_job(&self, id: i64) -> Result<Job> {
+		self.call_data("cancel_job", ParamsIded { id }).await
+	}
+
+	pub async fn retry_job(&self, id: i64) -> Result<Job> {
+		self.call_data("retry_job", ParamsIded { id }).await
+	}
+}
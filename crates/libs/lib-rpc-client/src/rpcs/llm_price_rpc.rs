@@ -0,0 +1,9 @@
+use crate::rpcs::impl_common_rpc_fns;
+use lib_core::model::llm_price::{LlmPrice, LlmPriceForCreate, LlmPriceForUpdate};
+
+impl_common_rpc_fns!(
+	Entity: LlmPrice,
+	ForCreate: LlmPriceForCreate,
+	ForUpdate: LlmPriceForUpdate,
+	Suffix: llm_price
+);
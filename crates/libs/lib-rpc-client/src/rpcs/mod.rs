@@ -0,0 +1,82 @@
+//! The typed methods of the gateway rpcs (same modules as the gateway `web/rpcs`).
+
+mod agent_rpc;
+mod conv_rpc;
+mod job_rpc;
+mod llm_price_rpc;
+mod quota_rpc;
+mod usage_rpc;
+mod webhook_rpc;
+
+/// Implement the `RpcClient` methods of the gateway `generate_common_rpc_fns!`
+/// (i.e., `create_...`, `get_...`, `list_...s`, `update_...`, `delete_...`).
+macro_rules! impl_common_rpc_fns {
+	(
+		Entity: $entity:ty,
+		ForCreate: $for_create:ty,
+		ForUpdate: $for_update:ty,
+		Suffix: $suffix:ident
+	) => {
+		paste::paste! {
+			impl $crate::RpcClient {
+				pub async fn [<create_ $suffix>](
+					&self,
+					data: $for_create,
+				) -> $crate::Result<$entity> {
+					self.call_data(
+						concat!("create_", stringify!($suffix)),
+						$crate::params::ParamsForCreate { data },
+					)
+					.await
+				}
+
+				pub async fn [<get_ $suffix>](&self, id: i64) -> $crate::Result<$entity> {
+					self.call_data(
+						concat!("get_", stringify!($suffix)),
+						$crate::params::ParamsIded { id },
+					)
+					.await
+				}
+
+				/// The `filters` are modql filters,
+				/// e.g., `json!({"name": {"$contains": "AAA"}})`.
+				pub async fn [<list_ $suffix s>](
+					&self,
+					filters: Option<serde_json::Value>,
+					list_options: Option<$crate::ListOptions>,
+				) -> $crate::Result<Vec<$entity>> {
+					self.call_data(
+						concat!("list_", stringify!($suffix), "s"),
+						$crate::params::ParamsList {
+							filters,
+							list_options,
+						},
+					)
+					.await
+				}
+
+				pub async fn [<update_ $suffix>](
+					&self,
+					id: i64,
+					data: $for_update,
+				) -> $crate::Result<$entity> {
+					self.call_data(
+						concat!("update_", stringify!($suffix)),
+						$crate::params::ParamsForUpdate { id, data },
+					)
+					.await
+				}
+
+				/// Returns the deleted entity.
+				pub async fn [<delete_ $suffix>](&self, id: i64) -> $crate::Result<$entity> {
+					self.call_data(
+						concat!("delete_", stringify!($suffix)),
+						$crate::params::ParamsIded { id },
+					)
+					.await
+				}
+			}
+		}
+	};
+}
+pub(crate) use impl_common_rpc_fns;
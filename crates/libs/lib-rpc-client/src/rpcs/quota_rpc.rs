@@ -0,0 +1,42 @@
+use crate::params::{ParamsForCreate, ParamsForUpdate, ParamsIded, ParamsList};
+use crate::{ListOptions, Result, RpcClient};
+use lib_core::model::quota::{Quota, QuotaForSet, QuotaOverride};
+use serde_json::Value;
+
+impl RpcClient {
+	/// (`Sys` user only)
+	pub async fn set_quota(&self, data: QuotaForSet) -> Result<Quota> {
+		self.call_data("set_quota", ParamsForCreate { data }).await
+	}
+
+	/// (`Sys` user only)
+	pub async fn grant_quota_override(
+		&self,
+		id: i64,
+		data: QuotaOverride,
+	) -> Result<Quota> {
+		self.call_data("grant_quota_override", ParamsForUpdate { id, data })
+			.await
+	}
+
+	pub async fn get_quota(&self, id: i64) -> Result<Quota> {
+		self.call_data("get_quota", ParamsIded { id }).await
+	}
+
+	pub async fn list_quotas(
+		&self,
+		filters: Option<Value>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Quota>> {
+		let params = ParamsList {
+			filters,
+			list_options,
+		};
+		self.call_data("list_quotas", params).await
+	}
+
+	/// (`Sys` user only)
+	pub async fn delete_quota(&self, id: i64) -> Result<Quota> {
+		self.call_data("delete_quota", ParamsIded { id }).await
+	}
+}
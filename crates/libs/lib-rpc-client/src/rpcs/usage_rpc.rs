@@ -0,0 +1,32 @@
+use crate::params::ParamsList;
+use crate::{ListOptions, Result, RpcClient};
+use lib_core::model::usage_event::{
+	UsageEvent, UsageGroupBy, UsageSummary, UsageSummaryFilter,
+};
+use serde_json::{json, Value};
+
+impl RpcClient {
+	pub async fn list_usage_events(
+		&self,
+		filters: Option<Value>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<UsageEvent>> {
+		let params = ParamsList {
+			filters,
+			list_options,
+		};
+		self.call_data("list_usage_events", params).await
+	}
+
+	pub async fn usage_summary(
+		&self,
+		group_by: UsageGroupBy,
+		filter: UsageSummaryFilter,
+	) -> Result<Vec<UsageSummary>> {
+		let params = json!({
+			"group_by": group_by,
+			"filter": filter,
+		});
+		self.call_data("usage_summary", params).await
+	}
+}
@@ -0,0 +1,29 @@
+use crate::rpcs::impl_common_rpc_fns;
+use crate::{ListOptions, Result, RpcClient};
+use lib_core::model::webhook::{
+	Webhook, WebhookDelivery, WebhookForCreate, WebhookForUpdate,
+};
+use serde_json::{json, Value};
+
+impl_common_rpc_fns!(
+	Entity: Webhook,
+	ForCreate: WebhookForCreate,
+	ForUpdate: WebhookForUpdate,
+	Suffix: webhook
+);
+
+impl RpcClient {
+	pub async fn list_webhook_deliveries(
+		&self,
+		webhook_id: i64,
+		filters: Option<Value>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<WebhookDelivery>> {
+		let params = json!({
+			"webhook_id": webhook_id,
+			"filters": filters,
+			"list_options": list_options,
+		});
+		self.call_data("list_webhook_deliveries", params).await
+	}
+}
@@ -0,0 +1,57 @@
+//! The client errors of the gateway JSON-RPC api.
+//!
+//! - Rendered by the gateway (`lib-web`, from its `Error`) as the JSON-RPC error
+//!   `message` (variant name) and `data.detail`.
+//! - Decoded by the rust rpc client (`lib-rpc-client`).
+//! - Exported to the TypeScript types (see `gen-ts` tool), with its `JsonSchema`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(
+	Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, strum_macros::AsRefStr,
+)]
+#[serde(tag = "message", content = "detail")]
+#[allow(non_camel_case_types)]
+pub enum ClientError {
+	LOGIN_FAIL,
+	NO_AUTH,
+	ACCESS_DENIED,
+	ENTITY_NOT_FOUND { entity: String, id: i64 },
+
+	AGENT_INVALID(String),
+
+	ATTACHMENT_INVALID(String),
+	ATTACHMENT_TOO_LARGE { max: usize, actual: usize },
+	ATTACHMENT_MIME_NOT_ALLOWED(String),
+
+	QUOTA_EXCEEDED {
+		scope: String,
+		period: String,
+		reset_time: String,
+	},
+
+	JOB_INVALID(String),
+	JOB_STATE_INVALID { id: i64, state: String },
+
+	WEBHOOK_INVALID(String),
+
+	RPC_REQUEST_INVALID(String),
+	RPC_REQUEST_METHOD_UNKNOWN(String),
+	RPC_PARAMS_INVALID(String),
+
+	REQUEST_TIMEOUT,
+
+	SERVICE_UNAVAILABLE,
+	SERVICE_ERROR,
+
+	/// Client side only, an error unknown by the client version (e.g., newer gateway),
+	/// with its raw message and detail.
+	#[serde(skip_deserializing)]
+	#[schemars(skip)]
+	UNKNOWN {
+		message: String,
+		detail: Option<Value>,
+	},
+}
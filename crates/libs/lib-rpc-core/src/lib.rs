@@ -1,11 +1,14 @@
 // region:    --- Modules
 
+mod client_error;
 mod error;
 mod rpc_params;
 mod rpc_result;
 mod rpcs;
+pub mod openrpc;
 pub mod prelude;
 
+pub use self::client_error::ClientError;
 pub use self::error::{Error, Result};
 pub use rpc_params::*;
 
@@ -0,0 +1,420 @@
+//! OpenRPC (1.x) document of the service rpc methods, served by the `rpc.discover` method.
+//!
+//! The params and result schemas of each method are generated (with `schemars`) from its
+//! rpc handler function signature, so the `rpc_builders!` creates both the router builder
+//! and the doc builder of an rpc module from one list of functions:
+//!
+//! ```ignore
+//! // `rpc_router_builder()` and `rpc_doc_builder()`
+//! rpc_builders!(create_agent, get_agent, list_agents);
+//! ```
+//!
+//! Notes:
+//!   - The params are by-name, each property of the handler params type is an OpenRPC param
+//!     (e.g., `data` for `ParamsForCreate<AgentForCreate>`).
+//!   - The named types (e.g., `Agent`) are in the `components.schemas`, referenced with `$ref`.
+//!   - The gateway document also has the worker methods, as `service/method`
+//!     (see `OpenRpcDoc::merge_service`).
+
+use crate::Result;
+use rpc_router::{FromResources, IntoParams, RpcResource};
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+
+pub const OPENRPC_VERSION: &str = "1.2.6";
+
+/// The OpenRPC service discovery method.
+pub const RPC_DISCOVER: &str = "rpc.discover";
+
+const SCHEMAS_PATH: &str = "#/components/schemas/";
+const PARAM_STRUCTURE_BY_NAME: &str = "by-name";
+
+// region:    --- OpenRPC Types
+
+/// The OpenRPC document, also the rpc resource of the `rpc.discover` handler.
+#[derive(Debug, Clone, Serialize, Deserialize, RpcResource)]
+pub struct OpenRpcDoc {
+	pub openrpc: String,
+	pub info: OpenRpcInfo,
+	pub methods: Vec<OpenRpcMethod>,
+	#[serde(default)]
+	pub components: OpenRpcComponents,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenRpcInfo {
+	pub title: String,
+	pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenRpcMethod {
+	pub name: String,
+	pub params: Vec<ContentDescriptor>,
+	pub result: ContentDescriptor,
+	pub param_structure: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentDescriptor {
+	pub name: String,
+	#[serde(default)]
+	pub required: bool,
+	pub schema: Schema,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpenRpcComponents {
+	pub schemas: BTreeMap<String, Schema>,
+}
+
+impl OpenRpcDoc {
+	/// Add the methods of the `service` document as `service/method`
+	/// (i.e., as dispatched by the gateway).
+	///
+	/// Note: The schemas already in this document are kept, since the services
+	///       share their named types (e.g., `ConvMsg` from `lib-core`).
+	pub fn merge_service(&mut self, service: &str, doc: OpenRpcDoc) {
+		for mut method in doc.methods {
+			method.name = format!("{service}/{}", method.name);
+			self.methods.push(method);
+		}
+		for (name, schema) in doc.components.schemas {
+			self.components.schemas.entry(name).or_insert(schema);
+		}
+	}
+}
+
+// endregion: --- OpenRPC Types
+
+// region:    --- RpcHandlerDoc
+
+/// The params and result schemas of an rpc handler function.
+///
+/// Implemented for the same function forms as the `rpc-router` handlers,
+/// i.e., `async fn(resources..., params) -> Result<R, E>` and `async fn(resources...) -> Result<R, E>`.
+pub trait RpcHandlerDoc<T, P> {
+	/// None when the handler does not take params.
+	fn params_schema(gen: &mut SchemaGenerator) -> Option<Schema>;
+
+	fn result_schema(gen: &mut SchemaGenerator) -> Schema;
+}
+
+macro_rules! impl_rpc_handler_doc {
+	($($T:ident),*) => {
+		impl<F, Fut, $($T,)* P, R, E> RpcHandlerDoc<($($T,)*), (P,)> for F
+		where
+			F: FnOnce($($T,)* P) -> Fut,
+			Fut: Future<Output = core::result::Result<R, E>>,
+			$($T: FromResources,)*
+			P: IntoParams + JsonSchema,
+			R: Serialize + JsonSchema,
+		{
+			fn params_schema(gen: &mut SchemaGenerator) -> Option<Schema> {
+				Some(P::json_schema(gen))
+			}
+
+			fn result_schema(gen: &mut SchemaGenerator) -> Schema {
+				R::json_schema(gen)
+			}
+		}
+
+		impl<F, Fut, $($T,)* R, E> RpcHandlerDoc<($($T,)*), ()> for F
+		where
+			F: FnOnce($($T,)*) -> Fut,
+			Fut: Future<Output = core::result::Result<R, E>>,
+			$($T: FromResources,)*
+			R: Serialize + JsonSchema,
+		{
+			fn params_schema(_gen: &mut SchemaGenerator) -> Option<Schema> {
+				None
+			}
+
+			fn result_schema(gen: &mut SchemaGenerator) -> Schema {
+				R::json_schema(gen)
+			}
+		}
+	};
+}
+
+impl_rpc_handler_doc!();
+impl_rpc_handler_doc!(T1);
+impl_rpc_handler_doc!(T1, T2);
+impl_rpc_handler_doc!(T1, T2, T3);
+impl_rpc_handler_doc!(T1, T2, T3, T4);
+impl_rpc_handler_doc!(T1, T2, T3, T4, T5);
+impl_rpc_handler_doc!(T1, T2, T3, T4, T5, T6);
+
+// endregion: --- RpcHandlerDoc
+
+// region:    --- RpcDocBuilder
+
+/// Builder of the `OpenRpcDoc` (see `rpc_doc_builder!`).
+#[derive(Default)]
+pub struct RpcDocBuilder {
+	methods: Vec<OpenRpcMethod>,
+	schemas: BTreeMap<String, Schema>,
+}
+
+impl RpcDocBuilder {
+	/// Add the method `name` with the params and result schemas of its `handler`.
+	pub fn append<F, T, P>(mut self, name: &'static str, _handler: F) -> Self
+	where
+		F: RpcHandlerDoc<T, P>,
+	{
+		let mut gen = new_schema_generator();
+
+		let params = F::params_schema(&mut gen)
+			.map(into_by_name_params)
+			.unwrap_or_default();
+		let result = ContentDescriptor {
+			name: "result".to_string(),
+			required: true,
+			schema: F::result_schema(&mut gen),
+		};
+		self.schemas.extend(gen.take_definitions());
+
+		self.methods.push(OpenRpcMethod {
+			name: name.to_string(),
+			params,
+			result,
+			param_structure: PARAM_STRUCTURE_BY_NAME.to_string(),
+		});
+
+		self
+	}
+
+	pub fn extend(mut self, other: RpcDocBuilder) -> Self {
+		self.methods.extend(other.methods);
+		self.schemas.extend(other.schemas);
+		self
+	}
+
+	pub fn build(self, title: impl Into<String>, version: impl Into<String>) -> OpenRpcDoc {
+		OpenRpcDoc {
+			openrpc: OPENRPC_VERSION.to_string(),
+			info: OpenRpcInfo {
+				title: title.into(),
+				version: version.into(),
+			},
+			methods: self.methods,
+			components: OpenRpcComponents {
+				schemas: self.schemas,
+			},
+		}
+	}
+}
+
+/// Create the `RpcDocBuilder` of the given rpc handler functions
+/// (same as `RpcDocBuilder::default().append("create_agent", create_agent)...`).
+#[macro_export]
+macro_rules! rpc_doc_builder {
+	($($fn_name:ident),+ $(,)?) => {
+		$crate::openrpc::RpcDocBuilder::default()
+			$(.append(stringify!($fn_name), $fn_name))+
+	};
+}
+
+/// Create the `rpc_router_builder()` and `rpc_doc_builder()` functions of an rpc module
+/// from the same rpc handler functions (i.e., the documented methods are the routed ones).
+#[macro_export]
+macro_rules! rpc_builders {
+	($($fn_name:ident),+ $(,)?) => {
+		pub fn rpc_router_builder() -> $crate::prelude::RouterBuilder {
+			$crate::prelude::router_builder!($($fn_name),+)
+		}
+
+		pub fn rpc_doc_builder() -> $crate::openrpc::RpcDocBuilder {
+			$crate::rpc_doc_builder!($($fn_name),+)
+		}
+	};
+}
+
+fn new_schema_generator() -> SchemaGenerator {
+	SchemaSettings::draft07()
+		.with(|settings| {
+			settings.definitions_path = SCHEMAS_PATH.to_string();
+		})
+		.into_generator()
+}
+
+/// Split the params object schema into one content descriptor per property.
+/// A non object params schema is a single `params` content descriptor.
+fn into_by_name_params(schema: Schema) -> Vec<ContentDescriptor> {
+	let Schema::Object(SchemaObject {
+		object: Some(object),
+		..
+	}) = &schema
+	else {
+		return vec![ContentDescriptor {
+			name: "params".to_string(),
+			required: true,
+			schema: schema.clone(),
+		}];
+	};
+
+	object
+		.properties
+		.iter()
+		.map(|(name, schema)| ContentDescriptor {
+			name: name.to_string(),
+			required: object.required.contains(name),
+			schema: schema.clone(),
+		})
+		.collect()
+}
+
+// endregion: --- RpcDocBuilder
+
+// region:    --- rpc.discover
+
+/// The `rpc.discover` handler, returns the `OpenRpcDoc` resource of the service.
+///
+/// Note: Register with `.append(RPC_DISCOVER, rpc_discover)` and the doc resource
+///       (the gateway has its own handler, with the worker methods).
+pub async fn rpc_discover(doc: OpenRpcDoc) -> Result<OpenRpcDoc> {
+	Ok(doc)
+}
+
+// endregion: --- rpc.discover
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use self::agent_rpc::{rpc_doc_builder, rpc_router_builder};
+	use lib_core::_dev_utils;
+	use lib_core::ctx::Ctx;
+	use rpc_router::resources_builder;
+	use serde_json::json;
+
+	/// The rpc module under test (the same handlers as the gateway `agent_rpc`).
+	mod agent_rpc {
+		use crate::prelude::*;
+		use lib_core::model::agent::{
+			Agent, AgentBmc, AgentFilter, AgentForCreate, AgentForUpdate,
+		};
+
+		rpc_builders!(create_agent, get_agent, list_agents, update_agent, delete_agent, ping);
+
+		generate_common_rpc_fns!(
+			Bmc: AgentBmc,
+			Entity: Agent,
+			ForCreate: AgentForCreate,
+			ForUpdate: AgentForUpdate,
+			Filter: AgentFilter,
+			Suffix: agent
+		);
+
+		pub async fn ping(ctx: Ctx) -> Result<DataRpcResult<i64>> {
+			Ok(ctx.user_id().into())
+		}
+	}
+
+	#[test]
+	fn test_rpc_doc_builder_ok() -> Result<()> {
+		// -- Exec
+		let doc = rpc_doc_builder().build("test", "0.1.0");
+		let doc = serde_json::to_value(doc)?;
+
+		// -- Check
+		let methods = doc["methods"].as_array().ok_or("should have methods")?;
+		let names: Vec<_> = methods.iter().map(|method| &method["name"]).collect();
+		assert_eq!(
+			names,
+			[
+				"create_agent",
+				"get_agent",
+				"list_agents",
+				"update_agent",
+				"delete_agent",
+				"ping"
+			]
+		);
+
+		let update = &methods[3];
+		assert_eq!(update["paramStructure"], "by-name");
+		assert_eq!(update["params"][0]["name"], "id");
+		assert_eq!(update["params"][0]["required"], true);
+		assert_eq!(update["params"][1]["name"], "data");
+		assert_eq!(
+			update["params"][1]["schema"]["$ref"],
+			"#/components/schemas/AgentForUpdate"
+		);
+		assert_eq!(
+			update["result"]["schema"]["properties"]["data"]["$ref"],
+			"#/components/schemas/Agent"
+		);
+
+		let list = &methods[2];
+		assert_eq!(list["params"][0]["name"], "filters");
+		assert_eq!(list["params"][0]["required"], false);
+
+		let ping = &methods[5];
+		assert_eq!(ping["params"], json!([]));
+
+		let schemas = &doc["components"]["schemas"];
+		assert_eq!(schemas["Agent"]["properties"]["ctime"]["type"], "string");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_rpc_builders_documented_methods_routed_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_name = "test_rpc_builders_documented_methods_routed_ok agent 01";
+		let router = rpc_router_builder().append_resource(mm.clone()).build();
+		let doc = rpc_doc_builder().build("test", "0.1.0");
+
+		// -- Exec
+		let create_res = router
+			.call_route_with_resources(
+				None,
+				"create_agent",
+				Some(json!({"data": {"name": fx_name}})),
+				resources_builder![ctx.clone()].build(),
+			)
+			.await?;
+		let agent_id = create_res.value["data"]["id"]
+			.as_i64()
+			.ok_or("should have data.id")?;
+
+		// -- Check
+		// Each documented method is routed (i.e., not a `MethodUnknown` call error).
+		for method in &doc.methods {
+			let params = match method.name.as_str() {
+				"ping" => None,
+				"list_agents" => Some(json!({})),
+				"create_agent" => Some(json!({"data": {"name": format!("{fx_name}-02")}})),
+				"update_agent" => Some(json!({"id": agent_id, "data": {}})),
+				_ => Some(json!({"id": agent_id})),
+			};
+			let res = router
+				.call_route_with_resources(
+					None,
+					method.name.clone(),
+					params,
+					resources_builder![ctx.clone()].build(),
+				)
+				.await;
+			assert!(res.is_ok(), "{} should be routed: {res:?}", method.name);
+		}
+
+		// -- Clean
+		_dev_utils::clean_agents(&ctx, &mm, fx_name).await?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
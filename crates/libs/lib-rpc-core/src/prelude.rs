@@ -2,6 +2,9 @@
 //! NOTE: This is only for the `rpcs` module and sub-modules.
 
 pub use crate::generate_common_rpc_fns;
+pub use crate::openrpc::RpcDocBuilder;
+pub use crate::rpc_builders;
+pub use crate::rpc_doc_builder;
 pub use crate::rpc_result::DataRpcResult;
 pub use crate::Result;
 pub use crate::{ParamsForCreate, ParamsForUpdate, ParamsIded, ParamsList};
@@ -6,15 +6,21 @@
 //!
 //! `IntoParams` or `IntoDefaultRpcParams` are implemented to ensure these Params conform to the
 //! `RpcRouter` (i.e., `rpc::router`) model.
+//!
+//! `JsonSchema` is implemented for the OpenRPC document (see `openrpc`).
 
 use modql::filter::ListOptions;
 use rpc_router::{IntoDefaultRpcParams, IntoParams};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{Metadata, Schema, SchemaObject, SubschemaValidation};
+use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde_with::{serde_as, OneOrMany};
+use std::marker::PhantomData;
 
 /// Params structure for any RPC Create call.
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct ParamsForCreate<D> {
 	pub data: D,
 }
@@ -22,7 +28,7 @@ pub struct ParamsForCreate<D> {
 impl<D> IntoParams for ParamsForCreate<D> where D: DeserializeOwned + Send {}
 
 /// Params structure for any RPC Update call.
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct ParamsForUpdate<D> {
 	pub id: i64,
 	pub data: D,
@@ -31,7 +37,7 @@ pub struct ParamsForUpdate<D> {
 impl<D> IntoParams for ParamsForUpdate<D> where D: DeserializeOwned + Send {}
 
 /// Params structure for any RPC Update call.
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct ParamsIded {
 	pub id: i64,
 }
@@ -53,3 +59,71 @@ impl<D> IntoDefaultRpcParams for ParamsList<D> where
 	D: DeserializeOwned + Send + Default
 {
 }
+
+/// Note: The filters are the entity filters (e.g., `AgentFilter`, see `ParamsListSchema`).
+impl<F> JsonSchema for ParamsList<F>
+where
+	F: DeserializeOwned + JsonSchema,
+{
+	fn schema_name() -> String {
+		format!("ParamsList_for_{}", F::schema_name())
+	}
+
+	fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+		ParamsListSchema::<F>::json_schema(gen)
+	}
+}
+
+// region:    --- Schemas
+
+/// Schema of the `ParamsList` params.
+#[derive(JsonSchema)]
+pub struct ParamsListSchema<F> {
+	pub filters: Option<FiltersSchema<F>>,
+	pub list_options: Option<ListOptionsSchema>,
+}
+
+/// Schema of one or many entity filters (i.e., `OneOrMany<F>`, inlined).
+pub struct FiltersSchema<F>(PhantomData<F>);
+
+impl<F> JsonSchema for FiltersSchema<F>
+where
+	F: JsonSchema,
+{
+	fn is_referenceable() -> bool {
+		false
+	}
+
+	fn schema_name() -> String {
+		format!("Filters_for_{}", F::schema_name())
+	}
+
+	fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+		SchemaObject {
+			metadata: Some(Box::new(Metadata {
+				description: Some(
+					"One or many filters (the matches of any filter are returned).".to_string(),
+				),
+				..Default::default()
+			})),
+			subschemas: Some(Box::new(SubschemaValidation {
+				any_of: Some(vec![gen.subschema_for::<F>(), gen.subschema_for::<Vec<F>>()]),
+				..Default::default()
+			})),
+			..Default::default()
+		}
+		.into()
+	}
+}
+
+/// Schema of the modql `ListOptions`.
+#[derive(JsonSchema)]
+#[schemars(rename = "ListOptions")]
+pub struct ListOptionsSchema {
+	pub limit: Option<i64>,
+	pub offset: Option<i64>,
+	/// Property names, prefixed with `!` for a descending order (e.g., `["!ctime", "title"]`).
+	pub order_bys: Option<Vec<String>>,
+}
+
+// endregion: --- Schemas
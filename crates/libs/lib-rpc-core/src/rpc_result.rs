@@ -10,9 +10,10 @@
 //!     it represents the `.result` property of a JSON-RPC response.
 //!
 
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct DataRpcResult<T>
 where
 	T: Serialize,
@@ -0,0 +1,265 @@
+//! Minimal cron schedule (5 fields, UTC).
+//!
+//! Format: `minute hour day-of-month month day-of-week`
+//!   - Each field supports `*`, values, lists (`1,15`), ranges (`1-5`) and steps (`*/10`, `0-30/5`).
+//!   - Day of week is 0-6 (Sunday is 0 or 7).
+//!   - As with the classic cron, when both day fields are restricted, a time matches either of them.
+
+use time::{Date, Duration, Month, OffsetDateTime, Time};
+
+/// The max look ahead of `next_after` (e.g., `0 0 29 2 *` on non leap years).
+const NEXT_MAX_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+	minutes: u64,
+	hours: u64,
+	days: u64,
+	months: u64,
+	weekdays: u64,
+	days_any: bool,
+	weekdays_any: bool,
+}
+
+impl CronSchedule {
+	pub fn parse(expr: &str) -> Result<Self> {
+		let fields: Vec<&str> = expr.split_whitespace().collect();
+		let [minutes, hours, days, months, weekdays] = fields[..] else {
+			return Err(Error::FieldCount(expr.to_string()));
+		};
+
+		let mut weekdays_mask = parse_field(weekdays, 0, 7)?;
+		// Sunday is both 0 and 7.
+		if weekdays_mask & (1 << 7) != 0 {
+			weekdays_mask = (weekdays_mask | 1) & !(1 << 7);
+		}
+
+		Ok(Self {
+			minutes: parse_field(minutes, 0, 59)?,
+			hours: parse_field(hours, 0, 23)?,
+			days: parse_field(days, 1, 31)?,
+			months: parse_field(months, 1, 12)?,
+			weekdays: weekdays_mask,
+			days_any: days == "*",
+			weekdays_any: weekdays == "*",
+		})
+	}
+
+	/// Returns the first matching time (minute precision) strictly after `time`.
+	pub fn next_after(&self, time: OffsetDateTime) -> Option<OffsetDateTime> {
+		let time = time.to_offset(time::UtcOffset::UTC);
+		let mut next = time.replace_time(Time::from_hms(time.hour(), time.minute(), 0).ok()?)
+			+ Duration::minutes(1);
+		let limit = next + Duration::days(NEXT_MAX_DAYS);
+
+		while next < limit {
+			if !has_bit(self.months, next.month() as u32) {
+				next = next_month_start(next)?;
+			} else if !self.day_matches(next.date()) {
+				next = next.replace_time(Time::MIDNIGHT) + Duration::days(1);
+			} else if !has_bit(self.hours, next.hour() as u32) {
+				next = next.replace_time(Time::from_hms(next.hour(), 0, 0).ok()?)
+					+ Duration::hours(1);
+			} else if !has_bit(self.minutes, next.minute() as u32) {
+				next += Duration::minutes(1);
+			} else {
+				return Some(next);
+			}
+		}
+
+		None
+	}
+
+	fn day_matches(&self, date: Date) -> bool {
+		let day = has_bit(self.days, date.day() as u32);
+		let weekday = has_bit(self.weekdays, date.weekday().number_days_from_sunday() as u32);
+		match (self.days_any, self.weekdays_any) {
+			(false, false) => day || weekday,
+			_ => day && weekday,
+		}
+	}
+}
+
+// region:    --- Support
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+	let invalid = || Error::FieldInvalid(field.to_string());
+
+	let mut mask = 0;
+	for part in field.split(',') {
+		let (range, step) = match part.split_once('/') {
+			Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+			None => (part, 1),
+		};
+		if step == 0 {
+			return Err(invalid());
+		}
+
+		let (start, end) = if range == "*" {
+			(min, max)
+		} else if let Some((start, end)) = range.split_once('-') {
+			(
+				start.parse::<u32>().map_err(|_| invalid())?,
+				end.parse::<u32>().map_err(|_| invalid())?,
+			)
+		} else {
+			let value = range.parse::<u32>().map_err(|_| invalid())?;
+			// e.g., `5/15` is from 5 to max, every 15
+			(value, if step > 1 { max } else { value })
+		};
+		if start < min || end > max || start > end {
+			return Err(invalid());
+		}
+
+		for value in (start..=end).step_by(step as usize) {
+			mask |= 1 << value;
+		}
+	}
+
+	Ok(mask)
+}
+
+fn has_bit(mask: u64, value: u32) -> bool {
+	mask & (1 << value) != 0
+}
+
+fn next_month_start(time: OffsetDateTime) -> Option<OffsetDateTime> {
+	let (year, month) = match time.month() {
+		Month::December => (time.year() + 1, Month::January),
+		month => (time.year(), month.next()),
+	};
+	let date = Date::from_calendar_date(year, month, 1).ok()?;
+
+	Some(date.midnight().assume_utc())
+}
+
+// endregion: --- Support
+
+// region:    --- Error
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+	FieldCount(String),
+	FieldInvalid(String),
+}
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+// endregion: --- Error Boilerplate
+
+// endregion: --- Error
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use time::format_description::well_known::Rfc3339;
+
+	fn next(expr: &str, after: &str) -> Result<String> {
+		let after = OffsetDateTime::parse(after, &Rfc3339)?;
+		let next = CronSchedule::parse(expr)?
+			.next_after(after)
+			.ok_or("should have a next time")?;
+		Ok(next.format(&Rfc3339)?)
+	}
+
+	#[test]
+	fn test_cron_next_after_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		// (expr, after, expected next)
+		let fx_cases = [
+			// -- Steps and ranges
+			("*/15 * * * *", "2024-05-10T10:07:30Z", "2024-05-10T10:15:00Z"),
+			("*/15 * * * *", "2024-05-10T10:15:00Z", "2024-05-10T10:30:00Z"),
+			("0-30/10 9-17 * * *", "2024-05-10T17:31:00Z", "2024-05-11T09:00:00Z"),
+			("5/20 * * * *", "2024-05-10T10:46:00Z", "2024-05-10T11:05:00Z"),
+			// -- Lists, across the day, month and year
+			("0 3,15 * * *", "2024-05-10T15:00:00Z", "2024-05-11T03:00:00Z"),
+			("0 0 1 * *", "2024-12-31T23:59:00Z", "2025-01-01T00:00:00Z"),
+			("30 12 15 6 *", "2024-06-15T12:30:00Z", "2025-06-15T12:30:00Z"),
+			// -- Day of week (2024-05-10 is a Friday), Sunday as 0 or 7
+			("0 8 * * 1-5", "2024-05-10T09:00:00Z", "2024-05-13T08:00:00Z"),
+			("0 8 * * 0", "2024-05-10T09:00:00Z", "2024-05-12T08:00:00Z"),
+			("0 8 * * 7", "2024-05-10T09:00:00Z", "2024-05-12T08:00:00Z"),
+			// -- Both day fields restricted, either matches
+			("0 0 20 * 1", "2024-05-10T09:00:00Z", "2024-05-13T00:00:00Z"),
+			// -- Leap day
+			("0 0 29 2 *", "2024-03-01T00:00:00Z", "2028-02-29T00:00:00Z"),
+			// -- Non UTC input
+			("0 * * * *", "2024-05-10T10:30:00+02:00", "2024-05-10T09:00:00Z"),
+		];
+
+		// -- Exec & Check
+		for (expr, after, expected) in fx_cases {
+			assert_eq!(next(expr, after)?, expected, "expr '{expr}' after {after}");
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cron_next_after_none() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_after = OffsetDateTime::parse("2024-05-10T00:00:00Z", &Rfc3339)?;
+
+		// -- Exec
+		let next = CronSchedule::parse("0 0 31 2 *")?.next_after(fx_after);
+
+		// -- Check
+		assert!(next.is_none(), "Feb 31 should never match, but was {next:?}");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cron_parse_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_field_count = ["", "* * * *", "* * * * * *"];
+		let fx_field_invalid = [
+			"60 * * * *",
+			"* 24 * * *",
+			"* * 0 * *",
+			"* * * 13 *",
+			"* * * * 8",
+			"*/0 * * * *",
+			"10-5 * * * *",
+			"a * * * *",
+			"1,,2 * * * *",
+		];
+
+		// -- Exec & Check
+		for expr in fx_field_count {
+			let res = CronSchedule::parse(expr);
+			assert!(
+				matches!(res, Err(super::Error::FieldCount(_))),
+				"'{expr}' should be FieldCount, but was {res:?}"
+			);
+		}
+		for expr in fx_field_invalid {
+			let res = CronSchedule::parse(expr);
+			assert!(
+				matches!(res, Err(super::Error::FieldInvalid(_))),
+				"'{expr}' should be FieldInvalid, but was {res:?}"
+			);
+		}
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
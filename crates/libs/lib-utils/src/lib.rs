@@ -5,6 +5,7 @@
 //!
 
 pub mod b64;
+pub mod cron;
 pub mod envs;
 pub mod time;
 pub mod errors;
@@ -6,17 +6,19 @@ use lib_utils::envs;
 use regex::Regex;
 use tracing::{info, warn};
 
-#[cfg(feature="dev-utils")]
-pub(crate) fn resolve_service(srv_name: &str) -> Result<&ServiceResolutionData> {
+pub(crate) fn resolve_service(srv_name: &str) -> Result<Vec<ServiceResolutionData>> {
     service_registry()
         .table
         .get(srv_name)
+        .filter(|instances| !instances.is_empty())
+        .cloned()
         .ok_or(Error::ServiceResolutionFailed)
 }
 
-#[cfg(not(feature="dev-utils"))]
-pub(crate) fn resolve_service(srv_name: &str) -> Result<&ServiceResolutionData> {
-    Err(Error::ServiceResolutionFailed)
+pub(crate) fn service_names() -> Vec<String> {
+    let mut names: Vec<String> = service_registry().table.keys().cloned().collect();
+    names.sort();
+    names
 }
 
 fn service_registry() -> &'static ServiceRegistry {
@@ -29,19 +31,24 @@ fn service_registry() -> &'static ServiceRegistry {
 	})
 }
 
+/// Note: The entries with the same service name are the instances of the service
+///       (e.g., `SERVICE_RESOLUTION_LLM` and `SERVICE_RESOLUTION_LLM_2`).
 struct ServiceRegistry {
-    pub table: HashMap<String, ServiceResolutionData>,
+    pub table: HashMap<String, Vec<ServiceResolutionData>>,
 }
 
 impl ServiceRegistry {
     fn load_from_env() -> Result<ServiceRegistry> {
         info!("{:<12} - Initialize service resolution from config.toml/env", "FOR-DEV-ONLY");
 
-        let mut src_info_map = HashMap::<String, ServiceResolutionData>::new();
+        let mut src_info_map = HashMap::<String, Vec<ServiceResolutionData>>::new();
 
         let re = Regex::new(r"SERVICE_RESOLUTION_(.*)").unwrap();			
         if let Ok(hmap) = envs::get_matching(re) {
-            for (k, v) in hmap {			
+            // Note: Sorted by key, for a stable instance order.
+            let mut entries: Vec<(String, String)> = hmap.into_iter().collect();
+            entries.sort();
+            for (k, v) in entries {			
                 match serde_json::from_str::<ServiceResolutionData>(&v) {
                     Ok(srd) => {
                         info!("Processing config key: {k}");
@@ -50,7 +57,7 @@ impl ServiceRegistry {
                             srd.host,
                             srd.port);					
                         
-                        src_info_map.insert(srd.name.clone(), srd);
+                        src_info_map.entry(srd.name.clone()).or_default().push(srd);
                     },
                     Err(e) => {
                         warn!("{:<12} - Wrong JSON format for {v:?}. Error = {e:?}", "FOR-DEV-ONLY");
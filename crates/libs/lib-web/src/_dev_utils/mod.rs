@@ -1,3 +1,3 @@
 mod dev_service_registry;
 
-pub(crate) use dev_service_registry::resolve_service;
\ No newline at end of file
+pub(crate) use dev_service_registry::{resolve_service, service_names};
\ No newline at end of file
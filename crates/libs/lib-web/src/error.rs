@@ -4,12 +4,16 @@ use axum::response::{IntoResponse, Response};
 use derive_more::From;
 use lib_auth::{pwd, token};
 use lib_core::model;
+use lib_utils::time::format_time;
 use serde::Serialize;
 use serde_json::Value;
 use serde_with::{serde_as, DisplayFromStr};
 use std::sync::Arc;
 use tracing::{debug, warn};
 
+// Note: Shared with the rpc client (see `lib_rpc_core::ClientError`).
+pub use lib_rpc_core::ClientError;
+
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[serde_as]
@@ -32,6 +36,27 @@ pub enum Error {
 	// -- Extractors
 	ReqStampNotInReqExt,
 
+	// -- Attachment
+	AttachmentFieldMissing(&'static str),
+	AttachmentFieldInvalid(&'static str),
+	AttachmentTooLarge {
+		max: usize,
+		actual: usize,
+	},
+	AttachmentMimeNotAllowed {
+		mime_type: String,
+	},
+	AttachmentResponseFail(String),
+	#[from]
+	Multipart(
+		#[serde_as(as = "DisplayFromStr")] axum::extract::multipart::MultipartError,
+	),
+
+	// -- Access
+	ConvAccessDenied {
+		conv_id: i64,
+	},
+
 	// -- webclient
 	WebClientResponseFailedNotJson { content_type: String,},
 
@@ -50,11 +75,18 @@ pub enum Error {
 	Token(token::Error),
 	#[from]
 	Rpc(lib_rpc_core::Error),
+	#[from]
+	Events(lib_events::Error),
 
 	// -- RpcError (deconstructed from rpc_router::Error)
 	// Simple mapping for the RpcRequestParsingError. It will have the eventual id, method context.
 	#[from]
 	RpcRequestParsing(rpc_router::RpcRequestParsingError),
+	RpcBatchEmpty,
+	RpcBatchTooLarge {
+		max: usize,
+		actual: usize,
+	},
 
 	// When encountering `rpc_router::Error::Handler`, we deconstruct it into the appropriate concrete application error types.
 	RpcLibRpc(lib_rpc_core::Error),
@@ -72,8 +104,48 @@ pub enum Error {
 	},
 
 	ServiceResolutionFailed,
+	/// The service key of the registration routes is missing or invalid.
+	ServiceKeyInvalid,
+	/// All the instances of the service have their circuit open (see `service_pool`).
+	ServiceUnavailable {
+		service: String,
+	},
+	ServiceRpcFail {
+		service: String,
+		method: String,
+		error: Value,
+	},
+
+	// -- Timeout
+	/// The route timeout elapsed (see `mw_req_timeout`).
+	ReqTimeout {
+		timeout_ms: u64,
+	},
+	/// The `Ctx` deadline passed before the request (or service call) started.
+	ReqDeadlineExceeded,
+	/// The worker did not respond before the `Ctx` deadline.
+	ServiceRpcTimeout {
+		service: String,
+		method: String,
+	},
+
+	// -- Telemetry
+	/// The tracing subscriber or the span exporter could not be initialized.
+	TelemetryInit(String),
+
+	// -- Log Sink
+	/// The `SERVICE_REQUEST_LOG_SINK` env value is not supported.
+	LogSinkConfigInvalid(String),
+	LogSinkAlreadyInit,
+	LogSinkTaskFail(String),
 
 	// -- External Modules
+	#[from]
+	Io(#[serde_as(as = "DisplayFromStr")] std::io::Error),
+
+	#[from]
+	Parquet(#[serde_as(as = "DisplayFromStr")] parquet::errors::ParquetError),
+
 	#[from]
 	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
 
@@ -167,11 +239,152 @@ impl Error {
 
 			// -- Auth
 			CtxExt(_) => (StatusCode::FORBIDDEN, ClientError::NO_AUTH),
+			ConvAccessDenied { .. } => {
+				(StatusCode::FORBIDDEN, ClientError::ACCESS_DENIED)
+			}
+
+			// -- Attachment
+			AttachmentFieldMissing(name) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::ATTACHMENT_INVALID(format!("field '{name}' missing")),
+			),
+			AttachmentFieldInvalid(name) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::ATTACHMENT_INVALID(format!("field '{name}' invalid")),
+			),
+			Multipart(ex) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::ATTACHMENT_INVALID(ex.to_string()),
+			),
+			AttachmentTooLarge { max, actual } => (
+				StatusCode::PAYLOAD_TOO_LARGE,
+				ClientError::ATTACHMENT_TOO_LARGE {
+					max: *max,
+					actual: *actual,
+				},
+			),
+			AttachmentMimeNotAllowed { mime_type } => (
+				StatusCode::UNSUPPORTED_MEDIA_TYPE,
+				ClientError::ATTACHMENT_MIME_NOT_ALLOWED(mime_type.to_string()),
+			),
 
 			// -- Model
 			Model(model::Error::EntityNotFound { entity, id }) => (
 				StatusCode::BAD_REQUEST,
-				ClientError::ENTITY_NOT_FOUND { entity, id: *id },
+				ClientError::ENTITY_NOT_FOUND {
+					entity: entity.to_string(),
+					id: *id,
+				},
+			),
+			Model(model::Error::QuotaExceeded {
+				scope,
+				period,
+				reset_time,
+				..
+			})
+			| RpcLibRpc(lib_rpc_core::Error::Model(model::Error::QuotaExceeded {
+				scope,
+				period,
+				reset_time,
+				..
+			})) => (
+				StatusCode::TOO_MANY_REQUESTS,
+				ClientError::QUOTA_EXCEEDED {
+					scope: scope.to_string(),
+					period: period.to_string(),
+					reset_time: format_time(*reset_time),
+				},
+			),
+			Model(model::Error::SysUserRequired { .. })
+			| RpcLibRpc(lib_rpc_core::Error::Model(
+				model::Error::SysUserRequired { .. },
+			)) => (StatusCode::FORBIDDEN, ClientError::ACCESS_DENIED),
+			Model(model::Error::AgentToolInvalid { name, cause })
+			| RpcLibRpc(lib_rpc_core::Error::Model(model::Error::AgentToolInvalid {
+				name,
+				cause,
+			})) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::AGENT_INVALID(format!("tool '{name}' invalid - {cause}")),
+			),
+			Model(
+				model::Error::ConvOwnerRequired { .. }
+				| model::Error::ConvMemberRequired { .. },
+			)
+			| RpcLibRpc(lib_rpc_core::Error::Model(
+				model::Error::ConvOwnerRequired { .. }
+				| model::Error::ConvMemberRequired { .. },
+			)) => (StatusCode::FORBIDDEN, ClientError::ACCESS_DENIED),
+			Model(model::Error::ConvMsgFilterConvRequired)
+			| RpcLibRpc(lib_rpc_core::Error::Model(
+				model::Error::ConvMsgFilterConvRequired,
+			)) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::RPC_PARAMS_INVALID(
+					"conv msgs filters require a 'conv_id' (single value)".to_string(),
+				),
+			),
+			Model(model::Error::JobCronInvalid { cron, cause })
+			| RpcLibRpc(lib_rpc_core::Error::Model(model::Error::JobCronInvalid {
+				cron,
+				cause,
+			})) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::JOB_INVALID(format!("cron '{cron}' invalid - {cause}")),
+			),
+			Model(
+				model::Error::JobNotCancellable { id, state }
+				| model::Error::JobNotRetryable { id, state },
+			)
+			| RpcLibRpc(lib_rpc_core::Error::Model(
+				model::Error::JobNotCancellable { id, state }
+				| model::Error::JobNotRetryable { id, state },
+			)) => (
+				StatusCode::CONFLICT,
+				ClientError::JOB_STATE_INVALID {
+					id: *id,
+					state: state.to_string(),
+				},
+			),
+
+			Model(model::Error::WebhookUrlInvalid { url })
+			| RpcLibRpc(lib_rpc_core::Error::Model(model::Error::WebhookUrlInvalid {
+				url,
+			})) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::WEBHOOK_INVALID(format!(
+					"url '{url}' invalid (http or https, with a resolvable host)"
+				)),
+			),
+			Model(model::Error::WebhookUrlNotPublic { url, .. })
+			| RpcLibRpc(lib_rpc_core::Error::Model(model::Error::WebhookUrlNotPublic {
+				url,
+				..
+			})) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::WEBHOOK_INVALID(format!(
+					"url '{url}' host must resolve to public addresses only"
+				)),
+			),
+			Model(model::Error::WebhookScopeDenied { .. })
+			| RpcLibRpc(lib_rpc_core::Error::Model(
+				model::Error::WebhookScopeDenied { .. },
+			))
+			| Model(model::Error::QuotaScopeDenied { .. })
+			| RpcLibRpc(lib_rpc_core::Error::Model(
+				model::Error::QuotaScopeDenied { .. },
+			)) => (StatusCode::FORBIDDEN, ClientError::ACCESS_DENIED),
+
+			// -- Timeout
+			ReqTimeout { .. } | ReqDeadlineExceeded | ServiceRpcTimeout { .. } => {
+				(StatusCode::GATEWAY_TIMEOUT, ClientError::REQUEST_TIMEOUT)
+			}
+
+			// -- Service
+			ServiceKeyInvalid => (StatusCode::FORBIDDEN, ClientError::NO_AUTH),
+			ServiceUnavailable { .. } => (
+				StatusCode::SERVICE_UNAVAILABLE,
+				ClientError::SERVICE_UNAVAILABLE,
 			),
 
 			// -- Rpc
@@ -179,6 +392,16 @@ impl Error {
 				StatusCode::BAD_REQUEST,
 				ClientError::RPC_REQUEST_INVALID(req_parsing_err.to_string()),
 			),
+			RpcBatchEmpty => (
+				StatusCode::BAD_REQUEST,
+				ClientError::RPC_REQUEST_INVALID("batch empty".to_string()),
+			),
+			RpcBatchTooLarge { max, actual } => (
+				StatusCode::BAD_REQUEST,
+				ClientError::RPC_REQUEST_INVALID(format!(
+					"batch too large ({actual} requests, max {max})"
+				)),
+			),
 			RpcRouter {
 				error: rpc_router::Error::MethodUnknown,
 				method,
@@ -216,18 +439,4 @@ impl Error {
 	}
 }
 
-#[derive(Debug, Serialize, strum_macros::AsRefStr)]
-#[serde(tag = "message", content = "detail")]
-#[allow(non_camel_case_types)]
-pub enum ClientError {
-	LOGIN_FAIL,
-	NO_AUTH,
-	ENTITY_NOT_FOUND { entity: &'static str, id: i64 },
-
-	RPC_REQUEST_INVALID(String),
-	RPC_REQUEST_METHOD_UNKNOWN(String),
-	RPC_PARAMS_INVALID(String),
-
-	SERVICE_ERROR,
-}
 // endregion: --- Client Error
@@ -0,0 +1,24 @@
+use crate::error::Result;
+use crate::middleware::mw_auth::CtxW;
+use crate::utils::service_pool::service_pool;
+use axum::extract::State;
+use axum::Json;
+use lib_core::model::user::UserBmc;
+use lib_core::model::ModelManager;
+use serde_json::{json, Value};
+use tracing::debug;
+
+/// The circuit breaker state of the worker service instances (`Sys` user only).
+pub async fn api_admin_services_handler(
+	State(mm): State<ModelManager>,
+	ctx: CtxW,
+) -> Result<Json<Value>> {
+	debug!("{:<12} - api_admin_services_handler", "HANDLER");
+	let ctx = ctx.0;
+
+	UserBmc::ensure_sys_user(&ctx, &mm).await?;
+
+	Ok(Json(json!({
+		"services": service_pool().status(),
+	})))
+}
@@ -0,0 +1,175 @@
+use crate::error::{Error, Result};
+use crate::middleware::mw_auth::CtxW;
+use axum::body::Body;
+use axum::extract::{Multipart, Path, State};
+use axum::http::header;
+use axum::response::Response;
+use axum::Json;
+use lib_core::ctx::Ctx;
+use lib_core::model::attachment::{AttachmentBmc, AttachmentForCreate};
+use lib_core::model::conv::ConvBmc;
+use lib_core::model::ModelManager;
+use serde_json::{json, Value};
+use tracing::debug;
+
+/// Max attachment content size (in bytes).
+pub const ATTACHMENT_MAX_SIZE: usize = 10 * 1024 * 1024;
+
+/// Max request body size for the attachment routes
+/// (content plus some room for the other multipart fields and boundaries).
+pub const ATTACHMENT_BODY_LIMIT: usize = ATTACHMENT_MAX_SIZE + 64 * 1024;
+
+/// Mime types accepted for attachments (as sniffed from the content).
+const ATTACHMENT_MIME_ALLOWED: &[&str] = &[
+	"text/plain",
+	"application/pdf",
+	"image/png",
+	"image/jpeg",
+	"image/gif",
+	"image/webp",
+];
+
+// region:    --- Upload
+
+/// Multipart upload with the `conv_msg_id` and `file` fields.
+///
+/// Note: The client provided content-type is ignored, the mime type is sniffed
+///       from the content, and must be in `ATTACHMENT_MIME_ALLOWED`.
+pub async fn api_attachment_upload_handler(
+	State(mm): State<ModelManager>,
+	ctx: CtxW,
+	mut multipart: Multipart,
+) -> Result<Json<Value>> {
+	debug!("{:<12} - api_attachment_upload_handler", "HANDLER");
+	let ctx = ctx.0;
+
+	// -- Extract the fields
+	let mut conv_msg_id: Option<i64> = None;
+	let mut file: Option<(String, Vec<u8>)> = None;
+	while let Some(field) = multipart.next_field().await? {
+		match field.name() {
+			Some("conv_msg_id") => {
+				let val = field
+					.text()
+					.await?
+					.parse()
+					.map_err(|_| Error::AttachmentFieldInvalid("conv_msg_id"))?;
+				conv_msg_id = Some(val);
+			}
+			Some("file") => {
+				let file_name = field.file_name().unwrap_or("unnamed").to_string();
+				let content = field.bytes().await?.to_vec();
+				file = Some((file_name, content));
+			}
+			_ => (),
+		}
+	}
+	let conv_msg_id =
+		conv_msg_id.ok_or(Error::AttachmentFieldMissing("conv_msg_id"))?;
+	let (file_name, content) = file.ok_or(Error::AttachmentFieldMissing("file"))?;
+
+	// -- Validate
+	if content.len() > ATTACHMENT_MAX_SIZE {
+		return Err(Error::AttachmentTooLarge {
+			max: ATTACHMENT_MAX_SIZE,
+			actual: content.len(),
+		});
+	}
+	let mime_type = sniff_mime_type(&content)?;
+	let msg = ConvBmc::get_msg(&ctx, &mm, conv_msg_id).await?;
+	assert_conv_member(&ctx, &mm, msg.conv_id).await?;
+
+	// -- Create
+	let att_c = AttachmentForCreate {
+		conv_msg_id,
+		file_name,
+		mime_type: mime_type.to_string(),
+	};
+	let att_id = AttachmentBmc::create(&ctx, &mm, att_c, content).await?;
+	let attachment = AttachmentBmc::get(&ctx, &mm, att_id).await?;
+
+	// Create the success body.
+	let body = Json(json!({
+		"result": {
+			"data": attachment
+		}
+	}));
+
+	Ok(body)
+}
+
+// endregion: --- Upload
+
+// region:    --- Download
+
+pub async fn api_attachment_download_handler(
+	State(mm): State<ModelManager>,
+	ctx: CtxW,
+	Path(att_id): Path<i64>,
+) -> Result<Response> {
+	debug!("{:<12} - api_attachment_download_handler", "HANDLER");
+	let ctx = ctx.0;
+
+	let attachment = AttachmentBmc::get(&ctx, &mm, att_id).await?;
+	assert_conv_member(&ctx, &mm, attachment.conv_id).await?;
+
+	let (attachment, content) =
+		AttachmentBmc::get_with_content(&ctx, &mm, att_id).await?;
+
+	// Note: Quotes and control chars are removed so that the file name cannot
+	//       break out of the header value.
+	let file_name: String = attachment
+		.file_name
+		.chars()
+		.filter(|c| *c != '"' && *c != '\\' && !c.is_control())
+		.collect();
+
+	let res = Response::builder()
+		.header(header::CONTENT_TYPE, attachment.mime_type)
+		.header(header::CONTENT_LENGTH, content.len())
+		.header(
+			header::CONTENT_DISPOSITION,
+			format!("attachment; filename=\"{file_name}\""),
+		)
+		.header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+		.body(Body::from(content))
+		.map_err(|ex| Error::AttachmentResponseFail(ex.to_string()))?;
+
+	Ok(res)
+}
+
+// endregion: --- Download
+
+// region:    --- Support
+
+async fn assert_conv_member(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	conv_id: i64,
+) -> Result<()> {
+	if ConvBmc::is_member(ctx, mm, conv_id, ctx.user_id()).await? {
+		Ok(())
+	} else {
+		Err(Error::ConvAccessDenied { conv_id })
+	}
+}
+
+/// Sniff the mime type from the content magic bytes.
+/// Content without magic bytes is accepted as `text/plain` if it is valid utf8.
+fn sniff_mime_type(content: &[u8]) -> Result<&'static str> {
+	let mime_type = match infer::get(content) {
+		Some(kind) => kind.mime_type(),
+		None if std::str::from_utf8(content).is_ok() => "text/plain",
+		None => "application/octet-stream",
+	};
+
+	if ATTACHMENT_MIME_ALLOWED.contains(&mime_type) {
+		Ok(mime_type)
+	} else {
+		Err(Error::AttachmentMimeNotAllowed {
+			mime_type: mime_type.to_string(),
+		})
+	}
+}
+
+// endregion: --- Support
@@ -1,18 +1,27 @@
 use crate::middleware::mw_auth;
 use crate::middleware::mw_auth::CtxW;
 use crate::middleware::mw_req_stamp::ReqStamp;
-use crate::utils::web_client::WebClient;
-use crate::utils::service_resolution::resolve_service;
+use crate::middleware::mw_res_map::client_error_body;
+use crate::utils::service_pool::service_pool;
+use crate::utils::service_rpc::{service_hop, service_rpc_error};
 
 use axum::extract::State;
+use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
+use futures::{stream, StreamExt};
 use lib_core::ctx::{Ctx, ReqChainLink};
 use lib_utils::proc;
 use rpc_router::resources_builder;
 use serde_json::{json, Value};
-use tracing::{error, debug};
+use tracing::{error, debug, info_span, Instrument};
 use std::sync::Arc;
+use uuid::Uuid;
+
+/// Max requests of a batch (a larger batch is an invalid request).
+const RPC_BATCH_MAX_LEN: usize = 32;
+/// Max batch entries dispatched concurrently.
+const RPC_BATCH_CONCURRENCY: usize = 4;
 
 /// RPC ID and Method Capture
 /// Note: This will be injected into the Axum Response extensions so that
@@ -24,6 +33,12 @@ pub struct RpcInfo {
 	pub method: String,
 }
 
+/// JSON-RPC 2.0 handler:
+/// - Request object: dispatched, and answered (error rendered by the `mw_res_map`).
+/// - Notification (request without `id`): dispatched, and not answered (`204`).
+/// - Batch (array of requests, up to `RPC_BATCH_MAX_LEN`): dispatched concurrently
+///   (up to `RPC_BATCH_CONCURRENCY` at a time), and answered with the array
+///   of the response entries in request order (none for the notifications).
 pub async fn rpc_axum_handler(
 	State(rpc_router): State<rpc_router::Router>,
 	ctx: CtxW,
@@ -32,6 +47,24 @@ pub async fn rpc_axum_handler(
 ) -> Response {	
 	let ctx = ctx.0;	
 
+	match rpc_req {
+		Value::Array(rpc_reqs) => {
+			rpc_batch_handler(rpc_router, ctx, req_stamp, rpc_reqs).await
+		}
+		rpc_req if is_notification(&rpc_req) => {
+			rpc_batch_entry(rpc_router, ctx, req_stamp, rpc_req).await;
+			StatusCode::NO_CONTENT.into_response()
+		}
+		rpc_req => rpc_single_handler(rpc_router, ctx, req_stamp, rpc_req).await,
+	}
+}
+
+async fn rpc_single_handler(
+	rpc_router: rpc_router::Router,
+	ctx: Ctx,
+	req_stamp: ReqStamp,
+	rpc_req: Value,
+) -> Response {
 	// -- Parse and RpcRequest validate the rpc_request
 	let rpc_req = match rpc_router::RpcRequest::try_from(rpc_req) {
 		Ok(rpc_req) => rpc_req,
@@ -48,25 +81,13 @@ pub async fn rpc_axum_handler(
 		method: rpc_req.method.clone(),
 	};
 
-	// -- Dispatch the call based on the form of the requested rpc method 
-	let json_result = match rpc_info.method.clone().split_once('/') {
-		Some((service,method)) => {
-			// Split per the "service / method" pattern
-			debug!("{:<12} - Split {:?} into service={service:?} and method={method:?}", "RPC Dispatch", &rpc_req.method);
-			do_rpc_handler_dispatch_server(ctx, req_stamp, rpc_req, service, method, &rpc_info).await
-		},
-		None => {
-			// No split so assume in-proc RPC call.
-			do_rpc_handler_dispatch_inproc(ctx, rpc_router, rpc_req).await
-		}
-	};	
+	let json_result = do_rpc_handler_dispatch(ctx, rpc_router, req_stamp, rpc_req).await;
 
 	// -- Create and Update Axum Response
 	// Note: We store data in the Axum Response extensions so that
 	//       we can unpack it in the `mw_res_map` for client-side rendering.
 	//       This approach centralizes error handling for the client at the `mw_res_map` module
-	let res: crate::error::Result<_> = json_result.map_err(crate::error::Error::from);
-	let mut res = res.into_response();
+	let mut res = json_result.into_response();
 	// Note: Here, add the capture RpcInfo (RPC ID and method) into the Axum response to be used
 	//       later in the `mw_res_map` for RequestLineLogging, and eventual JSON-RPC error serialization.
 	res.extensions_mut().insert(Arc::new(rpc_info));
@@ -74,6 +95,141 @@ pub async fn rpc_axum_handler(
 	res
 }
 
+async fn rpc_batch_handler(
+	rpc_router: rpc_router::Router,
+	ctx: Ctx,
+	req_stamp: ReqStamp,
+	rpc_reqs: Vec<Value>,
+) -> Response {
+	// Note: An empty batch is an invalid request (answered with a single error).
+	if rpc_reqs.is_empty() {
+		return crate::Error::RpcBatchEmpty.into_response();
+	}
+	if rpc_reqs.len() > RPC_BATCH_MAX_LEN {
+		return crate::Error::RpcBatchTooLarge {
+			max: RPC_BATCH_MAX_LEN,
+			actual: rpc_reqs.len(),
+		}
+		.into_response();
+	}
+
+	// -- Create the RPC Info (for the request log line, the methods of the batch)
+	let methods: Vec<&str> = rpc_reqs
+		.iter()
+		.map(|rpc_req| rpc_req.get("method").and_then(Value::as_str).unwrap_or_default())
+		.collect();
+	let rpc_info = RpcInfo {
+		id: None,
+		method: format!("[{}]", methods.join(",")),
+	};
+
+	// -- Dispatch concurrently (`buffered` keeps the entries in the request order)
+	let entries: Vec<Option<Value>> = stream::iter(rpc_reqs)
+		.map(|rpc_req| {
+			rpc_batch_entry(rpc_router.clone(), ctx.clone(), req_stamp.clone(), rpc_req)
+		})
+		.buffered(RPC_BATCH_CONCURRENCY)
+		.collect()
+		.await;
+	let entries: Vec<Value> = entries.into_iter().flatten().collect();
+
+	// Note: A batch of notifications only is not answered.
+	let mut res = if entries.is_empty() {
+		StatusCode::NO_CONTENT.into_response()
+	} else {
+		Json(Value::Array(entries)).into_response()
+	};
+	res.extensions_mut().insert(Arc::new(rpc_info));
+
+	res
+}
+
+/// Dispatch a batch entry (or a notification), and returns its response entry
+/// (None for a notification).
+async fn rpc_batch_entry(
+	rpc_router: rpc_router::Router,
+	ctx: Ctx,
+	req_stamp: ReqStamp,
+	mut rpc_req: Value,
+) -> Option<Value> {
+	let notification = is_notification(&rpc_req);
+	// Note: The notifications are dispatched with a null id (never sent back).
+	if let (true, Some(rpc_req)) = (notification, rpc_req.as_object_mut()) {
+		rpc_req.insert("id".to_string(), Value::Null);
+	}
+
+	let result = match rpc_router::RpcRequest::try_from(rpc_req) {
+		Ok(rpc_req) => {
+			let id = rpc_req.id.to_value();
+			do_rpc_handler_dispatch(ctx, rpc_router, req_stamp, rpc_req)
+				.await
+				.map(|Json(body)| body)
+				.map_err(|web_error| (id, web_error))
+		}
+		Err(rpc_req_error) => {
+			Err((Value::Null, crate::Error::RpcRequestParsing(rpc_req_error)))
+		}
+	};
+
+	match (notification, result) {
+		(false, Ok(body)) => Some(body),
+		(false, Err((id, web_error))) => {
+			debug!("{:<12} - batch entry error {web_error:?}", "RPC Dispatch");
+			let (_, client_error) = web_error.client_status_and_error();
+			Some(client_error_body(Some(id), &client_error, Uuid::new_v4()))
+		}
+		(true, Ok(_)) => None,
+		(true, Err((_, web_error))) => {
+			debug!("{:<12} - notification error {web_error:?}", "RPC Dispatch");
+			None
+		}
+	}
+}
+
+/// A JSON-RPC notification is a request object without `id`.
+fn is_notification(rpc_req: &Value) -> bool {
+	rpc_req.as_object().is_some_and(|obj| !obj.contains_key("id"))
+}
+
+/// Dispatch the call based on the form of the requested rpc method
+async fn do_rpc_handler_dispatch(
+	ctx: Ctx,
+	rpc_router: rpc_router::Router,
+	req_stamp: ReqStamp,
+	rpc_req: rpc_router::Request,
+) -> crate::error::Result<Json<Value>> {
+	let rpc_info = RpcInfo {
+		id: Some(rpc_req.id.to_value()),
+		method: rpc_req.method.clone(),
+	};
+
+	// The rpc span (child of the request one, see `mw_trace`).
+	let span = info_span!(
+		"rpc",
+		otel.name = %rpc_info.method,
+		rpc.system = "jsonrpc",
+		rpc.method = %rpc_info.method,
+	);
+
+	async {
+		match rpc_info.method.clone().split_once('/') {
+			Some((service,method)) => {
+				// Split per the "service / method" pattern
+				debug!("{:<12} - Split {:?} into service={service:?} and method={method:?}", "RPC Dispatch", &rpc_req.method);
+				do_rpc_handler_dispatch_server(ctx, req_stamp, rpc_req, service, method, &rpc_info).await
+			},
+			None => {
+				// No split so assume in-proc RPC call.
+				do_rpc_handler_dispatch_inproc(ctx, rpc_router, rpc_req)
+					.await
+					.map_err(crate::Error::from)
+			}
+		}
+	}
+	.instrument(span)
+	.await
+}
+
 async fn do_rpc_handler_dispatch_inproc(	
 	ctx: Ctx,
 	rpc_router: rpc_router::Router,
@@ -111,12 +267,15 @@ async fn do_rpc_handler_dispatch_server(
 	method : &str,
 	rpc_info: &RpcInfo
 ) 
--> Result<Json<Value>, rpc_router::CallError> {	
+-> crate::error::Result<Json<Value>> {	
 
 	// build up the request chain by adding this current ReqStamp's UUID to 
 	// the ctx which'll be sent downstream.
 	let ctx = _add_curr_req_to_chain(ctx, req_stamp);
 
+	// Shrink the ctx deadline for the worker, and the call timeout is the time left.
+	let (ctx, timeout) = service_hop(&ctx)?;
+
 	// Build the headers which will simply contain the serialized Ctx for now
 	// Workers are expected to resolve it frmo the headers	
 	// FIXME - Mark the headers as secure via HeaderVal.set_sensitive()..
@@ -127,33 +286,27 @@ async fn do_rpc_handler_dispatch_server(
 			_to_rpc_error("Converting Ctx to header", "Dispatch RPC", &rpc_info)
 		)?;
 
-	// Resolve the service to a URL or Fail
-	let srd = resolve_service(service)
-		.map_err(|e| 
-			_to_rpc_error(&e.to_string(), "Dispatch RPC", &rpc_info)
-		)?;
-
-	// FIXME: Hardcode for now
-	let url = format!("http://{}:{}/api/rpc", &srd.host, &srd.port);
-	debug!("{:<12} - Resolved {service:?} to {url:?}", "RPC Dispatch");
-
 	// Repack the request into a jrpc block with the resolved method name
 	let web_payload = json!({
 		"jsonrpc": "2.0",
 		"id": rpc_req.id,
 		"method": method,
-		"params": rpc_req.params.unwrap(),
+		"params": rpc_req.params.unwrap_or_default(),
 	});
 
+	// Post to an available instance of the service (see `service_pool`).
+	// Note: The pool adds the `traceparent`/`tracestate` headers of its call span,
+	//       so that the worker continues the trace.
 	// FIXME: Validate that the params are not empty. Or is it already done!
-	// FIXME: Proper error in case web-req fails (Simulate with false URL)
-	let web_res = WebClient
-		::default()
-		.do_post(url.as_str(), &web_req_headers, web_payload)
+	let web_res = service_pool()
+		.post_rpc(service, method, &web_req_headers, web_payload, timeout)
 		.await
-		.map_err(|e| 
-			_to_rpc_error(&e.to_string(), "Dispatch RPC", &rpc_info)
-		)?;
+		.map_err(|e| match service_rpc_error(e, service, method) {
+			e @ (crate::Error::ServiceRpcTimeout { .. }
+			| crate::Error::ServiceUnavailable { .. }
+			| crate::Error::ReqDeadlineExceeded) => e,
+			e => _to_rpc_error(&format!("{e}"), "Dispatch RPC", rpc_info),
+		})?;
 
 	debug!("{:<12} - WebResponse status {:?}", "RPC Dispatch", web_res.status);
 
@@ -164,7 +317,7 @@ fn _to_rpc_error(
 	err_msg: &str, 
 	err_cat: &str,
 	rpc_info: &RpcInfo) 
--> rpc_router::CallError 
+-> crate::Error 
 	{		
 		error!("{:<12} - rpc dispatch error {err_msg:?}", err_cat);
 
@@ -172,7 +325,7 @@ fn _to_rpc_error(
 			id: rpc_info.id.clone().unwrap_or("".into()),
 			method: rpc_info.method.clone(),
 			error: rpc_router::Error::MethodUnknown
-		}
+		}.into()
 }
 
 fn _add_curr_req_to_chain(
@@ -188,4 +341,130 @@ fn _add_curr_req_to_chain(
 	});
 
 	ctx
-}
\ No newline at end of file
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use lib_utils::time::now_utc;
+
+	mod fx_rpc {
+		use lib_rpc_core::prelude::*;
+		use std::time::Duration;
+
+		pub fn rpc_router() -> rpc_router::Router {
+			router_builder!(echo).build()
+		}
+
+		/// Returns the id, after a delay decreasing with it (i.e., completes out of order).
+		pub async fn echo(params: ParamsIded) -> Result<DataRpcResult<i64>> {
+			let ParamsIded { id } = params;
+			tokio::time::sleep(Duration::from_millis(10 * (5 - id.clamp(0, 5)) as u64)).await;
+			Ok(id.into())
+		}
+	}
+
+	async fn fx_call(rpc_req: Value) -> Result<(StatusCode, Option<Value>, Response)> {
+		let rpc_router = fx_rpc::rpc_router();
+		let req_stamp = ReqStamp {
+			uuid: Uuid::new_v4(),
+			time_in: now_utc(),
+		};
+		let res =
+			rpc_axum_handler(State(rpc_router), CtxW(Ctx::new(1)?), req_stamp, Json(rpc_req))
+				.await;
+		let (parts, body) = res.into_parts();
+		let bytes = axum::body::to_bytes(body, usize::MAX).await?;
+		let body = (!bytes.is_empty())
+			.then(|| serde_json::from_slice(&bytes))
+			.transpose()?;
+
+		Ok((parts.status, body, Response::from_parts(parts, axum::body::Body::empty())))
+	}
+
+	fn fx_req(id: Option<i64>, method: &str, echo_id: i64) -> Value {
+		let mut rpc_req = json!({"jsonrpc": "2.0", "method": method, "params": {"id": echo_id}});
+		if let Some(id) = id {
+			rpc_req["id"] = id.into();
+		}
+		rpc_req
+	}
+
+	fn web_error(res: &Response) -> Option<&crate::Error> {
+		res.extensions().get::<Arc<crate::Error>>().map(Arc::as_ref)
+	}
+
+	#[tokio::test]
+	async fn test_rpc_batch_order_and_notifications_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_batch = json!([
+			fx_req(Some(1), "echo", 1),
+			fx_req(None, "echo", 2),
+			fx_req(Some(3), "unknown_method", 3),
+			fx_req(Some(4), "echo", 4),
+			fx_req(None, "unknown_method", 5),
+		]);
+
+		// -- Exec
+		let (status, body, _) = fx_call(fx_batch).await?;
+
+		// -- Check
+		assert_eq!(status, StatusCode::OK);
+		let body = body.ok_or("should have a body")?;
+		let entries = body.as_array().ok_or("should be an array")?;
+		let ids: Vec<i64> = entries.iter().filter_map(|entry| entry["id"].as_i64()).collect();
+		assert_eq!(ids, [1, 3, 4], "request order, and no notification entry");
+		assert_eq!(entries[0]["result"]["data"], 1);
+		assert!(entries[1].get("error").is_some(), "should be an error entry");
+		assert_eq!(entries[2]["result"]["data"], 4);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_rpc_batch_notifications_only_ok() -> Result<()> {
+		// -- Exec
+		let (status, body, _) =
+			fx_call(json!([fx_req(None, "echo", 1), fx_req(None, "echo", 2)])).await?;
+
+		// -- Check
+		assert_eq!(status, StatusCode::NO_CONTENT);
+		assert!(body.is_none(), "should not be answered");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_rpc_batch_invalid_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_too_large: Vec<Value> = (0..=RPC_BATCH_MAX_LEN as i64)
+			.map(|id| fx_req(Some(id), "echo", 0))
+			.collect();
+
+		// -- Exec
+		let (_, _, empty_res) = fx_call(json!([])).await?;
+		let (_, _, too_large_res) = fx_call(Value::Array(fx_too_large)).await?;
+
+		// -- Check
+		let empty_error = web_error(&empty_res).ok_or("should have an error")?;
+		assert!(matches!(empty_error, crate::Error::RpcBatchEmpty));
+		let (status, client_error) = empty_error.client_status_and_error();
+		assert_eq!(status, StatusCode::BAD_REQUEST);
+		assert_eq!(client_error.as_ref(), "RPC_REQUEST_INVALID");
+
+		let too_large_error = web_error(&too_large_res).ok_or("should have an error")?;
+		assert!(matches!(
+			too_large_error,
+			crate::Error::RpcBatchTooLarge { actual, .. } if *actual == RPC_BATCH_MAX_LEN + 1
+		));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,80 @@
+use crate::error::{Error, Result};
+use crate::utils::service_registry::{
+	self, ServiceHeartbeat, ServiceRegistration, HEADER_SERVICE_KEY,
+};
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::Json;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::debug;
+
+/// The state of the service registration routes.
+#[derive(Clone)]
+pub struct ServicesState {
+	/// The key shared with the workers (see `HEADER_SERVICE_KEY`).
+	pub service_key: Arc<String>,
+}
+
+pub async fn api_service_register_handler(
+	State(state): State<ServicesState>,
+	headers: HeaderMap,
+	Json(registration): Json<ServiceRegistration>,
+) -> Result<Json<Value>> {
+	debug!("{:<12} - api_service_register_handler", "HANDLER");
+	check_service_key(&state, &headers)?;
+
+	service_registry::register(registration);
+
+	Ok(Json(json!({
+		"result": {
+			"success": true
+		}
+	})))
+}
+
+/// Note: `registered: false` when the instance is unknown (e.g., evicted),
+///       so that the worker registers again.
+pub async fn api_service_heartbeat_handler(
+	State(state): State<ServicesState>,
+	headers: HeaderMap,
+	Json(heartbeat): Json<ServiceHeartbeat>,
+) -> Result<Json<Value>> {
+	debug!("{:<12} - api_service_heartbeat_handler", "HANDLER");
+	check_service_key(&state, &headers)?;
+
+	let registered = service_registry::heartbeat(&heartbeat);
+
+	Ok(Json(json!({
+		"result": {
+			"registered": registered
+		}
+	})))
+}
+
+// region:    --- Support
+
+/// Note: Constant time comparison (the keys have a public length).
+fn check_service_key(state: &ServicesState, headers: &HeaderMap) -> Result<()> {
+	let key = headers
+		.get(HEADER_SERVICE_KEY)
+		.and_then(|value| value.to_str().ok())
+		.ok_or(Error::ServiceKeyInvalid)?;
+
+	let expected = state.service_key.as_bytes();
+	let valid = !expected.is_empty()
+		&& key.len() == expected.len()
+		&& key
+			.bytes()
+			.zip(expected)
+			.fold(0, |diff, (a, b)| diff | (a ^ b))
+			== 0;
+
+	if valid {
+		Ok(())
+	} else {
+		Err(Error::ServiceKeyInvalid)
+	}
+}
+
+// endregion: --- Support
@@ -0,0 +1,237 @@
+use crate::middleware::mw_auth::CtxW;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::Response;
+use lib_core::ctx::Ctx;
+use lib_core::model::conv::ConvBmc;
+use lib_core::model::conv_event::ConvEvent;
+use lib_core::model::ModelManager;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::debug;
+
+/// The membership of a subscribed conv is checked again before forwarding its events
+/// when older than this (and on each `conv.updated`, e.g., owner change),
+/// so that a removed member stops receiving the conv events.
+const MEMBER_RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The subscribed conv ids, with the time of their last membership check.
+type Subscriptions = HashMap<i64, Instant>;
+
+/// State of the websocket route, with the conv events broadcast
+/// (see `lib_core::model::conv_event::listen_conv_events`).
+#[derive(Clone)]
+pub struct WsState {
+	pub mm: ModelManager,
+	pub conv_events: broadcast::Sender<ConvEvent>,
+}
+
+/// Client messages, e.g., `{"type": "subscribe", "conv_ids": [1000, 1001]}`
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsClientMsg {
+	Subscribe { conv_ids: Vec<i64> },
+	Unsubscribe { conv_ids: Vec<i64> },
+}
+
+/// Server replies to the client messages.
+/// (the conv events are sent as `ConvEvent`, e.g., `{"type": "conv_msg.created", "data": {...}}`)
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsServerMsg {
+	Subscribed { conv_ids: Vec<i64>, denied: Vec<i64> },
+	Unsubscribed { conv_ids: Vec<i64> },
+	/// The user is no longer a member of the convs, which are unsubscribed.
+	Revoked { conv_ids: Vec<i64> },
+	Error { message: String },
+}
+
+/// Websocket of the ctx user, which receives the events of the
+/// subscribed convs (the ones the user is a member of).
+pub async fn ws_axum_handler(
+	State(state): State<WsState>,
+	ctx: CtxW,
+	ws: WebSocketUpgrade,
+) -> Response {
+	let ctx = ctx.0;
+	ws.on_upgrade(move |socket| handle_socket(socket, ctx, state))
+}
+
+async fn handle_socket(mut socket: WebSocket, ctx: Ctx, state: WsState) {
+	debug!("{:<12} - ws open for user {}", "WS", ctx.user_id());
+
+	let mut events = state.conv_events.subscribe();
+	let mut conv_ids = Subscriptions::new();
+
+	loop {
+		let sent = tokio::select! {
+			msg = socket.recv() => match msg {
+				Some(Ok(Message::Text(text))) => {
+					let reply = handle_client_msg(&ctx, &state.mm, &mut conv_ids, text.as_str()).await;
+					send_json(&mut socket, &reply).await
+				}
+				// Note: Ping/Pong are handled by axum.
+				Some(Ok(Message::Binary(_) | Message::Ping(_) | Message::Pong(_))) => true,
+				Some(Ok(Message::Close(_)) | Err(_)) | None => false,
+			},
+			event = events.recv() => match event {
+				Ok(event) => match check_member(&ctx, &state.mm, &mut conv_ids, &event).await {
+					Some(true) => send_json(&mut socket, &event).await,
+					Some(false) => {
+						let reply = WsServerMsg::Revoked { conv_ids: vec![event.conv_id()] };
+						send_json(&mut socket, &reply).await
+					}
+					None => true,
+				},
+				Err(RecvError::Lagged(count)) => {
+					let reply = WsServerMsg::Error { message: format!("lagged, {count} events missed") };
+					send_json(&mut socket, &reply).await
+				}
+				Err(RecvError::Closed) => false,
+			},
+		};
+
+		if !sent {
+			break;
+		}
+	}
+
+	debug!("{:<12} - ws closed for user {}", "WS", ctx.user_id());
+}
+
+/// Returns None if the event conv is not subscribed, otherwise if the user is
+/// (still) a member of the conv. A conv the user is no longer a member of is unsubscribed.
+async fn check_member(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	conv_ids: &mut Subscriptions,
+	event: &ConvEvent,
+) -> Option<bool> {
+	let conv_id = event.conv_id();
+	let checked_at = conv_ids.get(&conv_id)?;
+	if !needs_member_check(event, checked_at.elapsed()) {
+		return Some(true);
+	}
+
+	let is_member = ConvBmc::is_member(ctx, mm, conv_id, ctx.user_id())
+		.await
+		.unwrap_or(false);
+	if is_member {
+		conv_ids.insert(conv_id, Instant::now());
+	} else {
+		debug!("{:<12} - ws conv {conv_id} revoked for user {}", "WS", ctx.user_id());
+		conv_ids.remove(&conv_id);
+	}
+
+	Some(is_member)
+}
+
+fn needs_member_check(event: &ConvEvent, checked_elapsed: Duration) -> bool {
+	matches!(event, ConvEvent::ConvUpdated(_)) || checked_elapsed >= MEMBER_RECHECK_INTERVAL
+}
+
+async fn handle_client_msg(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	conv_ids: &mut Subscriptions,
+	text: &str,
+) -> WsServerMsg {
+	let client_msg = match serde_json::from_str::<WsClientMsg>(text) {
+		Ok(client_msg) => client_msg,
+		Err(ex) => {
+			return WsServerMsg::Error {
+				message: format!("invalid message - {ex}"),
+			}
+		}
+	};
+
+	match client_msg {
+		WsClientMsg::Subscribe { conv_ids: ids } => {
+			let (mut subscribed, mut denied) = (Vec::new(), Vec::new());
+			for conv_id in ids {
+				// Note: An unknown conv is denied as well.
+				let is_member = ConvBmc::is_member(ctx, mm, conv_id, ctx.user_id())
+					.await
+					.unwrap_or(false);
+				if is_member {
+					conv_ids.insert(conv_id, Instant::now());
+					subscribed.push(conv_id);
+				} else {
+					denied.push(conv_id);
+				}
+			}
+			WsServerMsg::Subscribed {
+				conv_ids: subscribed,
+				denied,
+			}
+		}
+		WsClientMsg::Unsubscribe { conv_ids: ids } => {
+			for conv_id in ids.iter() {
+				conv_ids.remove(conv_id);
+			}
+			WsServerMsg::Unsubscribed { conv_ids: ids }
+		}
+	}
+}
+
+/// Returns false if the socket is closed.
+async fn send_json(socket: &mut WebSocket, value: &impl Serialize) -> bool {
+	let text = serde_json::to_string(value)
+		.unwrap_or_else(|ex| json!({ "type": "error", "message": ex.to_string() }).to_string());
+
+	socket.send(Message::Text(text.into())).await.is_ok()
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+
+	const FX_TIMESTAMPS: &str = r#""cid": 1000, "ctime": "2024-01-01T00:00:00Z",
+		"mid": 1000, "mtime": "2024-01-01T00:00:00Z""#;
+
+	fn fx_msg_created() -> Result<ConvEvent> {
+		let msg = serde_json::from_str(&format!(
+			r#"{{"id": 1001, "conv_id": 1000, "user_id": 1000, "parent_msg_id": null,
+			"role": "User", "content": "hello", "content_parts": null, "metadata": null,
+			{FX_TIMESTAMPS}}}"#
+		))?;
+		Ok(ConvEvent::ConvMsgCreated(msg))
+	}
+
+	fn fx_conv_updated() -> Result<ConvEvent> {
+		let conv = serde_json::from_str(&format!(
+			r#"{{"id": 1000, "agent_id": 1000, "owner_id": 1000, "title": null,
+			"kind": "OwnerOnly", "state": "Active", {FX_TIMESTAMPS}}}"#
+		))?;
+		Ok(ConvEvent::ConvUpdated(conv))
+	}
+
+	#[test]
+	fn test_needs_member_check() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_msg_created = fx_msg_created()?;
+		let fx_conv_updated = fx_conv_updated()?;
+		let fx_recent = Duration::from_secs(1);
+
+		// -- Exec & Check
+		assert!(!needs_member_check(&fx_msg_created, fx_recent));
+		assert!(needs_member_check(&fx_msg_created, MEMBER_RECHECK_INTERVAL));
+		assert!(
+			needs_member_check(&fx_conv_updated, fx_recent),
+			"conv.updated (e.g., owner change) should always be checked"
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
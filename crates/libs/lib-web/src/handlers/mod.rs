@@ -1,2 +1,6 @@
+pub mod handlers_admin;
+pub mod handlers_attachment;
 pub mod handlers_login;
-pub mod handlers_rpc;
\ No newline at end of file
+pub mod handlers_rpc;
+pub mod handlers_services;
+pub mod handlers_ws;
\ No newline at end of file
@@ -0,0 +1,155 @@
+use crate::health::HealthCheck;
+use crate::utils::service_pool::service_pool;
+use async_trait::async_trait;
+use lib_core::model::ModelManager;
+use tracing::warn;
+
+// region:    --- DbCheck
+
+/// The db answers (through the `ModelManager` pool).
+pub struct DbCheck {
+	mm: ModelManager,
+}
+
+impl DbCheck {
+	pub fn new(mm: ModelManager) -> Self {
+		Self { mm }
+	}
+}
+
+#[async_trait]
+impl HealthCheck for DbCheck {
+	fn name(&self) -> String {
+		"db".to_string()
+	}
+
+	async fn check(&self) -> core::result::Result<(), String> {
+		self.mm.ping().await.map_err(|ex| {
+			warn!("{:<12} - db ping failed: {ex:?}", "HEALTH");
+			"db unreachable".to_string()
+		})
+	}
+}
+
+// endregion: --- DbCheck
+
+// region:    --- ServiceCheck
+
+/// At least one instance of the worker service is reachable (see `service_pool`).
+///
+/// Note: Not critical by default (e.g., the gateway still serves its own rpc methods).
+pub struct ServiceCheck {
+	service: String,
+	critical: bool,
+}
+
+impl ServiceCheck {
+	pub fn new(service: impl Into<String>) -> Self {
+		Self {
+			service: service.into(),
+			critical: false,
+		}
+	}
+
+	pub fn critical(mut self, critical: bool) -> Self {
+		self.critical = critical;
+		self
+	}
+}
+
+#[async_trait]
+impl HealthCheck for ServiceCheck {
+	fn name(&self) -> String {
+		format!("service:{}", self.service)
+	}
+
+	fn critical(&self) -> bool {
+		self.critical
+	}
+
+	async fn check(&self) -> core::result::Result<(), String> {
+		match service_pool().probe_service(&self.service).await {
+			Ok(0) => Err("no instance reachable".to_string()),
+			Ok(_) => Ok(()),
+			Err(ex) => {
+				warn!("{:<12} - {:?} probe failed: {ex:?}", "HEALTH", self.service);
+				Err("no instance resolved".to_string())
+			}
+		}
+	}
+}
+
+// endregion: --- ServiceCheck
+
+// region:    --- EnvCheck
+
+/// At least one of the environment variables is set (e.g., the LLM provider api keys).
+pub struct EnvCheck {
+	name: String,
+	env_names: Vec<String>,
+	critical: bool,
+}
+
+impl EnvCheck {
+	pub fn new(name: impl Into<String>, env_names: &[&str]) -> Self {
+		Self {
+			name: name.into(),
+			env_names: env_names.iter().map(|n| n.to_string()).collect(),
+			critical: true,
+		}
+	}
+
+	pub fn critical(mut self, critical: bool) -> Self {
+		self.critical = critical;
+		self
+	}
+}
+
+#[async_trait]
+impl HealthCheck for EnvCheck {
+	fn name(&self) -> String {
+		self.name.clone()
+	}
+
+	fn critical(&self) -> bool {
+		self.critical
+	}
+
+	async fn check(&self) -> core::result::Result<(), String> {
+		let is_set = self
+			.env_names
+			.iter()
+			.any(|name| std::env::var(name).is_ok_and(|value| !value.is_empty()));
+
+		if is_set {
+			Ok(())
+		} else {
+			Err(format!("none of {} set", self.env_names.join(", ")))
+		}
+	}
+}
+
+// endregion: --- EnvCheck
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+
+	#[tokio::test]
+	async fn test_service_check_unresolved_generic_err() -> Result<()> {
+		// -- Exec
+		let res = ServiceCheck::new("fx-health-unresolved").check().await;
+
+		// -- Check
+		assert_eq!(res, Err("no instance resolved".to_string()));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
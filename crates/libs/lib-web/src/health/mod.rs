@@ -0,0 +1,193 @@
+//! Health checks of the services, for the orchestrators (see `routes_health`).
+//!
+//! - `/health/live` - The process answers (no checks).
+//! - `/health/ready` - The `HealthCheck`s of the service, run concurrently (each within
+//!   `CHECK_TIMEOUT`). Not ready (`503`) when a critical check is down, degraded (still ready)
+//!   when a non critical one is down.
+//!
+//! ```ignore
+//! let checks = HealthChecks::new()
+//!     .with(DbCheck::new(mm.clone()))
+//!     .with(ServiceCheck::new("llm-worker"));
+//! let routes_all = Router::new().merge(routes_health::routes(checks));
+//! ```
+
+// region:    --- Modules
+
+mod checks;
+
+pub use checks::{DbCheck, EnvCheck, ServiceCheck};
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// endregion: --- Modules
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+	Up,
+	Degraded,
+	Down,
+}
+
+/// A check of the readiness (e.g., db ping).
+#[async_trait]
+pub trait HealthCheck: Send + Sync {
+	fn name(&self) -> String;
+
+	/// Note: A non critical check down makes the service degraded, but still ready.
+	fn critical(&self) -> bool {
+		true
+	}
+
+	/// Returns the cause when down.
+	///
+	/// Note: The cause is served by `/health/ready` (no auth), so it must be generic
+	///       (e.g., `db unreachable`), with the error details logged.
+	async fn check(&self) -> core::result::Result<(), String>;
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckReport {
+	pub name: String,
+	pub status: HealthStatus,
+	pub critical: bool,
+	pub latency_ms: u64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+	pub status: HealthStatus,
+	pub checks: Vec<CheckReport>,
+}
+
+// endregion: --- Types
+
+// region:    --- HealthChecks
+
+/// The readiness checks of a service.
+#[derive(Clone, Default)]
+pub struct HealthChecks {
+	checks: Vec<Arc<dyn HealthCheck>>,
+}
+
+impl HealthChecks {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn with(mut self, check: impl HealthCheck + 'static) -> Self {
+		self.checks.push(Arc::new(check));
+		self
+	}
+
+	/// Run all the checks concurrently.
+	pub async fn run(&self) -> HealthReport {
+		let checks = join_all(self.checks.iter().map(|check| run_check(check.as_ref()))).await;
+
+		let is_down = |critical: bool| {
+			checks
+				.iter()
+				.any(|check| check.critical == critical && check.status == HealthStatus::Down)
+		};
+		let status = if is_down(true) {
+			HealthStatus::Down
+		} else if is_down(false) {
+			HealthStatus::Degraded
+		} else {
+			HealthStatus::Up
+		};
+
+		HealthReport { status, checks }
+	}
+}
+
+async fn run_check(check: &dyn HealthCheck) -> CheckReport {
+	let start = Instant::now();
+	let res = match tokio::time::timeout(CHECK_TIMEOUT, check.check()).await {
+		Ok(res) => res,
+		Err(_) => Err(format!("timeout after {}ms", CHECK_TIMEOUT.as_millis())),
+	};
+	let latency_ms = start.elapsed().as_millis() as u64;
+
+	let (status, error) = match res {
+		Ok(()) => (HealthStatus::Up, None),
+		Err(error) => (HealthStatus::Down, Some(error)),
+	};
+
+	CheckReport {
+		name: check.name(),
+		status,
+		critical: check.critical(),
+		latency_ms,
+		error,
+	}
+}
+
+// endregion: --- HealthChecks
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+
+	struct FxCheck {
+		critical: bool,
+		up: bool,
+	}
+
+	#[async_trait]
+	impl HealthCheck for FxCheck {
+		fn name(&self) -> String {
+			format!("fx-{}-{}", self.critical, self.up)
+		}
+
+		fn critical(&self) -> bool {
+			self.critical
+		}
+
+		async fn check(&self) -> core::result::Result<(), String> {
+			if self.up {
+				Ok(())
+			} else {
+				Err("fx down".to_string())
+			}
+		}
+	}
+
+	#[tokio::test]
+	async fn test_health_checks_run_status_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_up = FxCheck { critical: true, up: true };
+		let fx_optional_down = FxCheck { critical: false, up: false };
+		let fx_critical_down = FxCheck { critical: true, up: false };
+
+		// -- Exec
+		let degraded = HealthChecks::new().with(fx_up).with(fx_optional_down).run().await;
+		let down = HealthChecks::new().with(fx_critical_down).run().await;
+
+		// -- Check
+		assert_eq!(degraded.status, HealthStatus::Degraded);
+		assert_eq!(degraded.checks.len(), 2);
+		assert_eq!(degraded.checks[1].error.as_deref(), Some("fx down"));
+		assert_eq!(down.status, HealthStatus::Down);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
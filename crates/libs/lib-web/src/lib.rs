@@ -1,10 +1,15 @@
 mod error;
 
-pub use error::Error;
+pub use error::{ClientError, Error};
 
 pub mod handlers;
+pub mod health;
 pub mod log;
+pub mod metrics;
 pub mod middleware;
 pub mod routes;
+pub mod telemetry;
 pub mod utils;
+pub mod webhooks;
+#[cfg(feature = "dev-utils")]
 mod _dev_utils;
@@ -0,0 +1,326 @@
+//! The active request log sink (see `log_request`).
+//!
+//! - The lines are sent to a bounded channel, never blocking the request. When it is full
+//!   (i.e., the sink is behind), the lines are dropped and counted (backpressure).
+//! - A task batches the lines (per `LogSink::batch_size` and `LogSink::flush_interval`)
+//!   and writes them to the sink.
+//! - The lines outcomes are counted in the `request_log_lines_total` metric.
+//! - On exit, `LogSinkHandle::shutdown` writes the pending lines (the lines sent after
+//!   are dropped).
+
+use crate::error::{Error, Result};
+use crate::log::{NdjsonFileSink, ParquetFileSink, RequestLogLine, StdoutSink};
+use crate::metrics;
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn};
+
+const ENV_REQUEST_LOG_SINK: &str = "SERVICE_REQUEST_LOG_SINK";
+
+const CHANNEL_CAPACITY: usize = 10_000;
+
+static LOG_SINK: OnceLock<LogSinkSender> = OnceLock::new();
+
+/// A destination of the request log lines (e.g., rotating ndjson files).
+#[async_trait]
+pub trait LogSink: Send + 'static {
+	/// The sink name (e.g., for the metrics).
+	fn name(&self) -> &'static str;
+
+	/// The max lines per `write`.
+	fn batch_size(&self) -> usize {
+		500
+	}
+
+	/// The max time the lines wait before being written (when the batch is not full).
+	fn flush_interval(&self) -> Duration {
+		Duration::from_secs(1)
+	}
+
+	async fn write(&mut self, lines: Vec<RequestLogLine>) -> Result<()>;
+}
+
+// region:    --- Init
+
+/// Start the sink of the `SERVICE_REQUEST_LOG_SINK` env, and returns its handle (None for `none`):
+/// - `none` (default) - Debug log line only.
+/// - `stdout` - Json lines on stdout (e.g., for the container log collector).
+/// - `ndjson:<dir>` - Rotating json lines files in the dir.
+/// - `parquet:<dir>` - A parquet file per batch in the dir.
+///
+/// Note: The `service_name` is the file name prefix (i.e., one dir can be shared).
+pub fn init_log_sink(service_name: &str) -> Result<Option<LogSinkHandle>> {
+	let config = std::env::var(ENV_REQUEST_LOG_SINK).unwrap_or_default();
+
+	let sink: Box<dyn LogSink> = match config.split_once(':') {
+		None if config.is_empty() || config == "none" => return Ok(None),
+		None if config == "stdout" => Box::new(StdoutSink::new()),
+		Some(("ndjson", dir)) => Box::new(NdjsonFileSink::new(dir, service_name)?),
+		Some(("parquet", dir)) => Box::new(ParquetFileSink::new(dir, service_name)?),
+		_ => {
+			return Err(Error::LogSinkConfigInvalid(format!(
+				"{ENV_REQUEST_LOG_SINK} '{config}' not supported (none, stdout, ndjson:<dir>, or parquet:<dir>)"
+			)))
+		}
+	};
+
+	start_log_sink(sink).map(Some)
+}
+
+/// Start the task writing the request log lines to the `sink` (e.g., a custom one).
+///
+/// Note: Only one sink per process.
+pub fn start_log_sink(sink: Box<dyn LogSink>) -> Result<LogSinkHandle> {
+	if LOG_SINK.get().is_some() {
+		return Err(Error::LogSinkAlreadyInit);
+	}
+
+	info!("{:<12} - request log lines to {:?}", "LOG SINK", sink.name());
+	let (sender, handle) = spawn_log_sink(sink, CHANNEL_CAPACITY);
+	LOG_SINK.set(sender).map_err(|_| Error::LogSinkAlreadyInit)?;
+
+	Ok(handle)
+}
+
+fn spawn_log_sink(sink: Box<dyn LogSink>, capacity: usize) -> (LogSinkSender, LogSinkHandle) {
+	let (tx, rx) = mpsc::channel(capacity);
+	let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+	let sender = LogSinkSender {
+		name: sink.name(),
+		tx,
+		dropping: AtomicBool::new(false),
+	};
+	let handle = LogSinkHandle {
+		shutdown_tx,
+		task: tokio::spawn(run_sink(sink, rx, shutdown_rx)),
+	};
+
+	(sender, handle)
+}
+
+/// The handle of the sink task, to write the pending lines before exit.
+///
+/// Note: Dropping the handle also stops the sink task (without waiting for it).
+pub struct LogSinkHandle {
+	shutdown_tx: oneshot::Sender<()>,
+	task: JoinHandle<()>,
+}
+
+impl LogSinkHandle {
+	/// Stop the sink, once the pending lines are written.
+	pub async fn shutdown(self) {
+		// Note: Err when the task already ended.
+		let _ = self.shutdown_tx.send(());
+		if let Err(ex) = self.task.await {
+			warn!("{:<12} - sink task failed: {ex:?}", "LOG SINK");
+		}
+	}
+}
+
+// endregion: --- Init
+
+// region:    --- Sender
+
+struct LogSinkSender {
+	name: &'static str,
+	tx: mpsc::Sender<RequestLogLine>,
+	/// To warn once per dropping streak.
+	dropping: AtomicBool,
+}
+
+/// Send the line to the active sink, if any (dropped when the sink is behind).
+pub(crate) fn send_log_line(log_line: RequestLogLine) {
+	if let Some(sender) = LOG_SINK.get() {
+		sender.send(log_line);
+	}
+}
+
+impl LogSinkSender {
+	fn send(&self, log_line: RequestLogLine) {
+		match self.tx.try_send(log_line) {
+			Ok(()) => self.dropping.store(false, Ordering::Relaxed),
+			Err(TrySendError::Full(_) | TrySendError::Closed(_)) => {
+				metrics::record_log_lines(self.name, "dropped", 1);
+				if !self.dropping.swap(true, Ordering::Relaxed) {
+					warn!("{:<12} - {:?} behind, dropping lines", "LOG SINK", self.name);
+				}
+			}
+		}
+	}
+}
+
+// endregion: --- Sender
+
+// region:    --- Sink Task
+
+async fn run_sink(
+	mut sink: Box<dyn LogSink>,
+	mut rx: mpsc::Receiver<RequestLogLine>,
+	mut shutdown_rx: oneshot::Receiver<()>,
+) {
+	let batch_size = sink.batch_size().max(1);
+	let mut batch = Vec::with_capacity(batch_size);
+
+	let mut interval = tokio::time::interval(sink.flush_interval());
+	interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+	loop {
+		tokio::select! {
+			log_line = rx.recv() => {
+				let Some(log_line) = log_line else {
+					break;
+				};
+				batch.push(log_line);
+				if batch.len() >= batch_size {
+					write_batch(sink.as_mut(), &mut batch).await;
+				}
+			}
+			_ = interval.tick() => write_batch(sink.as_mut(), &mut batch).await,
+			_ = &mut shutdown_rx => {
+				// Note: The lines already in the channel are still received.
+				rx.close();
+				while let Some(log_line) = rx.recv().await {
+					batch.push(log_line);
+					if batch.len() >= batch_size {
+						write_batch(sink.as_mut(), &mut batch).await;
+					}
+				}
+				break;
+			}
+		}
+	}
+
+	write_batch(sink.as_mut(), &mut batch).await;
+	info!("{:<12} - {:?} stopped", "LOG SINK", sink.name());
+}
+
+async fn write_batch(sink: &mut dyn LogSink, batch: &mut Vec<RequestLogLine>) {
+	if batch.is_empty() {
+		return;
+	}
+
+	let lines = std::mem::replace(batch, Vec::with_capacity(sink.batch_size()));
+	let count = lines.len();
+	match sink.write(lines).await {
+		Ok(()) => metrics::record_log_lines(sink.name(), "written", count),
+		Err(ex) => {
+			metrics::record_log_lines(sink.name(), "failed", count);
+			warn!("{:<12} - {:?} write failed ({count} lines): {ex:?}", "LOG SINK", sink.name());
+		}
+	}
+}
+
+// endregion: --- Sink Task
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::log::fx_log_line;
+	use std::sync::{Arc, Mutex};
+
+	/// Collects the written lines (one flush per hour, i.e., only on batch size or shutdown).
+	struct FxSink {
+		name: &'static str,
+		lines: Arc<Mutex<Vec<String>>>,
+	}
+
+	#[async_trait]
+	impl LogSink for FxSink {
+		fn name(&self) -> &'static str {
+			self.name
+		}
+
+		fn flush_interval(&self) -> Duration {
+			Duration::from_secs(3600)
+		}
+
+		async fn write(&mut self, lines: Vec<RequestLogLine>) -> crate::error::Result<()> {
+			let mut written = self.lines.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+			written.extend(lines.into_iter().map(|line| line.uuid));
+			Ok(())
+		}
+	}
+
+	/// The `request_log_lines_total` value of the sink and outcome.
+	fn recorded_log_lines(sink: &str, outcome: &str) -> u64 {
+		let (sink_label, outcome_label) =
+			(format!("sink=\"{sink}\""), format!("outcome=\"{outcome}\""));
+		metrics::encode()
+			.lines()
+			.find(|line| {
+				line.starts_with("request_log_lines_total{")
+					&& line.contains(&sink_label)
+					&& line.contains(&outcome_label)
+			})
+			.and_then(|line| line.rsplit(' ').next()?.parse().ok())
+			.unwrap_or_default()
+	}
+
+	#[tokio::test]
+	async fn test_log_sink_send_backpressure_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_name = "fx-sink-backpressure";
+		let (tx, mut rx) = mpsc::channel(2);
+		let sender = LogSinkSender {
+			name: fx_name,
+			tx,
+			dropping: AtomicBool::new(false),
+		};
+
+		// -- Exec & Check - Dropped when the channel is full
+		for uuid in ["fx-1", "fx-2", "fx-3", "fx-4"] {
+			sender.send(fx_log_line(uuid));
+		}
+		assert!(sender.dropping.load(Ordering::Relaxed));
+		assert_eq!(recorded_log_lines(fx_name, "dropped"), 2);
+
+		// -- Exec & Check - Sent again once the sink caught up
+		assert_eq!(rx.recv().await.map(|line| line.uuid).as_deref(), Some("fx-1"));
+		sender.send(fx_log_line("fx-5"));
+		assert!(!sender.dropping.load(Ordering::Relaxed));
+		assert_eq!(recorded_log_lines(fx_name, "dropped"), 2);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_log_sink_shutdown_flush_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_name = "fx-sink-shutdown";
+		let lines = Arc::new(Mutex::new(Vec::new()));
+		let sink = FxSink {
+			name: fx_name,
+			lines: lines.clone(),
+		};
+		let (sender, handle) = spawn_log_sink(Box::new(sink), 10);
+
+		// -- Exec
+		for uuid in ["fx-1", "fx-2", "fx-3"] {
+			sender.send(fx_log_line(uuid));
+		}
+		handle.shutdown().await;
+		sender.send(fx_log_line("fx-4"));
+
+		// -- Check - The pending lines written, the lines after dropped
+		let written = lines.lock().map_err(|ex| ex.to_string())?.clone();
+		assert_eq!(written, ["fx-1", "fx-2", "fx-3"]);
+		assert_eq!(recorded_log_lines(fx_name, "written"), 3);
+		assert_eq!(recorded_log_lines(fx_name, "dropped"), 1);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
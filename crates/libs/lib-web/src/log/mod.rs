@@ -1,3 +1,16 @@
+// region:    --- Modules
+
+mod log_sink;
+mod parquet_row;
+mod sink_ndjson;
+mod sink_parquet;
+mod sink_stdout;
+
+pub use log_sink::{init_log_sink, start_log_sink, LogSink, LogSinkHandle};
+pub use sink_ndjson::NdjsonFileSink;
+pub use sink_parquet::ParquetFileSink;
+pub use sink_stdout::StdoutSink;
+
 use crate::middleware::mw_req_stamp::ReqStamp;
 use crate::handlers::handlers_rpc::RpcInfo;
 use crate::error::{Error, ClientError};
@@ -11,6 +24,8 @@ use serde_with::skip_serializing_none;
 use time::Duration;
 use tracing::debug;
 
+// endregion: --- Modules
+
 pub async fn log_request(
 	http_method: Method,
 	uri: Uri,
@@ -65,33 +80,57 @@ pub async fn log_request(
 
 	debug!("REQUEST LOG LINE:\n{}", json!(log_line));
 
-	// TODO - Send to cloud-watch and/or have a `pack_and_send` logic as well (newline json and/or parquet file)
+	// -- Send to the active sink (see `init_log_sink`)
+	log_sink::send_log_line(log_line);
 
 	Ok(())
 }
 
 #[skip_serializing_none]
-#[derive(Serialize)]
-struct RequestLogLine {
-	uuid: String,      // uuid string formatted
-	timestamp: String, // (Rfc3339)
-	time_in: String,   // (Rfc3339)
-	duration_ms: f64,
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestLogLine {
+	pub uuid: String,      // uuid string formatted
+	pub timestamp: String, // (Rfc3339)
+	pub time_in: String,   // (Rfc3339)
+	pub duration_ms: f64,
 
 	// -- User and context attributes.
-	user_id: Option<i64>,
+	pub user_id: Option<i64>,
 
 	// -- http request attributes.
-	http_path: String,
-	http_method: String,
+	pub http_path: String,
+	pub http_method: String,
 
 	// -- rpc info.
-	rpc_id: Option<String>,
-	rpc_method: Option<String>,
-	rpc_chain: Option<Vec<String>>,
+	pub rpc_id: Option<String>,
+	pub rpc_method: Option<String>,
+	pub rpc_chain: Option<Vec<String>>,
 
 	// -- Errors attributes.
-	client_error_type: Option<String>,
-	error_type: Option<String>,
-	error_data: Option<Value>,
+	pub client_error_type: Option<String>,
+	pub error_type: Option<String>,
+	pub error_data: Option<Value>,
 }
+
+// region:    --- Test Support
+
+#[cfg(test)]
+pub(crate) fn fx_log_line(uuid: &str) -> RequestLogLine {
+	RequestLogLine {
+		uuid: uuid.to_string(),
+		timestamp: "2024-07-24T01:37:45Z".to_string(),
+		time_in: "2024-07-24T01:37:45Z".to_string(),
+		duration_ms: 1.5,
+		user_id: Some(1000),
+		http_path: "/api/rpc".to_string(),
+		http_method: "POST".to_string(),
+		rpc_id: Some("1".to_string()),
+		rpc_method: Some("list_convs".to_string()),
+		rpc_chain: None,
+		client_error_type: None,
+		error_type: None,
+		error_data: None,
+	}
+}
+
+// endregion: --- Test Support
@@ -0,0 +1,51 @@
+//! The parquet row of the `ParquetFileSink`.
+//!
+//! Note: In its own module, the `ParquetRecordWriter` derive code uses the std `Result`
+//!       (shadowed by `crate::error::Result` in the sink module).
+
+use crate::log::RequestLogLine;
+use parquet_derive::ParquetRecordWriter;
+
+/// The parquet row of a `RequestLogLine` (the nested values as json strings).
+#[derive(ParquetRecordWriter)]
+pub(super) struct RequestLogRow {
+	uuid: String,
+	timestamp: String,
+	time_in: String,
+	duration_ms: f64,
+
+	user_id: Option<i64>,
+
+	http_path: String,
+	http_method: String,
+
+	rpc_id: Option<String>,
+	rpc_method: Option<String>,
+	rpc_chain: Option<String>,
+
+	client_error_type: Option<String>,
+	error_type: Option<String>,
+	error_data: Option<String>,
+}
+
+impl From<RequestLogLine> for RequestLogRow {
+	fn from(line: RequestLogLine) -> Self {
+		Self {
+			uuid: line.uuid,
+			timestamp: line.timestamp,
+			time_in: line.time_in,
+			duration_ms: line.duration_ms,
+			user_id: line.user_id,
+			http_path: line.http_path,
+			http_method: line.http_method,
+			rpc_id: line.rpc_id,
+			rpc_method: line.rpc_method,
+			rpc_chain: line
+				.rpc_chain
+				.and_then(|rpc_chain| serde_json::to_string(&rpc_chain).ok()),
+			client_error_type: line.client_error_type,
+			error_type: line.error_type,
+			error_data: line.error_data.map(|error_data| error_data.to_string()),
+		}
+	}
+}
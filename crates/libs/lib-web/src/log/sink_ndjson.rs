@@ -0,0 +1,168 @@
+use crate::error::Result;
+use crate::log::sink_stdout::to_ndjson;
+use crate::log::{LogSink, RequestLogLine};
+use async_trait::async_trait;
+use lib_utils::time::now_utc;
+use std::path::{Path, PathBuf};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tracing::debug;
+
+const DEFAULT_MAX_FILE_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 10;
+
+const FILE_EXT: &str = "ndjson";
+
+/// The request log lines as json lines files in the dir, named `<prefix>.<unix_ms>.ndjson`.
+///
+/// - A new file when the current one would exceed the max file bytes (and at start).
+/// - Only the last max files are kept (the older ones are removed on rotation).
+pub struct NdjsonFileSink {
+	dir: PathBuf,
+	prefix: String,
+	max_file_bytes: u64,
+	max_files: usize,
+
+	file: Option<File>,
+	file_bytes: u64,
+}
+
+impl NdjsonFileSink {
+	pub fn new(dir: impl AsRef<Path>, prefix: impl Into<String>) -> Result<Self> {
+		let dir = dir.as_ref().to_path_buf();
+		std::fs::create_dir_all(&dir)?;
+
+		Ok(Self {
+			dir,
+			prefix: prefix.into(),
+			max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+			max_files: DEFAULT_MAX_FILES,
+			file: None,
+			file_bytes: 0,
+		})
+	}
+
+	pub fn with_rotation(mut self, max_file_bytes: u64, max_files: usize) -> Self {
+		self.max_file_bytes = max_file_bytes;
+		self.max_files = max_files.max(1);
+		self
+	}
+}
+
+#[async_trait]
+impl LogSink for NdjsonFileSink {
+	fn name(&self) -> &'static str {
+		"ndjson"
+	}
+
+	async fn write(&mut self, lines: Vec<RequestLogLine>) -> Result<()> {
+		let content = to_ndjson(&lines)?;
+		let content_bytes = content.len() as u64;
+
+		// Note: A batch larger than the max file bytes still goes to a single file.
+		let is_full = self.file_bytes > 0
+			&& self.file_bytes + content_bytes > self.max_file_bytes;
+		let file = match self.file.take() {
+			Some(file) if !is_full => file,
+			_ => self.rotate().await?,
+		};
+		let file = self.file.insert(file);
+
+		file.write_all(&content).await?;
+		file.flush().await?;
+		self.file_bytes += content_bytes;
+
+		Ok(())
+	}
+}
+
+// region:    --- Rotation
+
+impl NdjsonFileSink {
+	/// Open a new file, and remove the oldest ones over the max files.
+	async fn rotate(&mut self) -> Result<File> {
+		let file_name = format!(
+			"{}.{}.{FILE_EXT}",
+			self.prefix,
+			now_utc().unix_timestamp_nanos() / 1_000_000
+		);
+		let path = self.dir.join(file_name);
+		debug!("{:<12} - new ndjson file {path:?}", "LOG SINK");
+
+		let file = OpenOptions::new().create(true).append(true).open(&path).await?;
+		self.file_bytes = file.metadata().await?.len();
+
+		// -- Remove the oldest files
+		let mut paths = self.log_files().await?;
+		if paths.len() > self.max_files {
+			paths.sort();
+			let removed = paths.len() - self.max_files;
+			for path in paths.into_iter().take(removed) {
+				tokio::fs::remove_file(path).await?;
+			}
+		}
+
+		Ok(file)
+	}
+
+	async fn log_files(&self) -> Result<Vec<PathBuf>> {
+		let file_prefix = format!("{}.", self.prefix);
+		let file_suffix = format!(".{FILE_EXT}");
+
+		let mut paths = Vec::new();
+		let mut entries = tokio::fs::read_dir(&self.dir).await?;
+		while let Some(entry) = entries.next_entry().await? {
+			let file_name = entry.file_name();
+			let file_name = file_name.to_string_lossy();
+			if file_name.starts_with(&file_prefix) && file_name.ends_with(&file_suffix) {
+				paths.push(entry.path());
+			}
+		}
+
+		Ok(paths)
+	}
+}
+
+// endregion: --- Rotation
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::log::fx_log_line;
+	use std::time::Duration;
+
+	#[tokio::test]
+	async fn test_ndjson_file_sink_rotate_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_dir = std::env::temp_dir().join(format!("test-ndjson-{}", uuid::Uuid::new_v4()));
+		let mut sink = NdjsonFileSink::new(&fx_dir, "fx-service")?.with_rotation(1, 2);
+
+		// -- Exec
+		for uuid in ["fx-1", "fx-2", "fx-3"] {
+			sink.write(vec![fx_log_line(uuid)]).await?;
+			// For distinct file names (unix ms).
+			tokio::time::sleep(Duration::from_millis(5)).await;
+		}
+
+		// -- Check
+		let mut paths = sink.log_files().await?;
+		paths.sort();
+		assert_eq!(paths.len(), 2);
+		let content = std::fs::read_to_string(&paths[1])?;
+		assert!(content.ends_with('\n'));
+		let line: serde_json::Value = serde_json::from_str(content.trim_end())?;
+		assert_eq!(line["uuid"], "fx-3");
+
+		// -- Clean
+		std::fs::remove_dir_all(fx_dir)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,130 @@
+use crate::error::{Error, Result};
+use crate::log::parquet_row::RequestLogRow;
+use crate::log::{LogSink, RequestLogLine};
+use async_trait::async_trait;
+use lib_utils::time::now_utc;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::record::RecordWriter;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+const BATCH_SIZE: usize = 20_000;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The request log lines as parquet files in the dir (one per batch),
+/// named `<prefix>.<unix_ms>.parquet`.
+///
+/// Note: Large batches (`BATCH_SIZE` lines or `FLUSH_INTERVAL`), for columnar friendly files.
+///       The file is written under a `.tmp` name, then renamed (never read partially written).
+pub struct ParquetFileSink {
+	dir: PathBuf,
+	prefix: String,
+}
+
+impl ParquetFileSink {
+	pub fn new(dir: impl AsRef<Path>, prefix: impl Into<String>) -> Result<Self> {
+		let dir = dir.as_ref().to_path_buf();
+		std::fs::create_dir_all(&dir)?;
+
+		Ok(Self {
+			dir,
+			prefix: prefix.into(),
+		})
+	}
+}
+
+#[async_trait]
+impl LogSink for ParquetFileSink {
+	fn name(&self) -> &'static str {
+		"parquet"
+	}
+
+	fn batch_size(&self) -> usize {
+		BATCH_SIZE
+	}
+
+	fn flush_interval(&self) -> Duration {
+		FLUSH_INTERVAL
+	}
+
+	async fn write(&mut self, lines: Vec<RequestLogLine>) -> Result<()> {
+		let rows: Vec<RequestLogRow> = lines.into_iter().map(RequestLogRow::from).collect();
+		let path = self.dir.join(format!(
+			"{}.{}.parquet",
+			self.prefix,
+			now_utc().unix_timestamp_nanos() / 1_000_000
+		));
+		debug!("{:<12} - new parquet file {path:?} ({} rows)", "LOG SINK", rows.len());
+
+		// Note: The parquet writer is sync.
+		tokio::task::spawn_blocking(move || write_parquet_file(&path, &rows))
+			.await
+			.map_err(|ex| Error::LogSinkTaskFail(ex.to_string()))?
+	}
+}
+
+// region:    --- Support
+
+fn write_parquet_file(path: &Path, rows: &[RequestLogRow]) -> Result<()> {
+	let tmp_path = path.with_extension("parquet.tmp");
+	let file = File::create(&tmp_path)?;
+
+	let props = WriterProperties::builder()
+		.set_compression(Compression::SNAPPY)
+		.build();
+	let mut writer = SerializedFileWriter::new(file, rows.schema()?, Arc::new(props))?;
+	let mut row_group = writer.next_row_group()?;
+	rows.write_to_row_group(&mut row_group)?;
+	row_group.close()?;
+	writer.close()?;
+
+	std::fs::rename(tmp_path, path)?;
+
+	Ok(())
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::log::fx_log_line;
+	use parquet::file::reader::{FileReader, SerializedFileReader};
+
+	#[tokio::test]
+	async fn test_parquet_file_sink_write_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_dir = std::env::temp_dir().join(format!("test-parquet-{}", uuid::Uuid::new_v4()));
+		let mut sink = ParquetFileSink::new(&fx_dir, "fx-service")?;
+		let fx_lines = vec![fx_log_line("fx-1"), fx_log_line("fx-2"), fx_log_line("fx-3")];
+
+		// -- Exec
+		sink.write(fx_lines).await?;
+
+		// -- Check
+		let paths: Vec<_> = std::fs::read_dir(&fx_dir)?
+			.map(|entry| entry.map(|entry| entry.path()))
+			.collect::<core::result::Result<_, _>>()?;
+		assert_eq!(paths.len(), 1);
+		assert_eq!(paths[0].extension().and_then(|ext| ext.to_str()), Some("parquet"));
+		let reader = SerializedFileReader::new(File::open(&paths[0])?)?;
+		assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+
+		// -- Clean
+		std::fs::remove_dir_all(fx_dir)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,49 @@
+use crate::error::Result;
+use crate::log::{LogSink, RequestLogLine};
+use async_trait::async_trait;
+use tokio::io::{AsyncWriteExt, Stdout};
+
+/// The request log lines as json lines on stdout.
+pub struct StdoutSink {
+	stdout: Stdout,
+}
+
+impl StdoutSink {
+	pub fn new() -> Self {
+		Self {
+			stdout: tokio::io::stdout(),
+		}
+	}
+}
+
+impl Default for StdoutSink {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[async_trait]
+impl LogSink for StdoutSink {
+	fn name(&self) -> &'static str {
+		"stdout"
+	}
+
+	async fn write(&mut self, lines: Vec<RequestLogLine>) -> Result<()> {
+		let content = to_ndjson(&lines)?;
+		self.stdout.write_all(&content).await?;
+		self.stdout.flush().await?;
+
+		Ok(())
+	}
+}
+
+/// The lines as newline delimited json.
+pub(super) fn to_ndjson(lines: &[RequestLogLine]) -> Result<Vec<u8>> {
+	let mut content = Vec::new();
+	for line in lines {
+		serde_json::to_writer(&mut content, line)?;
+		content.push(b'\n');
+	}
+
+	Ok(content)
+}
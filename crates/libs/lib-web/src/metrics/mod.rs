@@ -0,0 +1,206 @@
+//! Prometheus metrics of the services (see `routes_metrics` for the `/metrics` endpoint).
+//!
+//! - `http_requests_total` and `http_request_duration_seconds` - By route, rpc method,
+//!   status and client error type (see `mw_metrics`).
+//! - `service_rpc_duration_seconds` and `service_rpc_retries_total` - The worker
+//!   dispatch, by service (see `service_pool`).
+//! - `db_pool_connections` - The `Dbx` pool connections, by state (at scrape time).
+//! - `llm_request_duration_seconds` and `llm_tokens_total` - The LLM calls, by provider
+//!   and model (llm-worker).
+//! - `request_log_lines_total` - The request log lines, by sink and outcome
+//!   (`written`, `dropped`, or `failed`, see `log::LogSink`).
+
+use prometheus::{
+	Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry,
+	TextEncoder,
+};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Note: The LLM calls take seconds (the default buckets stop at 10s).
+const LLM_DURATION_BUCKETS: &[f64] = &[0.25, 0.5, 1., 2.5, 5., 10., 20., 40., 80., 160.];
+
+fn metrics() -> &'static Metrics {
+	static INSTANCE: OnceLock<Metrics> = OnceLock::new();
+
+	INSTANCE.get_or_init(|| {
+		Metrics::new().unwrap_or_else(|ex| {
+			panic!("FATAL - WHILE REGISTERING METRICS - Cause: {ex:?}")
+		})
+	})
+}
+
+struct Metrics {
+	registry: Registry,
+
+	// -- Requests
+	http_requests: IntCounterVec,
+	http_request_duration: HistogramVec,
+
+	// -- Worker Dispatch
+	service_rpc_duration: HistogramVec,
+	service_rpc_retries: IntCounterVec,
+
+	// -- Db
+	db_pool_connections: IntGaugeVec,
+
+	// -- LLM
+	llm_request_duration: HistogramVec,
+	llm_tokens: IntCounterVec,
+
+	// -- Request Log
+	request_log_lines: IntCounterVec,
+}
+
+impl Metrics {
+	fn new() -> prometheus::Result<Self> {
+		let registry = Registry::new();
+		let request_labels = &["route", "rpc_method", "status", "client_error_type"];
+
+		let metrics = Metrics {
+			http_requests: IntCounterVec::new(
+				Opts::new("http_requests_total", "Requests"),
+				request_labels,
+			)?,
+			http_request_duration: HistogramVec::new(
+				HistogramOpts::new("http_request_duration_seconds", "Request duration"),
+				request_labels,
+			)?,
+			service_rpc_duration: HistogramVec::new(
+				HistogramOpts::new(
+					"service_rpc_duration_seconds",
+					"Worker dispatch duration (retries included)",
+				),
+				&["service", "outcome"],
+			)?,
+			service_rpc_retries: IntCounterVec::new(
+				Opts::new("service_rpc_retries_total", "Worker dispatch retries"),
+				&["service"],
+			)?,
+			db_pool_connections: IntGaugeVec::new(
+				Opts::new("db_pool_connections", "Db pool connections"),
+				&["state"],
+			)?,
+			llm_request_duration: HistogramVec::new(
+				HistogramOpts::new("llm_request_duration_seconds", "LLM call duration")
+					.buckets(LLM_DURATION_BUCKETS.to_vec()),
+				&["provider", "model", "outcome"],
+			)?,
+			llm_tokens: IntCounterVec::new(
+				Opts::new("llm_tokens_total", "LLM tokens"),
+				&["provider", "model", "kind"],
+			)?,
+			request_log_lines: IntCounterVec::new(
+				Opts::new("request_log_lines_total", "Request log lines"),
+				&["sink", "outcome"],
+			)?,
+			registry,
+		};
+
+		let registry = &metrics.registry;
+		registry.register(Box::new(metrics.http_requests.clone()))?;
+		registry.register(Box::new(metrics.http_request_duration.clone()))?;
+		registry.register(Box::new(metrics.service_rpc_duration.clone()))?;
+		registry.register(Box::new(metrics.service_rpc_retries.clone()))?;
+		registry.register(Box::new(metrics.db_pool_connections.clone()))?;
+		registry.register(Box::new(metrics.llm_request_duration.clone()))?;
+		registry.register(Box::new(metrics.llm_tokens.clone()))?;
+		registry.register(Box::new(metrics.request_log_lines.clone()))?;
+
+		Ok(metrics)
+	}
+}
+
+// region:    --- Recorders
+
+pub(crate) fn record_request(
+	route: &str,
+	rpc_method: &str,
+	status: u16,
+	client_error_type: &str,
+	duration: Duration,
+) {
+	let status = status.to_string();
+	let labels = [route, rpc_method, status.as_str(), client_error_type];
+
+	let metrics = metrics();
+	metrics.http_requests.with_label_values(&labels).inc();
+	metrics
+		.http_request_duration
+		.with_label_values(&labels)
+		.observe(duration.as_secs_f64());
+}
+
+pub(crate) fn record_service_rpc(service: &str, ok: bool, duration: Duration) {
+	metrics()
+		.service_rpc_duration
+		.with_label_values(&[service, outcome(ok)])
+		.observe(duration.as_secs_f64());
+}
+
+pub(crate) fn record_service_retry(service: &str) {
+	metrics().service_rpc_retries.with_label_values(&[service]).inc();
+}
+
+pub(crate) fn set_db_pool_connections(active: usize, idle: usize) {
+	let metrics = metrics();
+	metrics
+		.db_pool_connections
+		.with_label_values(&["active"])
+		.set(active as i64);
+	metrics
+		.db_pool_connections
+		.with_label_values(&["idle"])
+		.set(idle as i64);
+}
+
+/// Record a LLM call (e.g., genai `exec_chat`), with its token usage when known.
+pub fn record_llm_call(
+	provider: &str,
+	model: &str,
+	ok: bool,
+	duration: Duration,
+	input_tokens: Option<i32>,
+	output_tokens: Option<i32>,
+) {
+	let metrics = metrics();
+	metrics
+		.llm_request_duration
+		.with_label_values(&[provider, model, outcome(ok)])
+		.observe(duration.as_secs_f64());
+
+	for (kind, tokens) in [("input", input_tokens), ("output", output_tokens)] {
+		if let Some(tokens) = tokens.filter(|tokens| *tokens > 0) {
+			metrics
+				.llm_tokens
+				.with_label_values(&[provider, model, kind])
+				.inc_by(tokens as u64);
+		}
+	}
+}
+
+pub(crate) fn record_log_lines(sink: &str, outcome: &str, count: usize) {
+	metrics()
+		.request_log_lines
+		.with_label_values(&[sink, outcome])
+		.inc_by(count as u64);
+}
+
+// endregion: --- Recorders
+
+/// The metrics in the Prometheus text format.
+pub(crate) fn encode() -> String {
+	let mut buffer = Vec::new();
+	if let Err(ex) = TextEncoder::new().encode(&metrics().registry.gather(), &mut buffer) {
+		tracing::warn!("{:<12} - encode failed: {ex:?}", "METRICS");
+	}
+	String::from_utf8(buffer).unwrap_or_default()
+}
+
+fn outcome(ok: bool) -> &'static str {
+	if ok {
+		"ok"
+	} else {
+		"error"
+	}
+}
@@ -1,3 +1,6 @@
 pub mod mw_auth;
+pub mod mw_metrics;
 pub mod mw_req_stamp;
-pub mod mw_res_map;
\ No newline at end of file
+pub mod mw_res_map;
+pub mod mw_timeout;
+pub mod mw_trace;
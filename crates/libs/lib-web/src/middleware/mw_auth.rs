@@ -158,7 +158,7 @@ pub fn ctx_from_req_header(headers: &HeaderMap) -> Result<CtxW> {
 
 
 // region:    --- Ctx Extractor Result/Error
-type CtxExtResult = core::result::Result<CtxW, CtxExtError>;
+pub(crate) type CtxExtResult = core::result::Result<CtxW, CtxExtError>;
 
 #[derive(Clone, Serialize, Debug)]
 pub enum CtxExtError {
@@ -0,0 +1,211 @@
+use crate::error::{ClientError, Error};
+use crate::handlers::handlers_rpc::RpcInfo;
+use crate::metrics;
+use crate::utils::service_resolution::is_service_method;
+use axum::extract::{MatchedPath, Request};
+use axum::response::Response;
+use futures::future::BoxFuture;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+
+/// Tower layer recording the request metrics (see `metrics`).
+///
+/// Note: Must be layered inside the `mw_reponse_map` (i.e., added before it), so that
+///       the `RpcInfo` and the `Error` are still in the response extensions.
+#[derive(Clone, Default)]
+pub struct MetricsLayer;
+
+impl<S> Layer<S> for MetricsLayer {
+	type Service = MetricsService<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		MetricsService { inner }
+	}
+}
+
+#[derive(Clone)]
+pub struct MetricsService<S> {
+	inner: S,
+}
+
+impl<S> Service<Request> for MetricsService<S>
+where
+	S: Service<Request, Response = Response> + Send + 'static,
+	S::Future: Send + 'static,
+{
+	type Response = Response;
+	type Error = S::Error;
+	type Future = BoxFuture<'static, Result<Response, S::Error>>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, req: Request) -> Self::Future {
+		let route = req
+			.extensions()
+			.get::<MatchedPath>()
+			.map(|path| path.as_str().to_string())
+			.unwrap_or_else(|| "unmatched".to_string());
+		let start = Instant::now();
+		let res_fut = self.inner.call(req);
+
+		Box::pin(async move {
+			let res = res_fut.await?;
+			record_response(&route, &res, start);
+			Ok(res)
+		})
+	}
+}
+
+fn record_response(route: &str, res: &Response, start: Instant) {
+	// -- Status and client error (as rendered by the `mw_reponse_map`)
+	let web_error = res.extensions().get::<Arc<Error>>().map(Arc::as_ref);
+	let (status, client_error) = match web_error {
+		Some(web_error) => {
+			let (status, client_error) = web_error.client_status_and_error();
+			(status, Some(client_error))
+		}
+		None => (res.status(), None),
+	};
+
+	// -- Rpc method (bounded, the unknown methods are not labelled with their name)
+	let rpc_info = res.extensions().get::<Arc<RpcInfo>>().map(Arc::as_ref);
+	let rpc_method = match (rpc_info, &client_error) {
+		(_, Some(ClientError::RPC_REQUEST_METHOD_UNKNOWN(_))) => "unknown",
+		(Some(rpc_info), _) if rpc_info.method.starts_with('[') => "batch",
+		(Some(rpc_info), _) => known_rpc_method(&rpc_info.method),
+		(None, _) => "",
+	};
+	let client_error_type = client_error.as_ref().map(|ce| ce.as_ref()).unwrap_or_default();
+
+	metrics::record_request(
+		route,
+		rpc_method,
+		status.as_u16(),
+		client_error_type,
+		start.elapsed(),
+	);
+}
+
+/// The method, or `unknown` for a `service/method` not advertised by the service.
+///
+/// Note: The worker dispatch errors (e.g., `ServiceUnavailable`, `ReqDeadlineExceeded`)
+///       happen before the worker could answer with an unknown method error.
+fn known_rpc_method(method: &str) -> &str {
+	match method.split_once('/') {
+		Some((service, service_method)) if !is_service_method(service, service_method) => {
+			"unknown"
+		}
+		_ => method,
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::utils::service_registry::{self, ServiceRegistration};
+	use axum::body::Body;
+	use serde_json::Value;
+
+	fn fx_response(method: Option<&str>, web_error: Option<crate::error::Error>) -> Response {
+		let mut res = Response::new(Body::empty());
+		if let Some(method) = method {
+			res.extensions_mut().insert(Arc::new(RpcInfo {
+				id: Some(Value::from(1)),
+				method: method.to_string(),
+			}));
+		}
+		if let Some(web_error) = web_error {
+			res.extensions_mut().insert(Arc::new(web_error));
+		}
+		res
+	}
+
+	/// The `rpc_method` label of the `http_requests_total` line of the route.
+	fn recorded_rpc_method(route: &str) -> Option<String> {
+		let route_label = format!("route=\"{route}\"");
+		let metrics = metrics::encode();
+		let line = metrics
+			.lines()
+			.find(|line| line.starts_with("http_requests_total{") && line.contains(&route_label))?;
+		let (_, label) = line.split_once("rpc_method=\"")?;
+		label.split_once('"').map(|(method, _)| method.to_string())
+	}
+
+	#[test]
+	fn test_record_response_rpc_method_labels_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_service = "fx-metrics-service";
+		service_registry::register(ServiceRegistration {
+			name: fx_service.to_string(),
+			host: "localhost".to_string(),
+			port: 9001,
+			version: "0.1.0".to_string(),
+			methods: vec!["known_method".to_string()],
+		});
+		let fx_unavailable = || crate::error::Error::ServiceUnavailable {
+			service: fx_service.to_string(),
+		};
+		let fx_cases = [
+			(fx_response(Some("list_agents"), None), "list_agents"),
+			(fx_response(Some("[list_agents,bogus]"), None), "batch"),
+			(
+				fx_response(
+					Some("bogus_0"),
+					Some(crate::error::Error::RpcRouter {
+						id: Value::from(1),
+						method: "bogus_0".to_string(),
+						error: rpc_router::Error::MethodUnknown,
+					}),
+				),
+				"unknown",
+			),
+			(
+				fx_response(Some("fx-metrics-service/known_method"), Some(fx_unavailable())),
+				"fx-metrics-service/known_method",
+			),
+			(
+				fx_response(Some("fx-metrics-service/bogus_1"), Some(fx_unavailable())),
+				"unknown",
+			),
+			(
+				fx_response(
+					Some("fx-metrics-service/bogus_2"),
+					Some(crate::error::Error::ReqDeadlineExceeded),
+				),
+				"unknown",
+			),
+			(
+				fx_response(
+					Some("fx-metrics-other/bogus_3"),
+					Some(crate::error::Error::ReqDeadlineExceeded),
+				),
+				"unknown",
+			),
+			(fx_response(None, None), ""),
+		];
+
+		// -- Exec & Check
+		for (i, (res, expected)) in fx_cases.iter().enumerate() {
+			let fx_route = format!("/fx-metrics-{i}");
+			record_response(&fx_route, res, Instant::now());
+			assert_eq!(
+				recorded_rpc_method(&fx_route).as_deref(),
+				Some(*expected),
+				"case {i}"
+			);
+		}
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,4 +1,4 @@
-use crate::error::{Error, Result};
+use crate::error::{ClientError, Error, Result};
 use crate::handlers::handlers_rpc::RpcInfo;
 use crate::log::log_request;
 use crate::middleware::mw_auth::CtxW;
@@ -7,7 +7,7 @@ use crate::middleware::mw_req_stamp::ReqStamp;
 use axum::http::{Method, Uri};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
-use serde_json::{json, to_value};
+use serde_json::{json, to_value, Value};
 use std::sync::Arc;
 use tracing::debug;
 use uuid::Uuid;
@@ -35,20 +35,8 @@ pub async fn mw_reponse_map(
 		client_status_error
 			.as_ref()
 			.map(|(status_code, client_error)| {
-				let client_error = to_value(client_error).ok();
-				let message = client_error.as_ref().and_then(|v| v.get("message"));
-				let detail = client_error.as_ref().and_then(|v| v.get("detail"));
-
-				let client_error_body = json!({
-					"id": rpc_info.as_ref().map(|rpc| rpc.id.clone()),
-					"error": {
-						"message": message, // Variant name
-						"data": {
-							"req_uuid": uuid.to_string(),
-							"detail": detail
-						},
-					}
-				});
+				let id = rpc_info.and_then(|rpc| rpc.id.clone());
+				let client_error_body = client_error_body(id, client_error, uuid);
 
 				debug!("CLIENT ERROR BODY:\n{client_error_body}");
 
@@ -75,3 +63,27 @@ pub async fn mw_reponse_map(
 
 	error_response.unwrap_or(res)
 }
+
+/// The JSON-RPC error body of a client error
+/// (also used for the error entries of the batch responses).
+pub(crate) fn client_error_body(
+	id: Option<Value>,
+	client_error: &ClientError,
+	req_uuid: Uuid,
+) -> Value {
+	let client_error = to_value(client_error).ok();
+	let message = client_error.as_ref().and_then(|v| v.get("message"));
+	let detail = client_error.as_ref().and_then(|v| v.get("detail"));
+
+	json!({
+		"jsonrpc": "2.0",
+		"id": id,
+		"error": {
+			"message": message, // Variant name
+			"data": {
+				"req_uuid": req_uuid.to_string(),
+				"detail": detail
+			},
+		}
+	})
+}
@@ -0,0 +1,168 @@
+use crate::error::{Error, Result};
+use crate::middleware::mw_auth::{CtxExtResult, CtxW};
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::Request;
+use axum::middleware::Next;
+use axum::response::Response;
+use lib_utils::time::now_utc;
+use std::time::Duration;
+use tracing::debug;
+
+/// Route timeout, e.g.,
+/// `.route_layer(middleware::from_fn_with_state(Duration::from_secs(60), mw_req_timeout))`
+///
+/// - The ctx deadline is set to the route timeout (unless it has an earlier one,
+///   e.g., from the gateway for a worker request).
+/// - The request is cancelled (i.e., its handler future dropped, with its in-flight
+///   calls, like the genai ones) when the deadline is reached.
+///
+/// Note: Must be layered inside the ctx resolver (i.e., as a `route_layer`).
+pub async fn mw_req_timeout(
+	State(route_timeout): State<Duration>,
+	mut req: Request<Body>,
+	next: Next,
+) -> Result<Response> {
+	debug!("{:<12} - mw_req_timeout", "MIDDLEWARE");
+
+	// -- Set the ctx deadline, and get the request timeout
+	let mut timeout = route_timeout;
+	if let Some(Ok(CtxW(ctx))) = req.extensions_mut().get_mut::<CtxExtResult>() {
+		*ctx = ctx.with_deadline(now_utc() + route_timeout);
+		timeout = ctx.remaining().unwrap_or(route_timeout);
+	}
+
+	if timeout.is_zero() {
+		return Err(Error::ReqDeadlineExceeded);
+	}
+
+	tokio::time::timeout(timeout, next.run(req))
+		.await
+		.map_err(|_| Error::ReqTimeout {
+			timeout_ms: timeout.as_millis() as u64,
+		})
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use axum::http::StatusCode;
+	use axum::routing::get;
+	use axum::{middleware, Router};
+	use lib_core::ctx::Ctx;
+	use std::sync::Arc;
+	use tower::ServiceExt;
+
+	/// Returns the ms left before the ctx deadline.
+	async fn fx_fast_handler(ctx: CtxW) -> String {
+		let remaining = ctx.0.remaining().map(|remaining| remaining.as_millis());
+		format!("{remaining:?}")
+	}
+
+	async fn fx_slow_handler() -> &'static str {
+		tokio::time::sleep(Duration::from_secs(2)).await;
+		"slow"
+	}
+
+	async fn fx_call(route_timeout: Duration, ctx: Ctx, path: &str) -> Result<Response> {
+		let routes = Router::new()
+			.route("/fast", get(fx_fast_handler))
+			.route("/slow", get(fx_slow_handler))
+			.route_layer(middleware::from_fn_with_state(route_timeout, mw_req_timeout));
+		let mut req = Request::builder().uri(path).body(Body::empty())?;
+		req.extensions_mut().insert::<CtxExtResult>(Ok(CtxW(ctx)));
+
+		Ok(routes.oneshot(req).await?)
+	}
+
+	fn web_error(res: &Response) -> Option<&crate::Error> {
+		res.extensions().get::<Arc<crate::Error>>().map(Arc::as_ref)
+	}
+
+	async fn body_string(res: Response) -> Result<String> {
+		let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await?;
+		Ok(String::from_utf8(bytes.to_vec())?)
+	}
+
+	#[tokio::test]
+	async fn test_mw_req_timeout_deadline_ok() -> Result<()> {
+		// -- Exec
+		let res = fx_call(Duration::from_secs(10), Ctx::new(1)?, "/fast").await?;
+
+		// -- Check - The handler ctx has the route deadline
+		assert_eq!(res.status(), StatusCode::OK);
+		let remaining: Option<u128> = body_string(res)
+			.await?
+			.trim_start_matches("Some(")
+			.trim_end_matches(')')
+			.parse()
+			.ok();
+		let remaining = remaining.ok_or("Should have a ctx deadline")?;
+		assert!(remaining > 9_000 && remaining <= 10_000, "remaining: {remaining}");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_mw_req_timeout_route_timeout_err() -> Result<()> {
+		// -- Exec
+		let res = fx_call(Duration::from_millis(50), Ctx::new(1)?, "/slow").await?;
+
+		// -- Check
+		// Note: The timeout is the time left before the (route) ctx deadline.
+		assert!(
+			matches!(
+				web_error(&res),
+				Some(crate::Error::ReqTimeout { timeout_ms }) if *timeout_ms <= 50
+			),
+			"Should be ReqTimeout, but was: {:?}",
+			web_error(&res)
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_mw_req_timeout_earlier_ctx_deadline_err() -> Result<()> {
+		// -- Setup & Fixtures
+		// Note: e.g., the gateway deadline of a worker request.
+		let fx_ctx = Ctx::new(1)?.with_deadline(now_utc() + Duration::from_millis(50));
+
+		// -- Exec
+		let res = fx_call(Duration::from_secs(10), fx_ctx, "/slow").await?;
+
+		// -- Check
+		let timeout_ms = match web_error(&res) {
+			Some(crate::Error::ReqTimeout { timeout_ms }) => *timeout_ms,
+			other => return Err(format!("Should be ReqTimeout, but was: {other:?}").into()),
+		};
+		assert!(timeout_ms <= 50, "timeout_ms: {timeout_ms}");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_mw_req_timeout_deadline_exceeded_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_ctx = Ctx::new(1)?.with_deadline(now_utc() - Duration::from_millis(1));
+
+		// -- Exec
+		let res = fx_call(Duration::from_secs(10), fx_ctx, "/fast").await?;
+
+		// -- Check
+		assert!(
+			matches!(web_error(&res), Some(crate::Error::ReqDeadlineExceeded)),
+			"Should be ReqDeadlineExceeded, but was: {:?}",
+			web_error(&res)
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
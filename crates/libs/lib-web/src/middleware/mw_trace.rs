@@ -0,0 +1,38 @@
+use crate::telemetry::extract_trace_context;
+use axum::body::Body;
+use axum::extract::MatchedPath;
+use axum::http::Request;
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::field::Empty;
+use tracing::{info_span, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// The request span (see `telemetry`), continuing the trace of the caller
+/// when the request has a `traceparent` header (e.g., the gateway for a worker request).
+///
+/// Note: Must be the outer layer (i.e., added last), so that the span covers the whole
+///       request, and records the final status (as rendered by the `mw_reponse_map`).
+pub async fn mw_trace(req: Request<Body>, next: Next) -> Response {
+	let method = req.method().to_string();
+	let route = req
+		.extensions()
+		.get::<MatchedPath>()
+		.map(|path| path.as_str().to_string())
+		.unwrap_or_else(|| "unmatched".to_string());
+
+	let span = info_span!(
+		"request",
+		otel.name = %format!("{method} {route}"),
+		otel.kind = "server",
+		http.request.method = %method,
+		http.route = %route,
+		http.response.status_code = Empty,
+	);
+	span.set_parent(extract_trace_context(req.headers()));
+
+	let res = next.run(req).instrument(span.clone()).await;
+	span.record("http.response.status_code", res.status().as_u16());
+
+	res
+}
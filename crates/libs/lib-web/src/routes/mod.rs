@@ -1 +1,3 @@
+pub mod routes_health;
+pub mod routes_metrics;
 pub mod routes_static;
\ No newline at end of file
@@ -0,0 +1,32 @@
+use crate::health::{HealthChecks, HealthStatus};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::json;
+
+///  Build the Axum router for '/health/live' and '/health/ready'
+/// Note: To be merged outside of `mw_ctx_require` (for the orchestrators, no auth).
+pub fn routes(checks: HealthChecks) -> Router {
+	Router::new()
+		.route("/health/live", get(live_handler))
+		.route("/health/ready", get(ready_handler))
+		.with_state(checks)
+}
+
+async fn live_handler() -> Json<serde_json::Value> {
+	Json(json!({ "status": HealthStatus::Up }))
+}
+
+/// `503` when a critical check is down.
+async fn ready_handler(State(checks): State<HealthChecks>) -> Response {
+	let report = checks.run().await;
+
+	let status = match report.status {
+		HealthStatus::Up | HealthStatus::Degraded => StatusCode::OK,
+		HealthStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+	};
+
+	(status, Json(report)).into_response()
+}
@@ -0,0 +1,26 @@
+use crate::metrics;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use lib_core::model::ModelManager;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+///  Build the Axum router for '/metrics' (Prometheus text format)
+/// Note: To be merged outside of `mw_ctx_require` (for the scraper, no auth).
+pub fn routes(mm: ModelManager) -> Router {
+	Router::new()
+		.route("/metrics", get(metrics_handler))
+		.with_state(mm)
+}
+
+async fn metrics_handler(State(mm): State<ModelManager>) -> impl IntoResponse {
+	// -- Db pool stats (at scrape time)
+	let db = mm.dbx().db();
+	let idle = db.num_idle();
+	metrics::set_db_pool_connections((db.size() as usize).saturating_sub(idle), idle);
+
+	([(header::CONTENT_TYPE, CONTENT_TYPE)], metrics::encode())
+}
@@ -0,0 +1,80 @@
+//! Span exporter appending the spans as json lines to a file (e.g., for offline analysis
+//! with `jq`, without a collector).
+
+use futures::future::BoxFuture;
+use lib_utils::time::format_time;
+use opentelemetry::trace::{SpanId, Status, TraceError};
+use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use serde_json::{json, Map, Value};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use time::OffsetDateTime;
+
+#[derive(Debug)]
+pub struct FileSpanExporter {
+	writer: BufWriter<File>,
+}
+
+impl FileSpanExporter {
+	pub fn new(path: impl AsRef<Path>) -> std::io::Result<Self> {
+		let file = OpenOptions::new().create(true).append(true).open(path)?;
+		Ok(Self {
+			writer: BufWriter::new(file),
+		})
+	}
+
+	fn write_batch(&mut self, batch: Vec<SpanData>) -> std::io::Result<()> {
+		for span in batch {
+			serde_json::to_writer(&mut self.writer, &span_json(span))?;
+			self.writer.write_all(b"\n")?;
+		}
+		self.writer.flush()
+	}
+}
+
+impl SpanExporter for FileSpanExporter {
+	fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+		let res = self
+			.write_batch(batch)
+			.map_err(|ex| TraceError::from(ex.to_string()));
+		Box::pin(std::future::ready(res))
+	}
+}
+
+// region:    --- Support
+
+fn span_json(span: SpanData) -> Value {
+	let attributes: Map<String, Value> = span
+		.attributes
+		.iter()
+		.map(|kv| (kv.key.to_string(), Value::from(kv.value.as_str().to_string())))
+		.collect();
+
+	let parent_span_id =
+		(span.parent_span_id != SpanId::INVALID).then(|| span.parent_span_id.to_string());
+	let duration_us = span
+		.end_time
+		.duration_since(span.start_time)
+		.map(|duration| duration.as_micros() as u64)
+		.unwrap_or_default();
+	let status = match &span.status {
+		Status::Unset => Value::Null,
+		Status::Ok => json!("ok"),
+		Status::Error { description } => json!({ "error": description }),
+	};
+
+	json!({
+		"trace_id": span.span_context.trace_id().to_string(),
+		"span_id": span.span_context.span_id().to_string(),
+		"parent_span_id": parent_span_id,
+		"name": span.name,
+		"kind": format!("{:?}", span.span_kind),
+		"start_time": format_time(OffsetDateTime::from(span.start_time)),
+		"duration_us": duration_us,
+		"status": status,
+		"attributes": attributes,
+	})
+}
+
+// endregion: --- Support
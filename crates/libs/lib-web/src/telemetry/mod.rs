@@ -0,0 +1,209 @@
+//! Tracing of the services, with the spans exported to an OpenTelemetry backend.
+//!
+//! - Spans: one per request (`mw_trace`), per rpc call, per worker call (`service_pool`),
+//!   per sqlx query (`Dbx`), and per genai chat (llm-worker).
+//! - Propagation: the W3C `traceparent`/`tracestate` headers, injected in the worker calls
+//!   and extracted by `mw_trace` (the worker continues the gateway trace).
+//! - Export per the `SERVICE_TRACE_EXPORTER` env:
+//!   - `none` (default) - Log lines only.
+//!   - `otlp` - To the OTLP collector (grpc) at `OTEL_EXPORTER_OTLP_ENDPOINT`
+//!     (default `http://localhost:4317`).
+//!   - `file:<path>` - Appended as json lines to the file (for offline use).
+//!
+//! ```ignore
+//! let _telemetry = telemetry::init_tracing("web-gateway", "error")?;
+//! ```
+
+// region:    --- Modules
+
+mod file_exporter;
+
+use self::file_exporter::FileSpanExporter;
+use crate::error::{Error, Result};
+
+use axum::http::HeaderMap;
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{global, Context, KeyValue};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use tracing::level_filters::LevelFilter;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+// endregion: --- Modules
+
+const ENV_TRACE_EXPORTER: &str = "SERVICE_TRACE_EXPORTER";
+
+// region:    --- Init
+
+/// Shuts down the tracer provider (flushing the pending spans) when dropped.
+///
+/// Note: Must be held until the end of `main`.
+pub struct TelemetryGuard {
+	provider: Option<TracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+	fn drop(&mut self) {
+		if let Some(provider) = self.provider.take() {
+			if let Err(ex) = provider.shutdown() {
+				eprintln!("TELEMETRY - tracer provider shutdown failed: {ex:?}");
+			}
+		}
+	}
+}
+
+/// Init the tracing subscriber of the service:
+/// - The log lines, filtered by `RUST_LOG` (or `default_filter`).
+/// - The spans (info and above) to the exporter of the `SERVICE_TRACE_EXPORTER` env.
+pub fn init_tracing(service_name: &str, default_filter: &str) -> Result<TelemetryGuard> {
+	global::set_text_map_propagator(TraceContextPropagator::new());
+
+	let fmt_layer = tracing_subscriber::fmt::layer()
+		.without_time() // For early local development.
+		.with_target(false)
+		.with_filter(
+			EnvFilter::try_from_default_env()
+				.unwrap_or_else(|_| EnvFilter::new(default_filter)),
+		);
+
+	let exporter = std::env::var(ENV_TRACE_EXPORTER).unwrap_or_default();
+	let provider = new_tracer_provider(service_name, &exporter)?;
+	let otel_layer = provider.as_ref().map(|provider| {
+		tracing_opentelemetry::layer()
+			.with_tracer(provider.tracer(service_name.to_string()))
+			.with_filter(LevelFilter::INFO)
+	});
+
+	tracing_subscriber::registry()
+		.with(fmt_layer)
+		.with(otel_layer)
+		.try_init()
+		.map_err(|ex| Error::TelemetryInit(ex.to_string()))?;
+
+	if let Some(provider) = &provider {
+		global::set_tracer_provider(provider.clone());
+	}
+
+	Ok(TelemetryGuard { provider })
+}
+
+fn new_tracer_provider(service_name: &str, exporter: &str) -> Result<Option<TracerProvider>> {
+	let builder = TracerProvider::builder().with_resource(Resource::new([KeyValue::new(
+		"service.name",
+		service_name.to_string(),
+	)]));
+
+	let provider = match exporter {
+		"" | "none" => return Ok(None),
+		"otlp" => {
+			let exporter = opentelemetry_otlp::SpanExporter::builder()
+				.with_tonic()
+				.build()
+				.map_err(|ex| Error::TelemetryInit(ex.to_string()))?;
+			builder.with_batch_exporter(exporter, runtime::Tokio).build()
+		}
+		_ => match exporter.strip_prefix("file:") {
+			Some(path) => {
+				let exporter = FileSpanExporter::new(path)
+					.map_err(|ex| Error::TelemetryInit(format!("{path} - {ex}")))?;
+				builder.with_batch_exporter(exporter, runtime::Tokio).build()
+			}
+			None => {
+				return Err(Error::TelemetryInit(format!(
+					"{ENV_TRACE_EXPORTER} '{exporter}' not supported (none, otlp, or file:<path>)"
+				)))
+			}
+		},
+	};
+
+	Ok(Some(provider))
+}
+
+// endregion: --- Init
+
+// region:    --- Propagation
+
+/// The `traceparent`/`tracestate` headers of the current span (none when not traced),
+/// for the worker calls.
+pub(crate) fn trace_context_headers() -> Vec<(String, String)> {
+	let cx = tracing::Span::current().context();
+
+	let mut injector = HeadersInjector::default();
+	global::get_text_map_propagator(|propagator| {
+		propagator.inject_context(&cx, &mut injector)
+	});
+
+	injector.0
+}
+
+/// The remote parent context from the request `traceparent`/`tracestate` headers.
+pub(crate) fn extract_trace_context(headers: &HeaderMap) -> Context {
+	global::get_text_map_propagator(|propagator| {
+		propagator.extract(&HeadersExtractor(headers))
+	})
+}
+
+#[derive(Default)]
+struct HeadersInjector(Vec<(String, String)>);
+
+impl Injector for HeadersInjector {
+	fn set(&mut self, key: &str, value: String) {
+		self.0.push((key.to_string(), value));
+	}
+}
+
+struct HeadersExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeadersExtractor<'_> {
+	fn get(&self, key: &str) -> Option<&str> {
+		self.0.get(key).and_then(|value| value.to_str().ok())
+	}
+
+	fn keys(&self) -> Vec<&str> {
+		self.0.keys().map(|key| key.as_str()).collect()
+	}
+}
+
+// endregion: --- Propagation
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use axum::http::HeaderValue;
+	use opentelemetry::trace::TraceContextExt;
+
+	#[test]
+	fn test_extract_trace_context_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		global::set_text_map_propagator(TraceContextPropagator::new());
+		let fx_traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+		let mut headers = HeaderMap::new();
+		headers.insert("traceparent", HeaderValue::from_static(fx_traceparent));
+
+		// -- Exec
+		let cx = extract_trace_context(&headers);
+
+		// -- Check
+		let span_context = cx.span().span_context().clone();
+		assert!(span_context.is_remote());
+		assert_eq!(
+			span_context.trace_id().to_string(),
+			"4bf92f3577b34da6a3ce929d0e0e4736"
+		);
+		assert_eq!(span_context.span_id().to_string(), "00f067aa0ba902b7");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
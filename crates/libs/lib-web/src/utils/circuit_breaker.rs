@@ -0,0 +1,128 @@
+//! Circuit breaker of a service endpoint (see `service_pool`).
+//!
+//! - `Closed` - The requests are sent. Opens after `failure_threshold` consecutive failures.
+//! - `Open` - The requests are not sent, until `open_duration` elapsed.
+//! - `HalfOpen` - One trial request is sent. Closes on success, re-opens on failure.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, strum_macros::AsRefStr)]
+pub enum BreakerState {
+	Closed,
+	Open,
+	HalfOpen,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+	state: BreakerState,
+	consecutive_failures: u32,
+	failure_threshold: u32,
+	open_duration: Duration,
+	/// When the breaker opened, or when the last trial request was sent (half-open).
+	opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+	pub fn new(failure_threshold: u32, open_duration: Duration) -> Self {
+		Self {
+			state: BreakerState::Closed,
+			consecutive_failures: 0,
+			failure_threshold,
+			open_duration,
+			opened_at: None,
+		}
+	}
+
+	pub fn state(&self) -> BreakerState {
+		self.state
+	}
+
+	pub fn consecutive_failures(&self) -> u32 {
+		self.consecutive_failures
+	}
+
+	/// Returns true if a request can be sent now.
+	///
+	/// Note: A new trial request is allowed when the previous one did not report
+	///       back within the open duration (e.g., its call was cancelled).
+	pub fn try_acquire(&mut self, now: Instant) -> bool {
+		match self.state {
+			BreakerState::Closed => true,
+			BreakerState::Open | BreakerState::HalfOpen => {
+				let elapsed = match self.opened_at {
+					Some(opened_at) => now >= opened_at + self.open_duration,
+					None => true,
+				};
+				if elapsed {
+					self.state = BreakerState::HalfOpen;
+					self.opened_at = Some(now);
+				}
+				elapsed
+			}
+		}
+	}
+
+	pub fn on_success(&mut self) {
+		self.state = BreakerState::Closed;
+		self.consecutive_failures = 0;
+		self.opened_at = None;
+	}
+
+	pub fn on_failure(&mut self, now: Instant) {
+		self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+
+		let open = self.state == BreakerState::HalfOpen
+			|| self.consecutive_failures >= self.failure_threshold;
+		if open {
+			self.state = BreakerState::Open;
+			self.opened_at = Some(now);
+		}
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_circuit_breaker_open_half_open_close_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_open_duration = Duration::from_secs(30);
+		let mut breaker = CircuitBreaker::new(2, fx_open_duration);
+		let now = Instant::now();
+
+		// -- Exec & Check - Opens after the threshold
+		breaker.on_failure(now);
+		assert_eq!(breaker.state(), BreakerState::Closed);
+		assert!(breaker.try_acquire(now));
+		breaker.on_failure(now);
+		assert_eq!(breaker.state(), BreakerState::Open);
+		assert!(!breaker.try_acquire(now + Duration::from_secs(1)));
+
+		// -- Exec & Check - One trial once the open duration elapsed
+		let later = now + fx_open_duration;
+		assert!(breaker.try_acquire(later));
+		assert_eq!(breaker.state(), BreakerState::HalfOpen);
+		assert!(!breaker.try_acquire(later));
+
+		// -- Exec & Check - A failed trial re-opens, a successful one closes
+		breaker.on_failure(later);
+		assert_eq!(breaker.state(), BreakerState::Open);
+		let later = later + fx_open_duration;
+		assert!(breaker.try_acquire(later));
+		breaker.on_success();
+		assert_eq!(breaker.state(), BreakerState::Closed);
+		assert_eq!(breaker.consecutive_failures(), 0);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,5 +1,12 @@
+pub mod circuit_breaker;
+pub mod service_pool;
+pub mod service_registration;
+pub mod service_registry;
+pub mod service_rpc;
+pub mod shutdown;
 pub mod token;
 pub mod web_client;
+pub mod webhook_sink;
 
 // crate local for now.
 pub(crate) mod service_resolution;
\ No newline at end of file
@@ -0,0 +1,647 @@
+//! Resilient calls to the worker services (e.g., `llm-worker`).
+//!
+//! - One pooled reqwest client per service (shared by all its calls).
+//! - Round robin over the service instances advertising the method (see `service_resolution`).
+//! - A `CircuitBreaker` per instance endpoint, fed by the calls (passive health)
+//!   and by the `start_health_check` probes (active health).
+//! - Retries on the next instance: always when the endpoint could not be reached
+//!   (the request was not sent), and on timeout or `502`/`503` only for the methods
+//!   marked idempotent (see `ServicePool::set_idempotent_methods`).
+//! - A client span per call, propagated to the worker (see `telemetry`).
+
+use crate::error::{Error, Result};
+use crate::metrics;
+use crate::middleware::mw_auth;
+use crate::telemetry;
+use crate::utils::circuit_breaker::{BreakerState, CircuitBreaker};
+use crate::utils::service_resolution::{
+	resolve_service, resolve_service_method, resolve_service_names,
+};
+use crate::utils::web_client::{WebClient, WebResponse};
+
+use futures::future::join_all;
+use lib_core::ctx::Ctx;
+use lib_rpc_core::openrpc::RPC_DISCOVER;
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, info_span, warn, Instrument};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+const FAILURE_THRESHOLD: u32 = 5;
+const OPEN_DURATION: Duration = Duration::from_secs(30);
+
+const MAX_RETRIES: u32 = 2;
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn service_pool() -> &'static ServicePool {
+	static INSTANCE: OnceLock<ServicePool> = OnceLock::new();
+
+	INSTANCE.get_or_init(|| ServicePool {
+		inner: Mutex::new(PoolInner::default()),
+	})
+}
+
+// region:    --- Types
+
+/// The failures counted by the circuit breakers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CallFailure {
+	/// The endpoint could not be reached (the request was not sent).
+	Connect,
+	Timeout,
+	/// `502` or `503` response (e.g., the instance is shutting down).
+	Unavailable,
+}
+
+/// The breaker state of a service instance (see the admin services route).
+#[derive(Debug, Serialize)]
+pub struct EndpointStatus {
+	pub service: String,
+	pub endpoint: String,
+	pub state: BreakerState,
+	pub consecutive_failures: u32,
+	pub requests: u64,
+	pub failures: u64,
+	pub last_failure: Option<CallFailure>,
+}
+
+pub struct ServicePool {
+	inner: Mutex<PoolInner>,
+}
+
+#[derive(Default)]
+struct PoolInner {
+	clients: HashMap<String, reqwest::Client>,
+	/// Per `(service, endpoint)`.
+	endpoints: HashMap<(String, String), EndpointState>,
+	next_index: HashMap<String, usize>,
+	/// The `(service, method)` safe to retry.
+	idempotent_methods: HashSet<(String, String)>,
+}
+
+struct EndpointState {
+	breaker: CircuitBreaker,
+	requests: u64,
+	failures: u64,
+	last_failure: Option<CallFailure>,
+}
+
+impl Default for EndpointState {
+	fn default() -> Self {
+		Self {
+			breaker: CircuitBreaker::new(FAILURE_THRESHOLD, OPEN_DURATION),
+			requests: 0,
+			failures: 0,
+			last_failure: None,
+		}
+	}
+}
+
+// endregion: --- Types
+
+// region:    --- ServicePool
+
+impl ServicePool {
+	/// Mark the `methods` of the `service` safe to retry on another instance
+	/// after a timeout or an unavailable response (e.g., `get_model_list`).
+	pub fn set_idempotent_methods(&self, service: &str, methods: &[&str]) {
+		let mut inner = self.lock();
+		for method in methods {
+			inner
+				.idempotent_methods
+				.insert((service.to_string(), method.to_string()));
+		}
+	}
+
+	pub fn is_idempotent(&self, service: &str, method: &str) -> bool {
+		self.lock()
+			.idempotent_methods
+			.contains(&(service.to_string(), method.to_string()))
+	}
+
+	/// Post the JSON-RPC `payload` to an available instance of the `service`
+	/// (retried per the module rules), within the `timeout` (e.g., the ctx time left).
+	pub(crate) async fn post_rpc(
+		&self,
+		service: &str,
+		method: &str,
+		headers: &[(String, String)],
+		payload: Value,
+		timeout: Option<Duration>,
+	) -> Result<WebResponse> {
+		let span = info_span!(
+			"service_rpc",
+			otel.name = %format!("{service}/{method}"),
+			otel.kind = "client",
+			rpc.service = service,
+			rpc.method = method,
+		);
+
+		let start = Instant::now();
+		let res = async {
+			// The worker continues the trace from this span.
+			let mut headers = headers.to_vec();
+			headers.extend(telemetry::trace_context_headers());
+			self.do_post_rpc(service, method, &headers, payload, timeout)
+				.await
+		}
+		.instrument(span)
+		.await;
+		metrics::record_service_rpc(service, res.is_ok(), start.elapsed());
+
+		res
+	}
+
+	async fn do_post_rpc(
+		&self,
+		service: &str,
+		method: &str,
+		headers: &[(String, String)],
+		payload: Value,
+		timeout: Option<Duration>,
+	) -> Result<WebResponse> {
+		let deadline = timeout.map(|timeout| Instant::now() + timeout);
+		let idempotent = self.is_idempotent(service, method);
+
+		let mut retries = 0;
+		loop {
+			let timeout = match deadline {
+				Some(deadline) => {
+					let remaining = deadline.saturating_duration_since(Instant::now());
+					if remaining.is_zero() {
+						return Err(Error::ReqDeadlineExceeded);
+					}
+					Some(remaining)
+				}
+				None => None,
+			};
+
+			let (client, endpoint) = self.acquire_endpoint(service, method)?;
+			let url = format!("http://{endpoint}/api/rpc");
+			debug!("{:<12} - {service:?} method {method:?} at {url:?}", "SERVICE POOL");
+
+			let res = WebClient::from_reqwest_client(client)
+				.with_timeout(timeout)
+				.do_post(&url, headers, payload.clone())
+				.await;
+			let failure = call_failure(&res);
+			self.report(service, &endpoint, failure, true);
+
+			match failure {
+				Some(failure)
+					if retries < MAX_RETRIES
+						&& (failure == CallFailure::Connect || idempotent) =>
+				{
+					retries += 1;
+					metrics::record_service_retry(service);
+					warn!(
+						"{:<12} - {service:?} method {method:?} failed at {endpoint:?} ({failure:?}), retry {retries}",
+						"SERVICE POOL"
+					);
+					tokio::time::sleep(RETRY_BACKOFF * retries).await;
+				}
+				_ => return res,
+			}
+		}
+	}
+
+	/// The breaker state of all the resolved service instances.
+	pub fn status(&self) -> Vec<EndpointStatus> {
+		let mut statuses = Vec::new();
+		for service in resolve_service_names() {
+			let Ok(instances) = resolve_service(&service) else {
+				continue;
+			};
+
+			let mut inner = self.lock();
+			for instance in instances {
+				let endpoint = instance.endpoint();
+				let state = inner.endpoint_state(&service, &endpoint);
+				statuses.push(EndpointStatus {
+					service: service.clone(),
+					endpoint,
+					state: state.breaker.state(),
+					consecutive_failures: state.breaker.consecutive_failures(),
+					requests: state.requests,
+					failures: state.failures,
+					last_failure: state.last_failure,
+				});
+			}
+		}
+		statuses
+	}
+
+	/// Start the active health check, probing all the service instances
+	/// (`rpc.discover` as root) every `HEALTH_CHECK_INTERVAL`.
+	pub fn start_health_check(&'static self) {
+		info!("{:<12} - health check started", "SERVICE POOL");
+		tokio::spawn(async move {
+			loop {
+				tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+				self.check_health().await;
+			}
+		});
+	}
+
+	async fn check_health(&self) {
+		let probes = resolve_service_names().into_iter().map(|service| async move {
+			if let Err(ex) = self.probe_service(&service).await {
+				warn!("{:<12} - {service:?} probe failed: {ex:?}", "SERVICE POOL");
+			}
+		});
+		join_all(probes).await;
+	}
+
+	/// Probe all the instances of the service concurrently (`rpc.discover` as root),
+	/// and returns the number of reachable instances.
+	///
+	/// Note: The probe outcomes feed the breakers (e.g., for the readiness health check).
+	pub async fn probe_service(&self, service: &str) -> Result<usize> {
+		let headers = vec![mw_auth::get_ctx_headers(&Ctx::root_ctx())?];
+		let payload = json!({
+			"jsonrpc": "2.0",
+			"id": null,
+			"method": RPC_DISCOVER,
+		});
+
+		let instances = resolve_service(service)?;
+		let client = self.lock().client(service);
+
+		let probes = instances.into_iter().map(|instance| {
+			let client = client.clone();
+			let (headers, payload) = (&headers, &payload);
+			async move {
+				let endpoint = instance.endpoint();
+				let url = format!("http://{endpoint}/api/rpc");
+				let res = WebClient::from_reqwest_client(client)
+					.with_timeout(Some(HEALTH_CHECK_TIMEOUT))
+					.do_post(&url, headers, payload.clone())
+					.await;
+
+				let failure = call_failure(&res);
+				if let Some(failure) = failure {
+					debug!(
+						"{:<12} - {service:?} at {endpoint:?} unhealthy ({failure:?})",
+						"SERVICE POOL"
+					);
+				}
+				self.report(service, &endpoint, failure, false);
+				failure.is_none()
+			}
+		});
+		let reachable = join_all(probes).await.into_iter().filter(|ok| *ok).count();
+
+		Ok(reachable)
+	}
+
+	/// The client and endpoint of the next instance advertising the method,
+	/// with a breaker allowing the call.
+	fn acquire_endpoint(
+		&self,
+		service: &str,
+		method: &str,
+	) -> Result<(reqwest::Client, String)> {
+		let instances = resolve_service_method(service, method)?;
+		let now = Instant::now();
+
+		let mut inner = self.lock();
+		let next_index = inner.next_index.entry(service.to_string()).or_default();
+		let start = *next_index;
+		*next_index = next_index.wrapping_add(1);
+
+		for i in 0..instances.len() {
+			let endpoint = instances[(start + i) % instances.len()].endpoint();
+			let state = inner.endpoint_state(service, &endpoint);
+			if state.breaker.try_acquire(now) {
+				state.requests += 1;
+				return Ok((inner.client(service), endpoint));
+			}
+		}
+
+		Err(Error::ServiceUnavailable {
+			service: service.to_string(),
+		})
+	}
+
+	/// Report the outcome of a call (or of a health probe, not counted as a request).
+	fn report(
+		&self,
+		service: &str,
+		endpoint: &str,
+		failure: Option<CallFailure>,
+		is_request: bool,
+	) {
+		let mut inner = self.lock();
+		let state = inner.endpoint_state(service, endpoint);
+		match failure {
+			None => state.breaker.on_success(),
+			Some(failure) => {
+				state.breaker.on_failure(Instant::now());
+				state.last_failure = Some(failure);
+				if is_request {
+					state.failures += 1;
+				}
+				if state.breaker.state() == BreakerState::Open {
+					warn!("{:<12} - {service:?} at {endpoint:?} circuit open", "SERVICE POOL");
+				}
+			}
+		}
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, PoolInner> {
+		// Note: The state stays consistent even if a holder panicked.
+		self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+}
+
+impl PoolInner {
+	fn client(&mut self, service: &str) -> reqwest::Client {
+		self.clients
+			.entry(service.to_string())
+			.or_insert_with(|| {
+				reqwest::Client::builder()
+					.connect_timeout(CONNECT_TIMEOUT)
+					.pool_idle_timeout(POOL_IDLE_TIMEOUT)
+					.build()
+					.unwrap_or_default()
+			})
+			.clone()
+	}
+
+	fn endpoint_state(&mut self, service: &str, endpoint: &str) -> &mut EndpointState {
+		self.endpoints
+			.entry((service.to_string(), endpoint.to_string()))
+			.or_default()
+	}
+}
+
+// endregion: --- ServicePool
+
+// region:    --- Support
+
+/// The failure of the call, if it is an endpoint failure (the rpc errors are not).
+fn call_failure(res: &Result<WebResponse>) -> Option<CallFailure> {
+	match res {
+		Ok(_) => None,
+		Err(Error::Reqwest(ex)) if ex.is_connect() => Some(CallFailure::Connect),
+		Err(Error::Reqwest(ex)) if ex.is_timeout() => Some(CallFailure::Timeout),
+		Err(Error::WebClientResponseFailedStatus { status, .. })
+			if *status == StatusCode::BAD_GATEWAY
+				|| *status == StatusCode::SERVICE_UNAVAILABLE =>
+		{
+			Some(CallFailure::Unavailable)
+		}
+		_ => None,
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use crate::utils::service_registry::{self, ServiceRegistration};
+	use axum::routing::post;
+	use axum::{Json, Router};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use std::sync::Arc;
+	use tokio::net::TcpListener;
+
+	const FX_METHOD: &str = "fx_method";
+
+	/// A worker stub answering `/api/rpc` with the `status`, after the `delay`.
+	struct FxStub {
+		endpoint: String,
+		hits: Arc<AtomicUsize>,
+	}
+
+	impl FxStub {
+		async fn start(status: StatusCode, delay: Duration) -> Result<Self> {
+			let hits = Arc::new(AtomicUsize::new(0));
+			let handler_hits = hits.clone();
+			let routes = Router::new().route(
+				"/api/rpc",
+				post(move || async move {
+					handler_hits.fetch_add(1, Ordering::SeqCst);
+					tokio::time::sleep(delay).await;
+					(status, Json(json!({"jsonrpc": "2.0", "id": null, "result": {}})))
+				}),
+			);
+
+			let listener = TcpListener::bind("127.0.0.1:0").await?;
+			let endpoint = listener.local_addr()?.to_string();
+			tokio::spawn(async move { axum::serve(listener, routes).await });
+
+			Ok(Self { endpoint, hits })
+		}
+
+		fn hits(&self) -> usize {
+			self.hits.load(Ordering::SeqCst)
+		}
+	}
+
+	/// An endpoint refusing the connections (the listener is dropped).
+	async fn fx_closed_endpoint() -> Result<String> {
+		let listener = TcpListener::bind("127.0.0.1:0").await?;
+		Ok(listener.local_addr()?.to_string())
+	}
+
+	/// Register the endpoints (in this order) as instances of the `service`.
+	fn fx_register(service: &str, endpoints: &[&str]) -> Result<()> {
+		for endpoint in endpoints {
+			let (host, port) = endpoint.rsplit_once(':').ok_or("Endpoint should be host:port")?;
+			service_registry::register(ServiceRegistration {
+				name: service.to_string(),
+				host: host.to_string(),
+				port: port.parse()?,
+				version: "0.1.0".to_string(),
+				methods: vec![FX_METHOD.to_string()],
+			});
+		}
+		Ok(())
+	}
+
+	/// A new pool (not the shared one), so that the round robin starts at the first instance.
+	fn fx_pool() -> ServicePool {
+		ServicePool {
+			inner: Mutex::new(PoolInner::default()),
+		}
+	}
+
+	async fn fx_post(
+		pool: &ServicePool,
+		service: &str,
+		timeout: Option<Duration>,
+	) -> crate::error::Result<WebResponse> {
+		pool.post_rpc(service, FX_METHOD, &[], json!({"method": FX_METHOD}), timeout)
+			.await
+	}
+
+	#[tokio::test]
+	async fn test_service_pool_round_robin_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_service = "fx-pool-round-robin";
+		let stub_a = FxStub::start(StatusCode::OK, Duration::ZERO).await?;
+		let stub_b = FxStub::start(StatusCode::OK, Duration::ZERO).await?;
+		fx_register(fx_service, &[&stub_a.endpoint, &stub_b.endpoint])?;
+		let pool = fx_pool();
+
+		// -- Exec
+		for _ in 0..4 {
+			fx_post(&pool, fx_service, None).await?;
+		}
+
+		// -- Check
+		assert_eq!(stub_a.hits(), 2);
+		assert_eq!(stub_b.hits(), 2);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_service_pool_breaker_open_skipped_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_service = "fx-pool-breaker";
+		let closed_endpoint = fx_closed_endpoint().await?;
+		let stub = FxStub::start(StatusCode::OK, Duration::ZERO).await?;
+		fx_register(fx_service, &[&closed_endpoint, &stub.endpoint])?;
+		let pool = fx_pool();
+
+		// -- Exec - Open the breaker of the closed endpoint
+		for _ in 0..FAILURE_THRESHOLD {
+			pool.report(fx_service, &closed_endpoint, Some(CallFailure::Connect), true);
+		}
+		for _ in 0..4 {
+			fx_post(&pool, fx_service, None).await?;
+		}
+
+		// -- Check - The closed endpoint was not called
+		assert_eq!(stub.hits(), 4);
+		let mut inner = pool.lock();
+		let closed_state = inner.endpoint_state(fx_service, &closed_endpoint);
+		assert_eq!(closed_state.breaker.state(), BreakerState::Open);
+		assert_eq!(closed_state.requests, 0);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_service_pool_retry_rules_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_service = "fx-pool-retry";
+		let stub_unavailable = FxStub::start(StatusCode::SERVICE_UNAVAILABLE, Duration::ZERO).await?;
+		let stub_ok = FxStub::start(StatusCode::OK, Duration::ZERO).await?;
+		fx_register(fx_service, &[&stub_unavailable.endpoint, &stub_ok.endpoint])?;
+
+		// -- Exec & Check - Not retried (not idempotent)
+		let res = fx_post(&fx_pool(), fx_service, None).await;
+		assert!(
+			matches!(
+				res,
+				Err(crate::error::Error::WebClientResponseFailedStatus { status, .. })
+					if status == StatusCode::SERVICE_UNAVAILABLE
+			),
+			"Should be the 503, but was: {res:?}"
+		);
+		assert_eq!((stub_unavailable.hits(), stub_ok.hits()), (1, 0));
+
+		// -- Exec & Check - Retried on the next instance (idempotent)
+		let pool = fx_pool();
+		pool.set_idempotent_methods(fx_service, &[FX_METHOD]);
+		fx_post(&pool, fx_service, None).await?;
+		assert_eq!((stub_unavailable.hits(), stub_ok.hits()), (2, 1));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_service_pool_retry_connect_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_service = "fx-pool-retry-connect";
+		let closed_endpoint = fx_closed_endpoint().await?;
+		let stub = FxStub::start(StatusCode::OK, Duration::ZERO).await?;
+		fx_register(fx_service, &[&closed_endpoint, &stub.endpoint])?;
+		let pool = fx_pool();
+
+		// -- Exec - Not idempotent, but the request was not sent
+		fx_post(&pool, fx_service, None).await?;
+
+		// -- Check
+		assert_eq!(stub.hits(), 1);
+		let mut inner = pool.lock();
+		let closed_state = inner.endpoint_state(fx_service, &closed_endpoint);
+		assert_eq!(closed_state.last_failure, Some(CallFailure::Connect));
+		assert_eq!(closed_state.failures, 1);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_service_pool_probe_concurrent_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_service = "fx-pool-probe";
+		let fx_delay = Duration::from_millis(500);
+		let stub_a = FxStub::start(StatusCode::OK, fx_delay).await?;
+		let stub_b = FxStub::start(StatusCode::OK, fx_delay).await?;
+		let stub_unavailable = FxStub::start(StatusCode::SERVICE_UNAVAILABLE, fx_delay).await?;
+		let closed_endpoint = fx_closed_endpoint().await?;
+		fx_register(
+			fx_service,
+			&[&stub_a.endpoint, &stub_b.endpoint, &stub_unavailable.endpoint, &closed_endpoint],
+		)?;
+		let pool = fx_pool();
+
+		// -- Exec
+		let start = Instant::now();
+		let reachable = pool.probe_service(fx_service).await?;
+
+		// -- Check
+		assert_eq!(reachable, 2);
+		assert!(start.elapsed() < fx_delay * 2, "Should probe the instances concurrently");
+		let mut inner = pool.lock();
+		let unavailable_state = inner.endpoint_state(fx_service, &stub_unavailable.endpoint);
+		assert_eq!(unavailable_state.last_failure, Some(CallFailure::Unavailable));
+		assert_eq!(unavailable_state.requests, 0);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_service_pool_deadline_exceeded_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_service = "fx-pool-deadline";
+		let stub_slow = FxStub::start(StatusCode::OK, Duration::from_secs(2)).await?;
+		fx_register(fx_service, &[&stub_slow.endpoint])?;
+		let pool = fx_pool();
+		pool.set_idempotent_methods(fx_service, &[FX_METHOD]);
+
+		// -- Exec
+		// Note: The first call times out, then the backoff exhausts the time left.
+		let res = fx_post(&pool, fx_service, Some(Duration::from_millis(200))).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(crate::error::Error::ReqDeadlineExceeded)),
+			"Should be ReqDeadlineExceeded, but was: {res:?}"
+		);
+		assert_eq!(stub_slow.hits(), 1);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
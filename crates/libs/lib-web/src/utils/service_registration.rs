@@ -0,0 +1,101 @@
+//! The worker side of the service registry: registers the worker instance with the
+//! gateway, then heartbeats (registering again when the gateway does not know it).
+
+use crate::error::Result;
+use crate::utils::service_registry::{
+	ServiceHeartbeat, ServiceRegistration, HEADER_SERVICE_KEY, HEARTBEAT_INTERVAL,
+};
+use crate::utils::web_client::WebClient;
+use serde_json::Value;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+const PATH_REGISTER: &str = "/api/services/register";
+const PATH_HEARTBEAT: &str = "/api/services/heartbeat";
+
+const REGISTRATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Start the registration (and heartbeat) of the worker instance with the gateway
+/// at `gateway_url` (e.g., `http://localhost:8080`).
+pub fn start_registration(
+	gateway_url: impl Into<String>,
+	service_key: impl Into<String>,
+	registration: ServiceRegistration,
+) {
+	let registrar = Registrar {
+		gateway_url: gateway_url.into(),
+		headers: vec![(HEADER_SERVICE_KEY.to_string(), service_key.into())],
+		registration,
+	};
+	tokio::spawn(registrar.run());
+}
+
+struct Registrar {
+	gateway_url: String,
+	headers: Vec<(String, String)>,
+	registration: ServiceRegistration,
+}
+
+impl Registrar {
+	async fn run(self) {
+		let mut registered = false;
+		loop {
+			let res = if registered {
+				self.heartbeat().await
+			} else {
+				self.register().await
+			};
+
+			match res {
+				Ok(true) if !registered => {
+					info!(
+						"{:<12} - {:?} with {}",
+						"REGISTERED", self.registration.name, self.gateway_url
+					);
+					registered = true;
+				}
+				Ok(true) => debug!("{:<12} - heartbeat sent", "REGISTRATION"),
+				Ok(false) => {
+					warn!("{:<12} - unknown to the gateway, registering again", "REGISTRATION");
+					registered = false;
+					continue;
+				}
+				Err(ex) => warn!("{:<12} - gateway call failed: {ex:?}", "REGISTRATION"),
+			}
+
+			tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+		}
+	}
+
+	async fn register(&self) -> Result<bool> {
+		let content = serde_json::to_value(&self.registration)?;
+		self.post(PATH_REGISTER, content).await?;
+		Ok(true)
+	}
+
+	/// Returns false when the gateway does not know the instance.
+	async fn heartbeat(&self) -> Result<bool> {
+		let ServiceRegistration {
+			name, host, port, ..
+		} = &self.registration;
+		let content = serde_json::to_value(ServiceHeartbeat {
+			name: name.clone(),
+			host: host.clone(),
+			port: *port,
+		})?;
+
+		let body = self.post(PATH_HEARTBEAT, content).await?;
+		Ok(body.pointer("/result/registered").and_then(Value::as_bool) == Some(true))
+	}
+
+	/// Note: The gateway errors (e.g., invalid service key) are failed status errors.
+	async fn post(&self, path: &str, content: Value) -> Result<Value> {
+		let url = format!("{}{path}", self.gateway_url);
+		let web_res = WebClient::default()
+			.with_timeout(Some(REGISTRATION_TIMEOUT))
+			.do_post(&url, &self.headers, content)
+			.await?;
+
+		Ok(web_res.body)
+	}
+}
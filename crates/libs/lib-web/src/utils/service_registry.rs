@@ -0,0 +1,259 @@
+//! Registry of the worker instances registered with the gateway
+//! (see `service_registration` for the worker side).
+//!
+//! - A worker registers its name, address, version and rpc methods, then heartbeats
+//!   every `HEARTBEAT_INTERVAL`.
+//! - An instance without heartbeat for `REGISTRATION_TTL` is evicted.
+//! - The registered instances are resolved with the configured ones (see `service_resolution`),
+//!   and only the instances advertising the method are called.
+
+use crate::utils::service_resolution::ServiceResolutionData;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// The header with the service key of the registration routes.
+pub const HEADER_SERVICE_KEY: &str = "X-SERVICE-KEY";
+
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+/// Note: Three missed heartbeats.
+pub const REGISTRATION_TTL: Duration = Duration::from_secs(30);
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceRegistration {
+	pub name: String,
+	pub host: String,
+	pub port: i32,
+	pub version: String,
+	/// The rpc methods of the instance (without the service prefix, e.g., `one_shot_msg`).
+	pub methods: Vec<String>,
+}
+
+/// The instance of the heartbeat (as registered).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceHeartbeat {
+	pub name: String,
+	pub host: String,
+	pub port: i32,
+}
+
+struct RegisteredInstance {
+	registration: ServiceRegistration,
+	last_heartbeat: Instant,
+}
+
+impl RegisteredInstance {
+	fn is(&self, name: &str, host: &str, port: i32) -> bool {
+		let reg = &self.registration;
+		reg.name == name && reg.host == host && reg.port == port
+	}
+}
+
+// endregion: --- Types
+
+// region:    --- Registry
+
+/// Register (or re-register) the instance.
+pub fn register(registration: ServiceRegistration) {
+	let mut instances = lock_registry();
+
+	let ServiceRegistration {
+		name, host, port, ..
+	} = &registration;
+	info!(
+		"{:<12} - {name:?} at {host}:{port} (v{}, {} methods)",
+		"REGISTERED",
+		registration.version,
+		registration.methods.len()
+	);
+
+	instances.retain(|instance| !instance.is(name, host, *port));
+	instances.push(RegisteredInstance {
+		registration,
+		last_heartbeat: Instant::now(),
+	});
+}
+
+/// Refresh the registration of the instance, and returns false if the instance
+/// is not registered (e.g., evicted or gateway restarted), to be registered again.
+pub fn heartbeat(heartbeat: &ServiceHeartbeat) -> bool {
+	let mut instances = lock_registry();
+	let ServiceHeartbeat { name, host, port } = heartbeat;
+
+	match instances.iter_mut().find(|instance| instance.is(name, host, *port)) {
+		Some(instance) => {
+			instance.last_heartbeat = Instant::now();
+			true
+		}
+		None => false,
+	}
+}
+
+/// The registered instances of the service, advertising the `method` if any.
+pub(crate) fn registered_instances(
+	srv_name: &str,
+	method: Option<&str>,
+) -> Vec<ServiceResolutionData> {
+	lock_registry()
+		.iter()
+		.map(|instance| &instance.registration)
+		.filter(|reg| reg.name == srv_name)
+		.filter(|reg| match method {
+			Some(method) => reg.methods.iter().any(|m| m == method),
+			None => true,
+		})
+		.map(|reg| ServiceResolutionData {
+			name: reg.name.clone(),
+			host: reg.host.clone(),
+			port: reg.port,
+		})
+		.collect()
+}
+
+pub(crate) fn registered_names() -> Vec<String> {
+	lock_registry()
+		.iter()
+		.map(|instance| instance.registration.name.clone())
+		.collect()
+}
+
+/// The registry, without the stale instances.
+fn lock_registry() -> std::sync::MutexGuard<'static, Vec<RegisteredInstance>> {
+	static INSTANCE: OnceLock<Mutex<Vec<RegisteredInstance>>> = OnceLock::new();
+
+	let mut instances = INSTANCE
+		.get_or_init(|| Mutex::new(Vec::new()))
+		.lock()
+		.unwrap_or_else(|poisoned| poisoned.into_inner());
+
+	instances.retain(|instance| {
+		let fresh = instance.last_heartbeat.elapsed() < REGISTRATION_TTL;
+		if !fresh {
+			let reg = &instance.registration;
+			warn!(
+				"{:<12} - {:?} at {}:{} (no heartbeat)",
+				"EVICTED", reg.name, reg.host, reg.port
+			);
+		}
+		fresh
+	});
+
+	instances
+}
+
+// endregion: --- Registry
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+
+	/// Note: Each test has its own service name (the registry is shared).
+	fn fx_registration(name: &str, port: i32, methods: &[&str]) -> ServiceRegistration {
+		ServiceRegistration {
+			name: name.to_string(),
+			host: "localhost".to_string(),
+			port,
+			version: "0.1.0".to_string(),
+			methods: methods.iter().map(|m| m.to_string()).collect(),
+		}
+	}
+
+	fn fx_heartbeat(name: &str, port: i32) -> ServiceHeartbeat {
+		ServiceHeartbeat {
+			name: name.to_string(),
+			host: "localhost".to_string(),
+			port,
+		}
+	}
+
+	fn fx_ports(name: &str, method: Option<&str>) -> Vec<i32> {
+		registered_instances(name, method).iter().map(|i| i.port).collect()
+	}
+
+	#[test]
+	fn test_registry_register_reregister_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_name = "fx-registry-register";
+
+		// -- Exec
+		register(fx_registration(fx_name, 9001, &["one"]));
+		register(fx_registration(fx_name, 9002, &["one"]));
+		// Note: Same instance, new methods (e.g., restarted with a new version).
+		register(fx_registration(fx_name, 9001, &["one", "two"]));
+
+		// -- Check
+		assert_eq!(fx_ports(fx_name, None), [9002, 9001]);
+		assert_eq!(fx_ports(fx_name, Some("two")), [9001]);
+		assert!(registered_names().iter().any(|name| name == fx_name));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_registry_method_filter_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_name = "fx-registry-methods";
+		register(fx_registration(fx_name, 9001, &["one", "two"]));
+		register(fx_registration(fx_name, 9002, &["two"]));
+
+		// -- Exec & Check
+		assert_eq!(fx_ports(fx_name, Some("one")), [9001]);
+		assert_eq!(fx_ports(fx_name, Some("two")), [9001, 9002]);
+		assert!(fx_ports(fx_name, Some("three")).is_empty());
+		assert!(fx_ports("fx-registry-unknown", None).is_empty());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_registry_heartbeat_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_name = "fx-registry-heartbeat";
+		register(fx_registration(fx_name, 9001, &["one"]));
+
+		// -- Exec & Check
+		assert!(heartbeat(&fx_heartbeat(fx_name, 9001)));
+		// Note: Not registered, the instance registers again.
+		assert!(!heartbeat(&fx_heartbeat(fx_name, 9002)));
+		assert!(!heartbeat(&fx_heartbeat("fx-registry-unknown", 9001)));
+		assert_eq!(fx_ports(fx_name, None), [9001]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_registry_ttl_eviction_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_name = "fx-registry-ttl";
+		register(fx_registration(fx_name, 9001, &["one"]));
+		register(fx_registration(fx_name, 9002, &["one"]));
+
+		// -- Exec - The 9001 last heartbeat is older than the TTL
+		{
+			let mut instances = lock_registry();
+			let instance = instances
+				.iter_mut()
+				.find(|instance| instance.is(fx_name, "localhost", 9001))
+				.ok_or("Should be registered")?;
+			instance.last_heartbeat = Instant::now()
+				.checked_sub(REGISTRATION_TTL)
+				.ok_or("Should have an instant before the TTL")?;
+		}
+
+		// -- Check
+		assert_eq!(fx_ports(fx_name, None), [9002]);
+		assert!(!heartbeat(&fx_heartbeat(fx_name, 9001)));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,26 +1,84 @@
 use serde::Deserialize;
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::utils::service_registry;
 
 #[cfg(feature="dev-utils")]
 use crate::_dev_utils;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub(crate) struct ServiceResolutionData {
+	// Note: Only read by the dev service registry (the registry key).
+	#[cfg_attr(not(feature = "dev-utils"), allow(dead_code))]
 	pub name: String,
 	pub host : String,
 	pub port : i32,
 }
 
-pub(crate) fn resolve_service(srv_name: &str) -> Result<&ServiceResolutionData> {
-    do_resolve_service(srv_name)
+impl ServiceResolutionData {
+	/// The `host:port` of the service instance (e.g., `localhost:8081`).
+	pub fn endpoint(&self) -> String {
+		format!("{}:{}", self.host, self.port)
+	}
+}
+
+/// The instances of the service (at least one): the registered ones (see `service_registry`),
+/// then the configured ones.
+pub(crate) fn resolve_service(srv_name: &str) -> Result<Vec<ServiceResolutionData>> {
+    do_resolve_service(srv_name, None)
+}
+
+/// The instances of the service advertising the `method` (at least one).
+///
+/// Note: The configured instances advertise all the methods.
+pub(crate) fn resolve_service_method(srv_name: &str, method: &str) -> Result<Vec<ServiceResolutionData>> {
+    do_resolve_service(srv_name, Some(method))
+}
+
+/// The `method` is advertised by an instance of the service (e.g., to label the metrics).
+pub(crate) fn is_service_method(srv_name: &str, method: &str) -> bool {
+    resolve_service_method(srv_name, method).is_ok()
+}
+
+fn do_resolve_service(srv_name: &str, method: Option<&str>) -> Result<Vec<ServiceResolutionData>> {
+    let mut instances = service_registry::registered_instances(srv_name, method);
+    for instance in resolve_configured_service(srv_name) {
+        if !instances.iter().any(|i| i.endpoint() == instance.endpoint()) {
+            instances.push(instance);
+        }
+    }
+
+    if instances.is_empty() {
+        Err(Error::ServiceResolutionFailed)
+    } else {
+        Ok(instances)
+    }
 }
 
 #[cfg(feature="dev-utils")]
-fn do_resolve_service(srv_name: &str) -> Result<&ServiceResolutionData> {
-    _dev_utils::resolve_service(srv_name)
+fn resolve_configured_service(srv_name: &str) -> Vec<ServiceResolutionData> {
+    _dev_utils::resolve_service(srv_name).unwrap_or_default()
 }
 
 #[cfg(not(feature="dev-utils"))]
-fn do_resolve_service(srv_name: &str) -> Result<&ServiceResolutionData> {
-    todo!()    
-}
\ No newline at end of file
+fn resolve_configured_service(_srv_name: &str) -> Vec<ServiceResolutionData> {
+    Vec::new()
+}
+
+/// The names of the resolvable services (e.g., `llm-worker`).
+pub(crate) fn resolve_service_names() -> Vec<String> {
+    let mut names = service_registry::registered_names();
+    names.extend(do_resolve_service_names());
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(feature="dev-utils")]
+fn do_resolve_service_names() -> Vec<String> {
+    _dev_utils::service_names()
+}
+
+#[cfg(not(feature="dev-utils"))]
+fn do_resolve_service_names() -> Vec<String> {
+    Vec::new()
+}
@@ -0,0 +1,93 @@
+use crate::error::{Error, Result};
+use crate::middleware::mw_auth;
+use crate::utils::service_pool::service_pool;
+use crate::utils::service_resolution::resolve_service_names;
+
+use lib_core::ctx::Ctx;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::debug;
+
+/// Margin taken from the ctx deadline on each service hop, so that the worker
+/// times out (and responds) before its caller.
+const SERVICE_HOP_MARGIN: Duration = Duration::from_millis(250);
+
+/// Call the `method` of the worker `service` (e.g., `llm-worker`) on behalf of `ctx`,
+/// and returns the JSON-RPC `result`.
+///
+/// Note: This is the server side equivalent of a client `service/method` rpc call,
+///       for example, to trigger a worker from a gateway rpc handler.
+pub async fn call_service_rpc(
+	ctx: &Ctx,
+	service: &str,
+	method: &str,
+	params: Value,
+) -> Result<Value> {
+	let (ctx, timeout) = service_hop(ctx)?;
+	let headers = vec![mw_auth::get_ctx_headers(&ctx)?];
+
+	debug!("{:<12} - Call {service:?} method {method:?}", "SERVICE RPC");
+
+	let payload = json!({
+		"jsonrpc": "2.0",
+		"id": null,
+		"method": method,
+		"params": params,
+	});
+
+	let mut web_res = service_pool()
+		.post_rpc(service, method, &headers, payload, timeout)
+		.await
+		.map_err(|err| service_rpc_error(err, service, method))?;
+
+	match web_res.body.get_mut("error").map(Value::take) {
+		Some(error) => Err(Error::ServiceRpcFail {
+			service: service.to_string(),
+			method: method.to_string(),
+			error,
+		}),
+		None => Ok(web_res.body.get_mut("result").map(Value::take).unwrap_or_default()),
+	}
+}
+
+/// The names of the worker services callable with `call_service_rpc`.
+pub fn service_names() -> Vec<String> {
+	resolve_service_names()
+}
+
+// region:    --- Deadline Support
+
+/// Returns the ctx for the worker (deadline shrunk by the hop margin), and the
+/// timeout of the call (the time left before the ctx deadline, if any).
+pub(crate) fn service_hop(ctx: &Ctx) -> Result<(Ctx, Option<Duration>)> {
+	let timeout = ctx.remaining();
+	if timeout.is_some_and(|timeout| timeout.is_zero()) {
+		return Err(Error::ReqDeadlineExceeded);
+	}
+
+	Ok((ctx.shrink_deadline(SERVICE_HOP_MARGIN), timeout))
+}
+
+/// The service call timeouts (the call timeout, or the worker timeout response)
+/// as `ServiceRpcTimeout`.
+pub(crate) fn service_rpc_error(error: Error, service: &str, method: &str) -> Error {
+	let is_timeout = match &error {
+		Error::Reqwest(ex) => ex.is_timeout(),
+		Error::WebClientResponseFailedStatus { status, .. } => {
+			*status == StatusCode::GATEWAY_TIMEOUT
+		}
+		_ => false,
+	};
+
+	if is_timeout {
+		Error::ServiceRpcTimeout {
+			service: service.to_string(),
+			method: method.to_string(),
+		}
+	} else {
+		error
+	}
+}
+
+// endregion: --- Deadline Support
@@ -0,0 +1,35 @@
+use tracing::{info, warn};
+
+/// Resolves on `ctrl-c` or `SIGTERM` (e.g., the orchestrator stopping the container),
+/// for the axum graceful shutdown.
+pub async fn shutdown_signal() {
+	let ctrl_c = async {
+		if let Err(ex) = tokio::signal::ctrl_c().await {
+			warn!("{:<12} - ctrl-c handler failed: {ex:?}", "SHUTDOWN");
+			std::future::pending::<()>().await;
+		}
+	};
+
+	#[cfg(unix)]
+	let terminate = async {
+		use tokio::signal::unix::{signal, SignalKind};
+		match signal(SignalKind::terminate()) {
+			Ok(mut sigterm) => {
+				sigterm.recv().await;
+			}
+			Err(ex) => {
+				warn!("{:<12} - SIGTERM handler failed: {ex:?}", "SHUTDOWN");
+				std::future::pending::<()>().await;
+			}
+		}
+	};
+	#[cfg(not(unix))]
+	let terminate = std::future::pending::<()>();
+
+	tokio::select! {
+		_ = ctrl_c => {},
+		_ = terminate => {},
+	}
+
+	info!("{:<12} - signal received, draining the requests", "SHUTDOWN");
+}
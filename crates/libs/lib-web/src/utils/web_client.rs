@@ -2,19 +2,34 @@ use crate::error::{Error, Result};
 use reqwest::header::HeaderMap;
 use reqwest::{Method, RequestBuilder, StatusCode};
 use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Connect timeout of the default (shared) reqwest client.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Simple reqwest client wrapper for this library.
 #[derive(Debug)]
 pub struct WebClient {
 	reqwest_client: reqwest::Client,
+	/// Timeout of each request (none by default).
+	timeout: Option<Duration>,
 }
 
 // impl default
+/// Note: The default `WebClient` shares the same reqwest client (i.e., connection pool).
 impl Default for WebClient {
 	fn default() -> Self {
-		WebClient {
-			reqwest_client: reqwest::Client::new(),
-		}
+		static REQWEST_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+		let reqwest_client = REQWEST_CLIENT.get_or_init(|| {
+			reqwest::Client::builder()
+				.connect_timeout(CONNECT_TIMEOUT)
+				.build()
+				.unwrap_or_default()
+		});
+
+		WebClient::from_reqwest_client(reqwest_client.clone())
 	}
 }
 
@@ -22,7 +37,16 @@ impl Default for WebClient {
 
 impl WebClient {
 	pub fn from_reqwest_client(reqwest_client: reqwest::Client) -> Self {
-		WebClient { reqwest_client }
+		WebClient {
+			reqwest_client,
+			timeout: None,
+		}
+	}
+
+	/// Set the timeout of each request (e.g., the time left before the `Ctx` deadline).
+	pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.timeout = timeout;
+		self
 	}
 }
 
@@ -33,6 +57,9 @@ impl WebClient {
 impl WebClient {
 	pub async fn do_get(&self, url: &str, headers: &[(String, String)]) -> Result<WebResponse> {
 		let mut reqwest_builder = self.reqwest_client.request(Method::GET, url);
+		if let Some(timeout) = self.timeout {
+			reqwest_builder = reqwest_builder.timeout(timeout);
+		}
 
 		for (k, v) in headers.iter() {
 			reqwest_builder = reqwest_builder.header(k, v);
@@ -58,6 +85,9 @@ impl WebClient {
 		let method = Method::POST;
 
 		let mut reqwest_builder = self.reqwest_client.request(method, url);
+		if let Some(timeout) = self.timeout {
+			reqwest_builder = reqwest_builder.timeout(timeout);
+		}
 		for (k, v) in headers.iter() {
 			reqwest_builder = reqwest_builder.header(k, v);
 		}
@@ -0,0 +1,48 @@
+use crate::webhooks::pinned_web_client;
+use async_trait::async_trait;
+use lib_core::ctx::Ctx;
+use lib_core::model::ModelManager;
+use lib_core::outbox_relay::{OutboxSink, SinkResult};
+use lib_events::CloudEvent;
+
+/// Outbox sink posting each event (CloudEvents structured mode) to a webhook url.
+///
+/// Notes:
+///   - Any 2xx response is a success (the response body is ignored).
+///   - As for the webhook deliveries (see `lib_web::webhooks`), the `ctx` extension is
+///     not sent, the url host must resolve to public addresses (re-checked on each send),
+///     the redirects are not followed, and the request has a timeout
+///     (the relay holds the outbox rows lock while sending).
+pub struct WebhookSink {
+	url: String,
+}
+
+impl WebhookSink {
+	pub fn new(url: impl Into<String>) -> Self {
+		WebhookSink { url: url.into() }
+	}
+}
+
+#[async_trait]
+impl OutboxSink for WebhookSink {
+	async fn send(&self, _ctx: &Ctx, _mm: &ModelManager, event: &CloudEvent) -> SinkResult {
+		// Note: The `ctx` extension is internal (the ctx of the event user), never sent.
+		let mut event = event.clone();
+		event.ctx = None;
+		let content = serde_json::to_value(&event)?;
+
+		let web_client = pinned_web_client(&self.url).await?;
+		let res = web_client
+			.new_req_builder(&self.url, &[], content)?
+			.header("content-type", "application/cloudevents+json")
+			.send()
+			.await?;
+
+		let status = res.status();
+		if !status.is_success() {
+			return Err(format!("webhook '{}' responded with status {status}", self.url).into());
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,230 @@
+//! Outbound webhooks dispatch (see `lib_core::model::webhook`).
+//!
+//! - `WebhookFanout` consumes the conv events of the event bus, and for each webhook
+//!   subscribing to the event (conv owner user or org), logs a delivery and enqueues its job.
+//! - `WebhookDeliveryHandler` runs the delivery jobs: posts the CloudEvent, signed with the
+//!   webhook secret, with a `WebClient` pinned to the public addresses of the url host
+//!   (re-resolved and re-checked on each attempt, no redirects). A failed attempt fails the job (retried with backoff),
+//!   and the last failed attempt counts toward the webhook auto-disable.
+//!
+//! Request headers (for the receiver):
+//! - `webhook-id`: the CloudEvent id (same on retries, for dedup).
+//! - `webhook-timestamp`: unix seconds of the attempt.
+//! - `webhook-signature`: `v1=<sign_b64u>` (see `lib_auth::sign::validate_payload_sign`).
+
+use crate::error::Result;
+use crate::utils::web_client::WebClient;
+use async_trait::async_trait;
+use lib_auth::sign::sign_payload_into_b64u;
+use lib_core::ctx::Ctx;
+use lib_core::job_runtime::{JobHandler, JobResult, JobRuntime};
+use lib_core::model::conv::ConvBmc;
+use lib_core::model::job::{Job, JobBmc, JobForCreate};
+use lib_core::model::user::{User, UserBmc};
+use lib_core::model::webhook::{
+	resolve_public_url, Webhook, WebhookBmc, WebhookDelivery, WebhookScope,
+};
+use lib_core::model::{self, ModelManager};
+use lib_events::{CloudEvent, EventConsumer, EventHandler, HandlerResult, Subscription};
+use lib_utils::time::now_utc;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+pub const WEBHOOK_QUEUE: &str = "webhook";
+pub const WEBHOOK_DELIVERY_JOB: &str = "webhook_delivery";
+
+/// Note: Shared by all the service instances (each event is dispatched once).
+const WEBHOOK_SUBSCRIPTION: &str = "webhooks";
+/// The event types sent to the webhooks (`conv.` and `conv_msg.`).
+const WEBHOOK_EVENT_TYPES: &str = "conv";
+
+const WEBHOOK_QUEUE_CONCURRENCY: usize = 8;
+const WEBHOOK_MAX_ATTEMPTS: i32 = 8;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const HEADER_ID: &str = "webhook-id";
+const HEADER_TIMESTAMP: &str = "webhook-timestamp";
+const HEADER_SIGNATURE: &str = "webhook-signature";
+
+/// Start the webhook event consumer and the delivery job runtime of this service instance.
+pub async fn start_webhooks(mm: ModelManager, service_name: &str) -> Result<()> {
+	let subscription =
+		Subscription::new(WEBHOOK_SUBSCRIPTION).with_type(WEBHOOK_EVENT_TYPES);
+	EventConsumer::new(
+		mm.event_bus().clone(),
+		subscription,
+		WebhookFanout { mm: mm.clone() },
+	)
+	.start()
+	.await?;
+
+	let worker_id = format!("{service_name}-{}", Uuid::new_v4());
+	JobRuntime::new(mm, worker_id)
+		.with_queue(WEBHOOK_QUEUE, WEBHOOK_QUEUE_CONCURRENCY)
+		.with_handler(WEBHOOK_DELIVERY_JOB, WebhookDeliveryHandler)
+		.start();
+
+	Ok(())
+}
+
+// region:    --- WebhookFanout
+
+struct WebhookFanout {
+	mm: ModelManager,
+}
+
+#[async_trait]
+impl EventHandler for WebhookFanout {
+	async fn handle(&self, event: &CloudEvent) -> HandlerResult {
+		// The conv events have the conv id as subject.
+		let Some(conv_id) = event.subject.as_deref().and_then(|s| s.parse::<i64>().ok())
+		else {
+			return Ok(());
+		};
+
+		let ctx = Ctx::root_ctx();
+		let conv = match ConvBmc::get(&ctx, &self.mm, conv_id).await {
+			Ok(conv) => conv,
+			// Note: Conv deleted since, nothing to send.
+			Err(model::Error::EntityNotFound { .. }) => return Ok(()),
+			Err(ex) => return Err(ex.into()),
+		};
+
+		// -- The webhooks of the conv owner, and of its org
+		let owner: User = UserBmc::get(&ctx, &self.mm, conv.owner_id).await?;
+		let mut scopes = vec![(WebhookScope::User, owner.id)];
+		if let Some(org_id) = owner.org_id {
+			scopes.push((WebhookScope::Org, org_id));
+		}
+		let webhooks =
+			WebhookBmc::list_for_event(&ctx, &self.mm, &scopes, &event.typ).await?;
+
+		for webhook in webhooks {
+			// Start the transaction (the delivery log entry with its job)
+			let mm = self.mm.new_with_txn()?;
+
+			mm.dbx().begin_txn().await?;
+
+			let delivery_id = WebhookBmc::add_delivery(&ctx, &mm, webhook.id, event).await?;
+			let job_c = JobForCreate {
+				queue: Some(WEBHOOK_QUEUE.to_string()),
+				kind: WEBHOOK_DELIVERY_JOB.to_string(),
+				payload: Some(json!({ "delivery_id": delivery_id })),
+				max_attempts: Some(WEBHOOK_MAX_ATTEMPTS),
+				..Default::default()
+			};
+			JobBmc::enqueue(&ctx, &mm, job_c).await?;
+
+			// Commit the transaction
+			mm.dbx().commit_txn().await?;
+
+			debug!(
+				"{:<12} - {} delivery {delivery_id} to webhook {}",
+				"WEBHOOK", event.typ, webhook.id
+			);
+		}
+
+		Ok(())
+	}
+}
+
+// endregion: --- WebhookFanout
+
+// region:    --- WebhookDeliveryHandler
+
+#[derive(Deserialize)]
+struct WebhookJobPayload {
+	delivery_id: i64,
+}
+
+struct WebhookDeliveryHandler;
+
+#[async_trait]
+impl JobHandler for WebhookDeliveryHandler {
+	async fn run(&self, ctx: &Ctx, mm: &ModelManager, job: &Job) -> JobResult {
+		let WebhookJobPayload { delivery_id } = job.payload_as()?;
+		let delivery = WebhookBmc::get_delivery(ctx, mm, delivery_id).await?;
+		let webhook = WebhookBmc::get(ctx, mm, delivery.webhook_id).await?;
+		let last_attempt = job.attempts >= job.max_attempts;
+
+		// Note: Disabled since the delivery was enqueued, not sent.
+		if !webhook.enabled {
+			let error = "webhook disabled";
+			WebhookBmc::delivery_failed(ctx, mm, &delivery, None, error, true).await?;
+			return Ok(());
+		}
+
+		match self.send(&webhook, &delivery).await {
+			Ok(status) => {
+				WebhookBmc::delivery_succeeded(ctx, mm, &delivery, status).await?;
+				Ok(())
+			}
+			Err((status, error)) => {
+				warn!(
+					"{:<12} - delivery {delivery_id} to webhook {} failed: {error}",
+					"WEBHOOK", webhook.id
+				);
+				WebhookBmc::delivery_failed(ctx, mm, &delivery, status, &error, last_attempt)
+					.await?;
+				Err(error.into())
+			}
+		}
+	}
+}
+
+impl WebhookDeliveryHandler {
+	/// Returns the response status, or the (eventual status, error) of the failed attempt.
+	async fn send(
+		&self,
+		webhook: &Webhook,
+		delivery: &WebhookDelivery,
+	) -> core::result::Result<i32, (Option<i32>, String)> {
+		let timestamp = now_utc().unix_timestamp();
+		// Note: Same serialization as the request json body.
+		let body = serde_json::to_string(&delivery.payload)
+			.map_err(|ex| (None, ex.to_string()))?;
+		let sign_b64u = sign_payload_into_b64u(webhook.secret.as_bytes(), timestamp, &body)
+			.map_err(|ex| (None, ex.to_string()))?;
+
+		let headers = [
+			(HEADER_ID.to_string(), delivery.event_id.clone()),
+			(HEADER_TIMESTAMP.to_string(), timestamp.to_string()),
+			(HEADER_SIGNATURE.to_string(), format!("v1={sign_b64u}")),
+		];
+		let web_client = pinned_web_client(&webhook.url).await.map_err(|ex| (None, ex))?;
+		let res = web_client
+			.new_req_builder(&webhook.url, &headers, delivery.payload.clone())
+			.map_err(|ex| (None, ex.to_string()))?
+			.send()
+			.await
+			.map_err(|ex| (None, ex.to_string()))?;
+
+		let status = res.status();
+		if status.is_success() {
+			Ok(status.as_u16() as i32)
+		} else {
+			Err((Some(status.as_u16() as i32), format!("status {status}")))
+		}
+	}
+}
+
+/// Returns the `WebClient` connecting to the (re-checked) public addresses of the url host,
+/// and not following the redirects (which could target an internal address).
+///
+/// Note: Also used by the outbox `WebhookSink`.
+pub(crate) async fn pinned_web_client(url: &str) -> core::result::Result<WebClient, String> {
+	let (host, addrs) = resolve_public_url(url).await.map_err(|ex| ex.to_string())?;
+	let reqwest_client = reqwest::Client::builder()
+		.timeout(WEBHOOK_TIMEOUT)
+		.redirect(reqwest::redirect::Policy::none())
+		.resolve_to_addrs(&host, &addrs)
+		.build()
+		.map_err(|ex| ex.to_string())?;
+
+	Ok(WebClient::from_reqwest_client(reqwest_client))
+}
+
+// endregion: --- WebhookDeliveryHandler
@@ -0,0 +1,55 @@
+use lib_utils::envs::{get_env, get_env_parse};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+pub fn worker_config() -> &'static WorkerConfig {
+    static INSTANCE: OnceLock<WorkerConfig> = OnceLock::new();
+
+    INSTANCE.get_or_init(|| {
+        WorkerConfig::load_from_env().unwrap_or_else(|ex| {
+            panic!("FATAL - WHILE LOADING CONF - Cause: {ex:?}")
+        })
+    })
+}
+
+#[allow(non_snake_case)]
+pub struct WorkerConfig {
+    // -- Web
+    /// The address to listen on (e.g., `0.0.0.0:8081`, port `0` for any free port).
+    pub LISTEN_ADDR: String,
+
+    // -- Knowledge Base
+    /// `hash` (offline) or `openai:<model>` (e.g., `openai:text-embedding-3-small`)
+    pub EMBEDDER: String,
+
+    // -- Route Timeouts
+    /// Max duration of an rpc request (the gateway deadline is honored when earlier).
+    pub RPC_TIMEOUT: Duration,
+
+    // -- Service Registry
+    /// The gateway to register with (e.g., `http://localhost:8080`).
+    pub GATEWAY_URL: String,
+    /// The host of this instance, as called by the gateway.
+    pub ADVERTISED_HOST: String,
+    pub REGISTRY_KEY: String,
+}
+
+impl WorkerConfig {
+    fn load_from_env() -> lib_utils::envs::Result<WorkerConfig> {
+        Ok(WorkerConfig {
+            // -- Web
+            LISTEN_ADDR: get_env("SERVICE_WORKER_LISTEN_ADDR")?,
+
+            // -- Knowledge Base
+            EMBEDDER: get_env("SERVICE_EMBEDDER")?,
+
+            // -- Route Timeouts
+            RPC_TIMEOUT: Duration::from_secs(get_env_parse("SERVICE_RPC_TIMEOUT_SEC")?),
+
+            // -- Service Registry
+            GATEWAY_URL: get_env("SERVICE_GATEWAY_URL")?,
+            ADVERTISED_HOST: get_env("SERVICE_ADVERTISED_HOST")?,
+            REGISTRY_KEY: get_env("SERVICE_REGISTRY_KEY")?,
+        })
+    }
+}
@@ -1,12 +1,12 @@
 use derive_more::From;
 use lib_core::model;
-use rpc_router::RpcHandlerError;
+use rpc_router::{IntoRpcHandlerError, RpcHandlerError};
 use serde::Serialize;
 use serde_with::serde_as;
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[serde_as]
-#[derive(Debug, From, Serialize, RpcHandlerError)]
+#[derive(Debug, From, Serialize)]
 pub enum Error {
 	// -- Modules
 	#[from]
@@ -18,9 +18,44 @@ pub enum Error {
 		genai::Error
 	),
 
-	RpcError
+	#[from]
+	Web(lib_web::Error),
+
+	#[from]
+	Events(lib_events::Error),
+
+	RpcError,
+
+	// -- Chat
+	ConvChatNoMsg { conv_id: i64 },
+
+	// -- Tools
+	ToolHandlerUnknown { name: String },
+	ToolRpc { method: String, detail: String },
+	ToolLoopMaxIterations { max: usize },
+
+	// -- Knowledge Base
+	EmbedderConfigInvalid { embedder: String },
+	EmbedderFail(String),
+}
+
+// region:    --- RpcHandlerError
+
+/// The model errors are passed as `lib_rpc_core::Error`, so that `lib_web::Error`
+/// can unpack them into their typed `ClientError` (e.g., `QUOTA_EXCEEDED`).
+impl IntoRpcHandlerError for Error {
+	fn into_handler_error(self) -> RpcHandlerError {
+		match self {
+			Error::Model(model_error) => {
+				RpcHandlerError::new(lib_rpc_core::Error::Model(model_error))
+			}
+			other => RpcHandlerError::new(other),
+		}
+	}
 }
 
+// endregion: --- RpcHandlerError
+
 // region:    --- Error Boilerplate
 impl core::fmt::Display for Error {
 	fn fmt(
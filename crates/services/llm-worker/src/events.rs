@@ -0,0 +1,60 @@
+//! Event consumers of the llm-worker (see `lib_events`).
+//!
+//! The llm-worker reacts to the model events published on the event bus,
+//! rather than being dispatched to by the gateway.
+
+use crate::error::Result;
+use crate::jobs::enqueue_auto_responses;
+use async_trait::async_trait;
+use lib_core::ctx::Ctx;
+use lib_core::model::conv_event::CONV_MSG_CREATED;
+use lib_core::model::conv_msg::MsgRole;
+use lib_core::model::ModelManager;
+use lib_events::{CloudEvent, EventConsumer, EventHandler, HandlerResult, Subscription};
+use serde::Deserialize;
+
+/// Note: Shared by all the llm-worker instances (each event is handled once).
+const AUTO_RESPOND_SUBSCRIPTION: &str = "llm-worker.auto_respond";
+
+pub async fn start_event_consumers(mm: ModelManager) -> Result<()> {
+    let subscription = Subscription::new(AUTO_RESPOND_SUBSCRIPTION).with_type(CONV_MSG_CREATED);
+    EventConsumer::new(mm.event_bus().clone(), subscription, AutoRespondTrigger { mm })
+        .start()
+        .await?;
+
+    Ok(())
+}
+
+// region:    --- AutoRespondTrigger
+
+/// The `conv_msg.created` event data (subset of `ConvMsg`).
+#[derive(Deserialize)]
+struct MsgCreated {
+    id: i64,
+    conv_id: i64,
+    user_id: i64,
+    role: MsgRole,
+}
+
+/// On a human message, enqueue the replies of the conv auto-respond participants.
+struct AutoRespondTrigger {
+    mm: ModelManager,
+}
+
+#[async_trait]
+impl EventHandler for AutoRespondTrigger {
+    async fn handle(&self, event: &CloudEvent) -> HandlerResult {
+        let msg: MsgCreated = event.data_as()?;
+        // Only human messages trigger replies (e.g., no replies to replies).
+        if msg.role != MsgRole::User {
+            return Ok(());
+        }
+
+        let ctx = event.ctx_as::<Ctx>()?.unwrap_or_else(Ctx::root_ctx);
+        enqueue_auto_responses(&ctx, &self.mm, msg.conv_id, msg.id, msg.user_id).await?;
+
+        Ok(())
+    }
+}
+
+// endregion: --- AutoRespondTrigger
@@ -0,0 +1,137 @@
+//! Background jobs of the llm-worker (see `lib_core::job_runtime`).
+//!
+//! The jobs are run with the worker rpc router, so that a job is the same
+//! as the corresponding rpc call (e.g., `auto_respond` is a `conv_chat`).
+
+use crate::config::worker_config;
+use async_trait::async_trait;
+use lib_core::ctx::Ctx;
+use lib_core::job_runtime::{JobHandler, JobResult, JobRuntime};
+use lib_core::model::conv::ConvBmc;
+use lib_core::model::job::{Job, JobBmc, JobForCreate};
+use lib_core::model::ModelManager;
+use rpc_router::resources_builder;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// The queue of the llm-worker jobs.
+pub const LLM_QUEUE: &str = "llm";
+const LLM_QUEUE_CONCURRENCY: usize = 4;
+
+/// Job kind of the auto-respond replies (see `enqueue_auto_responses`).
+pub const AUTO_RESPOND_JOB: &str = "auto_respond";
+
+pub fn job_runtime(mm: ModelManager, rpc_router: rpc_router::Router) -> JobRuntime {
+    let worker_id = format!("llm-worker-{}", Uuid::new_v4());
+
+    JobRuntime::new(mm, worker_id)
+        .with_queue(LLM_QUEUE, LLM_QUEUE_CONCURRENCY)
+        // Note: A job is an rpc call, with the same timeout.
+        .with_job_timeout(worker_config().RPC_TIMEOUT)
+        .with_handler(AUTO_RESPOND_JOB, AutoRespondHandler { rpc_router })
+}
+
+// region:    --- AutoRespondHandler
+
+/// Enqueue the replies of the auto-respond participants (machine users) of the conv
+/// to the (human) message `msg_id` of `author_id`.
+pub async fn enqueue_auto_responses(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    conv_id: i64,
+    msg_id: i64,
+    author_id: i64,
+) -> lib_core::model::Result<()> {
+    let responders = ConvBmc::list_auto_responders(ctx, mm, conv_id).await?;
+    // No replies to an auto-respond participant.
+    if responders.iter().any(|responder| responder.user_id == author_id) {
+        return Ok(());
+    }
+
+    for responder in responders {
+        let job_c = JobForCreate {
+            queue: Some(LLM_QUEUE.to_string()),
+            kind: AUTO_RESPOND_JOB.to_string(),
+            payload: Some(json!({
+                "conv_id": conv_id,
+                "msg_id": msg_id,
+                "user_id": responder.user_id,
+            })),
+            ..Default::default()
+        };
+        JobBmc::enqueue(ctx, mm, job_c).await?;
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct AutoRespond {
+    conv_id: i64,
+    msg_id: i64,
+    /// The auto-respond (machine) user replying.
+    user_id: i64,
+}
+
+/// Reply to a conv message as an auto-respond participant (i.e., `conv_chat` with its ctx).
+///
+/// Note: The job runs as the message author (the job creator), and only switches to the
+///       responder ctx once the payload is verified against the conv (the responder is an
+///       auto-respond participant, and the message is of the author in this conv).
+struct AutoRespondHandler {
+    rpc_router: rpc_router::Router,
+}
+
+#[async_trait]
+impl JobHandler for AutoRespondHandler {
+    async fn run(&self, ctx: &Ctx, mm: &ModelManager, job: &Job) -> JobResult {
+        let AutoRespond { conv_id, msg_id, user_id } = job.payload_as()?;
+
+        // -- Verify the payload
+        let responders = ConvBmc::list_auto_responders(ctx, mm, conv_id).await?;
+        if !responders.iter().any(|responder| responder.user_id == user_id) {
+            return Err(format!("user {user_id} not an auto-responder of conv {conv_id}").into());
+        }
+        let msg = ConvBmc::get_msg(ctx, mm, msg_id).await?;
+        if msg.conv_id != conv_id || msg.user_id != ctx.user_id() {
+            return Err(format!("msg {msg_id} not of user {} in conv {conv_id}", ctx.user_id()).into());
+        }
+
+        // Note: The responder ctx keeps the job deadline.
+        let mut responder_ctx = Ctx::new(user_id)?.add_conv_id(conv_id);
+        if let Some(deadline) = ctx.deadline() {
+            responder_ctx = responder_ctx.with_deadline(deadline);
+        }
+
+        let params = json!({ "data": { "conv_id": conv_id, "msg_id": msg_id } });
+        call_rpc(&self.rpc_router, responder_ctx, job, "conv_chat", params).await
+    }
+}
+
+// endregion: --- AutoRespondHandler
+
+async fn call_rpc(
+    rpc_router: &rpc_router::Router,
+    ctx: Ctx,
+    job: &Job,
+    method: &str,
+    params: Value,
+) -> JobResult {
+    let rpc_req = rpc_router::Request::try_from(json!({
+        "jsonrpc": "2.0",
+        "id": format!("job-{}", job.id),
+        "method": method,
+        "params": params,
+    }))
+    .map_err(|ex| format!("rpc '{method}' request invalid: {ex}"))?;
+
+    // Note: The ctx is overlayed on the router resources, as in the rpc handlers.
+    let additional_resources = resources_builder![ctx].build();
+    rpc_router
+        .call_with_resources(rpc_req, additional_resources)
+        .await
+        .map_err(|ex| format!("rpc '{method}' failed: {:?}", ex.error))?;
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use lib_web::utils::web_client::WebClient;
+use serde_json::{json, Value};
+
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// The embedder model name, stored with the chunks.
+    /// (only the embeddings of the same model are comparable)
+    fn model(&self) -> &str;
+
+    /// Returns one embedding per text, in order.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+// region:    --- HashEmbedder
+
+/// Deterministic, offline, embedder (feature hashing of the lowercase words).
+///
+/// Only lexical similarity, but good enough for development and tests,
+/// and does not need any provider key.
+pub struct HashEmbedder {
+    model: String,
+    dim: usize,
+}
+
+impl Default for HashEmbedder {
+    fn default() -> Self {
+        HashEmbedder::new(256)
+    }
+}
+
+impl HashEmbedder {
+    pub fn new(dim: usize) -> Self {
+        HashEmbedder {
+            model: format!("hash-{dim}"),
+            dim,
+        }
+    }
+
+    fn embed_one(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0f32; self.dim];
+
+        let words = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_lowercase());
+        for word in words {
+            let hash = fnv1a(word.as_bytes());
+            let idx = (hash % self.dim as u64) as usize;
+            // The hash high bit gives the sign, to limit the collision bias.
+            let sign = if hash >> 63 == 0 { 1. } else { -1. };
+            vector[idx] += sign;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0. {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+
+        vector
+    }
+}
+
+#[async_trait]
+impl Embedder for HashEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed_one(text)).collect())
+    }
+}
+
+/// FNV-1a 64 bits (stable across runs and platforms, unlike the std hasher).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// endregion: --- HashEmbedder
+
+// region:    --- OpenAiEmbedder
+
+const OPENAI_EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+const OPENAI_API_KEY_ENV: &str = "OPENAI_API_KEY";
+
+/// OpenAI embeddings, with the same api key env as genai (`OPENAI_API_KEY`).
+///
+/// Note: genai 0.1 does not have an embeddings api, so this calls
+///       the embeddings endpoint directly.
+pub struct OpenAiEmbedder {
+    model: String,
+}
+
+impl OpenAiEmbedder {
+    pub fn new(model: impl Into<String>) -> Self {
+        OpenAiEmbedder { model: model.into() }
+    }
+}
+
+#[async_trait]
+impl Embedder for OpenAiEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let api_key = std::env::var(OPENAI_API_KEY_ENV)
+            .map_err(|_| Error::EmbedderFail(format!("env {OPENAI_API_KEY_ENV} missing")))?;
+        let headers = vec![("Authorization".to_string(), format!("Bearer {api_key}"))];
+        let payload = json!({
+            "model": self.model,
+            "input": texts,
+        });
+
+        let web_res = WebClient::default()
+            .do_post(OPENAI_EMBEDDINGS_URL, &headers, payload)
+            .await?;
+
+        let data = web_res
+            .body
+            .get("data")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::EmbedderFail("response has no data".to_string()))?;
+
+        data.iter()
+            .map(|item| {
+                item.get("embedding")
+                    .and_then(Value::as_array)
+                    .map(|vals| {
+                        vals.iter()
+                            .filter_map(Value::as_f64)
+                            .map(|v| v as f32)
+                            .collect()
+                    })
+                    .ok_or_else(|| Error::EmbedderFail("data item has no embedding".to_string()))
+            })
+            .collect()
+    }
+}
+
+// endregion: --- OpenAiEmbedder
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>; // For tests.
+
+    use super::*;
+
+    fn dot(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[tokio::test]
+    async fn test_hash_embedder_embed_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let embedder = HashEmbedder::new(64);
+        let fx_texts = [
+            "The cat sat on the mat",
+            "the CAT, sat on the mat!",
+            "Quantum chromodynamics lecture",
+            "",
+        ]
+        .map(String::from);
+
+        // -- Exec
+        let embeddings = embedder.embed(&fx_texts).await?;
+
+        // -- Check
+        assert_eq!(embedder.model(), "hash-64");
+        assert_eq!(embeddings.len(), 4);
+        assert!(embeddings.iter().all(|embedding| embedding.len() == 64));
+        // Unit norm (when any word).
+        assert!((dot(&embeddings[0], &embeddings[0]) - 1.).abs() < 1e-5);
+        // Case and punctuation insensitive.
+        assert_eq!(embeddings[0], embeddings[1]);
+        // Lexically closer texts score higher.
+        assert!(dot(&embeddings[0], &embeddings[1]) > dot(&embeddings[0], &embeddings[2]));
+        // No words, zero vector.
+        assert!(embeddings[3].iter().all(|v| *v == 0.));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hash_embedder_deterministic_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_texts = ["stable across runs".to_string()];
+
+        // -- Exec
+        let embedding_01 = HashEmbedder::default().embed(&fx_texts).await?;
+        let embedding_02 = HashEmbedder::default().embed(&fx_texts).await?;
+
+        // -- Check
+        assert_eq!(embedding_01, embedding_02);
+        // FNV-1a 64 reference values.
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+
+        Ok(())
+    }
+}
+
+// endregion: --- Tests
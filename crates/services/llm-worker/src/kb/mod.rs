@@ -0,0 +1,41 @@
+//! Knowledge base support (embedders and text splitting).
+//!
+//! The documents and chunks are in the model layer
+//! (see `lib_core::model::document` and `lib_core::model::chunk`).
+
+// region:    --- Modules
+
+mod embed;
+mod split;
+
+pub use embed::{Embedder, HashEmbedder, OpenAiEmbedder};
+pub use split::split_text;
+
+use crate::config::worker_config;
+use crate::error::{Error, Result};
+use std::sync::Arc;
+
+// endregion: --- Modules
+
+/// The worker embedder, as an rpc resource.
+#[derive(Clone, rpc_router::RpcResource)]
+pub struct KbEmbedder(pub Arc<dyn Embedder>);
+
+impl KbEmbedder {
+    /// Build the embedder from the `SERVICE_EMBEDDER` config.
+    pub fn from_config() -> Result<Self> {
+        let embedder_cfg = &worker_config().EMBEDDER;
+
+        let embedder: Arc<dyn Embedder> = match embedder_cfg.split_once(':') {
+            None if embedder_cfg == "hash" => Arc::new(HashEmbedder::default()),
+            Some(("openai", model)) => Arc::new(OpenAiEmbedder::new(model)),
+            _ => {
+                return Err(Error::EmbedderConfigInvalid {
+                    embedder: embedder_cfg.to_string(),
+                })
+            }
+        };
+
+        Ok(KbEmbedder(embedder))
+    }
+}
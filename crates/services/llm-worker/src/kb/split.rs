@@ -0,0 +1,110 @@
+/// Split a text into chunks of at most `max_chars` chars.
+///
+/// Paragraphs (blank line separated) are packed together up to `max_chars`,
+/// and the paragraphs longer than `max_chars` are split on whitespaces.
+pub fn split_text(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for para in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        for piece in split_long(para, max_chars) {
+            let sep_len = if current.is_empty() { 0 } else { 2 };
+            if current.chars().count() + sep_len + piece.chars().count() > max_chars
+                && !current.is_empty()
+            {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&piece);
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// Split a paragraph on whitespaces, in pieces of at most `max_chars`
+/// (unless a single word is longer).
+fn split_long(para: &str, max_chars: usize) -> Vec<String> {
+    if para.chars().count() <= max_chars {
+        return vec![para.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for word in para.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+            pieces.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+
+    pieces
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>; // For tests.
+
+    use super::*;
+
+    #[test]
+    fn test_split_text_pack_paragraphs_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_text = "para one\n\npara two\n\n\n\n  para three  \n\nlast";
+
+        // -- Exec
+        let chunks = split_text(fx_text, 20);
+
+        // -- Check
+        assert_eq!(chunks, ["para one\n\npara two", "para three\n\nlast"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_text_long_paragraph_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_text = "aaaa bbbb cccc dddd\n\nééé";
+
+        // -- Exec
+        let chunks = split_text(fx_text, 9);
+
+        // -- Check
+        assert_eq!(chunks, ["aaaa bbbb", "cccc dddd", "ééé"]);
+        // Max chars, not bytes.
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 9));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_text_edge_cases_ok() -> Result<()> {
+        // -- Exec & Check
+        assert!(split_text("", 10).is_empty());
+        assert!(split_text("\n\n  \n\n", 10).is_empty());
+        // A word longer than max chars is kept whole.
+        assert_eq!(split_text("abcdefghij kl", 4), ["abcdefghij", "kl"]);
+        // Zero max chars is one.
+        assert_eq!(split_text("a b", 0), ["a", "b"]);
+
+        Ok(())
+    }
+}
+
+// endregion: --- Tests
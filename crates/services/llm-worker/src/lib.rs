@@ -0,0 +1,16 @@
+//! The llm-worker modules, for its binary (`main.rs`) and for `gen-ts` (see `web::routes_rpc::rpc_doc`).
+
+// region:    --- Modules
+
+pub mod config;
+mod error;
+pub mod events;
+pub mod jobs;
+mod kb;
+mod rpc;
+mod tools;
+pub mod web;
+
+pub use self::error::{Error, Result};
+
+// endregion: --- Modules
@@ -1,55 +1,114 @@
 // region:    --- Modules
 
-mod error;
-mod web;
-mod rpc;
-
-pub use self::error::{Error, Result};
+use llm_worker::config::worker_config;
+use llm_worker::{events, jobs, web};
+use llm_worker::Result;
 
+use lib_web::health::{DbCheck, EnvCheck, HealthChecks};
+use lib_web::log;
 use lib_web::middleware::mw_auth::{mw_ctx_require, mw_ctx_leaf_resolver};
+use lib_web::middleware::mw_metrics::MetricsLayer;
 use lib_web::middleware::mw_req_stamp::mw_req_stamp_resolver;
 use lib_web::middleware::mw_res_map::mw_reponse_map;
+use lib_web::middleware::mw_timeout::mw_req_timeout;
+use lib_web::middleware::mw_trace::mw_trace;
+use lib_web::routes::{routes_health, routes_metrics};
+use lib_web::telemetry;
+use lib_web::utils::service_registration::start_registration;
+use lib_web::utils::service_registry::ServiceRegistration;
+use lib_web::utils::shutdown::shutdown_signal;
 
 use axum::{middleware, Router};
 use lib_core::model::ModelManager;
+use lib_rpc_core::openrpc::RPC_DISCOVER;
 use tokio::net::TcpListener;
 use tower_cookies::CookieManagerLayer;
 use tracing::info;
-use tracing_subscriber::EnvFilter;
 
 // endregion: --- Modules
 
+/// The api keys of the genai providers (none needed for Ollama).
+const LLM_PROVIDER_KEY_ENVS: &[&str] = &[
+	"OPENAI_API_KEY",
+	"ANTHROPIC_API_KEY",
+	"GEMINI_API_KEY",
+	"GROQ_API_KEY",
+	"COHERE_API_KEY",
+];
+
 #[tokio::main]
 async fn main() -> Result<()> {
-	tracing_subscriber::fmt()
-		.without_time() // For early local development.
-		.with_target(false)
-		.with_env_filter(
-			EnvFilter::try_from_default_env()
-			.unwrap_or_else(|_| EnvFilter::new("debug")))
-		.init();
+	// -- Tracing (log lines, and spans per the `SERVICE_TRACE_EXPORTER` env)
+	let _telemetry = telemetry::init_tracing(web::routes_rpc::SERVICE_NAME, "debug")?;
+
+	// -- Request Log Sink (per the `SERVICE_REQUEST_LOG_SINK` env)
+	let log_sink = log::init_log_sink(web::routes_rpc::SERVICE_NAME)?;
 
 	let mm = ModelManager::new().await?;
 
+	let rpc_router = web::routes_rpc::rpc_router(mm.clone())?;
+
+	// -- Start the Job Runtime and Event Consumers
+	jobs::job_runtime(mm.clone(), rpc_router.clone()).start();
+	events::start_event_consumers(mm.clone()).await?;
+
 	// -- Define Routes
-	let routes_rpc = web::routes_rpc::routes(mm.clone())
+	// Note: The gateway ctx deadline is honored (when earlier than the route timeout).
+	let routes_rpc = web::routes_rpc::routes(rpc_router)
+		.route_layer(middleware::from_fn_with_state(worker_config().RPC_TIMEOUT, mw_req_timeout))
 		.route_layer(middleware::from_fn(mw_ctx_require));
 
+	// -- Health Checks (the LLM provider keys are not critical, e.g., with Ollama)
+	let mut health_checks = HealthChecks::new()
+		.with(DbCheck::new(mm.clone()))
+		.with(EnvCheck::new("llm-provider", LLM_PROVIDER_KEY_ENVS).critical(false));
+	if worker_config().EMBEDDER.starts_with("openai:") {
+		health_checks = health_checks.with(EnvCheck::new("embedder", &["OPENAI_API_KEY"]));
+	}
+
 	let routes_all = Router::new()		
 		.nest("/api", routes_rpc)
+		.layer(MetricsLayer)
 		.layer(middleware::map_response(mw_reponse_map))
 		.layer(middleware::from_fn(mw_ctx_leaf_resolver))
 		.layer(CookieManagerLayer::new())
-		.layer(middleware::from_fn(mw_req_stamp_resolver));
+		.layer(middleware::from_fn(mw_req_stamp_resolver))
+		.layer(middleware::from_fn(mw_trace))
+		// Note: After the layers (no ctx, no request log line, no request metrics, no trace).
+		.merge(routes_health::routes(health_checks))
+		.merge(routes_metrics::routes(mm.clone()));
 
 	// region:    --- Start Server
 	// Note: For this block, ok to unwrap.
-	let listener = TcpListener::bind("127.0.0.1:8081").await.unwrap();
+	let listener = TcpListener::bind(&worker_config().LISTEN_ADDR).await.unwrap();
 	info!("{:<12} - {:?}\n", "LISTENING", listener.local_addr());
+
+	// -- Register with the gateway (and heartbeat)
+	let rpc_doc = web::routes_rpc::rpc_doc();
+	let mut methods: Vec<String> = rpc_doc.methods.into_iter().map(|m| m.name).collect();
+	methods.push(RPC_DISCOVER.to_string());
+	start_registration(
+		&worker_config().GATEWAY_URL,
+		&worker_config().REGISTRY_KEY,
+		ServiceRegistration {
+			name: web::routes_rpc::SERVICE_NAME.to_string(),
+			host: worker_config().ADVERTISED_HOST.clone(),
+			port: listener.local_addr().unwrap().port().into(),
+			version: rpc_doc.info.version,
+			methods,
+		},
+	);
+
 	axum::serve(listener, routes_all.into_make_service())
+		.with_graceful_shutdown(shutdown_signal())
 		.await
 		.unwrap();
 	// endregion: --- Start Server
 
+	// -- Write the pending request log lines
+	if let Some(log_sink) = log_sink {
+		log_sink.shutdown().await;
+	}
+
 	Ok(())
 }
@@ -0,0 +1,293 @@
+//! Conv memory: auto title and rolling summary (see the `Agent` conv settings).
+
+use crate::error::Result;
+use crate::rpc::genai_chat_rpc::{exec_chat_metered, record_usage};
+use genai::chat::{ChatMessage, ChatRequest};
+use genai::Client;
+use lib_core::ctx::Ctx;
+use lib_core::model::conv::{ConvBmc, ConvForUpdate};
+use lib_core::model::conv_msg::ConvMsg;
+use lib_core::model::conv_summary::ConvSummary;
+use lib_core::model::quota::QuotaBmc;
+use lib_core::model::ModelManager;
+
+const TITLE_MAX_CHARS: usize = 80;
+
+/// The provider/model (and agent) of the memory LLM calls.
+pub struct LlmTarget<'a> {
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub agent_id: i64,
+}
+
+/// Rough token estimate (~4 chars per token), good enough for a budget.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+// region:    --- Rolling Summary
+
+/// Returns the history to send to the model, as the (eventual) summary
+/// and the recent messages of the thread.
+///
+/// When the thread exceeds the `token_budget`, the older messages are summarized
+/// (with the previous summary) and the branch summary is updated. The recent messages
+/// kept are within half of the budget (at least the leaf message).
+pub async fn compact_thread(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    client: &Client,
+    target: &LlmTarget<'_>,
+    conv_id: i64,
+    token_budget: Option<i32>,
+    thread: Vec<ConvMsg>,
+) -> Result<(Option<String>, Vec<ConvMsg>)> {
+    let Some(token_budget) = token_budget.map(|b| b.max(0) as usize) else {
+        return Ok((None, thread));
+    };
+
+    // -- Start from the summary of this branch, if any
+    let conv_summary = ConvBmc::get_thread_summary(ctx, mm, conv_id, &thread).await?;
+    let (summary, mut msgs) = split_summarized(conv_summary, thread);
+
+    let Some(keep) = recent_len(summary.as_deref(), &msgs, token_budget) else {
+        return Ok((summary, msgs));
+    };
+    let recent = msgs.split_off(msgs.len() - keep);
+    let older = msgs;
+    let Some(summary_msg_id) = older.last().map(|msg| msg.id) else {
+        return Ok((summary, recent));
+    };
+
+    // -- Summarize the older messages
+    let mut text = String::new();
+    if let Some(summary) = &summary {
+        text.push_str(&format!("Previous summary:\n{summary}\n\n"));
+    }
+    for msg in &older {
+        text.push_str(&format!("{}: {}\n", msg.role, msg.content));
+    }
+    let chat_req = ChatRequest::default()
+        .with_system(
+            "Summarize the following conversation, keeping the facts, decisions and open questions. \
+             Reply with the summary only.",
+        )
+        .append_message(ChatMessage::user(text));
+    let new_summary = exec_chat_text(ctx, mm, client, target, chat_req).await?;
+
+    ConvBmc::update_summary(ctx, mm, conv_id, new_summary.clone(), summary_msg_id).await?;
+
+    Ok((Some(new_summary), recent))
+}
+
+/// Returns the branch summary text and the thread messages after it
+/// (the whole thread if the summary is not in the thread).
+fn split_summarized(
+    conv_summary: Option<ConvSummary>,
+    mut thread: Vec<ConvMsg>,
+) -> (Option<String>, Vec<ConvMsg>) {
+    let summarized_pos = conv_summary
+        .as_ref()
+        .and_then(|s| thread.iter().position(|msg| msg.id == s.msg_id));
+    match (conv_summary, summarized_pos) {
+        (Some(conv_summary), Some(pos)) => (Some(conv_summary.summary), thread.split_off(pos + 1)),
+        _ => (None, thread),
+    }
+}
+
+/// Returns the number of recent messages to keep when the summary and the messages
+/// exceed the `token_budget`, or None when there is nothing to summarize.
+fn recent_len(summary: Option<&str>, msgs: &[ConvMsg], token_budget: usize) -> Option<usize> {
+    let tokens = summary.map(estimate_tokens).unwrap_or_default()
+        + msgs.iter().map(|msg| estimate_tokens(&msg.content)).sum::<usize>();
+    if tokens <= token_budget || msgs.len() < 2 {
+        return None;
+    }
+
+    let mut keep = 0;
+    let mut recent_tokens = 0;
+    for msg in msgs.iter().rev() {
+        recent_tokens += estimate_tokens(&msg.content);
+        if keep > 0 && recent_tokens > token_budget / 2 {
+            break;
+        }
+        keep += 1;
+    }
+
+    (keep < msgs.len()).then_some(keep)
+}
+
+// endregion: --- Rolling Summary
+
+// region:    --- Auto Title
+
+/// Generate the conv title from the first exchange, and update the conv.
+pub async fn auto_title(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    client: &Client,
+    target: &LlmTarget<'_>,
+    conv_id: i64,
+    user_content: &str,
+    assistant_content: &str,
+) -> Result<()> {
+    let chat_req = ChatRequest::default()
+        .with_system(
+            "Give a short title (max 6 words) for the conversation starting with the following exchange. \
+             Reply with the title only.",
+        )
+        .append_message(ChatMessage::user(format!(
+            "User: {user_content}\nAssistant: {assistant_content}"
+        )));
+    let title = exec_chat_text(ctx, mm, client, target, chat_req).await?;
+
+    let title: String = title
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .chars()
+        .take(TITLE_MAX_CHARS)
+        .collect();
+    if title.is_empty() {
+        return Ok(());
+    }
+
+    let conv_u = ConvForUpdate {
+        title: Some(title),
+        ..Default::default()
+    };
+    ConvBmc::update(ctx, mm, conv_id, conv_u).await?;
+
+    Ok(())
+}
+
+// endregion: --- Auto Title
+
+/// Exec a chat (quota checked, usage and metrics recorded), and returns the response text.
+async fn exec_chat_text(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    client: &Client,
+    target: &LlmTarget<'_>,
+    chat_req: ChatRequest,
+) -> Result<String> {
+    QuotaBmc::check(ctx, mm).await?;
+    let chat_res = exec_chat_metered(client, target.provider, target.model, chat_req).await?;
+    record_usage(ctx, mm, target.provider, target.model, Some(target.agent_id), &chat_res.usage).await?;
+
+    Ok(chat_res.content_text_as_str().unwrap_or_default().trim().to_string())
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>; // For tests.
+
+    use super::*;
+    use serde_json::json;
+
+    fn fx_msg(id: i64, content: &str) -> Result<ConvMsg> {
+        Ok(serde_json::from_value(json!({
+            "id": id,
+            "conv_id": 1000,
+            "user_id": 1000,
+            "parent_msg_id": null,
+            "role": "User",
+            "content": content,
+            "content_parts": null,
+            "metadata": null,
+            "cid": 1000,
+            "ctime": "2024-01-01T00:00:00Z",
+            "mid": 1000,
+            "mtime": "2024-01-01T00:00:00Z",
+        }))?)
+    }
+
+    fn fx_summary(msg_id: i64, summary: &str) -> Result<ConvSummary> {
+        Ok(serde_json::from_value(json!({
+            "id": 1000,
+            "conv_id": 1000,
+            "msg_id": msg_id,
+            "summary": summary,
+            "cid": 1000,
+            "ctime": "2024-01-01T00:00:00Z",
+            "mid": 1000,
+            "mtime": "2024-01-01T00:00:00Z",
+        }))?)
+    }
+
+    fn ids(msgs: &[ConvMsg]) -> Vec<i64> {
+        msgs.iter().map(|msg| msg.id).collect()
+    }
+
+    #[test]
+    fn test_estimate_tokens_ok() -> Result<()> {
+        // -- Exec & Check
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abc"), 1);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        // chars, not bytes
+        assert_eq!(estimate_tokens("ééééé"), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_summarized_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_thread = vec![fx_msg(1, "a")?, fx_msg(2, "b")?, fx_msg(3, "c")?];
+
+        // -- Exec
+        let (summary, msgs) = split_summarized(Some(fx_summary(2, "summary 02")?), fx_thread.clone());
+        let (other_summary, other_msgs) =
+            split_summarized(Some(fx_summary(9, "other branch")?), fx_thread.clone());
+        let (no_summary, all_msgs) = split_summarized(None, fx_thread);
+
+        // -- Check
+        assert_eq!(summary.as_deref(), Some("summary 02"));
+        assert_eq!(ids(&msgs), &[3]);
+        assert_eq!(other_summary, None, "summary of another branch should be ignored");
+        assert_eq!(ids(&other_msgs), &[1, 2, 3]);
+        assert_eq!(no_summary, None);
+        assert_eq!(ids(&all_msgs), &[1, 2, 3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recent_len_within_budget_none() -> Result<()> {
+        // -- Setup & Fixtures
+        // 2 tokens each
+        let fx_msgs = vec![fx_msg(1, "12345678")?, fx_msg(2, "12345678")?];
+
+        // -- Exec & Check
+        assert_eq!(recent_len(None, &fx_msgs, 4), None);
+        assert_eq!(recent_len(Some("1234"), &fx_msgs, 5), None);
+        // a single message is never summarized
+        assert_eq!(recent_len(None, &fx_msgs[..1], 0), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recent_len_over_budget_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        // 2 tokens each
+        let fx_msgs: Vec<ConvMsg> =
+            (1..=6).map(|id| fx_msg(id, "12345678")).collect::<Result<_>>()?;
+
+        // -- Exec & Check
+        // 12 tokens > 8, keep the recent messages within 4 tokens
+        assert_eq!(recent_len(None, &fx_msgs, 8), Some(2));
+        // the summary tokens count in the budget
+        assert_eq!(recent_len(Some("1234"), &fx_msgs, 12), Some(3));
+        // at least the leaf message is kept
+        assert_eq!(recent_len(None, &fx_msgs, 1), Some(1));
+
+        Ok(())
+    }
+}
+
+// endregion: --- Tests
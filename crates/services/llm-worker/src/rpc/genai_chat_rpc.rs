@@ -1,29 +1,35 @@
 use lib_rpc_core::prelude::*;
+use lib_core::model::conv_msg::MsgRole;
+use lib_core::model::quota::QuotaBmc;
+use lib_core::model::usage_event::{UsageEventBmc, UsageEventForCreate};
 use crate::error::{Result, Error};
 use crate::rpc::ParamsW;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use genai::chat::{ChatMessage, ChatRequest};
+use genai::chat::{ChatMessage, ChatRequest, ChatResponse, MetaUsage};
 use genai::Client;
+use lib_web::metrics;
 
-use tracing::debug;
+use std::time::Instant;
 
-//-- Handler message params --------------------------
-#[derive(Debug, Clone, Deserialize)]
-pub enum ChatUserMode {
-    System,
-    User,
-    Assistant
-}
+use tracing::field::Empty;
+use tracing::{debug, info_span, Instrument};
+
+/// Default provider/model when the agent does not specify a real one.
+/// (see the model list below)
+pub(crate) const DEFAULT_PROVIDER: &str = "Groq";
+pub(crate) const DEFAULT_MODEL: &str = "llama3-70b-8192";
 
-#[derive(Debug, Clone, Deserialize)]
+//-- Handler message params --------------------------
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct OneShotMsg {
-    pub mode: ChatUserMode,
+    pub mode: MsgRole,
     pub prompt: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct OneShotMsgResponse {
     pub response: String,    
 }
@@ -66,7 +72,7 @@ pub struct OneShotMsgResponse {
 //-- Handler RPC  -----------------------------------
 pub async fn one_shot_msg(
     ctx: Ctx,
-    _mm: ModelManager,
+    mm: ModelManager,
     params: ParamsW<OneShotMsg>,) 
 -> Result<DataRpcResult<OneShotMsgResponse>> {
     
@@ -74,6 +80,8 @@ pub async fn one_shot_msg(
 
     let ParamsW{data: osm} = params;
 
+    QuotaBmc::check(&ctx, &mm).await?;
+
     // Ugly for now but can cache behind a conv_id later
     let client = Client::default();
 	let mut chat_req = ChatRequest::default().with_system("Answer with one sentence");        
@@ -81,23 +89,19 @@ pub async fn one_shot_msg(
 
     // Default chose the free Groq one. Later can allow choosing of provider/model.
     //let model = "gpt-4o-mini";
-    let model = "llama3-70b-8192";
+    let model = DEFAULT_MODEL;
 
      // Add the incoming msg
-     chat_req = chat_req.append_message(
-        match osm.mode {
-            ChatUserMode::System => ChatMessage::system(osm.prompt),
-            ChatUserMode::User => ChatMessage::user(osm.prompt),
-            ChatUserMode::Assistant => ChatMessage::assistant(osm.prompt),
-        }
-    );
+     chat_req = chat_req.append_message(to_chat_message(&osm.mode, osm.prompt));
     
     // see https://github.com/jeremychone/rust-genai/blob/HEAD/examples/c00-readme.rs
     // for examples
-    let chat_response = client.exec_chat(model, chat_req.clone(), None)
+    let chat_response = exec_chat_metered(&client, DEFAULT_PROVIDER, model, chat_req.clone())
         .await
         .map_err(|_| Error::RpcError)?;    
 
+    record_usage(&ctx, &mm, DEFAULT_PROVIDER, model, None, &chat_response.usage).await?;
+
     let response_text = chat_response.content_text_as_str()
         .unwrap_or("No Naswer from LLM");    
 
@@ -108,3 +112,82 @@ pub async fn one_shot_msg(
 
     Ok(ret.into())
 }
+
+//-- Role mapping -----------------------------------
+/// Map a `ConvMsg` role and content to the genai `ChatMessage`.
+///
+/// Note: genai 0.1 does not have a tool result message constructor, so `Tool`
+///       messages are sent as `User` messages, prefixed so the model can tell them apart.
+pub(crate) fn to_chat_message(role: &MsgRole, content: impl Into<String>) -> ChatMessage {
+    match role {
+        MsgRole::System => ChatMessage::system(content),
+        MsgRole::User => ChatMessage::user(content),
+        MsgRole::Assistant => ChatMessage::assistant(content),
+        MsgRole::Tool => ChatMessage::user(format!("[tool result]\n{}", content.into())),
+    }
+}
+
+//-- Usage -----------------------------------------
+/// Exec the chat, in its `llm.chat` span, and record its LLM metrics (latency and tokens).
+pub(crate) async fn exec_chat_metered(
+    client: &Client,
+    provider: &str,
+    model: &str,
+    chat_req: ChatRequest,
+) -> core::result::Result<ChatResponse, genai::Error> {
+    let span = info_span!(
+        "llm.chat",
+        otel.kind = "client",
+        gen_ai.system = provider,
+        gen_ai.request.model = model,
+        gen_ai.usage.input_tokens = Empty,
+        gen_ai.usage.output_tokens = Empty,
+    );
+
+    let start = Instant::now();
+    let res = client
+        .exec_chat(model, chat_req, None)
+        .instrument(span.clone())
+        .await;
+
+    let usage = res.as_ref().ok().map(|chat_res| &chat_res.usage);
+    if let Some(input_tokens) = usage.and_then(|usage| usage.input_tokens) {
+        span.record("gen_ai.usage.input_tokens", input_tokens);
+    }
+    if let Some(output_tokens) = usage.and_then(|usage| usage.output_tokens) {
+        span.record("gen_ai.usage.output_tokens", output_tokens);
+    }
+    metrics::record_llm_call(
+        provider,
+        model,
+        res.is_ok(),
+        start.elapsed(),
+        usage.and_then(|usage| usage.input_tokens),
+        usage.and_then(|usage| usage.output_tokens),
+    );
+
+    res
+}
+
+/// Record the LLM call token usage (and cost) for the ctx user.
+/// The conv is the one of the ctx, if any.
+pub(crate) async fn record_usage(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    provider: &str,
+    model: &str,
+    agent_id: Option<i64>,
+    usage: &MetaUsage,
+) -> Result<()> {
+    let ue_c = UsageEventForCreate {
+        agent_id,
+        conv_id: ctx.conv_id(),
+        provider: provider.to_string(),
+        model: model.to_string(),
+        input_tokens: usage.input_tokens.unwrap_or_default(),
+        output_tokens: usage.output_tokens.unwrap_or_default(),
+    };
+    UsageEventBmc::create(ctx, mm, ue_c).await?;
+
+    Ok(())
+}
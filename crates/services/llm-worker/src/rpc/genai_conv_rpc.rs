@@ -0,0 +1,270 @@
+use lib_rpc_core::prelude::*;
+use lib_core::model::agent::{Agent, AgentBmc};
+use lib_core::model::chunk::ChunkMatch;
+use lib_core::model::conv::ConvBmc;
+use lib_core::model::conv_msg::{
+    ContentPart, ConvMsg, ConvMsgForReply, ConvMsgMeta, MsgCitation, MsgRole,
+};
+use lib_core::model::quota::QuotaBmc;
+use lib_core::model;
+use crate::error::{Error, Result};
+use crate::rpc::genai_chat_rpc::{
+    exec_chat_metered, record_usage, to_chat_message, DEFAULT_MODEL, DEFAULT_PROVIDER,
+};
+use crate::kb::KbEmbedder;
+use crate::rpc::conv_memory::{auto_title, compact_thread, LlmTarget};
+use crate::rpc::kb_rpc::{search_chunks, SEARCH_TOP_K_MAX};
+use crate::rpc::ParamsW;
+use crate::tools::{parse_tool_call, tools_system_prompt, ToolRegistry};
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+use genai::chat::ChatRequest;
+use genai::Client;
+
+use std::time::Instant;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+//-- Handler message params --------------------------
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ConvChat {
+    pub conv_id: i64,
+
+    /// Leaf message of the branch to reply to.
+    /// Default to the latest message of the conv.
+    pub msg_id: Option<i64>,
+
+    /// When set, the `kb_top_k` knowledge base chunks most similar to the leaf message
+    /// are given to the model, and cited in the reply metadata (at most 50).
+    pub kb_top_k: Option<usize>,
+    /// Restrict the knowledge base retrieval to these documents.
+    pub kb_document_ids: Option<Vec<i64>>,
+}
+
+//-- Handler RPC  -----------------------------------
+/// Max model calls of one `conv_chat` (guard against tool call loops).
+const TOOL_LOOP_MAX_ITERATIONS: usize = 5;
+
+/// Reply to a conv branch with the conv agent model.
+///
+/// The ctx user must be a member of the conv (or sys).
+///
+/// The branch history (from the conv root message down to the leaf message) is sent
+/// with each message role, and the reply is added as an `Assistant` message,
+/// child of the leaf message, with the LLM call metadata.
+///
+/// When the agent has tools, each tool call is recorded as an `Assistant` message
+/// (with a `ToolCall` part) followed by a `Tool` message (with a `ToolResult` part),
+/// and the model is called again with the result, until it replies normally.
+///
+/// When the agent has a `summary_token_budget`, the older messages of a long branch are
+/// replaced by the conv rolling summary, and when the agent has `auto_title`, the conv
+/// title is generated after the first reply.
+pub async fn conv_chat(
+    ctx: Ctx,
+    mm: ModelManager,
+    tools: ToolRegistry,
+    embedder: KbEmbedder,
+    params: ParamsW<ConvChat>,)
+-> Result<DataRpcResult<ConvMsg>> {
+
+    debug!("{:<12} - conv_chat - {ctx:?}, {params:?}", "RPC");
+
+    let ParamsW{data: ConvChat { conv_id, msg_id, kb_top_k, kb_document_ids }} = params;
+
+    // -- Only the conv members (or sys) can chat in the conv
+    ConvBmc::ensure_member(&ctx, &mm, conv_id).await?;
+
+    // -- Resolve the branch to reply to
+    let leaf_msg_id = match msg_id {
+        Some(msg_id) => msg_id,
+        None => ConvBmc::last_msg(&ctx, &mm, conv_id)
+            .await?
+            .map(|msg| msg.id)
+            .ok_or(Error::ConvChatNoMsg { conv_id })?,
+    };
+    let thread = ConvBmc::get_conv_thread(&ctx, &mm, leaf_msg_id).await?;
+    if thread.iter().any(|msg| msg.conv_id != conv_id) {
+        return Err(model::Error::ConvMsgNotInConv { conv_id, msg_id: leaf_msg_id }.into());
+    }
+
+    // -- Resolve the agent provider/model and tools
+    let conv = ConvBmc::get(&ctx, &mm, conv_id).await?;
+    let agent = AgentBmc::get(&ctx, &mm, conv.agent_id).await?;
+    let (provider, model) = resolve_provider_model(&agent);
+    let agent_tools = agent.tools();
+
+    let client = Client::default();
+    let usage_ctx = ctx.add_conv_id(conv_id);
+    let llm_target = LlmTarget { provider, model, agent_id: agent.id };
+
+    // -- Capture the first user message (for the auto title)
+    let title_user_content = if agent.auto_title && conv.title.is_none() {
+        thread.iter().find(|msg| msg.role == MsgRole::User).map(|msg| msg.content.clone())
+    } else {
+        None
+    };
+
+    // -- Compact the branch (rolling summary)
+    let (summary, thread) = compact_thread(&usage_ctx, &mm, &client, &llm_target, conv_id,
+        agent.summary_token_budget, thread).await?;
+
+    // -- Retrieve the knowledge base chunks (for the leaf message)
+    let kb_matches = match (kb_top_k, thread.last()) {
+        (Some(top_k), Some(leaf_msg)) if top_k > 0 => {
+            let top_k = top_k.min(SEARCH_TOP_K_MAX);
+            search_chunks(&ctx, &mm, &embedder, leaf_msg.content.clone(), top_k, kb_document_ids).await?
+        }
+        _ => Vec::new(),
+    };
+    let citations = (!kb_matches.is_empty()).then(|| {
+        kb_matches.iter().map(|m| MsgCitation {
+            document_id: m.document_id,
+            document_title: m.document_title.clone(),
+            chunk_id: m.chunk_id,
+            score: m.score,
+        }).collect::<Vec<_>>()
+    });
+
+    // -- Build the chat request from the branch
+    let mut system_prompts = Vec::new();
+    if let Some(summary) = &summary {
+        system_prompts.push(format!("Summary of the earlier conversation:\n{summary}"));
+    }
+    if !agent_tools.is_empty() {
+        system_prompts.push(tools_system_prompt(agent_tools));
+    }
+    if !kb_matches.is_empty() {
+        system_prompts.push(kb_system_prompt(&kb_matches));
+    }
+    let mut chat_req = ChatRequest::default();
+    if !system_prompts.is_empty() {
+        chat_req = chat_req.with_system(system_prompts.join("\n\n"));
+    }
+    let mut chat_req = thread.into_iter().fold(chat_req, |chat_req, msg| {
+        chat_req.append_message(to_chat_message(&msg.role, msg.content))
+    });
+
+    let mut parent_msg_id = leaf_msg_id;
+
+    for _ in 0..TOOL_LOOP_MAX_ITERATIONS {
+        // -- Exec the chat
+        QuotaBmc::check(&ctx, &mm).await?;
+        let start = Instant::now();
+        let chat_res = exec_chat_metered(&client, provider, model, chat_req.clone()).await?;
+        let latency_ms = start.elapsed().as_secs_f64() * 1000.;
+
+        record_usage(&usage_ctx, &mm, provider, model, Some(agent.id), &chat_res.usage).await?;
+
+        let content = chat_res.content_text_as_str().unwrap_or_default().to_string();
+        let meta = ConvMsgMeta {
+            provider: Some(provider.to_string()),
+            model: Some(model.to_string()),
+            finish_reason: None, // Not exposed by genai 0.1
+            input_tokens: chat_res.usage.input_tokens,
+            output_tokens: chat_res.usage.output_tokens,
+            latency_ms: Some(latency_ms),
+            citations: citations.clone(),
+        };
+
+        let tool_call = match parse_tool_call(&content) {
+            Some(tool_call) if !agent_tools.is_empty() => tool_call,
+            // -- Not a tool call, save the reply
+            _ => {
+                let msg_id = add_reply_msg(&ctx, &mm, conv_id, parent_msg_id,
+                    MsgRole::Assistant, content.clone(), None, Some(meta)).await?;
+
+                // Note: A title failure does not fail the reply.
+                if let Some(user_content) = &title_user_content {
+                    if let Err(ex) = auto_title(&usage_ctx, &mm, &client, &llm_target,
+                        conv_id, user_content, &content).await {
+                        warn!("{:<12} - conv_chat - auto title failed for conv {conv_id}: {ex}", "RPC");
+                    }
+                }
+
+                let msg = ConvBmc::get_msg(&ctx, &mm, msg_id).await?;
+                return Ok(msg.into());
+            }
+        };
+
+        // -- Save the tool call
+        let call_id = Uuid::new_v4().to_string();
+        let call_part = ContentPart::ToolCall {
+            call_id: call_id.clone(),
+            name: tool_call.name.clone(),
+            arguments: tool_call.arguments.clone(),
+        };
+        let call_msg_id = add_reply_msg(&ctx, &mm, conv_id, parent_msg_id,
+            MsgRole::Assistant, content.clone(), Some(call_part), Some(meta)).await?;
+
+        // -- Exec the tool (with the caller ctx)
+        // Note: Tool errors are given back to the model, so that it can recover.
+        let result = match agent_tools.iter().find(|tool| tool.name == tool_call.name) {
+            Some(tool) => tools
+                .call(&ctx, &mm, tool, &call_id, tool_call.arguments)
+                .await
+                .unwrap_or_else(|ex| json!({ "error": ex.to_string() })),
+            None => json!({ "error": format!("tool '{}' unknown", tool_call.name) }),
+        };
+
+        // -- Save the tool result
+        let result_text = result.to_string();
+        let result_part = ContentPart::ToolResult { call_id, content: result };
+        parent_msg_id = add_reply_msg(&ctx, &mm, conv_id, call_msg_id,
+            MsgRole::Tool, result_text.clone(), Some(result_part), None).await?;
+
+        chat_req = chat_req
+            .append_message(to_chat_message(&MsgRole::Assistant, content))
+            .append_message(to_chat_message(&MsgRole::Tool, result_text));
+    }
+
+    Err(Error::ToolLoopMaxIterations { max: TOOL_LOOP_MAX_ITERATIONS })
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn add_reply_msg(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    conv_id: i64,
+    parent_msg_id: i64,
+    role: MsgRole,
+    content: String,
+    part: Option<ContentPart>,
+    metadata: Option<ConvMsgMeta>,
+) -> Result<i64> {
+    let msg_r = ConvMsgForReply {
+        conv_id,
+        parent_msg_id,
+        role,
+        content,
+        content_parts: part.map(|part| vec![part]),
+        metadata,
+    };
+    let msg_id = ConvBmc::add_reply_msg(ctx, mm, msg_r).await?;
+
+    Ok(msg_id)
+}
+
+/// The knowledge base excerpts, numbered for the model to cite them.
+fn kb_system_prompt(kb_matches: &[ChunkMatch]) -> String {
+    let mut prompt = String::from(
+        "Use the following knowledge base excerpts when relevant, and cite them as [n].\n",
+    );
+    for (i, m) in kb_matches.iter().enumerate() {
+        prompt.push_str(&format!("\n[{}] ({})\n{}\n", i + 1, m.document_title, m.content));
+    }
+    prompt
+}
+
+/// The `dev` provider (agent default) is not a real provider,
+/// so we fall back on the default one.
+fn resolve_provider_model(agent: &Agent) -> (&str, &str) {
+    if agent.ai_provider == "dev" {
+        (DEFAULT_PROVIDER, DEFAULT_MODEL)
+    } else {
+        (&agent.ai_provider, &agent.ai_model)
+    }
+}
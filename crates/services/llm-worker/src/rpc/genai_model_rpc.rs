@@ -7,6 +7,7 @@ use crate::error::Result;
 use genai::adapter::AdapterKind;
 use genai::Client;
 use lib_core::{ctx::Ctx, model::ModelManager};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
@@ -16,7 +17,7 @@ use tracing::debug;
 // be explicit about this.
 // - Removed Ollama
 // - Added All
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub enum GenAIProviderType {
     OpenAI,	
 	Gemini,
@@ -26,12 +27,12 @@ pub enum GenAIProviderType {
     All
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct GetModelListRequest {
     pub provider: GenAIProviderType,    
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct GetModelListResponse {
     // { ProviderTypeName, Vec<ModelName> }
     pub models: HashMap<String, Vec<String>>,    
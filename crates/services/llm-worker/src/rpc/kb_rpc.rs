@@ -0,0 +1,111 @@
+use lib_rpc_core::prelude::*;
+use lib_core::model::chunk::{ChunkBmc, ChunkForCreate, ChunkMatch};
+use lib_core::model::document::{Document, DocumentBmc, DocumentForCreate};
+use crate::error::Result;
+use crate::kb::{split_text, KbEmbedder};
+use crate::rpc::ParamsW;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tracing::debug;
+
+/// Default max chars of the ingested chunks.
+const CHUNK_MAX_CHARS_DEFAULT: usize = 1000;
+
+const SEARCH_TOP_K_DEFAULT: usize = 5;
+/// Max `top_k` of a search (the client value is clamped to it).
+pub(crate) const SEARCH_TOP_K_MAX: usize = 50;
+
+//-- Handler message params --------------------------
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct DocumentIngest {
+    pub title: String,
+    pub source: Option<String>,
+    pub text: String,
+    pub chunk_max_chars: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct KbSearch {
+    pub query: String,
+    /// Default to 5, at most 50.
+    pub top_k: Option<usize>,
+    pub document_ids: Option<Vec<i64>>,
+}
+
+//-- Handler RPC  -----------------------------------
+/// Split the text in chunks, embed them with the worker embedder,
+/// and create the document with its chunks.
+pub async fn ingest_document(
+    ctx: Ctx,
+    mm: ModelManager,
+    embedder: KbEmbedder,
+    params: ParamsW<DocumentIngest>,)
+-> Result<DataRpcResult<Document>> {
+
+    debug!("{:<12} - ingest_document - {ctx:?}", "RPC");
+
+    let ParamsW{data: DocumentIngest { title, source, text, chunk_max_chars }} = params;
+    let embedder = embedder.0;
+
+    let contents = split_text(&text, chunk_max_chars.unwrap_or(CHUNK_MAX_CHARS_DEFAULT));
+    let embeddings = embedder.embed(&contents).await?;
+
+    let chunks_c = contents
+        .into_iter()
+        .zip(embeddings)
+        .map(|(content, embedding)| ChunkForCreate {
+            content,
+            embed_model: embedder.model().to_string(),
+            embedding,
+        })
+        .collect();
+
+    let doc_c = DocumentForCreate { title, source };
+    let doc_id = DocumentBmc::create_with_chunks(&ctx, &mm, doc_c, chunks_c).await?;
+    let doc = DocumentBmc::get(&ctx, &mm, doc_id).await?;
+
+    Ok(doc.into())
+}
+
+/// Returns the knowledge base chunks most similar to the query.
+pub async fn search_kb(
+    ctx: Ctx,
+    mm: ModelManager,
+    embedder: KbEmbedder,
+    params: ParamsW<KbSearch>,)
+-> Result<DataRpcResult<Vec<ChunkMatch>>> {
+
+    debug!("{:<12} - search_kb - {ctx:?}, {params:?}", "RPC");
+
+    let ParamsW{data: KbSearch { query, top_k, document_ids }} = params;
+
+    let top_k = top_k.unwrap_or(SEARCH_TOP_K_DEFAULT).min(SEARCH_TOP_K_MAX);
+    let matches = search_chunks(&ctx, &mm, &embedder, query, top_k, document_ids).await?;
+
+    Ok(matches.into())
+}
+
+/// Embed the query and search the chunks of the embedder model.
+pub(crate) async fn search_chunks(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    embedder: &KbEmbedder,
+    query: String,
+    top_k: usize,
+    document_ids: Option<Vec<i64>>,
+) -> Result<Vec<ChunkMatch>> {
+    let embedder = &embedder.0;
+    let query_embedding = embedder
+        .embed(&[query])
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+
+    let matches = ChunkBmc::search(
+        ctx, mm, embedder.model(), &query_embedding, top_k, document_ids,
+    ).await?;
+
+    Ok(matches)
+}
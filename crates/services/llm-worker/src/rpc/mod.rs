@@ -2,6 +2,7 @@ use lib_rpc_core::prelude::*;
 
 use serde::Deserialize;
 use rpc_router::IntoParams;
+use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 
 mod genai_chat_rpc;
@@ -10,21 +11,30 @@ use genai_chat_rpc::one_shot_msg;
 mod genai_model_rpc;
 use genai_model_rpc::get_model_list;
 
-/// Equivalent to 
-///  RouterBuilder::default()
-///     .append_dyn("one_shot_msg", one_shot_msg.into_box())
-///
-pub fn rpc_router_builder() -> RouterBuilder {
-    router_builder!(
-        one_shot_msg,
-        get_model_list
-    )
-}
+mod genai_conv_rpc;
+use genai_conv_rpc::conv_chat;
+
+mod kb_rpc;
+use kb_rpc::{ingest_document, search_kb};
+
+mod conv_memory;
+
+// The `rpc_router_builder()` is equivalent to
+//  RouterBuilder::default()
+//     .append_dyn("one_shot_msg", one_shot_msg.into_box())
+//
+rpc_builders!(
+    one_shot_msg,
+    get_model_list,
+    conv_chat,
+    ingest_document,
+    search_kb
+);
 
 
 //-- Infra extension for wrapping params ------------
 /// Params structure for any RPC pass-through call.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct ParamsW<D> {
 	pub data: D,
 }
@@ -0,0 +1,36 @@
+//! The Rust tool handlers, for the `AgentToolTarget::Handler` tools.
+
+use crate::error::Result;
+use async_trait::async_trait;
+use lib_core::ctx::Ctx;
+use lib_core::model::ModelManager;
+use lib_utils::time::{format_time, now_utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait ToolHandler: Send + Sync {
+    async fn call(&self, ctx: &Ctx, mm: &ModelManager, arguments: Value) -> Result<Value>;
+}
+
+/// The handlers available to the agents, by handler name.
+pub(super) fn default_handlers() -> HashMap<String, Arc<dyn ToolHandler>> {
+    let mut handlers: HashMap<String, Arc<dyn ToolHandler>> = HashMap::new();
+    handlers.insert("current_time".to_string(), Arc::new(CurrentTime));
+    handlers
+}
+
+// region:    --- Handlers
+
+/// Returns the current UTC time (models do not know it).
+struct CurrentTime;
+
+#[async_trait]
+impl ToolHandler for CurrentTime {
+    async fn call(&self, _ctx: &Ctx, _mm: &ModelManager, _arguments: Value) -> Result<Value> {
+        Ok(json!({ "utc": format_time(now_utc()) }))
+    }
+}
+
+// endregion: --- Handlers
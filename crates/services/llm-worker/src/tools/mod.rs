@@ -0,0 +1,258 @@
+//! Agent tools (function calling).
+//!
+//! genai 0.1 does not expose the provider tool APIs, so the agent tools are described
+//! in the system prompt, and the model requests a tool call by replying only with
+//! `{"tool_call": {"name": "...", "arguments": {...}}}`.
+//!
+//! Tool calls are executed with the caller `Ctx`, so the model access rules
+//! are the ones of the user.
+
+// region:    --- Modules
+
+mod handlers;
+mod tool_rpc;
+
+pub use handlers::ToolHandler;
+
+use crate::error::{Error, Result};
+use lib_core::ctx::Ctx;
+use lib_core::model::agent::{AgentTool, AgentToolTarget};
+use lib_core::model::ModelManager;
+use rpc_router::resources_builder;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// endregion: --- Modules
+
+// region:    --- ToolRegistry
+
+/// Executes the agent tool calls, either with the tool rpc router
+/// (see `tool_rpc`) or with the registered `ToolHandler`s.
+#[derive(Clone, rpc_router::RpcResource)]
+pub struct ToolRegistry {
+    handlers: Arc<HashMap<String, Arc<dyn ToolHandler>>>,
+    rpc_router: rpc_router::Router,
+}
+
+impl ToolRegistry {
+    pub fn new(mm: ModelManager) -> Self {
+        let rpc_router = tool_rpc::rpc_router_builder()
+            .append_resource(mm)
+            .build();
+
+        ToolRegistry {
+            handlers: Arc::new(handlers::default_handlers()),
+            rpc_router,
+        }
+    }
+
+    /// Execute the tool with the caller `ctx`, and returns the tool result.
+    pub async fn call(
+        &self,
+        ctx: &Ctx,
+        mm: &ModelManager,
+        tool: &AgentTool,
+        call_id: &str,
+        arguments: Value,
+    ) -> Result<Value> {
+        match &tool.target {
+            AgentToolTarget::Rpc { method } => {
+                let rpc_req = rpc_router::Request::try_from(json!({
+                    "jsonrpc": "2.0",
+                    "id": call_id,
+                    "method": method,
+                    "params": arguments,
+                }))
+                .map_err(|ex| Error::ToolRpc {
+                    method: method.to_string(),
+                    detail: ex.to_string(),
+                })?;
+
+                // Note: The ctx is overlayed on the router resources, as in the rpc handlers.
+                let additional_resources = resources_builder![ctx.clone()].build();
+                let rpc_res = self
+                    .rpc_router
+                    .call_with_resources(rpc_req, additional_resources)
+                    .await
+                    .map_err(|ex| Error::ToolRpc {
+                        method: method.to_string(),
+                        detail: format!("{:?}", ex.error),
+                    })?;
+
+                Ok(rpc_res.value)
+            }
+            AgentToolTarget::Handler { name } => {
+                let handler = self
+                    .handlers
+                    .get(name)
+                    .ok_or_else(|| Error::ToolHandlerUnknown { name: name.to_string() })?;
+
+                handler.call(ctx, mm, arguments).await
+            }
+        }
+    }
+}
+
+// endregion: --- ToolRegistry
+
+// region:    --- Tool Call Protocol
+
+/// A tool call requested by the model.
+#[derive(Debug, Deserialize)]
+pub struct ToolCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+#[derive(Deserialize)]
+struct ToolCallReply {
+    tool_call: ToolCall,
+}
+
+/// Returns the tool call if the model reply is a tool call request.
+/// (the json can be in a markdown code block)
+pub fn parse_tool_call(content: &str) -> Option<ToolCall> {
+    let content = content.trim();
+    let content = content
+        .strip_prefix("```json")
+        .or_else(|| content.strip_prefix("```"))
+        .and_then(|c| c.strip_suffix("```"))
+        .unwrap_or(content)
+        .trim();
+
+    serde_json::from_str::<ToolCallReply>(content)
+        .ok()
+        .map(|reply| reply.tool_call)
+}
+
+/// The system prompt describing the tools and the tool call protocol to the model.
+pub fn tools_system_prompt(tools: &[AgentTool]) -> String {
+    let mut prompt = String::from(
+        r#"You can use the following tools.
+To call a tool, reply only with the json: {"tool_call": {"name": "<tool name>", "arguments": {...}}}
+The tool result will be sent back in a message starting with "[tool result]".
+When you have the information you need, reply normally to the user.
+
+Tools:
+"#,
+    );
+
+    for tool in tools {
+        prompt.push_str(&format!(
+            "- {}: {}\n  arguments json schema: {}\n",
+            tool.name,
+            tool.description.as_deref().unwrap_or(""),
+            tool.parameters
+        ));
+    }
+
+    prompt
+}
+
+// endregion: --- Tool Call Protocol
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+    type Error = Box<dyn std::error::Error>;
+    type Result<T> = core::result::Result<T, Error>; // For tests.
+
+    use super::*;
+
+    #[test]
+    fn test_parse_tool_call_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_contents = [
+            r#"{"tool_call": {"name": "list_convs", "arguments": {"limit": 2}}}"#,
+            "  ```json\n{\"tool_call\": {\"name\": \"list_convs\", \"arguments\": {\"limit\": 2}}}\n```  ",
+            "```\n{\"tool_call\": {\"name\": \"list_convs\", \"arguments\": {\"limit\": 2}}}\n```",
+        ];
+
+        for fx_content in fx_contents {
+            // -- Exec
+            let tool_call = parse_tool_call(fx_content).ok_or("should be a tool call")?;
+
+            // -- Check
+            assert_eq!(tool_call.name, "list_convs");
+            assert_eq!(tool_call.arguments, json!({"limit": 2}));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_tool_call_no_arguments_ok() -> Result<()> {
+        // -- Exec
+        let tool_call = parse_tool_call(r#"{"tool_call": {"name": "now"}}"#)
+            .ok_or("should be a tool call")?;
+
+        // -- Check
+        assert_eq!(tool_call.name, "now");
+        assert_eq!(tool_call.arguments, Value::Null);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_tool_call_none() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_contents = [
+            "Here are your convs.",
+            r#"{"name": "list_convs", "arguments": {}}"#,
+            r#"Sure: {"tool_call": {"name": "list_convs", "arguments": {}}}"#,
+            r#"{"tool_call": {"arguments": {}}}"#,
+            "",
+        ];
+
+        for fx_content in fx_contents {
+            // -- Exec & Check
+            assert!(
+                parse_tool_call(fx_content).is_none(),
+                "should not be a tool call: {fx_content:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tools_system_prompt_ok() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_tools = vec![
+            AgentTool {
+                name: "list_convs".to_string(),
+                description: Some("List the user convs".to_string()),
+                parameters: json!({"type": "object"}),
+                target: AgentToolTarget::Rpc {
+                    method: "list_convs".to_string(),
+                },
+            },
+            AgentTool {
+                name: "now".to_string(),
+                description: None,
+                parameters: json!({}),
+                target: AgentToolTarget::Handler {
+                    name: "now".to_string(),
+                },
+            },
+        ];
+
+        // -- Exec
+        let prompt = tools_system_prompt(&fx_tools);
+
+        // -- Check
+        assert!(prompt.contains(r#"{"tool_call": {"name": "<tool name>", "arguments": {...}}}"#));
+        assert!(prompt.contains(
+            "- list_convs: List the user convs\n  arguments json schema: {\"type\":\"object\"}\n"
+        ));
+        assert!(prompt.ends_with("- now: \n  arguments json schema: {}\n"));
+
+        Ok(())
+    }
+}
+
+// endregion: --- Tests
@@ -0,0 +1,17 @@
+//! The rpc methods the agent tools can target (`AgentToolTarget::Rpc`).
+//!
+//! Notes:
+//!   - The handlers are the web-gateway ones, so the access rules are the same
+//!     as for the rpc clients.
+//!   - Only the methods scoped to the caller ctx are exposed, as the tool calls are decided
+//!     by the model (e.g., `list_conv_msgs` requires the ctx user to be a member of the conv).
+//!   - Read only for now.
+
+use lib_rpc_core::prelude::*;
+use web_gateway::web::rpcs::conv_rpc::list_conv_msgs;
+
+pub fn rpc_router_builder() -> RouterBuilder {
+    router_builder!(
+        list_conv_msgs,
+    )
+}
@@ -1,21 +1,39 @@
 use axum::routing::post;
 use axum::Router;
 use lib_core::model::ModelManager;
+use lib_rpc_core::openrpc::{rpc_discover, OpenRpcDoc, RPC_DISCOVER};
 use lib_web::handlers::handlers_rpc;
+use crate::error::Result;
+use crate::kb::KbEmbedder;
+use crate::tools::ToolRegistry;
 
-///  Build the Axum router for '/api/rpc'
-/// Note: This will build the `rpc-router::Router` that will be used by the
-///       rpc_axum_handler
-pub fn routes(mm: ModelManager) -> Router {
+pub const SERVICE_NAME: &str = "llm-worker";
+
+/// The OpenRPC document (for `rpc.discover` and the service registration).
+pub fn rpc_doc() -> OpenRpcDoc {
+	crate::rpc::rpc_doc_builder().build(SERVICE_NAME, env!("CARGO_PKG_VERSION"))
+}
 
-	// Build the combined Rpc Router
+/// Build the combined `rpc-router::Router`, with the common resources for all rpc calls.
+/// Note: Shared by the '/api/rpc' route and the job runtime (see `jobs`).
+pub fn rpc_router(mm: ModelManager) -> Result<rpc_router::Router> {
 	let rpc_router = crate::rpc::rpc_router_builder()
+		.append(RPC_DISCOVER, rpc_discover)
 		// Add the common resources for all rpc calls
-		.append_resource(mm)
+		.append_resource(mm.clone())
+		.append_resource(ToolRegistry::new(mm))
+		.append_resource(KbEmbedder::from_config()?)
+		.append_resource(rpc_doc())
 		.build();	
 
+	Ok(rpc_router)
+}
+
+///  Build the Axum router for '/api/rpc'
+/// Note: The `rpc_router` will be used by the rpc_axum_handler
+pub fn routes(rpc_router: rpc_router::Router) -> Router {
 	// Build the Axum Router for '/rpc'
 	Router::new()
 		.route("/rpc", post(handlers_rpc::rpc_axum_handler))
 		.with_state(rpc_router)
-}
\ No newline at end of file
+}
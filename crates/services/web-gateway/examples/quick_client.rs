@@ -0,0 +1,75 @@
+#![allow(unused)] // For example code.
+
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // For examples.
+
+use lib_core::model::agent::{Agent, AgentForCreate};
+use lib_core::model::conv::{Conv, ConvForCreate};
+use lib_core::model::conv_msg::{ConvMsgForCreate, MsgRole};
+use lib_rpc_client::{OneShotMsg, RpcClient};
+use serde_json::json;
+
+/// Same as `quick_dev`, with the typed `lib-rpc-client`.
+#[tokio::main]
+async fn main() -> Result<()> {
+	let client = RpcClient::new("http://localhost:8080")?;
+
+	// -- Login
+	client.login("demo1", "welcome").await?;
+
+	// -- Create Agent
+	let agent = client
+		.create_agent(AgentForCreate {
+			name: "agent AAA".to_string(),
+			tools: None,
+			auto_title: None,
+			summary_token_budget: None,
+		})
+		.await?;
+	println!("->> agent: {:?}", agent.id);
+
+	// -- Create Conv
+	let conv = client
+		.create_conv(ConvForCreate {
+			agent_id: agent.id,
+			title: Some("conv 01".to_string()),
+			..Default::default()
+		})
+		.await?;
+
+	// -- Add ConvMsg
+	let conv_msg = client
+		.add_conv_msg(ConvMsgForCreate {
+			conv_id: conv.id,
+			content: "This is the first comment".to_string(),
+			..Default::default()
+		})
+		.await?;
+	println!("->> conv_msg: {:?}", conv_msg.id);
+
+	// -- Batch
+	let mut batch = client.batch();
+	let agent_call = batch.call_data::<Agent>("get_agent", json!({"id": agent.id}))?;
+	let convs_call = batch.call_data::<Vec<Conv>>(
+		"list_convs",
+		json!({"filters": {"agent_id": agent.id}}),
+	)?;
+	let mut batch_res = batch.send().await?;
+	println!("->> batch agent: {}", batch_res.take(agent_call)?.name);
+	println!("->> batch convs: {}", batch_res.take(convs_call)?.len());
+
+	// -- worker calls via gateway
+	let res = client
+		.llm()
+		.one_shot_msg(OneShotMsg {
+			mode: MsgRole::System,
+			prompt: "why is the sky blue".to_string(),
+		})
+		.await?;
+	println!("->> one_shot_msg: {}", res.response);
+
+	// -- Logoff
+	client.logoff().await?;
+
+	Ok(())
+}
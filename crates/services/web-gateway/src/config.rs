@@ -1,5 +1,6 @@
-use lib_utils::envs::get_env;
+use lib_utils::envs::{get_env, get_env_parse};
 use std::sync::OnceLock;
+use std::time::Duration;
 
 #[allow(unused)]
 pub fn web_config() -> &'static WebConfig {
@@ -16,12 +17,29 @@ pub fn web_config() -> &'static WebConfig {
 pub struct WebConfig {
 	#[allow(unused)]
 	pub WEB_FOLDER: String,
+
+	// -- Service Registry
+	/// The key of the worker registrations (shared with the workers).
+	pub SERVICE_KEY: String,
+
+	// -- Route Timeouts
+	pub RPC_TIMEOUT: Duration,
+	pub ATTACHMENT_TIMEOUT: Duration,
 }
 
 impl WebConfig {
 	fn load_from_env() -> lib_utils::envs::Result<WebConfig> {
 		Ok(WebConfig {
 			WEB_FOLDER: get_env("SERVICE_WEB_FOLDER")?,
+
+			// -- Service Registry
+			SERVICE_KEY: get_env("SERVICE_REGISTRY_KEY")?,
+
+			// -- Route Timeouts
+			RPC_TIMEOUT: Duration::from_secs(get_env_parse("SERVICE_RPC_TIMEOUT_SEC")?),
+			ATTACHMENT_TIMEOUT: Duration::from_secs(get_env_parse(
+				"SERVICE_ATTACHMENT_TIMEOUT_SEC",
+			)?),
 		})
 	}
 }
@@ -8,6 +8,8 @@ pub enum Error {
 	// -- Modules
 	#[from]
 	Model(model::Error),
+	#[from]
+	Web(lib_web::Error),
 }
 
 // region:    --- Error Boilerplate
@@ -0,0 +1,13 @@
+//! The web-gateway service (the binary is `main.rs`).
+//!
+//! Note: A lib target so that the tools (e.g., `gen-ts`) build the rpc doc from the rpc modules.
+
+// region:    --- Modules
+
+pub mod config;
+mod error;
+pub mod web;
+
+pub use self::error::{Error, Result};
+
+// endregion: --- Modules
@@ -1,53 +1,97 @@
 // region:    --- Modules
 
-mod config;
-mod error;
-mod web;
-
-pub use self::error::{Error, Result};
-use config::web_config;
+use web_gateway::config::web_config;
+use web_gateway::web;
+use web_gateway::Result;
 
+use lib_web::health::{DbCheck, HealthChecks, ServiceCheck};
+use lib_web::log;
 use lib_web::middleware::mw_auth::{mw_ctx_require, mw_ctx_root_resolver};
+use lib_web::middleware::mw_metrics::MetricsLayer;
 use lib_web::middleware::mw_req_stamp::mw_req_stamp_resolver;
 use lib_web::middleware::mw_res_map::mw_reponse_map;
-use lib_web::routes::routes_static;
+use lib_web::middleware::mw_timeout::mw_req_timeout;
+use lib_web::middleware::mw_trace::mw_trace;
+use lib_web::routes::{routes_health, routes_metrics, routes_static};
+use lib_web::telemetry;
+use lib_web::utils::service_pool::service_pool;
+use lib_web::utils::shutdown::shutdown_signal;
+use lib_web::webhooks;
 
-use crate::web::routes_login;
+use web_gateway::web::routes_login;
 
 use axum::{middleware, Router};
 use lib_core::_dev_utils;
 use lib_core::model::ModelManager;
+use lib_core::outbox_relay::{EventBusSink, OutboxRelay};
+use lib_events::EventPurger;
+use lib_rpc_core::openrpc::RPC_DISCOVER;
 use tokio::net::TcpListener;
 use tower_cookies::CookieManagerLayer;
 use tracing::info;
-use tracing_subscriber::EnvFilter;
 
 // endregion: --- Modules
 
 #[tokio::main]
 async fn main() -> Result<()> {
-	tracing_subscriber::fmt()
-		.without_time() // For early local development.
-		.with_target(false)
-		.with_env_filter(EnvFilter::from_default_env())
-		.init();
+	// -- Tracing (log lines, and spans per the `SERVICE_TRACE_EXPORTER` env)
+	let _telemetry = telemetry::init_tracing("web-gateway", "error")?;
+
+	// -- Request Log Sink (per the `SERVICE_REQUEST_LOG_SINK` env)
+	let log_sink = log::init_log_sink("web-gateway")?;
 
 	// -- FOR DEV ONLY
 	_dev_utils::init_dev().await;	
 
 	let mm = ModelManager::new().await?;
+
+	// -- Start the Outbox Relay (model events to the event bus)
+	OutboxRelay::new(mm.clone(), EventBusSink::new(mm.event_bus().clone())).start();
+
+	// -- Start the Event Purger (events older than the retention, dead-letters included)
+	EventPurger::new(mm.event_bus().clone()).start();
+
+	// -- Start the Webhooks (dispatch and delivery jobs)
+	webhooks::start_webhooks(mm.clone(), "web-gateway").await?;
+
+	// -- Start the Service Pool health check (worker dispatch, retries only for the idempotent methods)
+	service_pool().set_idempotent_methods(
+		"llm-worker",
+		&["get_model_list", "search_kb", RPC_DISCOVER],
+	);
+	service_pool().start_health_check();
 		
 	// -- Define Routes
-	let routes_rpc = web::routes_rpc::routes(mm.clone())
+	// Note: The ws route is long lived (no timeout).
+	let routes_api = web::routes_rpc::routes(mm.clone())
+		.route_layer(middleware::from_fn_with_state(web_config().RPC_TIMEOUT, mw_req_timeout))
+		.merge(
+			web::routes_attachment::routes(mm.clone()).route_layer(
+				middleware::from_fn_with_state(web_config().ATTACHMENT_TIMEOUT, mw_req_timeout),
+			),
+		)
+		.merge(web::routes_ws::routes(mm.clone()))
+		.merge(web::routes_admin::routes(mm.clone()))
 		.route_layer(middleware::from_fn(mw_ctx_require));
 
+	// -- Health Checks (the workers are not critical, the gateway serves its own rpc methods)
+	let health_checks = HealthChecks::new()
+		.with(DbCheck::new(mm.clone()))
+		.with(ServiceCheck::new("llm-worker"));
+
 	let routes_all = Router::new()
 		.merge(routes_login::routes(mm.clone()))
-		.nest("/api", routes_rpc)
+		.merge(web::routes_services::routes(&web_config().SERVICE_KEY))
+		.nest("/api", routes_api)
+		.layer(MetricsLayer)
 		.layer(middleware::map_response(mw_reponse_map))
 		.layer(middleware::from_fn_with_state(mm.clone(), mw_ctx_root_resolver))		
 		.layer(CookieManagerLayer::new())
-		.layer(middleware::from_fn(mw_req_stamp_resolver))		
+		.layer(middleware::from_fn(mw_req_stamp_resolver))
+		.layer(middleware::from_fn(mw_trace))
+		// Note: After the layers (no ctx, no request log line, no request metrics, no trace).
+		.merge(routes_health::routes(health_checks))
+		.merge(routes_metrics::routes(mm.clone()))
 		.fallback_service(routes_static::serve_dir(&web_config().WEB_FOLDER));
 
 	// region:    --- Start Server
@@ -55,9 +99,15 @@ async fn main() -> Result<()> {
 	let listener = TcpListener::bind("127.0.0.1:8080").await.unwrap();
 	info!("{:<12} - {:?}\n", "LISTENING", listener.local_addr());
 	axum::serve(listener, routes_all.into_make_service())
+		.with_graceful_shutdown(shutdown_signal())
 		.await
 		.unwrap();
 	// endregion: --- Start Server
 
+	// -- Write the pending request log lines
+	if let Some(log_sink) = log_sink {
+		log_sink.shutdown().await;
+	}
+
 	Ok(())
 }
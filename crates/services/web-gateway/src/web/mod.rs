@@ -1,6 +1,10 @@
 // region:    --- Modules
+pub mod routes_admin;
+pub mod routes_attachment;
 pub mod routes_login;
 pub mod routes_rpc;
+pub mod routes_services;
+pub mod routes_ws;
 pub mod rpcs;
 
 // endregion: --- Modules
@@ -0,0 +1,15 @@
+use axum::routing::get;
+use axum::Router;
+use lib_core::model::ModelManager;
+use lib_web::handlers::handlers_admin;
+
+///  Build the Axum router for '/api/admin'
+/// Note: Must be layered with `mw_ctx_require` (handlers need the Ctx).
+pub fn routes(mm: ModelManager) -> Router {
+	Router::new()
+		.route(
+			"/admin/services",
+			get(handlers_admin::api_admin_services_handler),
+		)
+		.with_state(mm)
+}
@@ -0,0 +1,21 @@
+use axum::extract::DefaultBodyLimit;
+use axum::routing::{get, post};
+use axum::Router;
+use lib_core::model::ModelManager;
+use lib_web::handlers::handlers_attachment::{self, ATTACHMENT_BODY_LIMIT};
+
+///  Build the Axum router for '/api/attachments'
+/// Note: Must be layered with `mw_ctx_require` (handlers need the Ctx).
+pub fn routes(mm: ModelManager) -> Router {
+	Router::new()
+		.route(
+			"/attachments",
+			post(handlers_attachment::api_attachment_upload_handler),
+		)
+		.route(
+			"/attachments/{id}",
+			get(handlers_attachment::api_attachment_download_handler),
+		)
+		.layer(DefaultBodyLimit::max(ATTACHMENT_BODY_LIMIT))
+		.with_state(mm)
+}
@@ -1,9 +1,17 @@
-use crate::web::rpcs::all_rpc_router_builder;
+use crate::web::rpcs::{all_rpc_doc_builder, all_rpc_router_builder};
 use axum::routing::post;
 use axum::Router;
 use lib_core::model::ModelManager;
+use lib_rpc_core::openrpc::OpenRpcDoc;
 use lib_web::handlers::handlers_rpc;
 
+pub const SERVICE_NAME: &str = "web-gateway";
+
+/// The OpenRPC document of the gateway methods (for `rpc.discover`, which adds the worker methods).
+pub fn rpc_doc() -> OpenRpcDoc {
+	all_rpc_doc_builder().build(SERVICE_NAME, env!("CARGO_PKG_VERSION"))
+}
+
 ///  Build the Axum router for '/api/rpc'
 /// Note: This will build the `rpc-router::Router` that will be used by the
 ///       rpc_axum_handler
@@ -12,6 +20,7 @@ pub fn routes(mm: ModelManager) -> Router {
 	let rpc_router = all_rpc_router_builder()
 		// Add the common resources for all rpc calls
 		.append_resource(mm)
+		.append_resource(rpc_doc())
 		.build();
 
 	// Build the Axum Router for '/rpc'
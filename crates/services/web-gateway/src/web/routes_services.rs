@@ -0,0 +1,21 @@
+use axum::routing::post;
+use axum::Router;
+use lib_web::handlers::handlers_services::{self, ServicesState};
+use std::sync::Arc;
+
+///  Build the Axum router for the worker registration ('/api/services/...')
+/// Note: Authenticated with the service key (no Ctx).
+pub fn routes(service_key: &str) -> Router {
+	Router::new()
+		.route(
+			"/api/services/register",
+			post(handlers_services::api_service_register_handler),
+		)
+		.route(
+			"/api/services/heartbeat",
+			post(handlers_services::api_service_heartbeat_handler),
+		)
+		.with_state(ServicesState {
+			service_key: Arc::new(service_key.to_string()),
+		})
+}
@@ -0,0 +1,31 @@
+use axum::routing::get;
+use axum::Router;
+use lib_core::model::conv_event::listen_conv_events;
+use lib_core::model::ModelManager;
+use lib_web::handlers::handlers_ws::{self, WsState};
+use tokio::sync::broadcast;
+use tracing::error;
+
+/// Conv events buffered per websocket (a slower socket gets a `lagged` error).
+const CONV_EVENT_CAPACITY: usize = 256;
+
+///  Build the Axum router for '/api/ws'
+/// Note: Must be layered with `mw_ctx_require` (handlers need the Ctx).
+///
+/// This spawns the conv events listener (postgres LISTEN), which
+/// broadcasts the events to the websockets of this gateway instance.
+pub fn routes(mm: ModelManager) -> Router {
+	let (conv_events, _) = broadcast::channel(CONV_EVENT_CAPACITY);
+
+	let listener_mm = mm.clone();
+	let listener_tx = conv_events.clone();
+	tokio::spawn(async move {
+		if let Err(ex) = listen_conv_events(listener_mm, listener_tx).await {
+			error!("{:<12} - conv events listener failed: {ex:?}", "WS");
+		}
+	});
+
+	Router::new()
+		.route("/ws", get(handlers_ws::ws_axum_handler))
+		.with_state(WsState { mm, conv_events })
+}
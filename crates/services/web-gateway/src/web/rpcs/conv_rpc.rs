@@ -1,20 +1,33 @@
 use lib_core::model::conv::{
 	Conv, ConvBmc, ConvFilter, ConvForCreate, ConvForUpdate,
 };
-use lib_core::model::conv_msg::{ConvMsg, ConvMsgForCreate};
+use lib_core::model::conv_msg::{
+	ConvMsg, ConvMsgFilter, ConvMsgForCreate, ConvMsgForEdit, ConvMsgForUpdate,
+};
+use lib_core::model::conv_user::{ConvUser, ConvUserForCreate};
 use lib_rpc_core::prelude::*;
+use rpc_router::IntoParams;
+use schemars::JsonSchema;
+use serde::Deserialize;
 
-pub fn rpc_router_builder() -> RouterBuilder {
-	router_builder!(
-		// Same as RpcRouter::new().add...
-		create_conv,
-		get_conv,
-		list_convs,
-		update_conv,
-		delete_conv,
-		add_conv_msg,
-	)
-}
+rpc_builders!(
+	// Same as RpcRouter::new().add...
+	create_conv,
+	get_conv,
+	list_convs,
+	update_conv,
+	delete_conv,
+	fork_conv,
+	add_conv_user,
+	list_conv_users,
+	add_conv_msg,
+	get_conv_msg,
+	list_conv_msgs,
+	update_conv_msg,
+	edit_conv_msg,
+	list_conv_msg_tree,
+	get_conv_thread,
+);
 
 generate_common_rpc_fns!(
 	Bmc: ConvBmc,
@@ -25,7 +38,63 @@ generate_common_rpc_fns!(
 	Suffix: conv
 );
 
+/// Params for `fork_conv`, the conv is forked at `msg_id`.
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForFork {
+	pub conv_id: i64,
+	pub msg_id: i64,
+}
+impl IntoParams for ParamsForFork {}
+
+/// Returns the new (forked) conv
+pub async fn fork_conv(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForFork,
+) -> Result<DataRpcResult<Conv>> {
+	let ParamsForFork { conv_id, msg_id } = params;
+
+	let fork_conv_id = ConvBmc::fork_conv(&ctx, &mm, conv_id, msg_id).await?;
+	let conv = ConvBmc::get(&ctx, &mm, fork_conv_id).await?;
+
+	Ok(conv.into())
+}
+
+/// Returns the new conv_user (conv participant)
+///
+/// Note: The ctx user must own the conv (see `ConvBmc::add_user`).
+pub async fn add_conv_user(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<ConvUserForCreate>,
+) -> Result<DataRpcResult<ConvUser>> {
+	let ParamsForCreate { data: conv_user_c } = params;
+
+	let conv_user_id = ConvBmc::add_user(&ctx, &mm, conv_user_c).await?;
+	let conv_user = ConvBmc::get_user(&ctx, &mm, conv_user_id).await?;
+
+	Ok(conv_user.into())
+}
+
+/// Returns the conv_users (participants) of the conv `id`
+pub async fn list_conv_users(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<DataRpcResult<Vec<ConvUser>>> {
+	let ParamsIded { id: conv_id } = params;
+
+	let conv_users = ConvBmc::list_users(&ctx, &mm, conv_id).await?;
+
+	Ok(conv_users.into())
+}
+
 /// Returns conv_msg
+///
+/// Note: The message is always a `User` message (see `ConvBmc::add_msg`).
+///
+/// Note: The replies of the conv auto-respond participants are triggered by the
+///       `conv_msg.created` event (see llm-worker), and added later to the conv.
 pub async fn add_conv_msg(
 	ctx: Ctx,
 	mm: ModelManager,
@@ -40,7 +109,6 @@ pub async fn add_conv_msg(
 }
 
 /// Returns conv_msg
-#[allow(unused)]
 pub async fn get_conv_msg(
 	ctx: Ctx,
 	mm: ModelManager,
@@ -51,4 +119,73 @@ pub async fn get_conv_msg(
 	let msg = ConvBmc::get_msg(&ctx, &mm, msg_id).await?;
 
 	Ok(msg.into())
-}
\ No newline at end of file
+}
+
+/// Returns conv_msgs (e.g., filtered by `conv_id` and `role`)
+///
+/// Note: Each filter requires a `conv_id` of a conv the ctx user is a member of
+///       (see `ConvBmc::list_msgs`).
+pub async fn list_conv_msgs(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<ConvMsgFilter>,
+) -> Result<DataRpcResult<Vec<ConvMsg>>> {
+	let msgs =
+		ConvBmc::list_msgs(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(msgs.into())
+}
+
+/// Returns the updated conv_msg
+pub async fn update_conv_msg(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<ConvMsgForUpdate>,
+) -> Result<DataRpcResult<ConvMsg>> {
+	let ParamsForUpdate { id, data: msg_u } = params;
+
+	ConvBmc::update_msg(&ctx, &mm, id, msg_u).await?;
+	let msg = ConvBmc::get_msg(&ctx, &mm, id).await?;
+
+	Ok(msg.into())
+}
+
+/// Returns the new conv_msg (sibling of the edited one, i.e., a new branch)
+pub async fn edit_conv_msg(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<ConvMsgForEdit>,
+) -> Result<DataRpcResult<ConvMsg>> {
+	let ParamsForUpdate { id, data: msg_e } = params;
+
+	let msg_id = ConvBmc::edit_msg(&ctx, &mm, id, msg_e).await?;
+	let msg = ConvBmc::get_msg(&ctx, &mm, msg_id).await?;
+
+	Ok(msg.into())
+}
+
+/// Returns all conv_msgs of the conv `id` (all branches)
+pub async fn list_conv_msg_tree(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<DataRpcResult<Vec<ConvMsg>>> {
+	let ParamsIded { id: conv_id } = params;
+
+	let msgs = ConvBmc::list_msg_tree(&ctx, &mm, conv_id).await?;
+
+	Ok(msgs.into())
+}
+
+/// Returns the conv_msgs from the conv root down to the leaf conv_msg `id`
+pub async fn get_conv_thread(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<DataRpcResult<Vec<ConvMsg>>> {
+	let ParamsIded { id: leaf_msg_id } = params;
+
+	let msgs = ConvBmc::get_conv_thread(&ctx, &mm, leaf_msg_id).await?;
+
+	Ok(msgs.into())
+}
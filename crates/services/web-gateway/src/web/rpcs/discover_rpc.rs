@@ -0,0 +1,33 @@
+use lib_rpc_core::openrpc::{OpenRpcDoc, RPC_DISCOVER};
+use lib_rpc_core::prelude::*;
+use lib_web::utils::service_rpc::{call_service_rpc, service_names};
+use serde_json::Value;
+use tracing::warn;
+
+pub fn rpc_router_builder() -> RouterBuilder {
+	RouterBuilder::default().append(RPC_DISCOVER, rpc_discover)
+}
+
+/// Returns the OpenRPC document of the gateway methods, with the methods of the
+/// workers as `service/method` (i.e., as dispatched by the gateway).
+///
+/// Note: A worker that does not answer is left out of the document (and logged).
+pub async fn rpc_discover(ctx: Ctx, doc: OpenRpcDoc) -> Result<OpenRpcDoc> {
+	let mut doc = doc;
+
+	for service in service_names() {
+		let service_doc = call_service_rpc(&ctx, &service, RPC_DISCOVER, Value::Null)
+			.await
+			.map_err(|ex| ex.to_string())
+			.and_then(|res| {
+				serde_json::from_value::<OpenRpcDoc>(res).map_err(|ex| ex.to_string())
+			});
+
+		match service_doc {
+			Ok(service_doc) => doc.merge_service(&service, service_doc),
+			Err(ex) => warn!("{:<12} - {service} rpc.discover failed: {ex}", "RPC DISCOVER"),
+		}
+	}
+
+	Ok(doc)
+}
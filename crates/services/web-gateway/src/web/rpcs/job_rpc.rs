@@ -0,0 +1,83 @@
+use lib_rpc_core::prelude::*;
+use lib_core::model::job::{Job, JobBmc, JobFilter, JobForCreate};
+use lib_core::model::user::UserBmc;
+
+rpc_builders!(
+	// Same as RpcRouter::new().add...
+	enqueue_job,
+	get_job,
+	list_jobs,
+	cancel_job,
+	retry_job,
+);
+
+/// Returns the enqueued job (run as the ctx user)
+///
+/// Note: `Sys` user only, since the job handlers trust their payload
+///       (e.g., the `webhook_delivery` id).
+pub async fn enqueue_job(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<JobForCreate>,
+) -> Result<DataRpcResult<Job>> {
+	UserBmc::ensure_sys_user(&ctx, &mm).await?;
+
+	let ParamsForCreate { data: job_c } = params;
+
+	let id = JobBmc::enqueue(&ctx, &mm, job_c).await?;
+	let job = JobBmc::get(&ctx, &mm, id).await?;
+
+	Ok(job.into())
+}
+
+/// Returns the job of the ctx user (e.g., to check its `state`)
+pub async fn get_job(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<DataRpcResult<Job>> {
+	let ParamsIded { id } = params;
+
+	let job = JobBmc::get_own(&ctx, &mm, id).await?;
+
+	Ok(job.into())
+}
+
+/// Returns the jobs of the ctx user (e.g., filtered by `queue` and `state`)
+pub async fn list_jobs(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<JobFilter>,
+) -> Result<DataRpcResult<Vec<Job>>> {
+	let jobs = JobBmc::list_own(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(jobs.into())
+}
+
+/// Returns the cancelled job
+pub async fn cancel_job(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<DataRpcResult<Job>> {
+	let ParamsIded { id } = params;
+
+	JobBmc::cancel(&ctx, &mm, id).await?;
+	let job = JobBmc::get_own(&ctx, &mm, id).await?;
+
+	Ok(job.into())
+}
+
+/// Returns the re-enqueued job (from `Dead` or `Cancelled`)
+pub async fn retry_job(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<DataRpcResult<Job>> {
+	let ParamsIded { id } = params;
+
+	JobBmc::retry(&ctx, &mm, id).await?;
+	let job = JobBmc::get_own(&ctx, &mm, id).await?;
+
+	Ok(job.into())
+}
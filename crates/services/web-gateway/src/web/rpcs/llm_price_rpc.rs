@@ -0,0 +1,23 @@
+use lib_rpc_core::prelude::*;
+use lib_core::model::llm_price::{
+	LlmPrice, LlmPriceBmc, LlmPriceFilter, LlmPriceForCreate, LlmPriceForUpdate,
+};
+
+rpc_builders!(
+	// Same as RpcRouter::new().add...
+	create_llm_price,
+	get_llm_price,
+	list_llm_prices,
+	update_llm_price,
+	delete_llm_price,
+);
+
+// Note: The create/update/delete are `Sys` user only (see `LlmPriceBmc`).
+generate_common_rpc_fns!(
+	Bmc: LlmPriceBmc,
+	Entity: LlmPrice,
+	ForCreate: LlmPriceForCreate,
+	ForUpdate: LlmPriceForUpdate,
+	Filter: LlmPriceFilter,
+	Suffix: llm_price
+);
@@ -2,7 +2,14 @@
 
 pub mod agent_rpc;
 pub mod conv_rpc;
+pub mod discover_rpc;
+pub mod job_rpc;
+pub mod llm_price_rpc;
+pub mod quota_rpc;
+pub mod usage_rpc;
+pub mod webhook_rpc;
 
+use lib_rpc_core::prelude::RpcDocBuilder;
 use rpc_router::{Router, RouterBuilder};
 
 // endregion: --- Modules
@@ -11,4 +18,23 @@ pub fn all_rpc_router_builder() -> RouterBuilder {
 	Router::builder()
 		.extend(agent_rpc::rpc_router_builder())
 		.extend(conv_rpc::rpc_router_builder())
+		.extend(job_rpc::rpc_router_builder())
+		.extend(llm_price_rpc::rpc_router_builder())
+		.extend(quota_rpc::rpc_router_builder())
+		.extend(usage_rpc::rpc_router_builder())
+		.extend(webhook_rpc::rpc_router_builder())
+		.extend(discover_rpc::rpc_router_builder())
+}
+
+/// The OpenRPC document builder of the `all_rpc_router_builder` methods
+/// (but `rpc.discover`, see `discover_rpc`).
+pub fn all_rpc_doc_builder() -> RpcDocBuilder {
+	RpcDocBuilder::default()
+		.extend(agent_rpc::rpc_doc_builder())
+		.extend(conv_rpc::rpc_doc_builder())
+		.extend(job_rpc::rpc_doc_builder())
+		.extend(llm_price_rpc::rpc_doc_builder())
+		.extend(quota_rpc::rpc_doc_builder())
+		.extend(usage_rpc::rpc_doc_builder())
+		.extend(webhook_rpc::rpc_doc_builder())
 }
@@ -0,0 +1,78 @@
+use lib_rpc_core::prelude::*;
+use lib_core::model::quota::{
+	Quota, QuotaBmc, QuotaFilter, QuotaForSet, QuotaOverride,
+};
+
+rpc_builders!(
+	// Same as RpcRouter::new().add...
+	set_quota,
+	grant_quota_override,
+	get_quota,
+	list_quotas,
+	delete_quota,
+);
+
+/// Returns the created or updated quota (`Sys` user only)
+pub async fn set_quota(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<QuotaForSet>,
+) -> Result<DataRpcResult<Quota>> {
+	let ParamsForCreate { data: quota_s } = params;
+
+	let id = QuotaBmc::set(&ctx, &mm, quota_s).await?;
+	let quota = QuotaBmc::get(&ctx, &mm, id).await?;
+
+	Ok(quota.into())
+}
+
+/// Returns the quota with its override (`Sys` user only)
+pub async fn grant_quota_override(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<QuotaOverride>,
+) -> Result<DataRpcResult<Quota>> {
+	let ParamsForUpdate { id, data: quota_o } = params;
+
+	QuotaBmc::grant_override(&ctx, &mm, id, quota_o).await?;
+	let quota = QuotaBmc::get(&ctx, &mm, id).await?;
+
+	Ok(quota.into())
+}
+
+pub async fn get_quota(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<DataRpcResult<Quota>> {
+	let ParamsIded { id } = params;
+
+	let quota = QuotaBmc::get(&ctx, &mm, id).await?;
+
+	Ok(quota.into())
+}
+
+pub async fn list_quotas(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<QuotaFilter>,
+) -> Result<DataRpcResult<Vec<Quota>>> {
+	let quotas =
+		QuotaBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(quotas.into())
+}
+
+/// Returns the deleted quota (`Sys` user only)
+pub async fn delete_quota(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<DataRpcResult<Quota>> {
+	let ParamsIded { id } = params;
+
+	let quota = QuotaBmc::get(&ctx, &mm, id).await?;
+	QuotaBmc::delete(&ctx, &mm, id).await?;
+
+	Ok(quota.into())
+}
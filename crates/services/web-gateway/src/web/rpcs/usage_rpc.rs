@@ -0,0 +1,50 @@
+use lib_rpc_core::prelude::*;
+use lib_core::model::usage_event::{
+	UsageEvent, UsageEventBmc, UsageEventFilter, UsageGroupBy, UsageSummary,
+	UsageSummaryFilter,
+};
+use rpc_router::IntoParams;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+rpc_builders!(
+	// Same as RpcRouter::new().add...
+	list_usage_events,
+	usage_summary,
+);
+
+/// Params for `usage_summary`.
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForUsageSummary {
+	pub group_by: UsageGroupBy,
+	#[serde(default)]
+	pub filter: UsageSummaryFilter,
+}
+impl IntoParams for ParamsForUsageSummary {}
+
+/// Returns the usage_events of the ctx user, or of all the users for a `Sys` user
+/// (e.g., filtered by `user_id`, `conv_id`)
+pub async fn list_usage_events(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<UsageEventFilter>,
+) -> Result<DataRpcResult<Vec<UsageEvent>>> {
+	let events =
+		UsageEventBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(events.into())
+}
+
+/// Returns the usage (tokens and cost) aggregated by day, user, agent, conv or model
+/// (of the ctx user, unless a `Sys` user)
+pub async fn usage_summary(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUsageSummary,
+) -> Result<DataRpcResult<Vec<UsageSummary>>> {
+	let ParamsForUsageSummary { group_by, filter } = params;
+
+	let summaries = UsageEventBmc::summary(&ctx, &mm, group_by, filter).await?;
+
+	Ok(summaries.into())
+}
@@ -0,0 +1,116 @@
+use lib_rpc_core::prelude::*;
+use lib_core::model::webhook::{
+	Webhook, WebhookBmc, WebhookDelivery, WebhookDeliveryFilter, WebhookFilter,
+	WebhookForCreate, WebhookForUpdate,
+};
+use lib_rpc_core::ListOptionsSchema;
+use modql::filter::ListOptions;
+use rpc_router::IntoParams;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+rpc_builders!(
+	// Same as RpcRouter::new().add...
+	create_webhook,
+	get_webhook,
+	list_webhooks,
+	update_webhook,
+	delete_webhook,
+	list_webhook_deliveries,
+);
+
+/// Params for `list_webhook_deliveries`.
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsListDeliveries {
+	pub webhook_id: i64,
+	pub filters: Option<Vec<WebhookDeliveryFilter>>,
+	#[schemars(with = "Option<ListOptionsSchema>")]
+	pub list_options: Option<ListOptions>,
+}
+impl IntoParams for ParamsListDeliveries {}
+
+/// Returns the created webhook (without its secret)
+pub async fn create_webhook(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<WebhookForCreate>,
+) -> Result<DataRpcResult<Webhook>> {
+	let ParamsForCreate { data: webhook_c } = params;
+
+	let id = WebhookBmc::create(&ctx, &mm, webhook_c).await?;
+	let webhook = WebhookBmc::get(&ctx, &mm, id).await?;
+
+	Ok(webhook.into())
+}
+
+/// Returns the webhook (e.g., to check `enabled` and `disabled_reason`)
+pub async fn get_webhook(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<DataRpcResult<Webhook>> {
+	let ParamsIded { id } = params;
+
+	let webhook = WebhookBmc::get(&ctx, &mm, id).await?;
+
+	Ok(webhook.into())
+}
+
+/// Returns the webhooks of the ctx user (and of its org)
+pub async fn list_webhooks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<WebhookFilter>,
+) -> Result<DataRpcResult<Vec<Webhook>>> {
+	let webhooks =
+		WebhookBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(webhooks.into())
+}
+
+/// Returns the updated webhook (`enabled: true` re-enables a disabled webhook)
+pub async fn update_webhook(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<WebhookForUpdate>,
+) -> Result<DataRpcResult<Webhook>> {
+	let ParamsForUpdate { id, data: webhook_u } = params;
+
+	WebhookBmc::update(&ctx, &mm, id, webhook_u).await?;
+	let webhook = WebhookBmc::get(&ctx, &mm, id).await?;
+
+	Ok(webhook.into())
+}
+
+/// Returns the deleted webhook (its delivery log is deleted too)
+pub async fn delete_webhook(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<DataRpcResult<Webhook>> {
+	let ParamsIded { id } = params;
+
+	let webhook = WebhookBmc::get(&ctx, &mm, id).await?;
+	WebhookBmc::delete(&ctx, &mm, id).await?;
+
+	Ok(webhook.into())
+}
+
+/// Returns the delivery log of a webhook (e.g., filtered by `state`)
+pub async fn list_webhook_deliveries(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsListDeliveries,
+) -> Result<DataRpcResult<Vec<WebhookDelivery>>> {
+	let ParamsListDeliveries {
+		webhook_id,
+		filters,
+		list_options,
+	} = params;
+
+	let deliveries =
+		WebhookBmc::list_deliveries(&ctx, &mm, webhook_id, filters, list_options)
+			.await?;
+
+	Ok(deliveries.into())
+}
@@ -0,0 +1,156 @@
+//! The `rpc-client.ts` generation, a thin `fetch` client with one method per rpc method.
+//!
+//! Note: The worker methods (e.g., `llm-worker/one_shot_msg`) are named without the
+//!       separators (e.g., `llm_worker_one_shot_msg`).
+
+use crate::gen_types::GENERATED_HEADER;
+use crate::ts_schema::ts_type;
+use crate::Result;
+use serde_json::Value;
+
+const TYPES_PREFIX: &str = "T.";
+
+const CLIENT_TS: &str = r#"
+import type * as T from "./rpc-types";
+
+/** The JSON-RPC error of a call, with the gateway `ClientError`. */
+export class RpcError extends Error {
+  constructor(
+    readonly method: string,
+    readonly error: T.ClientError,
+    readonly req_uuid?: string,
+  ) {
+    super(`${method} - ${error.message}`);
+  }
+}
+
+export class RpcClient {
+  private nextId = 1;
+
+  /** e.g., `new RpcClient()` (same origin) or `new RpcClient("http://localhost:8080")` */
+  constructor(private readonly baseUrl: string = "") {}
+
+  /** The `auth-token` cookie is then sent with the next calls. */
+  async login(username: string, pwd: string): Promise<void> {
+    await this.post("/api/login", "login", { username, pwd });
+  }
+
+  async logoff(): Promise<void> {
+    await this.post("/api/logoff", "logoff", { logoff: true });
+  }
+
+  async call<R>(method: string, params?: unknown): Promise<R> {
+    const body = { jsonrpc: "2.0", id: this.nextId++, method, params };
+    return (await this.post("/api/rpc", method, body)) as R;
+  }
+
+  private async post(path: string, method: string, body: unknown): Promise<unknown> {
+    const res = await fetch(`${this.baseUrl}${path}`, {
+      method: "POST",
+      credentials: "include",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify(body),
+    });
+    const resBody = await res.json();
+    if (resBody.error) {
+      const { message, data } = resBody.error;
+      const error = { message, detail: data?.detail } as T.ClientError;
+      throw new RpcError(method, error, data?.req_uuid);
+    }
+    return resBody.result;
+  }
+
+  // region:    --- Rpc Methods
+"#;
+
+pub fn gen_client(doc: &Value) -> Result<String> {
+	let methods = doc
+		.get("methods")
+		.and_then(Value::as_array)
+		.ok_or("OpenRPC document has no methods")?;
+
+	let mut out = String::from(GENERATED_HEADER);
+	out.push_str(CLIENT_TS);
+
+	for method in methods {
+		out.push('\n');
+		out.push_str(&ts_method(method)?);
+	}
+
+	out.push_str("\n  // endregion: --- Rpc Methods\n}\n");
+
+	Ok(out)
+}
+
+// region:    --- Support
+
+fn ts_method(method: &Value) -> Result<String> {
+	let name = method
+		.get("name")
+		.and_then(Value::as_str)
+		.ok_or("OpenRPC method has no name")?;
+	let params = method
+		.get("params")
+		.and_then(Value::as_array)
+		.cloned()
+		.unwrap_or_default();
+	let result_ts = method
+		.pointer("/result/schema")
+		.map(ts_result_type)
+		.unwrap_or_else(|| "unknown".to_string());
+
+	let fn_name: String = name
+		.chars()
+		.map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+		.collect();
+
+	// -- No params
+	if params.is_empty() {
+		return Ok(format!(
+			"  {fn_name}(): Promise<{result_ts}> {{\n    return this.call(\"{name}\");\n  }}\n"
+		));
+	}
+
+	// -- By-name params
+	let mut all_optional = true;
+	let members: Vec<String> = params
+		.iter()
+		.map(|param| {
+			let param_name = param.get("name").and_then(Value::as_str).unwrap_or("params");
+			let required = param.get("required").and_then(Value::as_bool) == Some(true);
+			all_optional &= !required;
+
+			let param_ts = param
+				.get("schema")
+				.map(|schema| ts_type(schema, TYPES_PREFIX))
+				.unwrap_or_else(|| "unknown".to_string());
+			let optional = if required { "" } else { "?" };
+			format!("{param_name}{optional}: {param_ts}")
+		})
+		.collect();
+	let default = if all_optional { " = {}" } else { "" };
+
+	Ok(format!(
+		"  {fn_name}(params: {{ {} }}{default}): Promise<{result_ts}> {{\n    return this.call(\"{name}\", params);\n  }}\n",
+		members.join("; ")
+	))
+}
+
+/// The `T.DataRpcResult<...>` for the `{ data }` results.
+fn ts_result_type(schema: &Value) -> String {
+	let data_schema = schema
+		.get("properties")
+		.and_then(Value::as_object)
+		.filter(|properties| properties.len() == 1)
+		.and_then(|properties| properties.get("data"));
+
+	match data_schema {
+		Some(data_schema) => format!(
+			"{TYPES_PREFIX}DataRpcResult<{}>",
+			ts_type(data_schema, TYPES_PREFIX)
+		),
+		None => ts_type(schema, TYPES_PREFIX),
+	}
+}
+
+// endregion: --- Support
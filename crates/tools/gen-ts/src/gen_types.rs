@@ -0,0 +1,138 @@
+//! The `rpc-types.ts` generation.
+//!
+//! Note: The entity filters (e.g., `ConvFilter`) and their modql operators (e.g., `OpValsString`)
+//!       are in the `components.schemas` (see `lib_core::model::modql_utils`).
+
+use crate::ts_schema::{ts_doc_comment, ts_properties, ts_type};
+use crate::Result;
+use serde_json::Value;
+
+pub const GENERATED_HEADER: &str =
+	"// Generated by `gen-ts` from the gateway OpenRPC document. Do not edit.\n";
+
+/// The generic types of `lib-rpc-core`.
+const PRELUDE_TS: &str = r#"
+// region:    --- rpc-core
+
+/** The `result` of the entity rpc methods (e.g., `get_agent`). */
+export interface DataRpcResult<T> {
+  data: T;
+}
+
+// endregion: --- rpc-core
+"#;
+
+pub fn gen_types(doc: &Value, client_error_schema: &Value) -> Result<String> {
+	let schemas = doc
+		.pointer("/components/schemas")
+		.and_then(Value::as_object)
+		.ok_or("OpenRPC document has no components.schemas")?;
+
+	let mut out = String::from(GENERATED_HEADER);
+	out.push_str(PRELUDE_TS);
+
+	// -- ClientError
+	out.push_str("\n// region:    --- ClientError\n\n");
+	out.push_str(&ts_definition("ClientError", client_error_schema));
+	out.push_str("\n// endregion: --- ClientError\n");
+
+	// -- Entities, Params & Filters
+	out.push_str("\n// region:    --- Schemas\n");
+	for (name, schema) in schemas {
+		out.push('\n');
+		out.push_str(&ts_definition(name, schema));
+	}
+	out.push_str("\n// endregion: --- Schemas\n");
+
+	Ok(out)
+}
+
+// region:    --- Support
+
+/// The `export` of the named schema, an interface for the objects with properties.
+fn ts_definition(name: &str, schema: &Value) -> String {
+	let mut out = String::new();
+	if let Some(comment) = ts_doc_comment(schema, "") {
+		out.push_str(&comment);
+		out.push('\n');
+	}
+
+	// -- Interface
+	if let Some(lines) = ts_properties(schema, "", "  ") {
+		out.push_str(&format!("export interface {name} {{\n"));
+		for line in lines {
+			out.push_str(&line);
+			out.push('\n');
+		}
+		out.push_str("}\n");
+		return out;
+	}
+
+	// -- Union (e.g., enum with data)
+	let variants = ["oneOf", "anyOf"]
+		.iter()
+		.find_map(|key| schema.get(key).and_then(Value::as_array));
+	if let Some(variants) = variants.filter(|variants| variants.len() > 1) {
+		out.push_str(&format!("export type {name} =\n"));
+		for variant in variants {
+			out.push_str(&format!("  | {}\n", ts_type(variant, "")));
+		}
+		out.pop();
+		out.push_str(";\n");
+		return out;
+	}
+
+	out.push_str(&format!("export type {name} = {};\n", ts_type(schema, "")));
+	out
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use lib_rpc_core::ClientError;
+
+	#[test]
+	fn test_gen_types_rpc_doc_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let doc = crate::rpc_doc()?;
+		let client_error_schema = serde_json::to_value(schemars::schema_for!(ClientError))?;
+
+		// -- Exec
+		let types_ts = gen_types(&doc, &client_error_schema)?;
+
+		// -- Check
+		// The entity filters are the Rust filters, with the modql operators per property type.
+		assert!(types_ts.contains("export interface AgentFilter {\n"));
+		assert!(types_ts.contains("  name?: OpValsString | null;\n"));
+		assert!(types_ts.contains("  id?: OpValsInt64 | null;\n"));
+		assert!(types_ts.contains("export type OpValsString =\n  | string\n  | OpValString;\n"));
+		assert!(types_ts.contains("  $startsWith?: string | null;\n"));
+		assert!(types_ts.contains("export interface WebhookDeliveryFilter {\n"));
+		assert!(types_ts.contains("export interface ListOptions {\n"));
+		assert!(types_ts.contains("export type ClientError =\n"));
+		assert!(types_ts.contains("export interface DataRpcResult<T> {\n"));
+		assert!(!types_ts.contains("Filter<"), "No generic entity filter");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_gen_types_no_schemas_err() -> Result<()> {
+		// -- Exec
+		let res = gen_types(&serde_json::json!({"methods": []}), &serde_json::json!({}));
+
+		// -- Check
+		assert!(res.is_err(), "Should fail without components.schemas");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
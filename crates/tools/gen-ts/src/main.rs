@@ -0,0 +1,91 @@
+//! Generate the TypeScript types and the fetch client of the gateway rpc api,
+//! from its OpenRPC document, built from the rpc modules of the services
+//! (i.e., from the Rust types and their `schemars` schemas, see `lib_rpc_core::openrpc`).
+//!
+//! ```sh
+//! cargo run -p gen-ts -- --out web-folder/ts
+//! ```
+//!
+//! Generates:
+//!
+//! - `rpc-types.ts` - The entities, params and entity filters (`components.schemas`),
+//!   the `ClientError`, and the `DataRpcResult<T>`.
+//! - `rpc-client.ts` - The `RpcClient` with one method per rpc method (worker methods included).
+
+pub type Result<T> = core::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error>; // Ok for tools.
+
+mod gen_client;
+mod gen_types;
+mod ts_schema;
+
+use lib_rpc_core::ClientError;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_OUT_DIR: &str = "web-folder/ts";
+
+const TYPES_FILE: &str = "rpc-types.ts";
+const CLIENT_FILE: &str = "rpc-client.ts";
+
+fn main() -> Result<()> {
+	let args = Args::parse()?;
+
+	// -- Build the OpenRPC document
+	let doc = rpc_doc()?;
+	let client_error_schema = serde_json::to_value(schemars::schema_for!(ClientError))?;
+
+	// -- Generate
+	let types_ts = gen_types::gen_types(&doc, &client_error_schema)?;
+	let client_ts = gen_client::gen_client(&doc)?;
+
+	// -- Write
+	fs::create_dir_all(&args.out_dir)?;
+	for (file_name, content) in [(TYPES_FILE, types_ts), (CLIENT_FILE, client_ts)] {
+		let path = args.out_dir.join(file_name);
+		fs::write(&path, content)?;
+		println!("->> {:<12} - {}", "GENERATED", path.display());
+	}
+
+	Ok(())
+}
+
+/// The gateway OpenRPC document with the worker methods (i.e., as served by the gateway `rpc.discover`).
+pub fn rpc_doc() -> Result<Value> {
+	use llm_worker::web::routes_rpc as worker_rpc;
+
+	let mut doc = web_gateway::web::routes_rpc::rpc_doc();
+	doc.merge_service(worker_rpc::SERVICE_NAME, worker_rpc::rpc_doc());
+
+	Ok(serde_json::to_value(doc)?)
+}
+
+// region:    --- Args
+
+struct Args {
+	out_dir: PathBuf,
+}
+
+impl Args {
+	fn parse() -> Result<Self> {
+		let mut args = Args {
+			out_dir: PathBuf::from(DEFAULT_OUT_DIR),
+		};
+
+		let mut argv = std::env::args().skip(1);
+		while let Some(name) = argv.next() {
+			let value = argv
+				.next()
+				.ok_or_else(|| format!("Argument '{name}' has no value"))?;
+			match name.as_str() {
+				"--out" => args.out_dir = PathBuf::from(value),
+				_ => return Err(format!("Argument '{name}' unknown").into()),
+			}
+		}
+
+		Ok(args)
+	}
+}
+
+// endregion: --- Args
@@ -0,0 +1,293 @@
+//! TypeScript types of the JSON Schemas (as generated by `schemars`, draft07).
+
+use serde_json::{Map, Value};
+
+/// The TypeScript type of the schema.
+///
+/// The named types (i.e., `$ref`) are prefixed with `prefix` (e.g., `T.` for the client).
+pub fn ts_type(schema: &Value, prefix: &str) -> String {
+	// Note: The `true` schema (any value).
+	let Some(obj) = schema.as_object() else {
+		return "unknown".to_string();
+	};
+
+	if let Some(reference) = obj.get("$ref").and_then(Value::as_str) {
+		return ts_ref_name(reference, prefix);
+	}
+	if let Some(value) = obj.get("const") {
+		return value.to_string();
+	}
+	if let Some(Value::Array(values)) = obj.get("enum") {
+		return ts_union(values.iter().map(Value::to_string).collect());
+	}
+	for key in ["oneOf", "anyOf"] {
+		if let Some(Value::Array(schemas)) = obj.get(key) {
+			return ts_union(schemas.iter().map(|s| ts_type(s, prefix)).collect());
+		}
+	}
+	if let Some(Value::Array(schemas)) = obj.get("allOf") {
+		let types: Vec<String> = schemas
+			.iter()
+			.map(|s| ts_type(s, prefix))
+			.map(|ts| if ts.contains(" | ") { format!("({ts})") } else { ts })
+			.collect();
+		return types.join(" & ");
+	}
+
+	match obj.get("type") {
+		Some(Value::String(typ)) => ts_json_type(typ, obj, prefix),
+		Some(Value::Array(types)) => ts_union(
+			types
+				.iter()
+				.filter_map(Value::as_str)
+				.map(|typ| ts_json_type(typ, obj, prefix))
+				.collect(),
+		),
+		_ => "unknown".to_string(),
+	}
+}
+
+/// The interface members of an object schema with properties, one per line
+/// (None when not such an object).
+pub fn ts_properties(schema: &Value, prefix: &str, indent: &str) -> Option<Vec<String>> {
+	let obj = schema.as_object()?;
+	let properties = obj.get("properties")?.as_object()?;
+	let required = required_names(obj);
+
+	let mut lines = Vec::new();
+	for (name, prop_schema) in properties {
+		if let Some(comment) = ts_doc_comment(prop_schema, indent) {
+			lines.push(comment);
+		}
+		let optional = if required.contains(&name.as_str()) { "" } else { "?" };
+		lines.push(format!(
+			"{indent}{}{optional}: {};",
+			ts_prop_name(name),
+			ts_type(prop_schema, prefix)
+		));
+	}
+
+	Some(lines)
+}
+
+/// The `/** ... */` comment of the schema `description`, if any.
+pub fn ts_doc_comment(schema: &Value, indent: &str) -> Option<String> {
+	let description = schema.get("description")?.as_str()?;
+	let lines: Vec<&str> = description.lines().collect();
+
+	let comment = match lines.as_slice() {
+		[line] => format!("{indent}/** {line} */"),
+		lines => {
+			let mut comment = format!("{indent}/**\n");
+			for line in lines {
+				comment.push_str(&format!("{indent} * {line}\n"));
+			}
+			comment.push_str(&format!("{indent} */"));
+			comment
+		}
+	};
+
+	Some(comment)
+}
+
+/// The TypeScript name of the `$ref` (e.g., `#/components/schemas/Agent` to `Agent`).
+pub fn ts_ref_name(reference: &str, prefix: &str) -> String {
+	let name = reference.rsplit('/').next().unwrap_or(reference);
+
+	format!("{prefix}{name}")
+}
+
+// region:    --- Support
+
+fn ts_json_type(typ: &str, obj: &Map<String, Value>, prefix: &str) -> String {
+	match typ {
+		"string" => "string".to_string(),
+		"integer" | "number" => "number".to_string(),
+		"boolean" => "boolean".to_string(),
+		"null" => "null".to_string(),
+		"array" => {
+			let item = obj
+				.get("items")
+				.map(|items| ts_type(items, prefix))
+				.unwrap_or_else(|| "unknown".to_string());
+			ts_array(item)
+		}
+		"object" => ts_object(obj, prefix),
+		_ => "unknown".to_string(),
+	}
+}
+
+/// The inline object type (e.g., the enum variant data).
+fn ts_object(obj: &Map<String, Value>, prefix: &str) -> String {
+	if let Some(properties) = obj.get("properties").and_then(Value::as_object) {
+		let required = required_names(obj);
+		let members: Vec<String> = properties
+			.iter()
+			.map(|(name, prop_schema)| {
+				let optional = if required.contains(&name.as_str()) { "" } else { "?" };
+				format!(
+					"{}{optional}: {}",
+					ts_prop_name(name),
+					ts_type(prop_schema, prefix)
+				)
+			})
+			.collect();
+		return format!("{{ {} }}", members.join("; "));
+	}
+
+	match obj.get("additionalProperties") {
+		Some(Value::Object(_)) | Some(Value::Bool(true)) | None => {
+			let value = obj
+				.get("additionalProperties")
+				.map(|schema| ts_type(schema, prefix))
+				.unwrap_or_else(|| "unknown".to_string());
+			format!("Record<string, {value}>")
+		}
+		Some(_) => "Record<string, never>".to_string(),
+	}
+}
+
+fn ts_array(item: String) -> String {
+	if item.contains(' ') {
+		format!("({item})[]")
+	} else {
+		format!("{item}[]")
+	}
+}
+
+fn ts_union(types: Vec<String>) -> String {
+	let mut union: Vec<String> = Vec::new();
+	for ts in types {
+		if !union.contains(&ts) {
+			union.push(ts);
+		}
+	}
+
+	match union.len() {
+		0 => "never".to_string(),
+		_ => union.join(" | "),
+	}
+}
+
+fn ts_prop_name(name: &str) -> String {
+	let is_ident = name
+		.chars()
+		.enumerate()
+		.all(|(i, c)| c == '_' || c == '$' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+
+	if is_ident && !name.is_empty() {
+		name.to_string()
+	} else {
+		Value::String(name.to_string()).to_string()
+	}
+}
+
+fn required_names(obj: &Map<String, Value>) -> Vec<&str> {
+	obj.get("required")
+		.and_then(Value::as_array)
+		.map(|names| names.iter().filter_map(Value::as_str).collect())
+		.unwrap_or_default()
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Error = Box<dyn std::error::Error>;
+	type Result<T> = core::result::Result<T, Error>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_ts_type_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_cases = [
+			(json!(true), ""),
+			(json!({"$ref": "#/components/schemas/Agent"}), "T.Agent"),
+			(json!({"const": "Pending"}), "\"Pending\""),
+			(json!({"enum": ["A", "B", "A"]}), "\"A\" | \"B\""),
+			(json!({"type": "integer", "format": "int64"}), "number"),
+			(json!({"type": ["string", "null"]}), "string | null"),
+			(json!({"type": "array", "items": {"type": "string"}}), "string[]"),
+			(
+				json!({"type": "array", "items": {"type": ["integer", "null"]}}),
+				"(number | null)[]",
+			),
+			(
+				json!({"anyOf": [{"$ref": "#/components/schemas/AgentFilter"}, {"type": "array", "items": {"$ref": "#/components/schemas/AgentFilter"}}]}),
+				"T.AgentFilter | T.AgentFilter[]",
+			),
+			(
+				json!({"allOf": [{"$ref": "#/components/schemas/Role"}]}),
+				"T.Role",
+			),
+			(
+				json!({"type": "object", "required": ["id"], "properties": {"id": {"type": "integer"}, "$gt": {"type": "integer"}}}),
+				"{ $gt?: number; id: number }",
+			),
+			(
+				json!({"type": "object", "properties": {"content-type": {"type": "string"}}}),
+				"{ \"content-type\"?: string }",
+			),
+			(json!({"type": "object"}), "Record<string, unknown>"),
+			(
+				json!({"type": "object", "additionalProperties": false}),
+				"Record<string, never>",
+			),
+		];
+
+		// -- Exec & Check
+		for (schema, expected) in fx_cases {
+			let expected = if expected.is_empty() { "unknown" } else { expected };
+			assert_eq!(ts_type(&schema, "T."), expected, "schema: {schema}");
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_ts_properties_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_schema = json!({
+			"type": "object",
+			"required": ["name"],
+			"properties": {
+				"model": {"type": ["string", "null"]},
+				"name": {"type": "string", "description": "The agent name."}
+			}
+		});
+
+		// -- Exec
+		let lines = ts_properties(&fx_schema, "", "  ").ok_or("Should have properties")?;
+
+		// -- Check
+		assert_eq!(
+			lines,
+			[
+				"  model?: string | null;",
+				"  /** The agent name. */",
+				"  name: string;"
+			]
+		);
+		assert!(ts_properties(&json!({"type": "string"}), "", "  ").is_none());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_ts_doc_comment_multiline_ok() -> Result<()> {
+		// -- Exec
+		let comment = ts_doc_comment(&json!({"description": "Line one.\nLine two."}), "  ")
+			.ok_or("Should have a comment")?;
+
+		// -- Check
+		assert_eq!(comment, "  /**\n   * Line one.\n   * Line two.\n   */");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
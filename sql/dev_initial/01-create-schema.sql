@@ -21,6 +21,10 @@ CREATE TABLE "user" (
   username varchar(128) NOT NULL UNIQUE,
   typ user_typ NOT NULL DEFAULT 'User',
 
+  -- FKs
+  org_id BIGINT, -- for the org quotas
+  org_admin BOOLEAN NOT NULL DEFAULT false, -- manages the org (e.g., its webhooks)
+
   -- Auth
   pwd varchar(256),
   pwd_salt uuid NOT NULL DEFAULT gen_random_uuid(),
@@ -46,6 +50,11 @@ CREATE TABLE agent (
   name varchar(256) NOT NULL,
   ai_provider varchar(256) NOT NULL default 'dev', -- For now only support 'dev' provider
   ai_model varchar(256) NOT NULL default 'parrot', -- For now only support 'parrot' model
+  tools jsonb, -- tools the model can call (see AgentTool)
+
+  -- Conv Settings
+  auto_title BOOLEAN NOT NULL DEFAULT false,
+  summary_token_budget integer, -- NULL for no rolling summary
 
   -- Timestamps
   cid bigint NOT NULL,
@@ -104,6 +113,8 @@ CREATE TABLE conv_user (
 );
 
 -- Conv Messages
+CREATE TYPE msg_role AS ENUM ('User', 'Assistant', 'System', 'Tool');
+
 CREATE TABLE conv_msg (
   -- PK
   id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
@@ -111,9 +122,13 @@ CREATE TABLE conv_msg (
   -- FKs
   conv_id BIGINT NOT NULL,
   user_id BIGINT NOT NULL, -- should be came as cid
+  parent_msg_id BIGINT, -- NULL for the root message of a conv
 
   -- Properties
-  content varchar(1024) NOT NULL,
+  role msg_role NOT NULL default 'User',
+  content text NOT NULL,
+  content_parts jsonb, -- structured parts (text, image, tool call/result)
+  metadata jsonb, -- e.g., model, finish_reason, token counts, latency
 
   -- Timestamps
   cid bigint NOT NULL,
@@ -126,6 +141,380 @@ ALTER TABLE conv_msg ADD CONSTRAINT fk_conv_msg_conv
   FOREIGN KEY (conv_id) REFERENCES "conv"(id)
   ON DELETE CASCADE;
 
+ALTER TABLE conv_msg ADD CONSTRAINT fk_conv_msg_parent
+  FOREIGN KEY (parent_msg_id) REFERENCES "conv_msg"(id)
+  ON DELETE CASCADE;
+
+CREATE INDEX idx_conv_msg_parent ON conv_msg(parent_msg_id);
+
+-- Conv Rolling Summaries (one per branch, of the messages up to msg_id)
+CREATE TABLE conv_summary (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FKs
+  conv_id BIGINT NOT NULL,
+  msg_id BIGINT NOT NULL UNIQUE,
+
+  -- Properties
+  summary text NOT NULL,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+ALTER TABLE conv_summary ADD CONSTRAINT fk_conv_summary_conv
+  FOREIGN KEY (conv_id) REFERENCES "conv"(id)
+  ON DELETE CASCADE;
+
+ALTER TABLE conv_summary ADD CONSTRAINT fk_conv_summary_msg
+  FOREIGN KEY (msg_id) REFERENCES "conv_msg"(id)
+  ON DELETE CASCADE;
+
+-- Conv Msg Attachments
+CREATE TABLE attachment (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FKs
+  conv_id BIGINT NOT NULL,
+  conv_msg_id BIGINT NOT NULL,
+
+  -- Properties
+  file_name varchar(256) NOT NULL,
+  mime_type varchar(128) NOT NULL,
+  size BIGINT NOT NULL,
+  blob_key varchar(512) NOT NULL UNIQUE, -- key in the BlobStore
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+ALTER TABLE attachment ADD CONSTRAINT fk_attachment_conv_msg
+  FOREIGN KEY (conv_msg_id) REFERENCES "conv_msg"(id)
+  ON DELETE CASCADE;
+
 ALTER TABLE conv_user ADD CONSTRAINT fk_conv_user_conv
   FOREIGN KEY (user_id) REFERENCES "user"(id)
   ON DELETE CASCADE;
+
+-- LLM Prices (in USD per 1M tokens)
+CREATE TABLE llm_price (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  provider varchar(256) NOT NULL,
+  model varchar(256) NOT NULL,
+  input_price double precision NOT NULL,
+  output_price double precision NOT NULL,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL,
+
+  UNIQUE (provider, model)
+);
+
+-- Usage Events (one per LLM call)
+-- Note: No FKs, usage events must outlive the user/agent/conv for billing.
+CREATE TABLE usage_event (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Relations
+  user_id BIGINT NOT NULL,
+  agent_id BIGINT,
+  conv_id BIGINT,
+
+  -- Properties
+  provider varchar(256) NOT NULL,
+  model varchar(256) NOT NULL,
+  input_tokens integer NOT NULL,
+  output_tokens integer NOT NULL,
+  cost double precision, -- USD, NULL when no llm_price for the provider/model
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+CREATE INDEX idx_usage_event_ctime ON usage_event(ctime);
+
+-- Quotas
+CREATE TYPE quota_scope AS ENUM ('User', 'Org');
+
+CREATE TYPE quota_period AS ENUM ('Daily', 'Monthly');
+
+CREATE TABLE quota (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  scope quota_scope NOT NULL,
+  scope_id BIGINT NOT NULL, -- user.id or org.id
+  period quota_period NOT NULL,
+  max_tokens BIGINT, -- NULL for no token cap
+  max_cost double precision, -- USD, NULL for no cost cap
+
+  -- Temporary override (replaces the caps until override_until)
+  override_max_tokens BIGINT,
+  override_max_cost double precision,
+  override_until timestamp with time zone,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL,
+
+  UNIQUE (scope, scope_id, period)
+);
+
+ALTER TABLE "user" ADD CONSTRAINT fk_user_org
+  FOREIGN KEY (org_id) REFERENCES "org"(id)
+  ON DELETE SET NULL;
+
+-- Knowledge Base Documents
+CREATE TABLE document (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FKs
+  owner_id BIGINT NOT NULL,
+
+  -- Properties
+  title varchar(256) NOT NULL,
+  source varchar(1024), -- e.g., url, file path
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+-- Knowledge Base Document Chunks
+CREATE TABLE chunk (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FKs
+  document_id BIGINT NOT NULL,
+
+  -- Properties
+  idx integer NOT NULL, -- position in the document
+  content text NOT NULL,
+  embed_model varchar(256) NOT NULL,
+  embedding bytea NOT NULL, -- little endian f32 array
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+ALTER TABLE chunk ADD CONSTRAINT fk_chunk_document
+  FOREIGN KEY (document_id) REFERENCES "document"(id)
+  ON DELETE CASCADE;
+
+CREATE INDEX idx_chunk_embed_model ON chunk(embed_model);
+
+-- Background Jobs
+CREATE TYPE job_state AS ENUM ('Pending', 'Running', 'Succeeded', 'Dead', 'Cancelled');
+
+CREATE TABLE job (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  queue varchar(128) NOT NULL,
+  kind varchar(128) NOT NULL, -- handler name
+  payload jsonb,
+
+  -- Scheduling
+  state job_state NOT NULL DEFAULT 'Pending',
+  run_at timestamp with time zone NOT NULL,
+  attempts integer NOT NULL DEFAULT 0,
+  max_attempts integer NOT NULL DEFAULT 5,
+  cron varchar(128), -- rescheduled at the next cron time after each run
+  last_error text,
+
+  -- Lock (while Running)
+  locked_by varchar(256),
+  locked_at timestamp with time zone,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+CREATE INDEX idx_job_due ON job(queue, run_at) WHERE state = 'Pending';
+
+-- Conv Events (LISTEN conv_event)
+-- Payload: {"type": "conv_msg.created" | "conv.updated", "id": <conv_msg.id | conv.id>}
+CREATE FUNCTION notify_conv_event() RETURNS trigger AS $$
+BEGIN
+  PERFORM pg_notify('conv_event', json_build_object('type', TG_ARGV[0], 'id', NEW.id)::text);
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER conv_msg_created_notify AFTER INSERT ON conv_msg
+  FOR EACH ROW EXECUTE FUNCTION notify_conv_event('conv_msg.created');
+
+CREATE TRIGGER conv_updated_notify AFTER UPDATE ON conv
+  FOR EACH ROW EXECUTE FUNCTION notify_conv_event('conv.updated');
+
+-- Event Bus (see lib-events PgEventBus)
+CREATE TABLE event (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  ce_id varchar(128) NOT NULL UNIQUE, -- CloudEvent id
+  typ varchar(256) NOT NULL,
+  source varchar(256) NOT NULL,
+  envelope jsonb NOT NULL, -- CloudEvent json
+
+  -- Timestamps
+  ctime timestamp with time zone NOT NULL
+);
+
+CREATE TABLE event_subscription (
+  -- PK
+  name varchar(256) PRIMARY KEY,
+
+  -- Properties
+  types text[] NOT NULL, -- type prefixes (empty for all)
+
+  -- Timestamps
+  ctime timestamp with time zone NOT NULL
+);
+
+CREATE TABLE event_delivery (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FKs
+  subscription varchar(256) NOT NULL,
+  event_id BIGINT NOT NULL,
+
+  -- Properties
+  attempts integer NOT NULL DEFAULT 0,
+  visible_at timestamp with time zone NOT NULL, -- redelivered after (when not acked)
+  last_error text,
+  dead_at timestamp with time zone -- dead-letter (max attempts reached, not delivered anymore)
+);
+
+ALTER TABLE event_delivery ADD CONSTRAINT fk_event_delivery_subscription
+  FOREIGN KEY (subscription) REFERENCES event_subscription(name)
+  ON DELETE CASCADE;
+
+ALTER TABLE event_delivery ADD CONSTRAINT fk_event_delivery_event
+  FOREIGN KEY (event_id) REFERENCES event(id)
+  ON DELETE CASCADE;
+
+CREATE INDEX idx_event_delivery_visible ON event_delivery(subscription, visible_at)
+  WHERE dead_at IS NULL;
+
+CREATE INDEX idx_event_delivery_event ON event_delivery(event_id);
+
+CREATE INDEX idx_event_ctime ON event(ctime);
+
+-- Transactional Outbox (see lib-core OutboxBmc and OutboxRelay)
+CREATE TABLE outbox (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  event_type varchar(256) NOT NULL,
+  event jsonb NOT NULL, -- CloudEvent json
+
+  -- Delivery
+  attempts integer NOT NULL DEFAULT 0,
+  last_error text,
+  sent_at timestamp with time zone,
+  dead_at timestamp with time zone, -- dead-letter (max attempts reached, not relayed anymore)
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL
+);
+
+CREATE INDEX idx_outbox_pending ON outbox(id) WHERE sent_at IS NULL AND dead_at IS NULL;
+
+-- Outbound Webhooks
+CREATE TYPE webhook_scope AS ENUM ('User', 'Org');
+
+CREATE TABLE webhook (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  scope webhook_scope NOT NULL,
+  scope_id BIGINT NOT NULL, -- user.id or org.id
+  url varchar(2048) NOT NULL,
+  event_types jsonb NOT NULL DEFAULT '[]', -- type prefixes (empty for all)
+  secret varchar(256) NOT NULL, -- HMAC secret of the payload signatures
+
+  -- Health
+  enabled BOOLEAN NOT NULL DEFAULT true,
+  failed_deliveries integer NOT NULL DEFAULT 0, -- consecutive
+  disabled_reason text,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+CREATE INDEX idx_webhook_scope ON webhook(scope, scope_id) WHERE enabled;
+
+CREATE TYPE webhook_delivery_state AS ENUM ('Pending', 'Succeeded', 'Failed');
+
+CREATE TABLE webhook_delivery (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  webhook_id BIGINT NOT NULL,
+
+  -- Properties
+  event_id varchar(128) NOT NULL, -- CloudEvent id
+  event_type varchar(256) NOT NULL,
+  payload jsonb NOT NULL, -- CloudEvent json
+
+  -- Delivery
+  state webhook_delivery_state NOT NULL DEFAULT 'Pending',
+  attempts integer NOT NULL DEFAULT 0,
+  last_status integer,
+  last_error text,
+  delivered_at timestamp with time zone,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+ALTER TABLE webhook_delivery ADD CONSTRAINT fk_webhook_delivery_webhook
+  FOREIGN KEY (webhook_id) REFERENCES webhook(id)
+  ON DELETE CASCADE;
+
+CREATE INDEX idx_webhook_delivery_webhook ON webhook_delivery(webhook_id);
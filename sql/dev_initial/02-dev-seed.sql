@@ -13,3 +13,12 @@ INSERT INTO "agent"
     (id,  owner_id, name,      cid, ctime, mid, mtime) VALUES
     (100, 0,        'mock-01', 0,   now(), 0,   now());
 
+-- LLM Prices (USD per 1M tokens, input/output)
+INSERT INTO "llm_price"
+    (provider, model,                input_price, output_price, cid, ctime, mid, mtime) VALUES
+    ('Groq',   'llama3-8b-8192',     0.05,        0.08,         0,   now(), 0,   now()),
+    ('Groq',   'llama3-70b-8192',    0.59,        0.79,         0,   now(), 0,   now()),
+    ('Groq',   'mixtral-8x7b-32768', 0.24,        0.24,         0,   now(), 0,   now()),
+    ('Groq',   'gemma-7b-it',        0.07,        0.07,         0,   now(), 0,   now()),
+    ('OpenAI', 'gpt-4o-mini',        0.15,        0.60,         0,   now(), 0,   now()),
+    ('OpenAI', 'gpt-3.5-turbo',      0.50,        1.50,         0,   now(), 0,   now());